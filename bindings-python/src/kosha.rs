@@ -1,11 +1,15 @@
+use crate::prakriya::args::{PyLakara, PyLinga, PyPrayoga, PyPurusha, PyVacana, PyVibhakti};
 use entries::{PyDhatuEntry, PyPadaEntry, PyPratipadikaEntry};
+use fst::{IntoStreamer, Streamer};
 use pyo3::exceptions::{PyKeyError, PyOSError, PyValueError};
 use pyo3::prelude::*;
+use std::collections::VecDeque;
 use std::path::PathBuf;
 use vidyut_kosha::entries::{
     BasicPratipadikaEntry, DhatuEntry, DhatuMeta, KrdantaEntry, PadaEntry, PratipadikaEntry,
-    SubantaEntry, TinantaEntry,
+    SubantaEntry, TaddhitantaEntry, TinantaEntry,
 };
+use vidyut_kosha::packing::{PackedEntry, SubantaSuffix, TinantaSuffix};
 use vidyut_kosha::{Builder, Kosha};
 use vidyut_prakriya::args as vp;
 
@@ -89,6 +93,214 @@ impl PyKosha {
         };
         Py::new(slf.py(), iter)
     }
+
+    /// Return an iterator over all (`key`, `entry`) pairs in the kosha.
+    ///
+    /// Unlike :meth:`dhatus` and :meth:`pratipadikas`, this method reads the kosha lazily, so
+    /// it is fast to start and uses little memory even on large koshas. Keys are returned in
+    /// the kosha's internal order, so the words in a paradigm are returned together and
+    /// might not be sorted with respect to other keys.
+    fn items(slf: PyRef<'_, Self>) -> PyResult<Py<KoshaItemIter>> {
+        let py = slf.py();
+        let iter = KoshaItemIter {
+            kosha: slf.into(),
+            last_key: None,
+            buffer: VecDeque::new(),
+            is_done: false,
+        };
+        Py::new(py, iter)
+    }
+
+    /// Return all subanta suffix tables in the kosha.
+    ///
+    /// Suffix tables are an implementation detail, but they might be useful for better
+    /// understanding how the kosha is constructed.
+    fn subanta_suffixes(&self) -> Vec<Vec<PySubantaSuffix>> {
+        self.0
+            .subanta_suffixes()
+            .iter()
+            .map(|table| table.endings().iter().map(|x| x.into()).collect())
+            .collect()
+    }
+
+    /// Return all tinanta suffix tables in the kosha.
+    ///
+    /// Suffix tables are an implementation detail, but they might be useful for better
+    /// understanding how the kosha is constructed.
+    fn tinanta_suffixes(&self) -> Vec<Vec<PyTinantaSuffix>> {
+        self.0
+            .tinanta_suffixes()
+            .iter()
+            .map(|table| table.endings().iter().map(|x| x.into()).collect())
+            .collect()
+    }
+
+    /// Pack the given `pada` into the integer code that the kosha uses internally.
+    ///
+    /// All lemmas used by `pada` must already exist in the kosha. Otherwise, this method
+    /// raises a `ValueError`.
+    fn pack(&self, pada: PyPadaEntry) -> PyResult<u32> {
+        let mut r = SmallRegistry::default();
+        let entry = r.to_pada_entry(&pada)?;
+        match self.0.pack(&entry) {
+            Ok(packed) => Ok(packed.to_u32()),
+            Err(e) => Err(PyValueError::new_err(format!("Could not pack entry. {e}"))),
+        }
+    }
+
+    /// Unpack the given integer code into a full :class:`~vidyut.kosha.PadaEntry`.
+    ///
+    /// This method is the inverse of :meth:`pack`. If `value` is not a valid code for this
+    /// kosha, this method raises a `ValueError`.
+    fn unpack(&self, value: u32) -> PyResult<PyPadaEntry> {
+        match self.0.unpack(PackedEntry::from_u32(value)) {
+            Ok(entry) => Ok((&entry).into()),
+            Err(e) => Err(PyValueError::new_err(format!(
+                "Could not unpack entry. {e}"
+            ))),
+        }
+    }
+}
+
+/// The number of FST entries that `KoshaItemIter` reads at a time.
+const ITEM_BATCH_SIZE: usize = 256;
+
+/// A lazy iterator over all (`key`, `entry`) pairs in a kosha.
+///
+/// Python can't hold a borrowed Rust iterator, so we instead read the kosha's FST in batches
+/// and resume each batch after the last raw key we read.
+#[pyclass]
+struct KoshaItemIter {
+    kosha: Py<PyKosha>,
+    /// The last raw FST key we read, or `None` if we haven't started.
+    last_key: Option<Vec<u8>>,
+    /// Items we have read but not yet returned.
+    buffer: VecDeque<(String, PyPadaEntry)>,
+    is_done: bool,
+}
+
+impl KoshaItemIter {
+    /// Reads the next batch of FST entries into `self.buffer`.
+    fn read_batch(&mut self, py: Python<'_>) -> PyResult<()> {
+        let kosha = self.kosha.borrow(py);
+        let fst = kosha.0.fst_ref();
+
+        let mut raw = Vec::with_capacity(ITEM_BATCH_SIZE);
+        {
+            let mut stream = match &self.last_key {
+                Some(key) => fst.range().gt(key).into_stream(),
+                None => fst.stream(),
+            };
+            while raw.len() < ITEM_BATCH_SIZE {
+                match stream.next() {
+                    Some((key, value)) => raw.push((key.to_vec(), value)),
+                    None => {
+                        self.is_done = true;
+                        break;
+                    }
+                }
+            }
+        }
+
+        for (key, value) in &raw {
+            let words = kosha
+                .0
+                .expand_entry(key, *value)
+                .map_err(|e| PyValueError::new_err(format!("Could not read entry. {e}")))?;
+            self.buffer
+                .extend(words.iter().map(|(k, entry)| (k.clone(), entry.into())));
+        }
+        if let Some((key, _)) = raw.pop() {
+            self.last_key = Some(key);
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl KoshaItemIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(mut slf: PyRefMut<'_, Self>) -> PyResult<Option<(String, PyPadaEntry)>> {
+        let py = slf.py();
+        while slf.buffer.is_empty() && !slf.is_done {
+            slf.read_batch(py)?;
+        }
+        Ok(slf.buffer.pop_front())
+    }
+}
+
+/// An ending in some subanta paradigm.
+#[pyclass(name = "SubantaSuffix", get_all, eq, ord)]
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct PySubantaSuffix {
+    text: String,
+    linga: PyLinga,
+    vibhakti: PyVibhakti,
+    vacana: PyVacana,
+}
+
+#[pymethods]
+impl PySubantaSuffix {
+    fn __repr__(&self) -> String {
+        format!(
+            "SubantaSuffix(text='{}', linga={}, vibhakti={}, vacana={})",
+            self.text,
+            self.linga.__repr__(),
+            self.vibhakti.__repr__(),
+            self.vacana.__repr__(),
+        )
+    }
+}
+
+impl From<&SubantaSuffix> for PySubantaSuffix {
+    fn from(val: &SubantaSuffix) -> Self {
+        Self {
+            text: val.text().to_string(),
+            linga: val.linga().into(),
+            vibhakti: val.vibhakti().into(),
+            vacana: val.vacana().into(),
+        }
+    }
+}
+
+/// An ending in some tinanta paradigm.
+#[pyclass(name = "TinantaSuffix", get_all, eq, ord)]
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct PyTinantaSuffix {
+    text: String,
+    prayoga: PyPrayoga,
+    lakara: PyLakara,
+    purusha: PyPurusha,
+    vacana: PyVacana,
+}
+
+#[pymethods]
+impl PyTinantaSuffix {
+    fn __repr__(&self) -> String {
+        format!(
+            "TinantaSuffix(text='{}', prayoga={}, lakara={}, purusha={}, vacana={})",
+            self.text,
+            self.prayoga.__repr__(),
+            self.lakara.__repr__(),
+            self.purusha.__repr__(),
+            self.vacana.__repr__(),
+        )
+    }
+}
+
+impl From<&TinantaSuffix> for PyTinantaSuffix {
+    fn from(val: &TinantaSuffix) -> Self {
+        Self {
+            text: val.text().to_string(),
+            prayoga: val.prayoga().into(),
+            lakara: val.lakara().into(),
+            purusha: val.purusha().into(),
+            vacana: val.vacana().into(),
+        }
+    }
 }
 
 #[pyclass]
@@ -136,6 +348,7 @@ pub struct PyBuilder {
 struct SmallRegistry {
     lingas: Vec<Vec<vp::Linga>>,
     dhatu_meta: Vec<DhatuMeta>,
+    samasas: Vec<vp::Pratipadika>,
 }
 
 impl SmallRegistry {
@@ -197,6 +410,22 @@ impl SmallRegistry {
                 prayoga.map(|x| x.into()),
                 lakara.map(|x| x.into()),
             )),
+            PyPratipadikaEntry::Taddhitanta {
+                pratipadika,
+                taddhita,
+                artha,
+            } => PratipadikaEntry::Taddhitanta(TaddhitantaEntry::new(
+                PratipadikaEntry::try_from(pratipadika.as_ref())
+                    .map_err(|_| PyValueError::new_err("Unsupported pratipadika type"))?,
+                (*taddhita).into(),
+                artha.map(|x| x.into()),
+            )),
+            PyPratipadikaEntry::Samasa { .. } => {
+                let i = self.samasas.len();
+                self.samasas.push(phit.to_prakriya_args()?.as_ref().clone());
+                PratipadikaEntry::try_from(&self.samasas[i])
+                    .map_err(|_| PyValueError::new_err("Unsupported pratipadika type"))?
+            }
         };
 
         Ok(ret)
@@ -241,6 +470,15 @@ impl SmallRegistry {
     }
 }
 
+impl PyBuilder {
+    fn builder_mut(&mut self) -> PyResult<&mut Builder> {
+        match self.builder {
+            Some(ref mut b) => Ok(b),
+            None => Err(PyOSError::new_err("Kosha has already been written.")),
+        }
+    }
+}
+
 #[pymethods]
 impl PyBuilder {
     /// Create a new builder whose output will be written to `path`.
@@ -276,6 +514,97 @@ impl PyBuilder {
         }
     }
 
+    /// Insert the given `key` with the packed entry `value`.
+    ///
+    /// `value` is usually the output of :meth:`pack`, :meth:`register_subanta_paradigm`, or
+    /// :meth:`register_tinanta_paradigm`. Keys must be inserted in lexicographic order. If a key
+    /// is received out of order, this method will raise a `ValueError`.
+    fn insert_packed(&mut self, key: String, value: u32) -> PyResult<()> {
+        let b = self.builder_mut()?;
+        match b.insert_packed(&key, &PackedEntry::from_u32(value)) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(PyValueError::new_err(format!("Could not write key. {e}"))),
+        }
+    }
+
+    /// Register all lemmas used by `pada` and pack it into an integer code.
+    ///
+    /// The result can be passed to :meth:`insert_packed`, which is useful when inserting the
+    /// same entry under many keys.
+    fn pack(&mut self, pada: PyPadaEntry) -> PyResult<u32> {
+        let b = self.builder_mut()?;
+        let mut r = SmallRegistry::default();
+        let entry = r.to_pada_entry(&pada)?;
+
+        b.register_pada_entry(&entry);
+        match b.pack(&entry) {
+            Ok(packed) => Ok(packed.to_u32()),
+            Err(e) => Err(PyValueError::new_err(format!("Could not pack entry. {e}"))),
+        }
+    }
+
+    /// Register a paradigm of subantas that all use `pratipadika_entry`.
+    ///
+    /// `padas` is a list of (`text`, `linga`, `vibhakti`, `vacana`) tuples. This method returns
+    /// the prefix that all of these *pada*s share and a packed value for that prefix. To add the
+    /// paradigm to the kosha, pass both to :meth:`insert_packed`.
+    fn register_subanta_paradigm(
+        &mut self,
+        pratipadika_entry: PyPratipadikaEntry,
+        padas: Vec<(String, PyLinga, PyVibhakti, PyVacana)>,
+    ) -> PyResult<(String, u32)> {
+        if padas.is_empty() {
+            return Err(PyValueError::new_err(
+                "Paradigm must have at least one pada.",
+            ));
+        }
+        let b = self.builder_mut()?;
+        let mut r = SmallRegistry::default();
+        let entry = r.to_pratipadika_entry(&pratipadika_entry)?;
+        let padas: Vec<_> = padas
+            .into_iter()
+            .map(|(text, li, vi, va)| (text, li.into(), vi.into(), va.into()))
+            .collect();
+
+        match b.register_subanta_paradigm(&entry, &padas) {
+            Ok((key, value)) => Ok((key, value.to_u32())),
+            Err(e) => Err(PyValueError::new_err(format!(
+                "Could not register paradigm. {e}"
+            ))),
+        }
+    }
+
+    /// Register a paradigm of tinantas that all use `dhatu_entry`.
+    ///
+    /// `padas` is a list of (`text`, `prayoga`, `lakara`, `purusha`, `vacana`) tuples. This
+    /// method returns the prefix that all of these *pada*s share and a packed value for that
+    /// prefix. To add the paradigm to the kosha, pass both to :meth:`insert_packed`.
+    fn register_tinanta_paradigm(
+        &mut self,
+        dhatu_entry: PyDhatuEntry,
+        padas: Vec<(String, PyPrayoga, PyLakara, PyPurusha, PyVacana)>,
+    ) -> PyResult<(String, u32)> {
+        if padas.is_empty() {
+            return Err(PyValueError::new_err(
+                "Paradigm must have at least one pada.",
+            ));
+        }
+        let b = self.builder_mut()?;
+        let mut r = SmallRegistry::default();
+        let entry = r.to_dhatu_entry(&dhatu_entry);
+        let padas: Vec<_> = padas
+            .into_iter()
+            .map(|(text, pr, la, pu, va)| (text, pr.into(), la.into(), pu.into(), va.into()))
+            .collect();
+
+        match b.register_tinanta_paradigm(&entry, &padas) {
+            Ok((key, value)) => Ok((key, value.to_u32())),
+            Err(e) => Err(PyValueError::new_err(format!(
+                "Could not register paradigm. {e}"
+            ))),
+        }
+    }
+
     /// Complete the build process.
     ///
     /// If this method is not called, the output data will be invalid.
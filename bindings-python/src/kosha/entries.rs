@@ -1,8 +1,9 @@
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
 use crate::prakriya::args::{
-    PyDhatu, PyKrt, PyLakara, PyLinga, PyPada, PyPratipadika, PyPrayoga, PyPurusha, PyVacana,
-    PyVibhakti,
+    PyDhatu, PyKrt, PyLakara, PyLinga, PyPada, PyPratipadika, PyPrayoga, PyPurusha, PySamasaType,
+    PyTaddhita, PyTaddhitaArtha, PyVacana, PyVibhakti,
 };
 use vidyut_kosha::entries::*;
 use vidyut_prakriya::args as vp;
@@ -130,6 +131,23 @@ pub enum PyPratipadikaEntry {
         prayoga: Option<PyPrayoga>,
         lakara: Option<PyLakara>,
     },
+
+    /// A *taddhitānta*, where `pratipadika` is the base that receives the `taddhita`. `artha` is
+    /// the meaning condition of the `taddhita`, if known.
+    #[pyo3(constructor = (*, pratipadika, taddhita, artha = None))]
+    Taddhitanta {
+        pratipadika: PyPratipadika,
+        taddhita: PyTaddhita,
+        artha: Option<PyTaddhitaArtha>,
+    },
+
+    /// A *samāsa*, where `padas` are the *subanta*s that it combines, in order.
+    #[pyo3(constructor = (*, padas, samasa_type, stri = false))]
+    Samasa {
+        padas: Vec<PyPadaEntry>,
+        samasa_type: PySamasaType,
+        stri: bool,
+    },
 }
 
 #[pymethods]
@@ -171,17 +189,52 @@ impl PyPratipadikaEntry {
                     lakara.map(|x| x.__repr__()).unwrap_or(String::from("None")),
                 )
             }
+            Self::Taddhitanta {
+                pratipadika,
+                taddhita,
+                artha,
+            } => {
+                format!(
+                    "PratipadikaEntry.Taddhitanta(pratipadika={}, taddhita={}, artha={})",
+                    pratipadika.__repr__(),
+                    taddhita.__repr__(),
+                    artha.map(|x| x.__repr__()).unwrap_or(String::from("None")),
+                )
+            }
+            Self::Samasa {
+                padas,
+                samasa_type,
+                stri,
+            } => {
+                let padas: Vec<_> = padas.iter().map(|x| x.__repr__()).collect();
+                format!(
+                    "PratipadikaEntry.Samasa(padas=[{}], samasa_type={}, stri={})",
+                    padas.join(", "),
+                    samasa_type.__repr__(),
+                    if *stri { "True" } else { "False" },
+                )
+            }
         }
     }
 
     /// The lemma that corresponds to this *prātipadika*.
     ///
-    /// The lemma is either a *dhātu* or a simple *prātipadika*.
+    /// The lemma is either a *dhātu* or a simple *prātipadika*. For a *samāsa*, the lemma is
+    /// the lemma of its last member.
     #[getter]
     pub fn lemma(&self) -> String {
         match &self {
             Self::Basic { pratipadika, .. } => pratipadika.text.clone(),
             Self::Krdanta { dhatu_entry, .. } => dhatu_entry.clean_text.clone(),
+            Self::Taddhitanta { .. } | Self::Samasa { .. } => self
+                .to_prakriya_args()
+                .ok()
+                .and_then(|p| {
+                    PratipadikaEntry::try_from(p.as_ref())
+                        .map(|x| x.lemma().to_string())
+                        .ok()
+                })
+                .unwrap_or_default(),
         }
     }
 
@@ -197,6 +250,7 @@ impl PyPratipadikaEntry {
                 .iter()
                 .map(|x| (*x).into())
                 .collect(),
+            Self::Taddhitanta { .. } | Self::Samasa { .. } => Vec::new(),
         }
     }
 
@@ -205,13 +259,25 @@ impl PyPratipadikaEntry {
         match self {
             Self::Basic { pratipadika, .. } => pratipadika.is_avyaya(),
             Self::Krdanta { krt, .. } => vp::BaseKrt::from(*krt).is_avyaya(),
+            Self::Taddhitanta { .. } | Self::Samasa { .. } => self
+                .to_prakriya_args()
+                .ok()
+                .and_then(|p| {
+                    PratipadikaEntry::try_from(p.as_ref())
+                        .ok()
+                        .map(|x| x.is_avyaya())
+                })
+                .unwrap_or(false),
         }
     }
 
     /// Convert this entry to a :class:`~vidyut.prakriya.Pratipadika`.
-    pub fn to_prakriya_args(&self) -> PyPratipadika {
+    ///
+    /// Raises a `ValueError` if this entry is a *samāsa* with no *padas* or with a *pada* that is
+    /// not a *subanta*.
+    pub fn to_prakriya_args(&self) -> PyResult<PyPratipadika> {
         use PyPratipadikaEntry as PE;
-        match self {
+        let ret = match self {
             PE::Basic { pratipadika, .. } => pratipadika.clone(),
             PE::Krdanta {
                 dhatu_entry,
@@ -225,7 +291,30 @@ impl PyPratipadikaEntry {
                 lakara.clone(),
                 None,
             ),
-        }
+            PE::Taddhitanta {
+                pratipadika,
+                taddhita,
+                artha,
+            } => PyPratipadika::taddhitanta(pratipadika.clone(), *taddhita, *artha),
+            PE::Samasa {
+                padas,
+                samasa_type,
+                stri,
+            } => {
+                let padas = padas
+                    .iter()
+                    .map(|x| x.to_subanta())
+                    .collect::<PyResult<Vec<_>>>()?;
+                let samasa = vp::Samasa::builder()
+                    .padas(padas)
+                    .samasa_type((*samasa_type).into())
+                    .build()
+                    .map_err(|_| PyValueError::new_err("A samasa must have at least one pada."))?
+                    .with_stri(*stri);
+                vp::Pratipadika::from(samasa).into()
+            }
+        };
+        Ok(ret)
     }
 }
 
@@ -253,6 +342,16 @@ impl<'a> From<&PratipadikaEntry<'a>> for PyPratipadikaEntry {
                 prayoga: k.prayoga().map(|x| x.into()),
                 lakara: k.lakara().map(|x| x.into()),
             },
+            PratipadikaEntry::Taddhitanta(t) => PyPratipadikaEntry::Taddhitanta {
+                pratipadika: vp::Pratipadika::from(t.pratipadika_entry()).into(),
+                taddhita: t.taddhita().into(),
+                artha: t.artha().map(|x| x.into()),
+            },
+            PratipadikaEntry::Samasa(s) => PyPratipadikaEntry::Samasa {
+                padas: s.padas().iter().map(|x| x.into()).collect(),
+                samasa_type: s.samasa_type().into(),
+                stri: s.stri(),
+            },
         }
     }
 }
@@ -402,6 +501,31 @@ impl PyPadaEntry {
     }
 }
 
+impl PyPadaEntry {
+    /// Converts this entry to a *subanta*, e.g. for use in a *samāsa*.
+    fn to_subanta(&self) -> PyResult<vp::Subanta> {
+        match self {
+            Self::Subanta {
+                pratipadika_entry,
+                linga,
+                vibhakti,
+                vacana,
+            } => {
+                let pratipadika = pratipadika_entry.to_prakriya_args()?.as_ref().clone();
+                Ok(match (linga, vibhakti, vacana) {
+                    (Some(li), Some(vi), Some(va)) => {
+                        vp::Subanta::new(pratipadika, (*li).into(), (*vi).into(), (*va).into())
+                    }
+                    _ => vp::Subanta::avyaya(pratipadika),
+                })
+            }
+            Self::Tinanta { .. } => Err(PyValueError::new_err(
+                "Each pada in a samasa must be a subanta.",
+            )),
+        }
+    }
+}
+
 impl TryFrom<&PyPadaEntry> for PyPada {
     type Error = PyErr;
 
@@ -414,7 +538,7 @@ impl TryFrom<&PyPadaEntry> for PyPada {
                 vibhakti,
                 vacana,
             } => PyPada::Subanta {
-                pratipadika: pratipadika_entry.to_prakriya_args(),
+                pratipadika: pratipadika_entry.to_prakriya_args()?,
                 linga: *linga,
                 vibhakti: *vibhakti,
                 vacana: *vacana,
@@ -440,15 +564,21 @@ impl TryFrom<&PyPadaEntry> for PyPada {
     }
 }
 
+impl<'a> From<&SubantaEntry<'a>> for PyPadaEntry {
+    fn from(val: &SubantaEntry) -> PyPadaEntry {
+        PyPadaEntry::Subanta {
+            pratipadika_entry: val.pratipadika_entry().into(),
+            linga: Some(val.linga().into()),
+            vibhakti: Some(val.vibhakti().into()),
+            vacana: Some(val.vacana().into()),
+        }
+    }
+}
+
 impl<'a> From<&PadaEntry<'a>> for PyPadaEntry {
     fn from(val: &PadaEntry) -> PyPadaEntry {
         match val {
-            PadaEntry::Subanta(s) => PyPadaEntry::Subanta {
-                pratipadika_entry: s.pratipadika_entry().into(),
-                linga: Some(s.linga().into()),
-                vibhakti: Some(s.vibhakti().into()),
-                vacana: Some(s.vacana().into()),
-            },
+            PadaEntry::Subanta(s) => s.into(),
             PadaEntry::Tinanta(t) => PyPadaEntry::Tinanta {
                 dhatu_entry: t.dhatu_entry().into(),
                 prayoga: t.prayoga().into(),
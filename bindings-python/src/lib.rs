@@ -108,6 +108,8 @@ fn py_cheda(m: &Bound<'_, PyModule>) -> PyResult<()> {
 fn py_kosha(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<kosha::PyKosha>()?;
     m.add_class::<kosha::PyBuilder>()?;
+    m.add_class::<kosha::PySubantaSuffix>()?;
+    m.add_class::<kosha::PyTinantaSuffix>()?;
 
     m.add_class::<kosha::entries::PyDhatuEntry>()?;
     m.add_class::<kosha::entries::PyPratipadikaEntry>()?;
@@ -161,8 +163,10 @@ fn py_prakriya(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<prakriya::args::PyLinga>()?;
     m.add_class::<prakriya::args::PyPrayoga>()?;
     m.add_class::<prakriya::args::PyPurusha>()?;
+    m.add_class::<prakriya::args::PySamasaType>()?;
     m.add_class::<prakriya::args::PySanadi>()?;
     m.add_class::<prakriya::args::PyTaddhita>()?;
+    m.add_class::<prakriya::args::PyTaddhitaArtha>()?;
     m.add_class::<prakriya::args::PyUnadi>()?;
     m.add_class::<prakriya::args::PyVacana>()?;
     m.add_class::<prakriya::args::PyVibhakti>()?;
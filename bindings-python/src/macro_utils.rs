@@ -369,6 +369,34 @@ macro_rules! py_aupadeshika {
     }
 }
 
+/// Defines conversions between a Python enum and the Rust enum that it mirrors.
+///
+/// `py_enum` also defines these conversions, but it requires the Rust enum to have an `as_str`
+/// method. For enums without one, use `py_only_enum` and this macro instead.
+macro_rules! py_enum_conversions {
+    ($Py:ident, $Rust:ident, [$( $variant:ident ),* $(,)?]) => {
+        impl From<$Rust> for $Py {
+            fn from(val: $Rust) -> Self {
+                match val {
+                    $(
+                        $Rust::$variant => $Py::$variant,
+                    )*
+                }
+            }
+        }
+
+        impl From<$Py> for $Rust {
+            fn from(val: $Py) -> Self {
+                match val {
+                    $(
+                        $Py::$variant => $Rust::$variant,
+                    )*
+                }
+            }
+        }
+    }
+}
+
 macro_rules! py_only_enum {
     ($Py:ident, $Name:ident, { $( $variant:ident => $str:literal ),* } ) => {
         impl std::fmt::Display for $Py {
@@ -402,7 +430,7 @@ macro_rules! py_only_enum {
                 }
             }
 
-            fn __repr__(&self) -> String {
+            pub(crate) fn __repr__(&self) -> String {
                 format!("{}.{}", stringify!{$Name}, self.name())
             }
 
@@ -476,4 +504,5 @@ macro_rules! py_only_enum {
 
 pub(crate) use py_aupadeshika;
 pub(crate) use py_enum;
+pub(crate) use py_enum_conversions;
 pub(crate) use py_only_enum;
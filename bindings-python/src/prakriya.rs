@@ -509,7 +509,7 @@ impl PyVyakarana {
             Derivable::Pratipadika(p) => self.derive_pratipadikas(&p),
             Derivable::Pada(p) => self.derive_padas(p),
             Derivable::DhatuEntry(d) => self.derive_dhatus(&d.to_prakriya_args()),
-            Derivable::PratipadikaEntry(p) => self.derive_pratipadikas(&p.to_prakriya_args()),
+            Derivable::PratipadikaEntry(p) => self.derive_pratipadikas(&p.to_prakriya_args()?),
             Derivable::PadaEntry(p) => self.derive_padas(p.to_prakriya_args()?),
         };
         Ok(ret)
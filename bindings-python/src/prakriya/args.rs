@@ -2,7 +2,7 @@
 //!
 //! Pyo3 doesn't allow us to annotate existing enums, and using a wrapping struct has poor
 //! ergonomics for callers. So instead, redefine our enums of interest.
-use crate::macro_utils::{py_aupadeshika, py_enum};
+use crate::macro_utils::{py_aupadeshika, py_enum, py_enum_conversions, py_only_enum};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use vidyut_prakriya::args::{BaseKrt as Krt, Krt as RustKrt, *};
@@ -1119,7 +1119,7 @@ py_aupadeshika!(
 ///
 /// Each pratyaya name is written in the SLP1 encoding scheme.
 #[pyclass(name = "Taddhita", module = "prakriya", eq, eq_int, ord)]
-#[derive(Copy, Clone, Eq, Hash, PartialEq, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[allow(non_camel_case_types)]
 pub enum PyTaddhita {
     /// a
@@ -1493,6 +1493,636 @@ py_aupadeshika!(
     ]
 );
 
+/// The meaning condition of a *taddhita pratyaya*.
+///
+/// Generally, *taddhita*s are available only in specific senses. A given *taddhita* might be
+/// allowed in one sense but blocked in another.
+#[pyclass(name = "TaddhitaArtha", module = "prakriya", eq, eq_int, ord)]
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PyTaddhitaArtha {
+    /// Descendant. (4.1.92)
+    TasyaApatyam,
+    /// Patronymic lineage. (4.1.98)
+    Gotra,
+    /// A class. (4.1.161)
+    Jatau,
+    /// Country. (4.1.168)
+    Janapada,
+    /// One dyed by this. (4.2.1)
+    TenaRaktam,
+    /// Food that is prepared with this vessel or medium. (4.2.16)
+    SamskrtamBhaksha,
+    /// Having this as a god. (4.2.24)
+    SaAsyaDevata,
+    /// A collection of this. (4.2.37)
+    TasyaSamuha,
+    /// A domain of this. (4.2.52)
+    TasyaVishayoDeshe,
+    /// What one studies or knows. (4.2.59)
+    TadAdhiteTadVeda,
+    /// Country, by entities present (4.2.67), creator (4.2.68), dwelling place (4.2.69), or nearby
+    /// features (4.2.70).
+    Caturarthika,
+    /// Miscellaneous. (4.2.92)
+    Sheshe,
+    /// Where this was born. (4.3.25)
+    TatraJata,
+    /// Where this was made, obtained, bought, or is skillful (4.3.38)
+    TatraKrtaLabdhaKritaKushala,
+    /// Where this generally occurs. (4.3.39)
+    TatraPrayabhava,
+    /// Where this was adapted. (4.3.41)
+    TatraSambhute,
+    /// Where this stays or exists. (4.3.53)
+    TatraBhava,
+    /// Where one came from. (4.3.74)
+    TataAgata,
+    /// Dwelling. (4.3.89)
+    AsyaNivasa,
+    /// Devotion. (4.3.95)
+    Bhakti,
+    /// What is proclaimed by this person. (4.3.101)
+    TenaProktam,
+    /// By whom this is made. (4.3.116)
+    TenaKrte,
+    /// One who has this. (4.3.120)
+    TasyaIdam,
+    /// Modification of this. (4.3.134)
+    TasyaVikara,
+    /// One who gambles, conquers, or is conquered with this. (4.4.2)
+    TenaDivyatiJayatiJitam,
+    /// Cultured or processed. (4.4.3)
+    TenaSamskrtam,
+    /// One who crosses with this. (4.4.5)
+    TenaTarati,
+    /// One who walks or eats with this. (4.4.8)
+    TenaCarati,
+    /// One who lives with this. (4.4.12)
+    TenaJivati,
+    /// One who conveys with this. (4.4.15)
+    TenaHarati,
+    /// Completed with. (4.4.19)
+    TenaNirvrtte,
+    /// Mixed with. (4.4.22)
+    TenaSamsrshte,
+    /// Sprinkled with. (4.4.26)
+    TenaUpasikte,
+    /// Existence. (4.4.27)
+    Vartate,
+    /// One who gives in a contemptible way. (4.4.30)
+    PrayacchatiGarhyam,
+    /// What is gleaned. (4.4.32)
+    Unchati,
+    /// What is protected. (4.4.33)
+    TadRakshati,
+    /// Who does this. (4.4.34)
+    Karoti,
+    /// What one kills. (4.4.35)
+    Hanti,
+    /// What one takes. (4.4.39)
+    Grhnati,
+    /// What one practices. (4.4.41)
+    Carati,
+    /// Who goes. (4.4.42)
+    Eti,
+    /// Assembly. (4.4.43)
+    Samavaiti,
+    /// What one sees. (4.4.46)
+    Pashyati,
+    /// One who has this custom. (4.4.47)
+    TasyaDharmyam,
+    /// One who sells this item. (4.4.51)
+    TadAsyaPanyam,
+    /// One who has this weapon. (4.4.57)
+    Praharanam,
+    /// What is taxed. (4.4.50)
+    Avakraya,
+    /// One who has this art. (4.4.55)
+    Shilpam,
+    /// One who has this belief. (4.4.60)
+    Mati,
+    /// One who has this habit. (4.4.61)
+    Shilam,
+    /// One who has this act in their studies. (4.4.63)
+    KarmaAdhyayaneVrttam,
+    /// One for whom this is a good diet. (4.4.65)
+    HitamBhaksha,
+    /// To whom this is given rightfully. (4.4.66)
+    TadAsmaiDiyateNiyuktam,
+    /// Where one is appointed. (4.4.69)
+    Niyuktam,
+    /// Where one does work. (4.4.72)
+    Vyavaharati,
+    /// Who dwells here. (4.4.73)
+    Vasati,
+    /// What one bears. (4.4.78)
+    TadVahati,
+    /// What one pierces. (4.4.83)
+    TadVidhyati,
+    /// What one has obtained. (4.4.85)
+    Labdha,
+    /// Where one has gone. (4.4.86)
+    Gata,
+    /// In which this is seen. (4.4.87)
+    AsminDrshyam,
+    /// Whose root is removed. (4.4.88)
+    AsyaAbarhi,
+    /// Joined with this. (4.4.90)
+    Samyukta,
+    /// Not deviating from this. (4.4.92)
+    Anapeta,
+    /// Made of this. (4.4.93)
+    Nirmita,
+    /// Dear to this. (4.4.95)
+    Priya,
+    /// Skilled in this. (4.4.98)
+    TatraSadhu,
+    /// Living there. (4.4.107)
+    TatraVasi,
+    /// One for whom this is good. (5.1.7)
+    TasmaiHitam,
+    /// What could be created from this raw material. (5.1.12)
+    TadarthamVikrtehPrakrtau,
+    /// What one could be connected to or be in. (5.1.16)
+    TadAsyaTadAsminSyat,
+    /// What is bought with this. (5.1.37)
+    TenaKritam,
+    /// For the sake of this, when the meaning is a relation or appearance. (5.1.38)
+    TasyaNimittamSamyogotpattau,
+    /// Sown with this. (5.1.45)
+    TasyaVapa,
+    /// In which this is an interest, rent, profit, tax, or bribe. (5.1.47)
+    TadAsminVrddhiAyaLabhaSulkaUpada,
+    /// What one carries away, or conveys, or brings. (5.1.50)
+    TadDharatiVahatiAvahati,
+    /// What is capable of this, or holds this, or cooks this. (5.152)
+    SambhavatiAharatiPacati,
+    /// The measure of this. (5.1.57)
+    TadAsyaParimanam,
+    /// One deserves this. (5.1.63)
+    TadArhati,
+    /// One performs this. (5.1.72)
+    TadVartayati,
+    /// Fallen into this. (5.1.73)
+    Apanna,
+    /// Who goes. (5.1.75)
+    Gacchati,
+    /// Who deserves approach (5.1.74 vArttika)
+    AbhigamanamArhati,
+    /// What is conveyed by that way. (5.1.77)
+    Ahrtam,
+    /// Completed by this time. (5.1.79)
+    TenaNirvrttam,
+    /// For which time one instructs, is paid, existed, or will exist. (5.1.80)
+    TamAdhisteBhrtoBhutoBhavi,
+    /// Age. (5.1.81)
+    Vayasi,
+    /// The existence of which. (5.1.119)
+    TasyaBhava,
+    /// A place of growing, when that place is a field. (5.2.1)
+    DhanyanamBhavaneKshetre,
+    /// Wholly made of this. (5.2.5)
+    Krta,
+    /// Mirror. (5.2.6)
+    Darshana,
+    /// Pervades. (5.2.7)
+    Vyapnoti,
+    /// Obtains. (5.2.8)
+    Prapnoti,
+    /// Who witnesses this. (5.2.10)
+    TadAnubhavati,
+    /// Who intends to go. (5.2.11)
+    Gami,
+    /// Fit to go. (5.2.15)
+    AlamGami,
+    /// Formerly this. (5.2.18)
+    BhutaPurva,
+    /// What can be traveled in one day. (5.2.19)
+    EkahaGama,
+    /// The ripening season of this. (5.2.24)
+    TasyaPakamula,
+    /// The root of this. (5.2.25)
+    TasyaMula,
+    /// Celebrated through this. (5.2.26)
+    TenaVitta,
+    /// Of which this is observed. (5.2.36)
+    TadAsyaSamjatam,
+    /// Measure. (5.2.37)
+    TadAsyaPramanam,
+    /// Volume. (5.2.39)
+    Parimana,
+    /// Parts of which. (5.2.42)
+    Avasana,
+    /// Given in exchange. (5.2.47)
+    Nimana,
+    /// Making full. (5.2.48)
+    Purana,
+    /// Skilled in this. (5.2.63)
+    TatraKushala,
+    /// A desire for which. (5.2.65)
+    TatraKama,
+    /// Voracious. (5.2.67)
+    TatraAdyuna,
+    /// Supplied richly with. (5.2.68)
+    TatraParijata,
+    /// Who must take this.. (5.2.69)
+    Hari,
+    /// Taken recently. (5.2.70)
+    AciraApahrta,
+    /// Going to work in this manner. (5.2.72)
+    Karin,
+    /// Who strives to gain by this. (5.2.75)
+    Anvicchati,
+    /// What one has or is in. (5.2.94)
+    TadAsyaAstiAsmin,
+    /// Words meaning direction, location, or time. (5.3.27)
+    DigDeshaKala,
+    /// Not known. (5.3.73)
+    Ajnate,
+    /// Contempt. (5.3.75)
+    Kutsite,
+    /// TODO
+    Anukampayam,
+    /// Slenderness. (5.3.91)
+    Tanutve,
+    /// One of two. (5.3.92)
+    DvayorEka,
+    /// One of many. (5.3.93)
+    BahunamEka,
+    /// Derision. (5.3.95)
+    Avakshepane,
+    /// TODO
+    Alpe,
+    /// TODO
+    Hrasve,
+    /// TODO
+    IvePratikrtau,
+    /// Those who make a living by arms. (5.3.114)
+    AyudhaJiviSangha,
+    /// TODO
+    AnatyantaGati,
+    /// TODO
+    Acchadana,
+    /// TODO
+    Svarthe,
+    /// TODO
+    Matsye,
+    /// TODO
+    KriyaAbhyavrttiGanana,
+    /// Expressing manner. (5.4.3)
+    PrakaraVacane,
+    /// What one is made of. (5.4.21)
+    TatPrakrtaVacane,
+    /// For the sake of which. (5.4.24)
+    Tadarthye,
+    /// Praise of this. (5.3.66, 5.4.41)
+    Prashamsa,
+    /// Becoming what one was not. (5.4.50)
+    AbhutaTadbhava,
+}
+
+py_only_enum!(
+    PyTaddhitaArtha,
+    TaddhitaArtha,
+    {
+        TasyaApatyam => "TasyaApatyam",
+        Gotra => "Gotra",
+        Jatau => "Jatau",
+        Janapada => "Janapada",
+        TenaRaktam => "TenaRaktam",
+        SamskrtamBhaksha => "SamskrtamBhaksha",
+        SaAsyaDevata => "SaAsyaDevata",
+        TasyaSamuha => "TasyaSamuha",
+        TasyaVishayoDeshe => "TasyaVishayoDeshe",
+        TadAdhiteTadVeda => "TadAdhiteTadVeda",
+        Caturarthika => "Caturarthika",
+        Sheshe => "Sheshe",
+        TatraJata => "TatraJata",
+        TatraKrtaLabdhaKritaKushala => "TatraKrtaLabdhaKritaKushala",
+        TatraPrayabhava => "TatraPrayabhava",
+        TatraSambhute => "TatraSambhute",
+        TatraBhava => "TatraBhava",
+        TataAgata => "TataAgata",
+        AsyaNivasa => "AsyaNivasa",
+        Bhakti => "Bhakti",
+        TenaProktam => "TenaProktam",
+        TenaKrte => "TenaKrte",
+        TasyaIdam => "TasyaIdam",
+        TasyaVikara => "TasyaVikara",
+        TenaDivyatiJayatiJitam => "TenaDivyatiJayatiJitam",
+        TenaSamskrtam => "TenaSamskrtam",
+        TenaTarati => "TenaTarati",
+        TenaCarati => "TenaCarati",
+        TenaJivati => "TenaJivati",
+        TenaHarati => "TenaHarati",
+        TenaNirvrtte => "TenaNirvrtte",
+        TenaSamsrshte => "TenaSamsrshte",
+        TenaUpasikte => "TenaUpasikte",
+        Vartate => "Vartate",
+        PrayacchatiGarhyam => "PrayacchatiGarhyam",
+        Unchati => "Unchati",
+        TadRakshati => "TadRakshati",
+        Karoti => "Karoti",
+        Hanti => "Hanti",
+        Grhnati => "Grhnati",
+        Carati => "Carati",
+        Eti => "Eti",
+        Samavaiti => "Samavaiti",
+        Pashyati => "Pashyati",
+        TasyaDharmyam => "TasyaDharmyam",
+        TadAsyaPanyam => "TadAsyaPanyam",
+        Praharanam => "Praharanam",
+        Avakraya => "Avakraya",
+        Shilpam => "Shilpam",
+        Mati => "Mati",
+        Shilam => "Shilam",
+        KarmaAdhyayaneVrttam => "KarmaAdhyayaneVrttam",
+        HitamBhaksha => "HitamBhaksha",
+        TadAsmaiDiyateNiyuktam => "TadAsmaiDiyateNiyuktam",
+        Niyuktam => "Niyuktam",
+        Vyavaharati => "Vyavaharati",
+        Vasati => "Vasati",
+        TadVahati => "TadVahati",
+        TadVidhyati => "TadVidhyati",
+        Labdha => "Labdha",
+        Gata => "Gata",
+        AsminDrshyam => "AsminDrshyam",
+        AsyaAbarhi => "AsyaAbarhi",
+        Samyukta => "Samyukta",
+        Anapeta => "Anapeta",
+        Nirmita => "Nirmita",
+        Priya => "Priya",
+        TatraSadhu => "TatraSadhu",
+        TatraVasi => "TatraVasi",
+        TasmaiHitam => "TasmaiHitam",
+        TadarthamVikrtehPrakrtau => "TadarthamVikrtehPrakrtau",
+        TadAsyaTadAsminSyat => "TadAsyaTadAsminSyat",
+        TenaKritam => "TenaKritam",
+        TasyaNimittamSamyogotpattau => "TasyaNimittamSamyogotpattau",
+        TasyaVapa => "TasyaVapa",
+        TadAsminVrddhiAyaLabhaSulkaUpada => "TadAsminVrddhiAyaLabhaSulkaUpada",
+        TadDharatiVahatiAvahati => "TadDharatiVahatiAvahati",
+        SambhavatiAharatiPacati => "SambhavatiAharatiPacati",
+        TadAsyaParimanam => "TadAsyaParimanam",
+        TadArhati => "TadArhati",
+        TadVartayati => "TadVartayati",
+        Apanna => "Apanna",
+        Gacchati => "Gacchati",
+        AbhigamanamArhati => "AbhigamanamArhati",
+        Ahrtam => "Ahrtam",
+        TenaNirvrttam => "TenaNirvrttam",
+        TamAdhisteBhrtoBhutoBhavi => "TamAdhisteBhrtoBhutoBhavi",
+        Vayasi => "Vayasi",
+        TasyaBhava => "TasyaBhava",
+        DhanyanamBhavaneKshetre => "DhanyanamBhavaneKshetre",
+        Krta => "Krta",
+        Darshana => "Darshana",
+        Vyapnoti => "Vyapnoti",
+        Prapnoti => "Prapnoti",
+        TadAnubhavati => "TadAnubhavati",
+        Gami => "Gami",
+        AlamGami => "AlamGami",
+        BhutaPurva => "BhutaPurva",
+        EkahaGama => "EkahaGama",
+        TasyaPakamula => "TasyaPakamula",
+        TasyaMula => "TasyaMula",
+        TenaVitta => "TenaVitta",
+        TadAsyaSamjatam => "TadAsyaSamjatam",
+        TadAsyaPramanam => "TadAsyaPramanam",
+        Parimana => "Parimana",
+        Avasana => "Avasana",
+        Nimana => "Nimana",
+        Purana => "Purana",
+        TatraKushala => "TatraKushala",
+        TatraKama => "TatraKama",
+        TatraAdyuna => "TatraAdyuna",
+        TatraParijata => "TatraParijata",
+        Hari => "Hari",
+        AciraApahrta => "AciraApahrta",
+        Karin => "Karin",
+        Anvicchati => "Anvicchati",
+        TadAsyaAstiAsmin => "TadAsyaAstiAsmin",
+        DigDeshaKala => "DigDeshaKala",
+        Ajnate => "Ajnate",
+        Kutsite => "Kutsite",
+        Anukampayam => "Anukampayam",
+        Tanutve => "Tanutve",
+        DvayorEka => "DvayorEka",
+        BahunamEka => "BahunamEka",
+        Avakshepane => "Avakshepane",
+        Alpe => "Alpe",
+        Hrasve => "Hrasve",
+        IvePratikrtau => "IvePratikrtau",
+        AyudhaJiviSangha => "AyudhaJiviSangha",
+        AnatyantaGati => "AnatyantaGati",
+        Acchadana => "Acchadana",
+        Svarthe => "Svarthe",
+        Matsye => "Matsye",
+        KriyaAbhyavrttiGanana => "KriyaAbhyavrttiGanana",
+        PrakaraVacane => "PrakaraVacane",
+        TatPrakrtaVacane => "TatPrakrtaVacane",
+        Tadarthye => "Tadarthye",
+        Prashamsa => "Prashamsa",
+        AbhutaTadbhava => "AbhutaTadbhava"
+    }
+);
+py_enum_conversions!(
+    PyTaddhitaArtha,
+    TaddhitaArtha,
+    [
+        TasyaApatyam,
+        Gotra,
+        Jatau,
+        Janapada,
+        TenaRaktam,
+        SamskrtamBhaksha,
+        SaAsyaDevata,
+        TasyaSamuha,
+        TasyaVishayoDeshe,
+        TadAdhiteTadVeda,
+        Caturarthika,
+        Sheshe,
+        TatraJata,
+        TatraKrtaLabdhaKritaKushala,
+        TatraPrayabhava,
+        TatraSambhute,
+        TatraBhava,
+        TataAgata,
+        AsyaNivasa,
+        Bhakti,
+        TenaProktam,
+        TenaKrte,
+        TasyaIdam,
+        TasyaVikara,
+        TenaDivyatiJayatiJitam,
+        TenaSamskrtam,
+        TenaTarati,
+        TenaCarati,
+        TenaJivati,
+        TenaHarati,
+        TenaNirvrtte,
+        TenaSamsrshte,
+        TenaUpasikte,
+        Vartate,
+        PrayacchatiGarhyam,
+        Unchati,
+        TadRakshati,
+        Karoti,
+        Hanti,
+        Grhnati,
+        Carati,
+        Eti,
+        Samavaiti,
+        Pashyati,
+        TasyaDharmyam,
+        TadAsyaPanyam,
+        Praharanam,
+        Avakraya,
+        Shilpam,
+        Mati,
+        Shilam,
+        KarmaAdhyayaneVrttam,
+        HitamBhaksha,
+        TadAsmaiDiyateNiyuktam,
+        Niyuktam,
+        Vyavaharati,
+        Vasati,
+        TadVahati,
+        TadVidhyati,
+        Labdha,
+        Gata,
+        AsminDrshyam,
+        AsyaAbarhi,
+        Samyukta,
+        Anapeta,
+        Nirmita,
+        Priya,
+        TatraSadhu,
+        TatraVasi,
+        TasmaiHitam,
+        TadarthamVikrtehPrakrtau,
+        TadAsyaTadAsminSyat,
+        TenaKritam,
+        TasyaNimittamSamyogotpattau,
+        TasyaVapa,
+        TadAsminVrddhiAyaLabhaSulkaUpada,
+        TadDharatiVahatiAvahati,
+        SambhavatiAharatiPacati,
+        TadAsyaParimanam,
+        TadArhati,
+        TadVartayati,
+        Apanna,
+        Gacchati,
+        AbhigamanamArhati,
+        Ahrtam,
+        TenaNirvrttam,
+        TamAdhisteBhrtoBhutoBhavi,
+        Vayasi,
+        TasyaBhava,
+        DhanyanamBhavaneKshetre,
+        Krta,
+        Darshana,
+        Vyapnoti,
+        Prapnoti,
+        TadAnubhavati,
+        Gami,
+        AlamGami,
+        BhutaPurva,
+        EkahaGama,
+        TasyaPakamula,
+        TasyaMula,
+        TenaVitta,
+        TadAsyaSamjatam,
+        TadAsyaPramanam,
+        Parimana,
+        Avasana,
+        Nimana,
+        Purana,
+        TatraKushala,
+        TatraKama,
+        TatraAdyuna,
+        TatraParijata,
+        Hari,
+        AciraApahrta,
+        Karin,
+        Anvicchati,
+        TadAsyaAstiAsmin,
+        DigDeshaKala,
+        Ajnate,
+        Kutsite,
+        Anukampayam,
+        Tanutve,
+        DvayorEka,
+        BahunamEka,
+        Avakshepane,
+        Alpe,
+        Hrasve,
+        IvePratikrtau,
+        AyudhaJiviSangha,
+        AnatyantaGati,
+        Acchadana,
+        Svarthe,
+        Matsye,
+        KriyaAbhyavrttiGanana,
+        PrakaraVacane,
+        TatPrakrtaVacane,
+        Tadarthye,
+        Prashamsa,
+        AbhutaTadbhava,
+    ]
+);
+
+/// A *samāsa* type.
+#[pyclass(name = "SamasaType", module = "prakriya", eq, eq_int, ord)]
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum PySamasaType {
+    /// 2.1.5
+    Avyayibhava,
+    /// 2.1.22
+    Tatpurusha,
+    /// 1.2.42
+    Karmadharaya,
+    /// 2.1.52
+    Dvigu,
+    /// 2.2.1
+    Ekadeshin,
+    /// 2.2.23
+    Bahuvrihi,
+    /// 2.2.29
+    Dvandva,
+    /// 2.2.29 (not defined explicitly)
+    SamaharaDvandva,
+}
+
+py_only_enum!(
+    PySamasaType,
+    SamasaType,
+    {
+        Avyayibhava => "avyayIBAvaH",
+        Tatpurusha => "tatpuruzaH",
+        Karmadharaya => "karmaDArayaH",
+        Dvigu => "dviguH",
+        Ekadeshin => "ekadeSI",
+        Bahuvrihi => "bahuvrIhiH",
+        Dvandva => "dvandvaH",
+        SamaharaDvandva => "samAhAradvandvaH"
+    }
+);
+py_enum_conversions!(
+    PySamasaType,
+    SamasaType,
+    [
+        Avyayibhava,
+        Tatpurusha,
+        Karmadharaya,
+        Dvigu,
+        Ekadeshin,
+        Bahuvrihi,
+        Dvandva,
+        SamaharaDvandva,
+    ]
+);
+
 /// The lakara to use in the derivation.
 #[pyclass(name = "Lakara", module = "prakriya", eq, eq_int, ord)]
 #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
@@ -1972,10 +2602,23 @@ impl PyPratipadika {
     }
 
     /// Create a new pratipadika that is a taddhitanta.
+    ///
+    /// If `artha` is set, the taddhita must be allowed in that meaning.
     #[staticmethod]
-    #[pyo3(signature = (pratipadika, taddhita))]
-    pub fn taddhitanta(pratipadika: PyPratipadika, taddhita: PyTaddhita) -> Self {
-        let taddhitanta = Taddhitanta::new(pratipadika.pratipadika, taddhita.into());
+    #[pyo3(signature = (pratipadika, taddhita, artha=None))]
+    pub fn taddhitanta(
+        pratipadika: PyPratipadika,
+        taddhita: PyTaddhita,
+        artha: Option<PyTaddhitaArtha>,
+    ) -> Self {
+        let mut builder = Taddhitanta::builder();
+        builder
+            .pratipadika(pratipadika.pratipadika)
+            .taddhita(taddhita.into());
+        if let Some(artha) = artha {
+            builder.artha(artha.into());
+        }
+        let taddhitanta = builder.build().expect("required fields are set");
         Self {
             pratipadika: Pratipadika::Taddhitanta(taddhitanta.into()),
             text: "".to_string(),
@@ -1,3 +1,5 @@
+import pytest
+
 from vidyut.kosha import DhatuEntry, PratipadikaEntry, PadaEntry
 from vidyut.prakriya import (
     Vyakarana,
@@ -11,6 +13,9 @@ from vidyut.prakriya import (
     Prayoga,
     Purusha,
     Lakara,
+    SamasaType,
+    Taddhita,
+    TaddhitaArtha,
 )
 
 
@@ -78,6 +83,71 @@ def test_pratipadika_entry__krdanta():
     assert results == {"gata"}
 
 
+def test_pratipadika_entry__taddhitanta():
+    dasharatha = Pratipadika.basic("daSaraTa")
+    dasharathi = PratipadikaEntry.Taddhitanta(
+        pratipadika=dasharatha, taddhita=Taddhita.iY
+    )
+    assert dasharathi.pratipadika == dasharatha
+    assert dasharathi.taddhita == Taddhita.iY
+    assert dasharathi.lemma == "daSaraTa"
+    assert dasharathi.lingas == []
+    assert not dasharathi.is_avyaya
+
+    sarvatra = PratipadikaEntry.Taddhitanta(
+        pratipadika=Pratipadika.basic("sarva"), taddhita=Taddhita.tral
+    )
+    assert sarvatra.is_avyaya
+
+    assert dasharathi.artha is None
+    dasharathi = PratipadikaEntry.Taddhitanta(
+        pratipadika=dasharatha,
+        taddhita=Taddhita.iY,
+        artha=TaddhitaArtha.TasyaApatyam,
+    )
+    assert dasharathi.artha == TaddhitaArtha.TasyaApatyam
+    assert "artha=TaddhitaArtha.TasyaApatyam" in repr(dasharathi)
+
+    v = Vyakarana()
+    results = {p.text for p in v.derive(dasharathi)}
+    assert results == {"dASaraTi"}
+
+
+def test_pratipadika_entry__samasa():
+    def subanta(text, vibhakti):
+        return PadaEntry.Subanta(
+            pratipadika_entry=PratipadikaEntry.Basic(
+                pratipadika=Pratipadika.basic(text), lingas=[Linga.Pum]
+            ),
+            linga=Linga.Pum,
+            vibhakti=vibhakti,
+            vacana=Vacana.Eka,
+        )
+
+    rajan = subanta("rAjan", Vibhakti.Sasthi)
+    purusha = subanta("puruza", Vibhakti.Prathama)
+    rajapurusha = PratipadikaEntry.Samasa(
+        padas=[rajan, purusha], samasa_type=SamasaType.Tatpurusha
+    )
+    assert rajapurusha.padas == [rajan, purusha]
+    assert rajapurusha.samasa_type == SamasaType.Tatpurusha
+    assert not rajapurusha.stri
+    assert rajapurusha.lemma == "puruza"
+    assert not rajapurusha.is_avyaya
+    assert "samasa_type=SamasaType.Tatpurusha" in repr(rajapurusha)
+
+    gacchati = PadaEntry.Tinanta(
+        dhatu_entry=DhatuEntry(dhatu=Dhatu.mula("ga\\mx~", Gana.Bhvadi), clean_text="gam"),
+        prayoga=Prayoga.Kartari,
+        lakara=Lakara.Lat,
+        purusha=Purusha.Prathama,
+        vacana=Vacana.Eka,
+    )
+    invalid = PratipadikaEntry.Samasa(padas=[gacchati], samasa_type=SamasaType.Tatpurusha)
+    with pytest.raises(ValueError):
+        invalid.to_prakriya_args()
+
+
 def test_pratipadika_entry__dunders():
     rama = Pratipadika.basic("rAma")
     rama_entry = PratipadikaEntry.Basic(pratipadika=rama, lingas=[Linga.Pum])
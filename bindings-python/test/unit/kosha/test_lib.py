@@ -23,6 +23,9 @@ from vidyut.prakriya import (
     Linga,
     Vibhakti,
     Dhatu,
+    SamasaType,
+    Taddhita,
+    TaddhitaArtha,
 )
 
 
@@ -148,3 +151,207 @@ def test_pratipadikas(kosha):
     items = [d for d in kosha.pratipadikas()]
     assert len(items) == 1
     assert items[0] == pratipadika_entry
+
+
+@pytest.fixture(scope="session")
+def paradigm_kosha():
+    ca = PadaEntry.Subanta(
+        pratipadika_entry=PratipadikaEntry.Basic(
+            pratipadika=Pratipadika.basic("ca", is_avyaya=True), lingas=[]
+        )
+    )
+    deva_entry = PratipadikaEntry.Basic(
+        pratipadika=Pratipadika.basic("deva"), lingas=[Linga.Pum]
+    )
+    gam_entry = DhatuEntry(dhatu=Dhatu.mula("ga\\mx", Gana.Bhvadi), clean_text="gam")
+
+    with tempfile.TemporaryDirectory() as tempdir:
+        b = Builder(tempdir)
+        b.insert_packed("ca", b.pack(ca))
+
+        key, value = b.register_subanta_paradigm(
+            deva_entry,
+            [
+                ("devaH", Linga.Pum, Vibhakti.Prathama, Vacana.Eka),
+                ("devam", Linga.Pum, Vibhakti.Dvitiya, Vacana.Eka),
+            ],
+        )
+        assert key == "deva"
+        b.insert_packed(key, value)
+
+        key, value = b.register_tinanta_paradigm(
+            gam_entry,
+            [
+                ("gacCati", Prayoga.Kartari, Lakara.Lat, Purusha.Prathama, Vacana.Eka),
+                ("gacCataH", Prayoga.Kartari, Lakara.Lat, Purusha.Prathama, Vacana.Dvi),
+            ],
+        )
+        assert key == "gacCat"
+        b.insert_packed(key, value)
+        b.finish()
+
+        return Kosha(tempdir)
+
+
+def test_paradigms(paradigm_kosha):
+    kosha = paradigm_kosha
+    assert len(kosha) == 5
+    assert "devam" in kosha
+    assert "gacCataH" in kosha
+
+    [devam] = kosha["devam"]
+    assert devam.lemma == "deva"
+    assert devam.vibhakti == Vibhakti.Dvitiya
+
+    [gacchatah] = kosha["gacCataH"]
+    assert gacchatah.lemma == "gam"
+    assert gacchatah.vacana == Vacana.Dvi
+
+
+def test_paradigms_with_empty_padas(tmp_path):
+    gam_entry = DhatuEntry(dhatu=Dhatu.mula("ga\\mx", Gana.Bhvadi), clean_text="gam")
+    b = Builder(tmp_path)
+    with pytest.raises(ValueError):
+        b.register_tinanta_paradigm(gam_entry, [])
+
+
+def test_paradigms_with_shorter_pada(tmp_path):
+    deva_entry = PratipadikaEntry.Basic(
+        pratipadika=Pratipadika.basic("deva"), lingas=[Linga.Pum]
+    )
+    b = Builder(tmp_path)
+    key, value = b.register_subanta_paradigm(
+        deva_entry,
+        [
+            ("devas", Linga.Pum, Vibhakti.Prathama, Vacana.Eka),
+            ("deva", Linga.Pum, Vibhakti.Sambodhana, Vacana.Eka),
+        ],
+    )
+    assert key == "deva"
+    b.insert_packed(key, value)
+    b.finish()
+
+    kosha = Kosha(tmp_path)
+    assert [e.vibhakti for e in kosha["deva"]] == [Vibhakti.Sambodhana]
+    assert [e.vibhakti for e in kosha["devas"]] == [Vibhakti.Prathama]
+
+
+def test_insert_packed_out_of_order(tmp_path):
+    ca = PadaEntry.Subanta(
+        pratipadika_entry=PratipadikaEntry.Basic(
+            pratipadika=Pratipadika.basic("ca", is_avyaya=True), lingas=[]
+        )
+    )
+    b = Builder(tmp_path)
+    value = b.pack(ca)
+    b.insert_packed("ca", value)
+    with pytest.raises(ValueError):
+        b.insert_packed("a", value)
+
+
+def test_items(paradigm_kosha):
+    items = list(paradigm_kosha.items())
+    assert [k for k, _ in items] == ["ca", "devaH", "devam", "gacCati", "gacCataH"]
+    assert items[0][1].is_avyaya
+    assert all(paradigm_kosha.get(k)[0] == v for k, v in items)
+
+
+def test_items_with_many_keys(tmp_path):
+    ca = PadaEntry.Subanta(
+        pratipadika_entry=PratipadikaEntry.Basic(
+            pratipadika=Pratipadika.basic("ca", is_avyaya=True), lingas=[]
+        )
+    )
+    letters = "abcdefghij"
+    keys = [f"ca{x}{y}{z}" for x in letters for y in letters for z in letters]
+
+    b = Builder(tmp_path)
+    value = b.pack(ca)
+    for key in keys:
+        b.insert_packed(key, value)
+    b.finish()
+
+    kosha = Kosha(tmp_path)
+    assert [k for k, _ in kosha.items()] == keys
+
+
+def test_items_with_duplicates(kosha):
+    items = list(kosha.items())
+    assert [k for k, _ in items] == ["gacCati", "gacCati", "icCati"]
+    assert [v for k, v in items[:2]] == kosha.get("gacCati")
+
+
+def test_subanta_suffixes(paradigm_kosha):
+    [table] = paradigm_kosha.subanta_suffixes()
+    assert [s.text for s in table] == ["H", "m"]
+    assert table[1].linga == Linga.Pum
+    assert table[1].vibhakti == Vibhakti.Dvitiya
+    assert table[1].vacana == Vacana.Eka
+
+
+def test_tinanta_suffixes(paradigm_kosha):
+    [table] = paradigm_kosha.tinanta_suffixes()
+    assert [s.text for s in table] == ["i", "aH"]
+    assert table[1].prayoga == Prayoga.Kartari
+    assert table[1].lakara == Lakara.Lat
+    assert table[1].purusha == Purusha.Prathama
+    assert table[1].vacana == Vacana.Dvi
+
+
+def test_pack_and_unpack(kosha):
+    for _, entry in kosha.items():
+        value = kosha.pack(entry)
+        assert isinstance(value, int)
+        assert kosha.unpack(value) == entry
+
+
+def test_pack_missing_lemma(kosha):
+    bhu_entry = DhatuEntry(dhatu=Dhatu.mula("BU", Gana.Bhvadi), clean_text="BU")
+    bhavati = PadaEntry.Tinanta(
+        dhatu_entry=bhu_entry,
+        prayoga=Prayoga.Kartari,
+        lakara=Lakara.Lat,
+        purusha=Purusha.Prathama,
+        vacana=Vacana.Eka,
+    )
+    with pytest.raises(ValueError):
+        kosha.pack(bhavati)
+
+
+def test_taddhitantas_and_samasas(tmp_path):
+    def subanta(pratipadika_entry, vibhakti):
+        return PadaEntry.Subanta(
+            pratipadika_entry=pratipadika_entry,
+            linga=Linga.Pum,
+            vibhakti=vibhakti,
+            vacana=Vacana.Eka,
+        )
+
+    def basic(text):
+        return PratipadikaEntry.Basic(pratipadika=Pratipadika.basic(text), lingas=[])
+
+    dasharathi = PratipadikaEntry.Taddhitanta(
+        pratipadika=Pratipadika.basic("daSaraTa"),
+        taddhita=Taddhita.iY,
+        artha=TaddhitaArtha.TasyaApatyam,
+    )
+    rajapurusha = PratipadikaEntry.Samasa(
+        padas=[
+            subanta(basic("rAjan"), Vibhakti.Sasthi),
+            subanta(basic("puruza"), Vibhakti.Prathama),
+        ],
+        samasa_type=SamasaType.Tatpurusha,
+    )
+    dasharathih = subanta(dasharathi, Vibhakti.Prathama)
+    rajapurushah = subanta(rajapurusha, Vibhakti.Prathama)
+
+    b = Builder(tmp_path)
+    b.insert("dASaraTiH", dasharathih)
+    b.insert("rAjapuruzaH", rajapurushah)
+    b.finish()
+
+    k = Kosha(tmp_path)
+    assert k.get("dASaraTiH") == [dasharathih]
+    assert k.get("rAjapuruzaH") == [rajapurushah]
+    assert k.get("dASaraTiH")[0].pratipadika_entry.artha == TaddhitaArtha.TasyaApatyam
+    assert k.get("rAjapuruzaH")[0].pratipadika_entry.padas[0].lemma == "rAjan"
@@ -8,3 +8,6 @@ Kosha = __mod.Kosha
 DhatuEntry = __mod.DhatuEntry
 PratipadikaEntry = __mod.PratipadikaEntry
 PadaEntry = __mod.PadaEntry
+
+SubantaSuffix = __mod.SubantaSuffix
+TinantaSuffix = __mod.TinantaSuffix
@@ -51,10 +51,41 @@ class Pada:
     def make_avyaya(*, pratipadika: Pratipadika) -> Pada:
         pass
 
+@dataclass
+class SubantaSuffix:
+    text: str
+    linga: Linga
+    vibhakti: Vibhakti
+    vacana: Vacana
+
+@dataclass
+class TinantaSuffix:
+    text: str
+    prayoga: Prayoga
+    lakara: Lakara
+    purusha: Purusha
+    vacana: Vacana
+
 class Builder:
     def __init__(self, path: Path | str):
         pass
-    def insert(self, key: str, pada: Pada):
+    def insert(self, key: str, pada: PadaEntry):
+        pass
+    def insert_packed(self, key: str, value: int):
+        pass
+    def pack(self, pada: PadaEntry) -> int:
+        pass
+    def register_subanta_paradigm(
+        self,
+        pratipadika_entry: PratipadikaEntry,
+        padas: List[Tuple[str, Linga, Vibhakti, Vacana]],
+    ) -> Tuple[str, int]:
+        pass
+    def register_tinanta_paradigm(
+        self,
+        dhatu_entry: DhatuEntry,
+        padas: List[Tuple[str, Prayoga, Lakara, Purusha, Vacana]],
+    ) -> Tuple[str, int]:
         pass
     def finish(self):
         pass
@@ -64,7 +95,25 @@ class Kosha:
         pass
     def __contains__(self, key: str) -> bool:
         pass
+    def __getitem__(self, key: str) -> List[PadaEntry]:
+        pass
+    def __len__(self) -> int:
+        pass
     def contains_prefix(self, key: str) -> bool:
         pass
     def get(self, key: str) -> List[PadaEntry]:
         pass
+    def dhatus(self) -> Iterator[DhatuEntry]:
+        pass
+    def pratipadikas(self) -> Iterator[PratipadikaEntry]:
+        pass
+    def items(self) -> Iterator[Tuple[str, PadaEntry]]:
+        pass
+    def subanta_suffixes(self) -> List[List[SubantaSuffix]]:
+        pass
+    def tinanta_suffixes(self) -> List[List[TinantaSuffix]]:
+        pass
+    def pack(self, pada: PadaEntry) -> int:
+        pass
+    def unpack(self, value: int) -> PadaEntry:
+        pass
@@ -25,9 +25,11 @@ Linga = __mod.Linga
 Pada = __mod.Pada
 Prayoga = __mod.Prayoga
 Purusha = __mod.Purusha
+SamasaType = __mod.SamasaType
 Unadi = __mod.Unadi
 Sanadi = __mod.Sanadi
 Taddhita = __mod.Taddhita
+TaddhitaArtha = __mod.TaddhitaArtha
 Vacana = __mod.Vacana
 Vibhakti = __mod.Vibhakti
 
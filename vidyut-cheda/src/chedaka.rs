@@ -1,15 +1,19 @@
 use crate::errors::Result;
-use crate::normalize_text;
-use crate::scoring::{Model, POSTag};
+use crate::input::Input;
+use crate::lattice::Lattice;
+use crate::samasa;
+use crate::scoring::{self, Context, History, Model, POSTag, Scorer};
 use crate::sounds;
 use crate::strict_mode;
 use compact_str::CompactString;
 use priority_queue::PriorityQueue;
 use rustc_hash::FxHashMap;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use vidyut_kosha::entries::PadaEntry;
 use vidyut_kosha::Kosha;
-use vidyut_sandhi::{Split, Splitter};
+use vidyut_lipi::Scheme;
+use vidyut_sandhi::{Kind, Split, Splitter};
 
 /// A Sanskrit word and its data.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -18,14 +22,50 @@ pub struct Token<'a> {
     pub(crate) text: CompactString,
     /// The data associated with this word.
     pub(crate) data: Option<PadaEntry<'a>>,
+    /// The byte span of the original input that this word came from, if known.
+    pub(crate) span: Option<Range<usize>>,
+    /// The text of the original input that this word came from, if known.
+    pub(crate) surface: Option<CompactString>,
+    /// The sandhi change that joins this word to the text after it, if known.
+    pub(crate) sandhi: Option<Sandhi>,
+    /// If this word is a *samāsa*, the tokens for its members.
+    pub(crate) members: Vec<Token<'a>>,
+}
+
+/// The sandhi change that joins a token to the text that follows it.
+///
+/// We store the split that our sandhi splitter returned as-is. For example, for the token `sA` in
+/// `sEva`, `first` is `sA`, `second` is `eva`, and `text` is `sEva`. To get just the sounds that
+/// changed, use `change`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Sandhi {
+    first: CompactString,
+    second: CompactString,
+    text: CompactString,
+    kind: Kind,
+}
+
+/// A complete segmentation of some input text.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Segmentation<'a> {
+    tokens: Vec<Token<'a>>,
+    score: i32,
 }
 
 /// A small cache that stores all tokens seen during a segmentation.
 #[derive(Debug, Default)]
 pub(crate) struct TokenPool<'a> {
     tokens: Vec<Token<'a>>,
+    /// The prior probability of each token's analysis. For details, see `History::prior`.
+    priors: Vec<Option<f32>>,
 }
 
+/// The best phrases we have seen for some remainder and context, as `(score, tokens)` pairs.
+type Rivals = Vec<(i32, Vec<usize>)>;
+
+/// The analyses of some text in our kosha.
+type Analyses<'a> = Vec<(Option<PadaEntry<'a>>, Option<f32>)>;
+
 /// Represents an in-progress segment of a phrase.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Phrase {
@@ -37,14 +77,17 @@ pub struct Phrase {
     pub score: i32,
 }
 
-pub(crate) struct Config {
+/// The paths to the data files that `Chedaka` uses.
+pub struct Config {
     vidyut_base_path: PathBuf,
 }
 
 pub struct Chedaka {
     sandhi: Splitter,
     kosha: Kosha,
-    model: Model,
+    scorer: Box<dyn Scorer>,
+    input_scheme: Option<Scheme>,
+    group_samasas: bool,
 }
 
 impl<'a> TokenPool<'a> {
@@ -55,14 +98,26 @@ impl<'a> TokenPool<'a> {
 
     /// Inserts the given token into the pool and returns its index.
     pub fn insert(&mut self, token: Token<'a>) -> usize {
+        self.insert_with_prior(token, None)
+    }
+
+    /// Inserts the given token and the prior probability of its analysis into the pool and
+    /// returns its index.
+    pub fn insert_with_prior(&mut self, token: Token<'a>, prior: Option<f32>) -> usize {
         self.tokens.push(token);
+        self.priors.push(prior);
         self.tokens.len() - 1
     }
 
     /// Returns the token corresponding to the given index, if one exists.
-    pub fn get(&self, index: usize) -> Option<&Token> {
+    pub fn get(&self, index: usize) -> Option<&Token<'a>> {
         self.tokens.get(index)
     }
+
+    /// Returns the prior probability of the token at the given index, if it has one.
+    pub fn prior(&self, index: usize) -> Option<f32> {
+        self.priors.get(index).copied().flatten()
+    }
 }
 
 impl Phrase {
@@ -77,12 +132,136 @@ impl Phrase {
     }
 }
 
+impl<'a> Segmentation<'a> {
+    /// The tokens in this segmentation.
+    pub fn tokens(&self) -> &[Token<'a>] {
+        &self.tokens
+    }
+
+    /// The score of this segmentation. Higher scores are better.
+    ///
+    /// This score is the log probability of the segmentation multiplied by 100, as computed by
+    /// our `Scorer`.
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    /// Consumes this segmentation and returns its tokens.
+    pub fn into_tokens(self) -> Vec<Token<'a>> {
+        self.tokens
+    }
+}
+
+impl Sandhi {
+    /// Creates a new `Sandhi` from a `split` of the `remaining` text.
+    pub fn new(remaining: &str, split: &Split) -> Self {
+        Self {
+            first: CompactString::from(split.first()),
+            second: CompactString::from(split.second()),
+            text: CompactString::from(remaining),
+            kind: split.kind(),
+        }
+    }
+
+    /// This token's text before sandhi.
+    pub fn first(&self) -> &str {
+        &self.first
+    }
+
+    /// The rest of the input before sandhi, starting with the next token.
+    pub fn second(&self) -> &str {
+        &self.second
+    }
+
+    /// The rest of the input after sandhi, starting with this token.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the fewest sounds that explain this change, as `(first, second, result)`: the
+    /// sounds at the end of this token and at the start of the next token, and the sounds they
+    /// became after sandhi.
+    ///
+    /// For example, we describe `sEva` = `sA` + `eva` as `A` + `e` = `E`, and we describe
+    /// `devo gacCati` = `devaH` + `gacCati` as `aH` + `` = `o`. If the text did not change, all
+    /// three are empty.
+    ///
+    /// A vowel never disappears in sandhi without merging with its neighbor. So if the fewest
+    /// sounds would delete a vowel, we widen the change by one sound on the other side. For
+    /// example, we describe `devAlayaH` = `deva` + `AlayaH` as `a` + `A` = `A` rather than
+    /// `a` + `` = ``.
+    pub fn change(&self) -> (&str, &str, &str) {
+        let (first, second, text) = (self.first(), self.second(), self.text());
+
+        // `second` ends with the text after the sandhi change, so `text` does too.
+        let mut n_suffix = text
+            .bytes()
+            .rev()
+            .zip(second.bytes().rev())
+            .take_while(|(x, y)| x == y)
+            .count();
+        let head = &text[..text.len() - n_suffix];
+        // `first` starts with the text before the sandhi change, so `head` does too.
+        let mut n_prefix = head
+            .bytes()
+            .zip(first.bytes())
+            .take_while(|(x, y)| x == y)
+            .count();
+
+        let has_vowel = |x: &str| x.contains(sounds::is_ac);
+        if head[n_prefix..].trim().is_empty() {
+            if has_vowel(&first[n_prefix..]) && n_suffix > 0 {
+                n_suffix -= 1;
+            } else if has_vowel(&second[..second.len() - n_suffix]) && n_prefix > 0 {
+                n_prefix -= 1;
+            }
+        }
+
+        let head = &text[..text.len() - n_suffix];
+        (
+            &first[n_prefix..],
+            &second[..second.len() - n_suffix],
+            head[n_prefix..].trim(),
+        )
+    }
+
+    /// The kind of split that separated this token from the next. If `Kind::Prefix`, the text
+    /// did not change.
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+}
+
 impl<'a> Token<'a> {
     /// Creates a new `Token`.
     pub fn new(text: &str, data: PadaEntry<'a>) -> Self {
         Self {
             text: CompactString::from(text),
             data: Some(data),
+            span: None,
+            surface: None,
+            sandhi: None,
+            members: Vec::new(),
+        }
+    }
+
+    /// Creates a token for the text in `input.text()[start..end]`.
+    fn from_input(
+        input: &Input,
+        start: usize,
+        end: usize,
+        text: &str,
+        data: Option<PadaEntry<'a>>,
+        sandhi: Option<Sandhi>,
+    ) -> Self {
+        let span = input.source_span(start, end);
+        Self {
+            text: CompactString::from(text),
+            data,
+            surface: Some(CompactString::from(&input.original()[span.clone()])),
+            span: Some(span),
+            sandhi,
+            members: Vec::new(),
         }
     }
 
@@ -96,6 +275,42 @@ impl<'a> Token<'a> {
         &self.data
     }
 
+    /// The byte span of the original input that this word came from.
+    ///
+    /// Sandhi can merge the sounds at word boundaries, and we assign a merged sound to the word
+    /// after it. For example, the spans for *ca* and *iti* in *ceti* are `c` and `eti`. But if a
+    /// script writes several sounds with one character, the spans of adjacent words might
+    /// overlap. If this token was not created by `Chedaka`, we return `None`.
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.span.clone()
+    }
+
+    /// The text of the original input that this word came from, i.e. the text in `span`.
+    pub fn surface(&self) -> Option<&str> {
+        self.surface.as_deref()
+    }
+
+    /// The sandhi change that joins this word to the text after it.
+    ///
+    /// If this token is not Sanskrit or was not created by `Chedaka`, we return `None`.
+    pub fn sandhi(&self) -> Option<&Sandhi> {
+        self.sandhi.as_ref()
+    }
+
+    /// The members of this word, if it is a *samāsa*.
+    ///
+    /// Each member is a *pūrvapada* except for the last, which has the ending of the whole
+    /// *samāsa*. If this word is not a *samāsa*, we return an empty slice. For details, see
+    /// `Chedaka::with_samasa_grouping`.
+    pub fn members(&self) -> &[Token<'a>] {
+        &self.members
+    }
+
+    /// Returns whether this word is a *samāsa* whose members we grouped together.
+    pub fn is_samasa(&self) -> bool {
+        !self.members.is_empty()
+    }
+
     pub fn lemma(&self) -> &str {
         match &self.data {
             Some(data) => data.lemma().unwrap_or(""),
@@ -119,23 +334,32 @@ impl<'a> Token<'a> {
 }
 
 impl Config {
+    /// Creates a config for the data directory at `path`.
     pub fn new(path: &Path) -> Self {
         Self {
             vidyut_base_path: path.to_path_buf(),
         }
     }
 
+    /// The path to our sandhi rules.
     pub fn sandhi_rules(&self) -> PathBuf {
         self.vidyut_base_path.join("sandhi/rules.csv")
     }
 
+    /// The path to our kosha.
     pub fn kosha_path(&self) -> PathBuf {
         self.vidyut_base_path.join("kosha")
     }
 
+    /// The path to our default `Model`.
     pub fn model_path(&self) -> PathBuf {
         self.vidyut_base_path.join("cheda/model.msgpack")
     }
+
+    /// The path to our `FeatureModel`, if one has been trained.
+    pub fn feature_model_path(&self) -> PathBuf {
+        self.vidyut_base_path.join("cheda/feature_model.msgpack")
+    }
 }
 
 impl Chedaka {
@@ -148,10 +372,42 @@ impl Chedaka {
         Ok(Chedaka {
             sandhi: Splitter::from_csv(config.sandhi_rules())?,
             kosha: Kosha::new(config.kosha_path())?,
-            model: Model::read(&config.model_path())?,
+            scorer: Box::new(Model::read(&config.model_path())?),
+            input_scheme: Some(Scheme::Slp1),
+            group_samasas: false,
         })
     }
 
+    /// Sets the scorer that this chedaka uses to rank its segmentations.
+    ///
+    /// By default, we use the `Model` in our data directory.
+    pub fn with_scorer(mut self, scorer: impl Scorer + 'static) -> Self {
+        self.scorer = Box::new(scorer);
+        self
+    }
+
+    /// Sets the scheme of the text that this chedaka segments.
+    ///
+    /// By default, we expect SLP1 text. If `scheme` is `None`, we detect the scheme of each input
+    /// and fall back to SLP1 if detection fails. Regardless of the input scheme, the tokens we
+    /// return are in SLP1.
+    pub fn with_input_scheme(mut self, scheme: Option<Scheme>) -> Self {
+        self.input_scheme = scheme;
+        self
+    }
+
+    /// Sets whether this chedaka merges the members of each *samāsa* into a single token.
+    ///
+    /// By default, we return each member of a *samāsa* as a separate token, as the Digital Corpus
+    /// of Sanskrit does. If `group` is true, we instead return a single token whose data is a
+    /// *subanta* with a `SamasaEntry` and whose `members` are the original tokens. The token's
+    /// text joins the texts of its members with `-`, e.g. `rAja-puruzaH`, and its *samāsa* type
+    /// is a rough guess. This setting doesn't affect `lattice`.
+    pub fn with_samasa_grouping(mut self, group: bool) -> Self {
+        self.group_samasas = group;
+        self
+    }
+
     /// Returns a reference to this chedaka's underlying kosha.
     ///
     /// We provide this method so that callers who want direct access to a kosha can reuse the
@@ -160,33 +416,75 @@ impl Chedaka {
         &self.kosha
     }
 
-    /// Returns a reference to this chedaka's underlying model.
-    pub fn model(&self) -> &Model {
-        &self.model
+    /// Returns a reference to this chedaka's underlying scorer.
+    pub fn scorer(&self) -> &dyn Scorer {
+        self.scorer.as_ref()
     }
 
     /// Segments the input text into tokens.
     pub fn run<'a>(&'a self, input_text: &str) -> Result<Vec<Token<'a>>> {
-        if !input_text.is_ascii() {
-            return Err(crate::Error::NonAsciiText);
+        let mut best = self.run_n_best(input_text, 1)?;
+        Ok(best
+            .pop()
+            .map(Segmentation::into_tokens)
+            .unwrap_or_default())
+    }
+
+    /// Segments the input text and returns its `n` best segmentations, best first.
+    ///
+    /// We might return fewer than `n` segmentations if the input has fewer valid segmentations.
+    pub fn run_n_best<'a>(&'a self, input_text: &str, n: usize) -> Result<Vec<Segmentation<'a>>> {
+        let input = Input::new(input_text, self.input_scheme)?;
+        let mut ret = self.search(&input, n, None)?;
+        if self.group_samasas {
+            for segmentation in &mut ret {
+                let tokens = std::mem::take(&mut segmentation.tokens);
+                segmentation.tokens = samasa::group(&input, tokens);
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Returns a lattice of every candidate token considered while searching for the `n` best
+    /// segmentations of the input text.
+    pub fn lattice<'a>(&'a self, input_text: &str, n: usize) -> Result<Lattice<'a>> {
+        let input = Input::new(input_text, self.input_scheme)?;
+        let mut lattice = Lattice::new(input.text().to_string());
+        self.search(&input, n, Some(&mut lattice))?;
+        lattice.finish();
+        Ok(lattice)
+    }
+
+    /// Finds the `n` best segmentations of `input`.
+    ///
+    /// If `lattice` is defined, also adds every candidate token we consider to it.
+    fn search<'a>(
+        &'a self,
+        input: &Input,
+        n: usize,
+        mut lattice: Option<&mut Lattice<'a>>,
+    ) -> Result<Vec<Segmentation<'a>>> {
+        let mut ret: Vec<Segmentation<'a>> = Vec::new();
+        if n == 0 {
+            return Ok(ret);
         }
 
-        let normalized_text = normalize_text::normalize(input_text);
-        let initial_state = Phrase::new(normalized_text.to_string());
+        let text = input.text();
+        let initial_state = Phrase::new(text.to_string());
 
         let mut pq = PriorityQueue::new();
-        let mut word_cache: FxHashMap<String, Vec<Option<PadaEntry<'a>>>> = FxHashMap::default();
+        let mut word_cache: FxHashMap<String, Analyses<'a>> = FxHashMap::default();
         let mut token_pool: TokenPool<'a> = TokenPool::new();
 
-        // viterbi_cache[remainder][state] = the best result that ends with $state and has $remainder
-        // text remaining in the input.
-        let mut viterbi_cache: FxHashMap<String, FxHashMap<String, Phrase>> = FxHashMap::default();
+        // viterbi_cache[remainder][context] = the `n` best results that end with $context and have
+        // $remainder text remaining in the input, in descending order of score.
+        let mut viterbi_cache: FxHashMap<String, FxHashMap<Context, Rivals>> = FxHashMap::default();
 
         let score = initial_state.score;
         pq.push(initial_state, score);
 
         while let Some((cur, cur_score)) = pq.pop() {
-            // The best solution remaining is complete, so we can stop here.
+            // The best solution remaining is complete, so we can add it to our results.
             //
             // Our current scoring model is a probabilistic model that adjusts the probability of a
             // solution by multiplying it by other probabilities. Since a probability is at most 1, a
@@ -200,52 +498,63 @@ impl Chedaka {
             // priority queues and (b) cannot possibly produce a result better than C per our result
             // above.
             //
-            // So once we find a finished solution in our priority queue, we can suspend execution.
+            // So once we have found `n` finished solutions in our priority queue, we can suspend
+            // execution.
             //
             // NOTE: this doesn't hold if using an actual Viterbi algorithm as we can suspend only once
             // we've seen each of our N possible states.
             if cur.remaining.is_empty() {
-                break;
+                let tokens: Vec<_> = cur
+                    .tokens
+                    .iter()
+                    .map(|i| token_pool.get(*i).expect("present").clone())
+                    .collect();
+                // Different search paths can create the same segmentation, so skip duplicates.
+                if !ret.iter().any(|s| s.tokens == tokens) {
+                    ret.push(Segmentation {
+                        tokens,
+                        score: cur_score,
+                    });
+                }
+                if ret.len() >= n {
+                    break;
+                }
+                continue;
             }
 
+            let start = text.len().saturating_sub(cur.remaining.len());
+
             // Non-Sanskrit token: emit and continue.
             if cur.remaining.starts_with(|c| !sounds::is_sanskrit(c)) {
-                let mut new = match cur.remaining.split_once(' ') {
-                    Some((first, second)) => {
-                        let mut new = Phrase {
-                            tokens: cur.tokens.clone(),
-                            remaining: second.to_string(),
-                            // HACK: this is buggy -- scoring based on cur score set here?
-                            score: cur_score,
-                        };
-                        let i = token_pool.insert(Token {
-                            text: CompactString::from(first),
-                            data: None,
-                        });
-                        new.tokens.push(i);
-                        new
-                    }
-                    None => {
-                        let mut new = Phrase {
-                            tokens: cur.tokens.clone(),
-                            remaining: "".to_string(),
-                            // HACK: this is buggy -- scoring based on cur score set here?
-                            score: cur_score,
-                        };
-                        let i = token_pool.insert(Token {
-                            text: CompactString::from(cur.remaining),
-                            data: None,
-                        });
-                        new.tokens.push(i);
-                        new
-                    }
+                let (first, second) = match cur.remaining.split_once(' ') {
+                    Some((first, second)) => (first, second),
+                    None => (cur.remaining.as_str(), ""),
                 };
-
-                new.score = self.model.score(&new, &token_pool);
-                viterbi_cache
-                    .entry(new.remaining.clone())
-                    .or_default()
-                    .insert("STATE".to_string(), new.clone());
+                let mut new = Phrase {
+                    tokens: cur.tokens.clone(),
+                    remaining: second.to_string(),
+                    // HACK: this is buggy -- scoring based on cur score set here?
+                    score: cur_score,
+                };
+                let end = start + first.len();
+                let next = text.len().saturating_sub(second.len());
+                let i = token_pool.insert(Token::from_input(input, start, end, first, None, None));
+                new.tokens.push(i);
+
+                new.score = scoring::score(self.scorer.as_ref(), &new, &token_pool);
+                if let Some(lattice) = lattice.as_mut() {
+                    let token = token_pool.get(i).expect("present");
+                    lattice.add(start, end, next, token, new.score);
+                }
+                if !insert_rival(
+                    &mut viterbi_cache,
+                    self.scorer.as_ref(),
+                    &new,
+                    &token_pool,
+                    n,
+                ) {
+                    continue;
+                }
 
                 let new_score = new.score;
                 pq.push(new, new_score);
@@ -264,7 +573,7 @@ impl Chedaka {
                 let second = split.second();
                 self.analyze_pada(first, &split, &mut word_cache)?;
 
-                for artha in word_cache.get(first).unwrap_or(&no_results) {
+                for (artha, prior) in word_cache.get(first).unwrap_or(&no_results) {
                     if !strict_mode::is_valid_word(&cur, &token_pool, &split, artha) {
                         continue;
                     }
@@ -275,59 +584,36 @@ impl Chedaka {
                         // HACK: this is buggy -- scoring based on cur score set here?
                         score: cur_score,
                     };
-                    let i = token_pool.insert(Token {
-                        text: CompactString::from(first),
-                        data: artha.clone(),
-                    });
+                    let end = span_end(text, start, second);
+                    let next = text.len().saturating_sub(second.len());
+                    let sandhi = Sandhi::new(&cur.remaining, &split);
+                    let token =
+                        Token::from_input(input, start, end, first, artha.clone(), Some(sandhi));
+                    let i = token_pool.insert_with_prior(token, *prior);
                     new.tokens.push(i);
-                    new.score = self.model.score(&new, &token_pool);
+                    new.score = scoring::score(self.scorer.as_ref(), &new, &token_pool);
+
+                    if let Some(lattice) = lattice.as_mut() {
+                        let token = token_pool.get(i).expect("present");
+                        lattice.add(start, end, next, token, new.score);
+                    }
 
-                    // Use state "STATE" for now since we don't have any states implemented.
-                    let maybe_rival = viterbi_cache
-                        .entry(new.remaining.clone())
-                        .or_default()
-                        .get("STATE");
+                    if !insert_rival(
+                        &mut viterbi_cache,
+                        self.scorer.as_ref(),
+                        &new,
+                        &token_pool,
+                        n,
+                    ) {
+                        continue;
+                    }
                     let new_score = new.score;
-                    if let Some(rival) = maybe_rival {
-                        if rival.score >= new.score {
-                            continue;
-                        }
-                    };
-                    viterbi_cache
-                        .entry(new.remaining.clone())
-                        .or_default()
-                        .insert("STATE".to_string(), new.clone());
                     pq.push(new, new_score);
                 }
             }
         }
 
-        // Return the best result we could find above.
-        if let Some(solutions) = viterbi_cache.get("") {
-            if let Some(best) = solutions.values().max_by_key(|s| s.score) {
-                let mut ret = Vec::new();
-                for i in &best.tokens {
-                    let token = token_pool.get(*i).expect("present").clone();
-
-                    // Do this goofy round-trip to avoid lifetime complaints.
-                    let data = match token.data {
-                        Some(data) => {
-                            let packed_data = self.kosha.pack(&data).expect("ok");
-                            Some(self.kosha.unpack(packed_data).expect("ok"))
-                        }
-                        None => None,
-                    };
-
-                    let token = Token {
-                        text: token.text,
-                        data,
-                    };
-                    ret.push(token);
-                }
-                return Ok(ret);
-            }
-        }
-        Ok(Vec::new())
+        Ok(ret)
     }
 
     // FIXME: better as an iterator, but hard to implement. For now, update statefully then iterate in
@@ -336,18 +622,17 @@ impl Chedaka {
         &'a self,
         text: &str,
         split: &Split,
-        cache: &mut FxHashMap<String, Vec<Option<PadaEntry<'a>>>>,
+        cache: &mut FxHashMap<String, Analyses<'a>>,
     ) -> Result<()> {
         if !cache.contains_key(text) {
-            let mut added = Vec::new();
-
-            for entry in self.kosha.get_all(text) {
-                added.push(Some(entry));
-            }
+            let entries = self.kosha.get_all(text);
+            let weights: Vec<_> = entries.iter().map(|e| self.kosha.weight(e)).collect();
+            let priors = priors(&weights);
+            let mut added: Analyses = entries.into_iter().map(Some).zip(priors).collect();
 
             // Add the option to skip an entire chunk. (For typos, junk, etc.)
             if split.is_end_of_chunk() || text.starts_with(|c| !sounds::is_sanskrit(c)) {
-                added.push(None);
+                added.push((None, None));
             }
 
             cache.insert(text.to_string(), added);
@@ -355,3 +640,222 @@ impl Chedaka {
         Ok(())
     }
 }
+
+/// Converts the kosha weights for the analyses of some text to prior probabilities.
+///
+/// We smooth each weight by adding 1 so that an analysis with no weight or weight 0 is unlikely
+/// but still possible. If no analysis has a weight, the kosha has no opinion, so we return no
+/// priors.
+fn priors(weights: &[Option<f32>]) -> Vec<Option<f32>> {
+    if weights.iter().all(|w| w.is_none()) {
+        return vec![None; weights.len()];
+    }
+    let total: f32 = weights.iter().map(|w| w.unwrap_or(0.0) + 1.0).sum();
+    weights
+        .iter()
+        .map(|w| Some((w.unwrap_or(0.0) + 1.0) / total))
+        .collect()
+}
+
+/// Adds `phrase` to the `n` best phrases we have seen for its remainder and its context, as
+/// defined by `scorer`.
+///
+/// We return whether we added `phrase`. If we already have `n` better phrases, or if we already
+/// have a phrase with the same tokens, we don't add it, and the caller should stop searching from
+/// it. Different sandhi splits often produce the same tokens, and without this check, copies of
+/// one segmentation could crowd out the other `n - 1` best segmentations.
+fn insert_rival(
+    cache: &mut FxHashMap<String, FxHashMap<Context, Rivals>>,
+    scorer: &dyn Scorer,
+    phrase: &Phrase,
+    pool: &TokenPool,
+    n: usize,
+) -> bool {
+    let context = scorer.context(&History::new(&phrase.tokens, pool));
+    let rivals = cache
+        .entry(phrase.remaining.clone())
+        .or_default()
+        .entry(context)
+        .or_default();
+    if rivals.len() >= n && rivals[n - 1].0 >= phrase.score {
+        return false;
+    }
+
+    let same_tokens = |tokens: &[usize]| {
+        tokens.len() == phrase.tokens.len()
+            && tokens
+                .iter()
+                .zip(&phrase.tokens)
+                .all(|(x, y)| x == y || pool.get(*x) == pool.get(*y))
+    };
+    // Identical phrases have identical scores, so we need to check only phrases with our score.
+    if rivals
+        .iter()
+        .any(|(score, tokens)| *score == phrase.score && same_tokens(tokens))
+    {
+        return false;
+    }
+
+    let i = rivals.partition_point(|(score, _)| *score >= phrase.score);
+    rivals.insert(i, (phrase.score, phrase.tokens.clone()));
+    rivals.truncate(n);
+    true
+}
+
+/// Returns where a word that starts at `start` in `text` ends, given the text that follows it.
+///
+/// Sandhi might change the sounds at the boundary, so we measure from the end of `text`.
+fn span_end(text: &str, start: usize, second: &str) -> usize {
+    let end = std::cmp::max(start, text.len().saturating_sub(second.len()));
+    start + text[start..end].trim_end().len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vidyut_sandhi::Location;
+
+    fn sandhi(remaining: &str, first: &str, second: &str, kind: Kind) -> (String, String, String) {
+        let split = Split::new(
+            first.to_string(),
+            second.to_string(),
+            Location::WithinChunk,
+            kind,
+        );
+        let s = Sandhi::new(remaining, &split);
+        assert_eq!(s.kind(), kind);
+        assert_eq!(
+            (s.first(), s.second(), s.text()),
+            (first, second, remaining)
+        );
+        let (x, y, z) = s.change();
+        (x.to_string(), y.to_string(), z.to_string())
+    }
+
+    #[test]
+    fn insert_rival_keeps_n_best() {
+        use vidyut_kosha::entries::{PratipadikaEntry, SubantaEntry};
+        use vidyut_prakriya::args::{Pratipadika, Slp1String};
+
+        let ca = Pratipadika::avyaya(Slp1String::from("ca").expect("ok"));
+        let ca_entry = PratipadikaEntry::try_from(&ca).expect("ok");
+        fn token<'a>(text: &str, data: Option<PadaEntry<'a>>) -> Token<'a> {
+            Token {
+                text: CompactString::from(text),
+                data,
+                span: None,
+                surface: None,
+                sandhi: None,
+                members: Vec::new(),
+            }
+        }
+        let phrase = |tokens: Vec<usize>, score| Phrase {
+            tokens,
+            remaining: "iti".to_string(),
+            score,
+        };
+
+        let mut pool = TokenPool::new();
+        let a_1 = pool.insert(token("a", None));
+        let a_2 = pool.insert(token("a", None));
+        let b = pool.insert(token("b", None));
+        let ca = pool.insert(token("ca", Some(SubantaEntry::avyaya(ca_entry).into())));
+
+        let model = Model::new();
+        let mut cache = FxHashMap::default();
+        let mut insert = |p: Phrase| insert_rival(&mut cache, &model, &p, &pool, 2);
+        assert!(insert(phrase(vec![a_1], -10)));
+        // The same tokens from a different search path.
+        assert!(!insert(phrase(vec![a_2], -10)));
+        assert!(insert(phrase(vec![b], -10)));
+        // We already have two better phrases that end in this state.
+        assert!(!insert(phrase(vec![b, a_1], -20)));
+        assert!(insert(phrase(vec![b, a_1], -5)));
+        // But phrases that end in another state don't compete with them.
+        assert!(insert(phrase(vec![ca], -30)));
+    }
+
+    #[test]
+    fn insert_rival_uses_scorer_context() {
+        use crate::features::FeatureModel;
+        use vidyut_kosha::entries::{PratipadikaEntry, SubantaEntry};
+        use vidyut_prakriya::args::{Pratipadika, Slp1String};
+
+        let ca = Pratipadika::avyaya(Slp1String::from("ca").expect("ok"));
+        let api = Pratipadika::avyaya(Slp1String::from("api").expect("ok"));
+        fn token<'a>(p: &'a Pratipadika) -> Token<'a> {
+            let entry = PratipadikaEntry::try_from(p).expect("ok");
+            Token {
+                text: CompactString::from(entry.lemma()),
+                data: Some(SubantaEntry::avyaya(entry).into()),
+                span: None,
+                surface: None,
+                sandhi: None,
+                members: Vec::new(),
+            }
+        }
+        let phrase = |tokens: Vec<usize>, score| Phrase {
+            tokens,
+            remaining: "iti".to_string(),
+            score,
+        };
+
+        let mut pool = TokenPool::new();
+        let ca = pool.insert(token(&ca));
+        let api = pool.insert(token(&api));
+
+        // Both tokens have the same state, so `Model` keeps only the better phrase.
+        let model = Model::new();
+        let mut cache = FxHashMap::default();
+        let mut insert = |p: Phrase| insert_rival(&mut cache, &model, &p, &pool, 1);
+        assert!(insert(phrase(vec![ca], -10)));
+        assert!(!insert(phrase(vec![api], -20)));
+
+        // But `FeatureModel` also reads the lemma of the previous token, so it keeps both.
+        let model = FeatureModel::new();
+        let mut cache = FxHashMap::default();
+        let mut insert = |p: Phrase| insert_rival(&mut cache, &model, &p, &pool, 1);
+        assert!(insert(phrase(vec![ca], -10)));
+        assert!(insert(phrase(vec![api], -20)));
+        assert!(!insert(phrase(vec![api], -30)));
+    }
+
+    #[test]
+    fn priors_from_weights() {
+        assert_eq!(priors(&[None, None]), [None, None]);
+        assert_eq!(
+            priors(&[Some(2.0), None, Some(0.0)]),
+            [Some(0.6), Some(0.2), Some(0.2)]
+        );
+        assert!(priors(&[]).is_empty());
+    }
+
+    #[test]
+    fn sandhi_new() {
+        let cases = [
+            ("ceti", "ca", "iti", ("a", "i", "e")),
+            ("sEva", "sA", "eva", ("A", "e", "E")),
+            ("devo gacCati", "devaH", "gacCati", ("aH", "", "o")),
+            ("tacca", "tat", "ca", ("t", "", "c")),
+            ("rAmaH", "rAmas", "", ("s", "", "H")),
+            ("devAlayaH", "deva", "AlayaH", ("a", "A", "A")),
+            ("te pi", "te", "api", ("e", "a", "e")),
+            ("devA gacCanti", "devAH", "gacCanti", ("H", "", "")),
+        ];
+        for (remaining, first, second, (x, y, z)) in cases {
+            let expected = (x.to_string(), y.to_string(), z.to_string());
+            assert_eq!(
+                sandhi(remaining, first, second, Kind::Standard),
+                expected,
+                "{remaining}"
+            );
+        }
+
+        let empty = (String::new(), String::new(), String::new());
+        assert_eq!(
+            sandhi("devaH gacCati", "devaH", "gacCati", Kind::Prefix),
+            empty
+        );
+        assert_eq!(sandhi("gacCati", "gacCati", "", Kind::Prefix), empty);
+    }
+}
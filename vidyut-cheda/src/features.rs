@@ -0,0 +1,525 @@
+//! A model that combines relative-frequency estimates for several kinds of features.
+//!
+//! Our default `Model` knows only the coarse `State` of the previous token and the lemma of the
+//! newest token. `FeatureModel` instead scores each token with a scaled sum of feature weights,
+//! which lets it use a wider context and other kinds of information:
+//!
+//! - the states of the previous two tokens (a trigram model)
+//! - the lemma of the previous token (a lemma bigram model)
+//! - whether the previous token is the first member of a compound
+//! - the sandhi change that joins the previous token to the newest one
+//!
+//! `FeatureModelBuilder` estimates each feature's weight as a log probability from counts in our
+//! training data. Sandhi features have weights only if the training data says how sandhi was
+//! applied (see `Observation::sandhi`).
+//!
+//! Our score has the form of a log-linear model, but it is not a trained one. We don't fit the
+//! scale of each kind of feature to data. Instead, we use fixed defaults (see
+//! `FeatureKind::default_scale`) that callers can change with `FeatureModel::set_scale`. So our
+//! score is a heuristic combination of separately estimated probabilities, not a normalized
+//! probability, and the best scales for some corpus might be quite different from our defaults.
+
+use crate::chedaka::{Config, Token};
+use crate::errors::Result;
+use crate::scoring::{log_prob, Context, History, POSTag, Scorer, State};
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use vidyut_kosha::entries::PadaEntry;
+
+/// The log probability of a transition we have never seen. (Same as in `Model`.)
+const TRANSITION_LOG_EPSILON: f32 = -5.0;
+
+/// A smoothing factor for lemmas we have never seen. (Same as in `Model`.)
+const LEMMA_EPSILON: f64 = 1e-100;
+
+/// A kind of `Feature`.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum FeatureKind {
+    /// See `Feature::StateTrigram`.
+    StateTrigram,
+    /// See `Feature::StateBigram`.
+    StateBigram,
+    /// See `Feature::Lemma`.
+    Lemma,
+    /// See `Feature::LemmaBigram`.
+    LemmaBigram,
+    /// See `Feature::Compound`.
+    Compound,
+    /// See `Feature::Sandhi`.
+    Sandhi,
+}
+
+/// A feature of the newest token in a segmentation.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub enum Feature {
+    /// The states of the two tokens before the newest token and of the newest token.
+    StateTrigram(State, State, State),
+    /// The states of the previous token and of the newest token.
+    StateBigram(State, State),
+    /// The lemma and part of speech of the newest token.
+    Lemma(String, POSTag),
+    /// The lemmas of the previous token and of the newest token.
+    LemmaBigram(String, String),
+    /// Whether the previous token is the first member of a compound, and the part of speech of
+    /// the newest token.
+    Compound(bool, POSTag),
+    /// The sandhi change that joins the previous token to the newest token, as returned by
+    /// `Sandhi::change`. An empty change means that the split did not undo any sandhi.
+    Sandhi(String, String, String),
+}
+
+/// What a `FeatureModel` knows about a single token.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Observation {
+    /// The state of this token.
+    pub state: State,
+    /// The lemma of this token.
+    pub lemma: String,
+    /// Whether this token is the first member of a compound.
+    pub is_purvapada: bool,
+    /// The sandhi change that joins this token to the token after it, as returned by
+    /// `Sandhi::change`, if known.
+    pub sandhi: Option<(String, String, String)>,
+}
+
+/// A model that scores tokens with a scaled sum of the log probabilities of their features.
+///
+/// For details, see the module comment.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FeatureModel {
+    /// The weight of each feature we know about.
+    weights: FxHashMap<Feature, f32>,
+    /// The weight of features that aren't in `weights`, by kind.
+    unknown_weights: FxHashMap<FeatureKind, f32>,
+    /// How much each kind of feature contributes to our score.
+    scales: FxHashMap<FeatureKind, f32>,
+}
+
+/// Counts features in our training data.
+#[derive(Debug, Default)]
+pub struct FeatureModelBuilder {
+    counts: FxHashMap<Feature, u32>,
+}
+
+/// The condition that a feature's probability depends on.
+#[derive(Debug, Eq, Hash, PartialEq)]
+enum Condition {
+    States(State, State),
+    State(State),
+    Lemma(String),
+    Compound(bool),
+    Kind(FeatureKind),
+}
+
+impl FeatureKind {
+    /// Iterates over all feature kinds.
+    pub fn iter() -> impl Iterator<Item = FeatureKind> {
+        use FeatureKind::*;
+        [
+            StateTrigram,
+            StateBigram,
+            Lemma,
+            LemmaBigram,
+            Compound,
+            Sandhi,
+        ]
+        .into_iter()
+    }
+
+    /// The default scale for this kind of feature.
+    ///
+    /// Some of our features overlap. For example, a trigram feature and a bigram feature both
+    /// describe the newest token's state. So by default, we give overlapping features a combined
+    /// scale of 1. We chose these values by hand and haven't tuned them on held-out data.
+    fn default_scale(&self) -> f32 {
+        match self {
+            Self::StateTrigram | Self::StateBigram => 0.5,
+            Self::Lemma | Self::Compound | Self::Sandhi => 1.0,
+            Self::LemmaBigram => 0.25,
+        }
+    }
+}
+
+impl Feature {
+    /// The kind of this feature.
+    pub fn kind(&self) -> FeatureKind {
+        match self {
+            Self::StateTrigram(..) => FeatureKind::StateTrigram,
+            Self::StateBigram(..) => FeatureKind::StateBigram,
+            Self::Lemma(..) => FeatureKind::Lemma,
+            Self::LemmaBigram(..) => FeatureKind::LemmaBigram,
+            Self::Compound(..) => FeatureKind::Compound,
+            Self::Sandhi(..) => FeatureKind::Sandhi,
+        }
+    }
+
+    fn condition(&self) -> Condition {
+        match self {
+            Self::StateTrigram(x, y, _) => Condition::States(*x, *y),
+            Self::StateBigram(x, _) => Condition::State(*x),
+            Self::LemmaBigram(x, _) => Condition::Lemma(x.clone()),
+            Self::Compound(x, _) => Condition::Compound(*x),
+            // We model lemmas and sandhi changes with unconditional probabilities.
+            Self::Lemma(..) | Self::Sandhi(..) => Condition::Kind(self.kind()),
+        }
+    }
+}
+
+impl<'a> From<&Token<'a>> for Observation {
+    fn from(token: &Token<'a>) -> Self {
+        let is_purvapada = match token.data() {
+            Some(PadaEntry::Subanta(s)) => s.is_purvapada(),
+            _ => false,
+        };
+        Self {
+            state: token.data().into(),
+            lemma: token.lemma().to_string(),
+            is_purvapada,
+            sandhi: token.sandhi().map(|s| {
+                let (first, second, result) = s.change();
+                (first.into(), second.into(), result.into())
+            }),
+        }
+    }
+}
+
+/// Returns the features of the last observation in `window` given the observations before it.
+///
+/// We use at most the last three observations in `window`.
+pub fn extract_features(window: &[Observation]) -> Vec<Feature> {
+    let n = window.len();
+    let cur = match window.last() {
+        Some(x) => x,
+        None => return Vec::new(),
+    };
+    let prev = if n >= 2 { window.get(n - 2) } else { None };
+    let prev_prev = if n >= 3 { window.get(n - 3) } else { None };
+
+    let state = |x: Option<&Observation>| x.map_or(State::Initial, |x| x.state);
+    let pos_tag = cur.state.pos_tag();
+
+    let mut ret = vec![
+        Feature::StateTrigram(state(prev_prev), state(prev), cur.state),
+        Feature::StateBigram(state(prev), cur.state),
+        Feature::Lemma(cur.lemma.clone(), pos_tag),
+    ];
+    if let Some(prev) = prev {
+        ret.push(Feature::LemmaBigram(prev.lemma.clone(), cur.lemma.clone()));
+        ret.push(Feature::Compound(prev.is_purvapada, pos_tag));
+        if let Some((first, second, result)) = &prev.sandhi {
+            ret.push(Feature::Sandhi(
+                first.clone(),
+                second.clone(),
+                result.clone(),
+            ));
+        }
+    } else {
+        ret.push(Feature::Compound(false, pos_tag));
+    }
+    ret
+}
+
+impl Default for FeatureModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FeatureModel {
+    /// Creates a model with no weights and default scales.
+    pub fn new() -> Self {
+        Self {
+            weights: FxHashMap::default(),
+            unknown_weights: FxHashMap::default(),
+            scales: FeatureKind::iter()
+                .map(|k| (k, k.default_scale()))
+                .collect(),
+        }
+    }
+
+    /// Reads a model from disk.
+    pub fn read(model_path: &Path) -> Result<Self> {
+        let file = File::open(model_path)?;
+        let reader = BufReader::new(file);
+        let ret: FeatureModel = rmp_serde::from_read(reader)?;
+
+        Ok(ret)
+    }
+
+    /// Writes this model to disk.
+    pub fn write(&self, model_path: &Path) -> Result<()> {
+        let out = rmp_serde::to_vec(&self)?;
+        let file = File::create(model_path)?;
+        let mut writer = BufWriter::new(file);
+        writer.write_all(&out)?;
+
+        Ok(())
+    }
+
+    /// Returns the unscaled weight of the given feature.
+    pub fn weight(&self, feature: &Feature) -> f32 {
+        match self.weights.get(feature) {
+            Some(w) => *w,
+            None => self.unknown_weight(feature.kind()),
+        }
+    }
+
+    /// Sets the unscaled weight of the given feature.
+    ///
+    /// Our search assumes that scores never increase, so `weight` should not be positive.
+    pub fn set_weight(&mut self, feature: Feature, weight: f32) {
+        self.weights.insert(feature, weight);
+    }
+
+    /// Returns the weight we use for features of this kind that have no weight of their own.
+    pub fn unknown_weight(&self, kind: FeatureKind) -> f32 {
+        self.unknown_weights.get(&kind).copied().unwrap_or(0.0)
+    }
+
+    /// Sets the weight we use for features of this kind that have no weight of their own.
+    pub fn set_unknown_weight(&mut self, kind: FeatureKind, weight: f32) {
+        self.unknown_weights.insert(kind, weight);
+    }
+
+    /// Returns how much features of this kind contribute to our score.
+    pub fn scale(&self, kind: FeatureKind) -> f32 {
+        self.scales.get(&kind).copied().unwrap_or(0.0)
+    }
+
+    /// Sets how much features of this kind contribute to our score. To disable a kind of
+    /// feature, set its scale to 0.
+    pub fn set_scale(&mut self, kind: FeatureKind, scale: f32) {
+        self.scales.insert(kind, scale);
+    }
+
+    /// Returns the score of the given features.
+    pub fn score_features(&self, features: &[Feature]) -> f32 {
+        features
+            .iter()
+            .map(|f| self.scale(f.kind()) * self.weight(f))
+            .sum()
+    }
+}
+
+impl Scorer for FeatureModel {
+    fn log_prob(&self, history: &History) -> f32 {
+        let window: Vec<Observation> = (0..3)
+            .rev()
+            .filter_map(|i| history.back(i))
+            .map(Observation::from)
+            .collect();
+        self.score_features(&extract_features(&window))
+    }
+
+    /// Our features for the next token read the state of the token before the newest one and
+    /// everything we observe about the newest one.
+    fn context(&self, history: &History) -> Context {
+        let mut context = Context::new();
+        context.push_state(history.back(1).map_or(State::Initial, |t| t.data().into()));
+        if let Some(token) = history.back(0) {
+            let Observation {
+                state,
+                lemma,
+                is_purvapada,
+                sandhi,
+            } = Observation::from(token);
+            context.push_state(state);
+            context.push_text(&lemma);
+            context.push_flag(is_purvapada);
+            match sandhi {
+                Some((first, second, result)) => {
+                    context.push_flag(true);
+                    context.push_text(&first);
+                    context.push_text(&second);
+                    context.push_text(&result);
+                }
+                None => context.push_flag(false),
+            }
+        }
+        context
+    }
+}
+
+impl FeatureModelBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts the features of each token in the given sentence.
+    pub fn count_sentence(&mut self, sentence: &[Observation]) {
+        for i in 0..sentence.len() {
+            let start = i.saturating_sub(2);
+            for feature in extract_features(&sentence[start..=i]) {
+                let val = self.counts.entry(feature).or_insert(0);
+                *val += 1;
+            }
+        }
+    }
+
+    /// Adds the counts from `other` to this builder.
+    pub fn merge(&mut self, other: FeatureModelBuilder) {
+        for (key, value) in other.counts.into_iter() {
+            let v = self.counts.entry(key).or_insert(0);
+            *v += value;
+        }
+    }
+
+    /// Creates a model from the counts we've seen so far.
+    pub fn build(&self) -> FeatureModel {
+        let mut model = FeatureModel::new();
+
+        let mut condition_counts: FxHashMap<Condition, u32> = FxHashMap::default();
+        let mut kind_counts: FxHashMap<FeatureKind, u32> = FxHashMap::default();
+        for (feature, count) in &self.counts {
+            *condition_counts.entry(feature.condition()).or_insert(0) += count;
+            *kind_counts.entry(feature.kind()).or_insert(0) += count;
+        }
+
+        for (feature, count) in &self.counts {
+            let denominator = condition_counts[&feature.condition()];
+            let weight = log_prob(f64::from(*count), f64::from(denominator));
+            model.weights.insert(feature.clone(), weight);
+        }
+
+        for (kind, n) in kind_counts {
+            let n = f64::from(n);
+            let weight = match kind {
+                FeatureKind::StateTrigram | FeatureKind::StateBigram | FeatureKind::Compound => {
+                    TRANSITION_LOG_EPSILON
+                }
+                // Most out-of-vocabulary lemmas are errors, so penalize them heavily.
+                FeatureKind::Lemma => log_prob(LEMMA_EPSILON, n),
+                // Treat unseen pairs as if we had seen them once.
+                FeatureKind::LemmaBigram | FeatureKind::Sandhi => log_prob(1.0, n),
+            };
+            model.unknown_weights.insert(kind, weight);
+        }
+
+        model
+    }
+
+    /// Writes the model to disk.
+    pub fn write_model(&self, base_path: &Path) -> Result<()> {
+        let model_path = Config::new(base_path).feature_model_path();
+        if let Some(model_dir) = model_path.parent() {
+            std::fs::create_dir_all(model_dir)?;
+        }
+        self.build().write(&model_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vidyut_prakriya::args::{Linga, Purusha, Vacana, Vibhakti};
+
+    fn obs(state: State, lemma: &str) -> Observation {
+        Observation {
+            state,
+            lemma: lemma.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn extract_features_with_context() {
+        let deva = State::Subanta(Linga::Pum, Vibhakti::Prathama, Vacana::Eka);
+        let gam = State::Tinanta(Purusha::Prathama, Vacana::Eka);
+        let mut darma = obs(State::Unknown, "Darma");
+        darma.is_purvapada = true;
+        darma.sandhi = Some((String::new(), String::new(), String::new()));
+
+        let window = [obs(State::Avyaya, "ca"), darma, obs(deva, "deva")];
+        assert_eq!(
+            extract_features(&window),
+            vec![
+                Feature::StateTrigram(State::Avyaya, State::Unknown, deva),
+                Feature::StateBigram(State::Unknown, deva),
+                Feature::Lemma("deva".to_string(), POSTag::Subanta),
+                Feature::LemmaBigram("Darma".to_string(), "deva".to_string()),
+                Feature::Compound(true, POSTag::Subanta),
+                Feature::Sandhi(String::new(), String::new(), String::new()),
+            ]
+        );
+
+        let window = [obs(gam, "gam")];
+        assert_eq!(
+            extract_features(&window),
+            vec![
+                Feature::StateTrigram(State::Initial, State::Initial, gam),
+                Feature::StateBigram(State::Initial, gam),
+                Feature::Lemma("gam".to_string(), POSTag::Tinanta),
+                Feature::Compound(false, POSTag::Tinanta),
+            ]
+        );
+        assert!(extract_features(&[]).is_empty());
+    }
+
+    #[test]
+    fn build_model() {
+        let deva = State::Subanta(Linga::Pum, Vibhakti::Prathama, Vacana::Eka);
+        let gam = State::Tinanta(Purusha::Prathama, Vacana::Eka);
+
+        let mut builder = FeatureModelBuilder::new();
+        builder.count_sentence(&[obs(deva, "deva"), obs(gam, "gam")]);
+        let mut other = FeatureModelBuilder::new();
+        other.count_sentence(&[obs(deva, "deva"), obs(State::Avyaya, "ca")]);
+        builder.merge(other);
+        let model = builder.build();
+
+        // P(gam | deva) = 1/2
+        let f = Feature::StateBigram(deva, gam);
+        assert_eq!(model.weight(&f), log_prob(1.0, 2.0));
+        // P(deva | Initial) = 2/2
+        let f = Feature::StateBigram(State::Initial, deva);
+        assert_eq!(model.weight(&f), 0.0);
+        // P(deva, Subanta) = 2/4
+        let f = Feature::Lemma("deva".to_string(), POSTag::Subanta);
+        assert_eq!(model.weight(&f), log_prob(2.0, 4.0));
+
+        // Unseen features have a penalty, except for sandhi, which we didn't observe.
+        let f = Feature::StateBigram(gam, gam);
+        assert_eq!(model.weight(&f), TRANSITION_LOG_EPSILON);
+        let f = Feature::Lemma("rAma".to_string(), POSTag::Subanta);
+        assert!(model.weight(&f) < -50.0);
+        let f = Feature::Sandhi("a".to_string(), "i".to_string(), "e".to_string());
+        assert_eq!(model.weight(&f), 0.0);
+    }
+
+    #[test]
+    fn score_features() {
+        let mut model = FeatureModel::new();
+        let x = Feature::Lemma("deva".to_string(), POSTag::Subanta);
+        let y = Feature::StateBigram(State::Initial, State::Avyaya);
+        model.set_weight(x.clone(), -1.0);
+        model.set_weight(y.clone(), -2.0);
+        assert_eq!(model.score_features(&[x.clone(), y.clone()]), -2.0);
+
+        model.set_scale(FeatureKind::StateBigram, 0.0);
+        assert_eq!(model.score_features(&[x.clone(), y.clone()]), -1.0);
+
+        let z = Feature::LemmaBigram("ca".to_string(), "deva".to_string());
+        model.set_unknown_weight(FeatureKind::LemmaBigram, -4.0);
+        assert_eq!(model.score_features(&[x, y, z]), -2.0);
+    }
+
+    #[test]
+    fn read_and_write() -> Result<()> {
+        let mut model = FeatureModel::new();
+        model.set_weight(Feature::Compound(true, POSTag::Subanta), -0.5);
+        model.set_unknown_weight(FeatureKind::Compound, -5.0);
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("model.msgpack");
+        model.write(&path)?;
+        let read = FeatureModel::read(&path)?;
+        assert_eq!(read.weights, model.weights);
+        assert_eq!(read.unknown_weights, model.unknown_weights);
+        assert_eq!(read.scales, model.scales);
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+//! Converts input text into the normalized SLP1 text that our search uses.
+//!
+//! Our search works only on SLP1, but callers want to know which part of their original input
+//! produced a given token. So as we transliterate and normalize, we also track the span of the
+//! original input that produced each byte of our SLP1 text.
+use crate::errors::{Error, Result};
+use crate::normalize_text;
+use std::ops::Range;
+use vidyut_lipi::{detect, transliterate_with_alignment, Mapping, Scheme};
+
+/// Normalized SLP1 text and its mapping back to the original input.
+#[derive(Clone, Debug)]
+pub(crate) struct Input {
+    /// The original input.
+    original: String,
+    /// The normalized SLP1 text.
+    text: String,
+    /// `sources[i]` is the span of the original input that produced `text[i]`.
+    sources: Vec<Range<usize>>,
+}
+
+impl Input {
+    /// Creates a new `Input` from `original`, which is in the given scheme.
+    ///
+    /// If `scheme` is `None`, we detect the scheme of `original` and fall back to SLP1.
+    pub(crate) fn new(original: &str, scheme: Option<Scheme>) -> Result<Self> {
+        let scheme = scheme.or_else(|| detect(original)).unwrap_or(Scheme::Slp1);
+
+        let (slp1, slp1_sources) = if scheme == Scheme::Slp1 {
+            let sources = (0..original.len()).map(|i| i..i + 1).collect();
+            (original.to_string(), sources)
+        } else {
+            let mapping = Mapping::new(scheme, Scheme::Slp1);
+            let (slp1, alignments) = transliterate_with_alignment(original, &mapping);
+            let mut sources = Vec::with_capacity(slp1.len());
+            for a in alignments {
+                sources.extend(a.target.map(|_| a.source.clone()));
+            }
+            (slp1, sources)
+        };
+
+        if !slp1.is_ascii() {
+            return Err(Error::NonAsciiText);
+        }
+
+        let (text, offsets) = normalize_text::normalize_with_offsets(&slp1);
+        let sources = offsets
+            .iter()
+            .map(|i| match slp1_sources.get(*i) {
+                Some(r) => r.clone(),
+                None => original.len()..original.len(),
+            })
+            .collect();
+
+        Ok(Self {
+            original: original.to_string(),
+            text,
+            sources,
+        })
+    }
+
+    /// The original input.
+    pub(crate) fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// The normalized SLP1 text.
+    pub(crate) fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the span of the original input that produced `self.text()[start..end]`.
+    ///
+    /// Transliteration might merge several sounds into a single character, so the spans for
+    /// adjacent ranges might overlap.
+    pub(crate) fn source_span(&self, start: usize, end: usize) -> Range<usize> {
+        let source_start = match self.sources.get(start) {
+            Some(r) => r.start,
+            None => self.original.len(),
+        };
+        if start >= end {
+            return source_start..source_start;
+        }
+        match self.sources.get(end - 1) {
+            Some(r) => source_start..r.end,
+            None => source_start..self.original.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans<'a>(original: &'a str, input: &'a Input) -> Vec<(&'a str, &'a str)> {
+        let text = input.text();
+        let mut ret = Vec::new();
+        let mut start = 0;
+        for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+            if c == ' ' {
+                let span = input.source_span(start, i);
+                ret.push((&text[start..i], &original[span]));
+                start = i + 1;
+            }
+        }
+        ret
+    }
+
+    #[test]
+    fn slp1() -> Result<()> {
+        let original = "  devo\tgacCati. ";
+        let input = Input::new(original, Some(Scheme::Slp1))?;
+        assert_eq!(input.text(), "devo gacCati .");
+        assert_eq!(
+            spans(original, &input),
+            vec![("devo", "devo"), ("gacCati", "gacCati"), (".", ".")]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn devanagari() -> Result<()> {
+        let original = "देवो गच्छति ।";
+        let input = Input::new(original, Some(Scheme::Devanagari))?;
+        assert_eq!(input.text(), "devo gacCati .");
+        assert_eq!(
+            spans(original, &input),
+            vec![("devo", "देवो"), ("gacCati", "गच्छति"), (".", "।")]
+        );
+
+        // A consonant and its vowel sign are a single unit.
+        assert_eq!(&original[input.source_span(0, 1)], "दे");
+        assert_eq!(&original[input.source_span(2, 4)], "वो");
+        assert_eq!(input.source_span(4, 4), 12..12);
+        Ok(())
+    }
+
+    #[test]
+    fn detect_scheme() -> Result<()> {
+        let original = "rāmaḥ";
+        let input = Input::new(original, None)?;
+        assert_eq!(input.text(), "rAmaH");
+        assert_eq!(&original[input.source_span(1, 2)], "ā");
+        assert_eq!(input.source_span(5, 5), original.len()..original.len());
+        Ok(())
+    }
+
+    #[test]
+    fn non_ascii_slp1() {
+        assert!(Input::new("devo gacCati", Some(Scheme::Slp1)).is_ok());
+        assert!(Input::new("देवो", Some(Scheme::Slp1)).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! A record of every candidate token that a search considered.
+//!
+//! Our search explores many candidate words before it settles on a final segmentation. For
+//! annotation tools and for downstream reranking, these candidates are often more useful than the
+//! final result, so we let callers collect them in a `Lattice`.
+use crate::chedaka::Token;
+use rustc_hash::FxHashMap;
+
+/// A candidate token that spans some part of the input text.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LatticeEdge<'a> {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    pub(crate) next: usize,
+    pub(crate) token: Token<'a>,
+    pub(crate) score: i32,
+}
+
+impl<'a> LatticeEdge<'a> {
+    /// The byte offset in `Lattice::text` at which this candidate starts.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The byte offset in `Lattice::text` at which this candidate ends.
+    ///
+    /// Since sandhi can merge the sounds at word boundaries, this offset is approximate: a sound
+    /// shared by two words (e.g. the `E` in `sEva` = `sA eva`) belongs to the second word.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// The byte offset in `Lattice::text` at which the next candidate in a path starts.
+    ///
+    /// This offset skips any whitespace after this candidate, so it is the `start` of any edge
+    /// that can follow this one. If this candidate ends the text, `next` is `Lattice::text().len()`.
+    pub fn next(&self) -> usize {
+        self.next
+    }
+
+    /// The candidate token.
+    pub fn token(&self) -> &Token<'a> {
+        &self.token
+    }
+
+    /// The score of the best partial segmentation that ends with this candidate.
+    ///
+    /// This score uses the same units as `Segmentation::score`.
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+}
+
+/// A directed acyclic graph of all candidate tokens that a search considered.
+///
+/// Each edge in the lattice is a candidate token that spans `start` to `end` and that leads to the
+/// node at `next`. A path of edges from offset 0 to `text().len()`, where each edge starts at the
+/// `next` offset of the edge before it, is a possible segmentation of the input.
+#[derive(Clone, Debug, Default)]
+pub struct Lattice<'a> {
+    text: String,
+    edges: Vec<LatticeEdge<'a>>,
+    index: FxHashMap<(usize, usize, Token<'a>), usize>,
+}
+
+impl<'a> Lattice<'a> {
+    /// Creates an empty lattice over the given normalized text.
+    pub(crate) fn new(text: String) -> Self {
+        Self {
+            text,
+            ..Default::default()
+        }
+    }
+
+    /// The normalized SLP1 text that the search processed.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// All candidate tokens in this lattice, ordered by `start`, then `next`, then best score.
+    pub fn edges(&self) -> &[LatticeEdge<'a>] {
+        &self.edges
+    }
+
+    /// Iterates over all candidate tokens that start at the given offset.
+    pub fn edges_from(&self, start: usize) -> impl Iterator<Item = &LatticeEdge<'a>> {
+        self.edges.iter().filter(move |e| e.start == start)
+    }
+
+    /// Adds a candidate to the lattice.
+    ///
+    /// If we have already seen this candidate between the same nodes, we keep its best score.
+    pub(crate) fn add(
+        &mut self,
+        start: usize,
+        end: usize,
+        next: usize,
+        token: &Token<'a>,
+        score: i32,
+    ) {
+        let key = (start, next, token.clone());
+        match self.index.get(&key) {
+            Some(i) => {
+                let edge = &mut self.edges[*i];
+                edge.score = std::cmp::max(edge.score, score);
+            }
+            None => {
+                self.index.insert(key, self.edges.len());
+                self.edges.push(LatticeEdge {
+                    start,
+                    end,
+                    next,
+                    token: token.clone(),
+                    score,
+                });
+            }
+        }
+    }
+
+    /// Sorts our edges and drops any bookkeeping we needed during the search.
+    pub(crate) fn finish(&mut self) {
+        self.index.clear();
+        self.edges.sort_by(|x, y| {
+            (x.start, x.next)
+                .cmp(&(y.start, y.next))
+                .then(y.score.cmp(&x.score))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use compact_str::CompactString;
+
+    fn token(text: &str) -> Token<'static> {
+        Token {
+            text: CompactString::from(text),
+            data: None,
+            span: None,
+            surface: None,
+            sandhi: None,
+            members: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn add_keeps_best_score() {
+        let mut lattice = Lattice::new("ca iti".to_string());
+        lattice.add(3, 6, 6, &token("iti"), -300);
+        lattice.add(0, 2, 3, &token("ca"), -200);
+        lattice.add(3, 6, 6, &token("iti"), -100);
+        lattice.add(0, 2, 3, &token("c"), -500);
+        lattice.finish();
+
+        let spans: Vec<_> = lattice
+            .edges()
+            .iter()
+            .map(|e| (e.start(), e.end(), e.next(), e.token().text(), e.score()))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (0, 2, 3, "ca", -200),
+                (0, 2, 3, "c", -500),
+                (3, 6, 6, "iti", -100)
+            ]
+        );
+        assert_eq!(lattice.edges_from(3).count(), 1);
+        assert_eq!(lattice.edges_from(1).count(), 0);
+    }
+}
@@ -1,10 +1,21 @@
 mod chedaka;
 mod errors;
+mod features;
+mod input;
+mod lattice;
 mod normalize_text;
+mod samasa;
 mod scoring;
 mod sounds;
 mod strict_mode;
 
-pub use crate::chedaka::{Chedaka, Token};
+pub use crate::chedaka::{Chedaka, Config, Sandhi, Segmentation, Token};
 pub use crate::errors::{Error, Result};
-pub use crate::scoring::{Model, ModelBuilder, POSTag, State};
+pub use crate::features::{
+    extract_features, Feature, FeatureKind, FeatureModel, FeatureModelBuilder, Observation,
+};
+pub use crate::lattice::{Lattice, LatticeEdge};
+pub use crate::samasa::guess_samasa_type;
+pub use crate::scoring::{
+    Context, History, Model, ModelBuilder, POSTag, PriorScorer, Scorer, State,
+};
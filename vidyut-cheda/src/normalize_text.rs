@@ -8,25 +8,36 @@ use regex::Regex;
 /// 1. Separate all input into three kinds of spans: text, whitespace, and symbols.
 /// 2. Delete all whitespace spans.
 /// 3. Separate all remaining spans with a single " ".
-pub fn normalize(text: &str) -> String {
+///
+/// We also return the byte offset in `text` of each byte in our output. The single " " that
+/// separates two spans has the offset where the first span ends.
+pub fn normalize_with_offsets(text: &str) -> (String, Vec<usize>) {
     static RE: OnceLock<Regex> = OnceLock::new();
 
-    let re =
-        RE.get_or_init(|| Regex::new(r"([a-zA-Z']+)|(\s+)|([^a-zA-Z']+)").expect("always defined"));
+    let re = RE
+        .get_or_init(|| Regex::new(r"([a-zA-Z']+)|(\s+)|([^a-zA-Z'\s]+)").expect("always defined"));
 
-    let mut ret = re
-        .find_iter(text)
-        .map(|m| m.as_str())
-        .filter(|s| !s.trim().is_empty())
-        .fold(String::new(), |s, x| s + x + " ");
-    ret.pop();
-    ret
+    let mut ret = String::new();
+    let mut offsets = Vec::new();
+    for m in re.find_iter(text).filter(|m| !m.as_str().trim().is_empty()) {
+        if !ret.is_empty() {
+            ret.push(' ');
+            offsets.push(offsets.last().map_or(0, |i| i + 1));
+        }
+        ret += m.as_str();
+        offsets.extend(m.range());
+    }
+    (ret, offsets)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn normalize(text: &str) -> String {
+        normalize_with_offsets(text).0
+    }
+
     #[test]
     fn test_basic() {
         let cases = vec![
@@ -59,9 +70,24 @@ mod tests {
             ("deva--iti", "deva -- iti"),
             (":deva12345iti!", ": deva 12345 iti !"),
             (":deva  12345iti!", ": deva 12345 iti !"),
+            ("deva. iti", "deva . iti"),
+            ("deva iti! ", "deva iti !"),
         ];
         for (input, output) in cases {
             assert_eq!(normalize(input), output.to_string());
         }
     }
+
+    #[test]
+    fn test_offsets() {
+        let text = "  deva\t\niti! ";
+        let (normalized, offsets) = normalize_with_offsets(text);
+        assert_eq!(normalized, "deva iti !");
+        assert_eq!(offsets, vec![2, 3, 4, 5, 6, 8, 9, 10, 11, 11]);
+        for (c, i) in normalized.chars().zip(&offsets) {
+            if c != ' ' {
+                assert_eq!(text[*i..].chars().next(), Some(c));
+            }
+        }
+    }
 }
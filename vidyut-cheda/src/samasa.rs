@@ -0,0 +1,259 @@
+//! Groups the members of a *samāsa* into a single token.
+//!
+//! Our search splits a *samāsa* into its members: zero or more *pūrvapada*s, which have no
+//! ending of their own, followed by a final member that carries the ending of the whole
+//! *samāsa*. Downstream tools usually want to treat a *samāsa* as a single word, so we can merge
+//! these members into a single token whose data is a `SamasaEntry`.
+use crate::chedaka::Token;
+use crate::input::Input;
+use compact_str::CompactString;
+use vidyut_kosha::entries::{PadaEntry, PratipadikaEntry, SamasaEntry, SubantaEntry};
+use vidyut_prakriya::args::{SamasaType, Vacana};
+
+/// Stems that denote numbers. A *samāsa* that starts with one of these is often a *dvigu*.
+const SANKHYA: &[&str] = &[
+    "eka", "dvi", "tri", "catur", "paYcan", "zaz", "saptan", "azwan", "navan", "daSan",
+];
+
+/// Returns the subanta data for `token`, if it has any.
+fn subanta<'a, 't>(token: &'t Token<'a>) -> Option<&'t SubantaEntry<'a>> {
+    match &token.data {
+        Some(PadaEntry::Subanta(s)) => Some(s),
+        _ => None,
+    }
+}
+
+/// Returns whether `token` can end a *samāsa*.
+fn is_uttarapada(token: &Token) -> bool {
+    match subanta(token) {
+        Some(s) => !s.is_purvapada() && !s.is_avyaya(),
+        None => false,
+    }
+}
+
+/// Guesses the type of a *samāsa* from its members.
+///
+/// This guess uses only a few shallow cues, so treat it as a rough hint:
+///
+/// - If the *samāsa* uses a *liṅga* that its final member doesn't allow, it probably describes
+///   something else, so we guess *bahuvrīhi*.
+/// - If the *samāsa* is dual with two members or plural with more, we guess *dvandva*.
+/// - If the first member is a number, we guess *dvigu*.
+/// - Otherwise, we guess *tatpuruṣa*.
+pub fn guess_samasa_type(padas: &[SubantaEntry]) -> SamasaType {
+    let (first, last) = match (padas.first(), padas.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return SamasaType::Tatpurusha,
+    };
+
+    let lingas = last.pratipadika_entry().lingas();
+    if !lingas.is_empty() && !lingas.contains(&last.linga()) {
+        SamasaType::Bahuvrihi
+    } else if (padas.len() == 2 && last.vacana() == Vacana::Dvi)
+        || (padas.len() > 2 && last.vacana() == Vacana::Bahu)
+    {
+        SamasaType::Dvandva
+    } else if SANKHYA.contains(&first.pratipadika_entry().lemma()) {
+        SamasaType::Dvigu
+    } else {
+        SamasaType::Tatpurusha
+    }
+}
+
+/// Returns the data for a *samāsa* with the given members.
+///
+/// `members` should be zero or more *pūrvapada*s followed by a final member.
+fn samasa_data<'a>(members: &[Token<'a>]) -> Option<SubantaEntry<'a>> {
+    let last = subanta(members.last()?)?;
+    let padas: Vec<SubantaEntry<'a>> = members
+        .iter()
+        .map(|m| subanta(m).cloned())
+        .collect::<Option<_>>()?;
+
+    let samasa_type = guess_samasa_type(&padas);
+    let (linga, vibhakti, vacana) = (last.linga(), last.vibhakti(), last.vacana());
+    let samasa = SamasaEntry::new(padas, samasa_type).ok()?;
+    let data = SubantaEntry::new(PratipadikaEntry::Samasa(samasa), linga, vibhakti, vacana);
+    Some(data)
+}
+
+/// Merges `members` into a single token.
+///
+/// If `members` isn't a valid *samāsa*, we return it unchanged as our error.
+fn merge<'a>(input: &Input, members: Vec<Token<'a>>) -> Result<Token<'a>, Vec<Token<'a>>> {
+    let data = match samasa_data(&members) {
+        Some(data) => data,
+        None => return Err(members),
+    };
+
+    let texts: Vec<&str> = members.iter().map(|m| m.text()).collect();
+    let first_span = members.first().and_then(|m| m.span());
+    let last_span = members.last().and_then(|m| m.span());
+    let span = match (first_span, last_span) {
+        (Some(first), Some(last)) => Some(first.start..last.end),
+        _ => None,
+    };
+    let surface = span
+        .as_ref()
+        .and_then(|s| input.original().get(s.clone()))
+        .map(CompactString::from);
+
+    Ok(Token {
+        text: CompactString::from(texts.join("-")),
+        data: Some(data.into()),
+        span,
+        surface,
+        sandhi: members.last().and_then(|m| m.sandhi.clone()),
+        members,
+    })
+}
+
+/// Merges the members of each *samāsa* in `tokens` into a single token.
+///
+/// If a run of *pūrvapada*s is not followed by a final member, we leave it as is.
+pub(crate) fn group<'a>(input: &Input, tokens: Vec<Token<'a>>) -> Vec<Token<'a>> {
+    let mut ret = Vec::with_capacity(tokens.len());
+    let mut purvapadas: Vec<Token<'a>> = Vec::new();
+
+    for token in tokens {
+        if subanta(&token).is_some_and(|s| s.is_purvapada()) {
+            purvapadas.push(token);
+        } else if !purvapadas.is_empty() && is_uttarapada(&token) {
+            let mut members = std::mem::take(&mut purvapadas);
+            members.push(token);
+            match merge(input, members) {
+                Ok(samasa) => ret.push(samasa),
+                Err(members) => ret.extend(members),
+            }
+        } else {
+            ret.append(&mut purvapadas);
+            ret.push(token);
+        }
+    }
+    ret.append(&mut purvapadas);
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::Range;
+    use vidyut_lipi::Scheme;
+    use vidyut_prakriya::args::{BasicPratipadika, Linga, Pratipadika, Slp1String, Vibhakti};
+
+    const LINGAS: &[Linga] = &[Linga::Pum, Linga::Napumsaka];
+
+    fn basic(text: &str) -> BasicPratipadika {
+        match Pratipadika::basic(Slp1String::from(text).expect("ok")) {
+            Pratipadika::Basic(b) => b,
+            _ => unreachable!(),
+        }
+    }
+
+    fn purvapada(p: &BasicPratipadika) -> SubantaEntry<'_> {
+        SubantaEntry::purvapada(PratipadikaEntry::basic(p, &[]))
+    }
+
+    fn pada(p: &BasicPratipadika, linga: Linga, vacana: Vacana) -> SubantaEntry<'_> {
+        let entry = PratipadikaEntry::basic(p, LINGAS);
+        SubantaEntry::new(entry, linga, Vibhakti::Prathama, vacana)
+    }
+
+    fn token<'a>(text: &str, data: Option<SubantaEntry<'a>>, span: Range<usize>) -> Token<'a> {
+        Token {
+            text: CompactString::from(text),
+            data: data.map(|s| s.into()),
+            span: Some(span),
+            surface: None,
+            sandhi: None,
+            members: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn guess_types() {
+        use Linga::*;
+        use Vacana::*;
+
+        let [bahu, dhana, rama, lakshmana, tri, bhuvana, raja, purusha] = [
+            "bahu", "Dana", "rAma", "lakzmaRa", "tri", "Buvana", "rAja", "puruza",
+        ]
+        .map(basic);
+
+        let cases = [
+            (
+                vec![purvapada(&bahu), pada(&dhana, Stri, Eka)],
+                SamasaType::Bahuvrihi,
+            ),
+            (
+                vec![purvapada(&rama), pada(&lakshmana, Pum, Dvi)],
+                SamasaType::Dvandva,
+            ),
+            (
+                vec![purvapada(&tri), pada(&bhuvana, Napumsaka, Eka)],
+                SamasaType::Dvigu,
+            ),
+            (
+                vec![purvapada(&raja), pada(&purusha, Pum, Eka)],
+                SamasaType::Tatpurusha,
+            ),
+            (Vec::new(), SamasaType::Tatpurusha),
+        ];
+        for (padas, expected) in cases {
+            assert_eq!(guess_samasa_type(&padas), expected);
+        }
+    }
+
+    #[test]
+    fn group_members() {
+        let [raja, purusha, deva] = ["rAja", "puruza", "deva"].map(basic);
+        let input = Input::new("rAjapuruzo deva", Some(Scheme::Slp1)).expect("ok");
+        let tokens = vec![
+            token("rAja", Some(purvapada(&raja)), 0..4),
+            token(
+                "puruzaH",
+                Some(pada(&purusha, Linga::Pum, Vacana::Eka)),
+                4..10,
+            ),
+            token("deva", Some(purvapada(&deva)), 11..15),
+        ];
+
+        let grouped = group(&input, tokens.clone());
+        assert_eq!(grouped.len(), 2);
+
+        let samasa = &grouped[0];
+        assert_eq!(samasa.text(), "rAja-puruzaH");
+        assert_eq!(samasa.members(), &tokens[..2]);
+        assert_eq!(samasa.span(), Some(0..10));
+        assert_eq!(samasa.surface(), Some("rAjapuruzo"));
+        assert_eq!(samasa.lemma(), "puruza");
+        match samasa.data() {
+            Some(PadaEntry::Subanta(s)) => {
+                assert_eq!(s.vibhakti(), Vibhakti::Prathama);
+                match s.pratipadika_entry() {
+                    PratipadikaEntry::Samasa(x) => {
+                        assert_eq!(x.samasa_type(), SamasaType::Tatpurusha);
+                        assert_eq!(x.padas().len(), 2);
+                        assert!(x.padas()[0].is_purvapada());
+                    }
+                    _ => panic!("expected samasa"),
+                }
+            }
+            _ => panic!("expected subanta"),
+        }
+
+        // A trailing purvapada has no final member, so we leave it as is.
+        assert_eq!(&grouped[1], &tokens[2]);
+    }
+
+    #[test]
+    fn group_without_final_member() {
+        let raja = basic("rAja");
+        let input = Input::new("rAja ca", Some(Scheme::Slp1)).expect("ok");
+        let tokens = vec![
+            token("rAja", Some(purvapada(&raja)), 0..4),
+            token("ca", None, 5..7),
+        ];
+        assert_eq!(group(&input, tokens.clone()), tokens);
+    }
+}
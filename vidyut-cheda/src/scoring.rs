@@ -1,6 +1,10 @@
 //! A simple statistical model.
+//!
+//! `Chedaka` scores its candidate segmentations with a `Scorer`. Our default scorer is `Model`,
+//! which combines a bigram model over coarse `State`s with a lemma unigram model. For richer
+//! models, see `FeatureModel`.
 
-use crate::chedaka::{Config, Phrase, TokenPool};
+use crate::chedaka::{Config, Phrase, Token, TokenPool};
 use crate::errors::Result;
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
@@ -64,14 +68,18 @@ impl State {
     }
 }
 
+/// Returns the state of a token that `Chedaka` found in its kosha.
+///
+/// This conversion must agree with `State::from_pada`, which we use on our training data.
+/// Otherwise, we score tokens with probabilities that we learned for some other state.
 impl<'a> From<&Option<PadaEntry<'a>>> for State {
     fn from(val: &Option<PadaEntry>) -> Self {
         match val {
             Some(PadaEntry::Subanta(s)) => {
                 if s.pratipadika_entry().is_avyaya() {
-                    State::Subanta(s.linga(), s.vibhakti(), s.vacana())
-                } else {
                     State::Avyaya
+                } else {
+                    State::Subanta(s.linga(), s.vibhakti(), s.vacana())
                 }
             }
             Some(PadaEntry::Tinanta(t)) => State::Tinanta(t.purusha(), t.vacana()),
@@ -80,6 +88,190 @@ impl<'a> From<&Option<PadaEntry<'a>>> for State {
     }
 }
 
+/// The tokens in a partial segmentation, as seen by a `Scorer`.
+#[derive(Clone, Copy)]
+pub struct History<'s, 'a> {
+    tokens: &'s [usize],
+    pool: &'s TokenPool<'a>,
+}
+
+impl<'s, 'a> History<'s, 'a> {
+    pub(crate) fn new(tokens: &'s [usize], pool: &'s TokenPool<'a>) -> Self {
+        Self { tokens, pool }
+    }
+
+    /// The number of tokens in this history.
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Returns whether this history has no tokens.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Returns the token `i` positions before the newest token. `back(0)` is the newest token.
+    pub fn back(&self, i: usize) -> Option<&'s Token<'a>> {
+        let n = self.tokens.len();
+        if i < n {
+            self.pool.get(self.tokens[n - 1 - i])
+        } else {
+            None
+        }
+    }
+
+    /// Returns the prior probability of the analysis of the token `i` positions before the newest
+    /// token, as estimated from the weights in our kosha.
+    ///
+    /// This probability is relative to the other analyses of the same text. If the kosha has no
+    /// weights for the token's text, we return `None`.
+    pub fn prior(&self, i: usize) -> Option<f32> {
+        let n = self.tokens.len();
+        if i < n {
+            self.pool.prior(self.tokens[n - 1 - i])
+        } else {
+            None
+        }
+    }
+}
+
+/// The parts of a partial segmentation that a `Scorer` reads when it scores later tokens.
+///
+/// Each `Scorer` builds its context by pushing the states, texts, and flags that it reads, in
+/// some fixed order. Two contexts are equal only if they have the same values in the same order.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Context(Vec<ContextValue>);
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum ContextValue {
+    State(State),
+    Text(String),
+    Flag(bool),
+}
+
+impl Context {
+    /// Creates an empty context.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a state to this context.
+    pub fn push_state(&mut self, state: State) {
+        self.0.push(ContextValue::State(state));
+    }
+
+    /// Adds some text, such as a lemma, to this context.
+    pub fn push_text(&mut self, text: &str) {
+        self.0.push(ContextValue::Text(text.to_string()));
+    }
+
+    /// Adds a flag to this context.
+    pub fn push_flag(&mut self, flag: bool) {
+        self.0.push(ContextValue::Flag(flag));
+    }
+}
+
+/// Scores the tokens in a partial segmentation.
+///
+/// `Chedaka` calls `log_prob` each time it adds a token to a partial segmentation and adds the
+/// result to that segmentation's score. Our search assumes that scores never increase as we add
+/// more tokens, so `log_prob` should never return a positive value. Otherwise, we might stop
+/// searching before we find the best segmentation.
+///
+/// Our search also merges partial segmentations that have the same remaining text and the same
+/// `context`, and it keeps only the best few of them. So `context` must record everything in a
+/// history that `log_prob` might read when it scores the tokens that follow. Otherwise, we might
+/// discard the best segmentation.
+pub trait Scorer: Send + Sync {
+    /// Returns the log probability (base 10) of the newest token in `history` given the tokens
+    /// before it.
+    fn log_prob(&self, history: &History) -> f32;
+
+    /// Returns everything in `history` that this scorer reads when it scores a new token after
+    /// it. Our search treats two histories with the same context as interchangeable.
+    ///
+    /// By default, the context is the state of the newest token, which suits a bigram model over
+    /// states like `Model`.
+    fn context(&self, history: &History) -> Context {
+        let mut context = Context::new();
+        context.push_state(history.back(0).map_or(State::Initial, |t| t.data().into()));
+        context
+    }
+}
+
+/// Scores the given phrase by adding the score of its newest token to its current score.
+///
+/// We return our float score as an i32 because floats aren't hashed by default in Rust. To
+/// represent "floatness," multiply the float score by 100 so that the ones and tens places
+/// represent the tenths and hundredths places, respectively.
+pub(crate) fn score(scorer: &dyn Scorer, phrase: &Phrase, pool: &TokenPool) -> i32 {
+    let delta = if phrase.tokens.is_empty() {
+        0.0
+    } else {
+        scorer.log_prob(&History::new(&phrase.tokens, pool))
+    };
+
+    // To simplify the scoring, assume that:
+    //
+    //     P(W[0], ..., W[n]) = P(W[0], ..., W[n-1]) * P(W[n] | W[0], ..., W[n-1])
+    phrase.score + (100_f32 * delta) as i32
+}
+
+/// Adds the prior probabilities from our kosha's weights to the scores of some other `Scorer`.
+///
+/// Kosha weights usually come from corpus counts (see `Builder::set_pada_weight` in
+/// `vidyut-kosha`), so they help us choose among the analyses of an ambiguous word. Tokens
+/// without a prior keep the score from the inner scorer.
+///
+/// # Usage
+///
+/// ```no_run
+/// use std::path::Path;
+/// use vidyut_cheda::{Chedaka, Config, Model, PriorScorer};
+///
+/// let path = Path::new("/path/to/vidyut/data");
+/// let model = Model::read(&Config::new(path).model_path())?;
+/// let chedaka = Chedaka::new(path)?.with_scorer(PriorScorer::new(model));
+/// # Ok::<(), vidyut_cheda::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct PriorScorer<S> {
+    scorer: S,
+    scale: f32,
+}
+
+impl<S: Scorer> PriorScorer<S> {
+    /// Creates a scorer that adds kosha priors to the scores of `scorer`.
+    pub fn new(scorer: S) -> Self {
+        Self { scorer, scale: 1.0 }
+    }
+
+    /// Sets how much the kosha priors count relative to the inner scorer. The default is 1.
+    ///
+    /// Our search assumes that scores never increase, so `scale` should not be negative.
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+}
+
+impl<S: Scorer> Scorer for PriorScorer<S> {
+    fn log_prob(&self, history: &History) -> f32 {
+        let log_prob = self.scorer.log_prob(history);
+        match history.prior(0) {
+            Some(p) => log_prob + self.scale * p.log10(),
+            None => log_prob,
+        }
+    }
+
+    /// The prior of a token doesn't depend on the tokens before it, so our context is the same
+    /// as our inner scorer's.
+    fn context(&self, history: &History) -> Context {
+        self.scorer.context(history)
+    }
+}
+
+/// A bigram model over `State`s combined with a lemma unigram model.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Model {
     /// Internal map of states to small codes.
@@ -100,7 +292,7 @@ pub struct Model {
 }
 
 /// Calculates the log probability given some numerator and denominator.
-fn log_prob(num: f64, denom: f64) -> f32 {
+pub(crate) fn log_prob(num: f64, denom: f64) -> f32 {
     (num.log10() - denom.log10()) as f32
 }
 
@@ -157,58 +349,6 @@ impl Model {
         Some(*log_prob)
     }
 
-    /// Scores the given phrase by using lemma probabilities.
-    ///
-    /// We return our float score as an i32 because floats aren't hashed by default in Rust. To
-    /// represent "floatness," multiply the float score by 100 so that the ones and tens places
-    /// represent the tenths and hundredths places, respectively.
-    pub(crate) fn score(&self, phrase: &Phrase, pool: &TokenPool) -> i32 {
-        let delta = if let Some(i_last) = phrase.tokens.last() {
-            let n = phrase.tokens.len();
-            let prev_state = if n >= 2 {
-                let i = phrase.tokens[n - 2];
-                self.to_state_code(pool.get(i).expect("present").data())
-            } else {
-                StateCode(0)
-            };
-
-            let last = pool.get(*i_last).expect("present");
-            let cur_state = self.to_state_code(last.data());
-
-            let maybe_lemma_log_prob = {
-                let pada = last.data();
-                let state: State = pada.into();
-                let lemma = match pada {
-                    Some(p) => p.lemma().unwrap_or(""),
-                    None => "",
-                };
-                self.lemma_log_probability(lemma, state.pos_tag())
-            };
-
-            let lemma_log_prob = match maybe_lemma_log_prob {
-                Some(p) => p,
-                None => self.lemma_log_p_unknown,
-            };
-
-            let transition_log_prob = {
-                let key = (prev_state, cur_state);
-                match self.transition_probs.get(&key) {
-                    Some(s) => *s,
-                    None => self.transition_log_epsilon,
-                }
-            };
-
-            lemma_log_prob + transition_log_prob
-        } else {
-            0.0
-        };
-
-        // To simplify the scoring, assume that:
-        //
-        //     P(W[0], ..., W[n]) = P(W[0], ..., W[n-1]) * P(W[n] | W[0], ..., W[n-1])
-        phrase.score + (100_f32 * delta) as i32
-    }
-
     /// Packs a PadaEntry into a `StateCode`.
     pub(crate) fn to_state_code(&self, entry: &Option<PadaEntry>) -> StateCode {
         let state: State = entry.into();
@@ -220,6 +360,45 @@ impl Model {
     }
 }
 
+impl Scorer for Model {
+    fn log_prob(&self, history: &History) -> f32 {
+        let last = match history.back(0) {
+            Some(t) => t,
+            None => return 0.0,
+        };
+        let prev_state = match history.back(1) {
+            Some(t) => self.to_state_code(t.data()),
+            None => StateCode(0),
+        };
+        let cur_state = self.to_state_code(last.data());
+
+        let maybe_lemma_log_prob = {
+            let pada = last.data();
+            let state: State = pada.into();
+            let lemma = match pada {
+                Some(p) => p.lemma().unwrap_or(""),
+                None => "",
+            };
+            self.lemma_log_probability(lemma, state.pos_tag())
+        };
+
+        let lemma_log_prob = match maybe_lemma_log_prob {
+            Some(p) => p,
+            None => self.lemma_log_p_unknown,
+        };
+
+        let transition_log_prob = {
+            let key = (prev_state, cur_state);
+            match self.transition_probs.get(&key) {
+                Some(s) => *s,
+                None => self.transition_log_epsilon,
+            }
+        };
+
+        lemma_log_prob + transition_log_prob
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct ModelBuilder {
     /// Freq(`state[n]` | `state[n-1]`).
@@ -339,6 +518,33 @@ impl ModelBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use vidyut_prakriya::args::{Pratipadika, Slp1String, Subanta};
+
+    #[test]
+    fn state_from_pada_entry() {
+        let safe = |s: &str| Slp1String::from(s).expect("ok");
+        let ca = Subanta::avyaya(Pratipadika::avyaya(safe("ca")));
+        let devas = Subanta::new(
+            Pratipadika::basic(safe("deva")),
+            Linga::Pum,
+            Vibhakti::Prathama,
+            Vacana::Eka,
+        );
+
+        let cases = [
+            (ca, State::Avyaya),
+            (
+                devas,
+                State::Subanta(Linga::Pum, Vibhakti::Prathama, Vacana::Eka),
+            ),
+        ];
+        for (pada, state) in cases {
+            let entry = Some(PadaEntry::Subanta((&pada).try_into().expect("ok")));
+            assert_eq!(State::from(&entry), state);
+            assert_eq!(State::from_pada(&pada.into()), state);
+        }
+        assert_eq!(State::from(&None), State::Unknown);
+    }
 
     #[test]
     fn test_log_prob() {
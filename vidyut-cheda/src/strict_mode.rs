@@ -105,6 +105,10 @@ mod tests {
         token_pool.insert(Token {
             text: CompactString::from("tatra"),
             data: data.clone(),
+            span: None,
+            surface: None,
+            sandhi: None,
+            members: Vec::new(),
         });
         assert!(is_valid_word(&cur, &token_pool, &split, &data));
     }
@@ -129,6 +133,10 @@ mod tests {
         token_pool.insert(Token {
             text: CompactString::from("grAme"),
             data: data.clone(),
+            span: None,
+            surface: None,
+            sandhi: None,
+            members: Vec::new(),
         });
         assert!(!is_valid_word(&cur, &token_pool, &split, &data));
     }
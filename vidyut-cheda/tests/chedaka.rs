@@ -0,0 +1,341 @@
+//! End-to-end tests for `Chedaka` on a tiny lexicon.
+use std::fs;
+use std::path::Path;
+use tempfile::{tempdir, TempDir};
+use vidyut_cheda::{
+    Chedaka, Config, FeatureModel, FeatureModelBuilder, Model, ModelBuilder, Observation,
+    PriorScorer, State, Token,
+};
+use vidyut_kosha::entries::{DhatuEntry, PadaEntry, PratipadikaEntry, SubantaEntry, TinantaEntry};
+use vidyut_kosha::Builder;
+use vidyut_lipi::Scheme;
+use vidyut_prakriya::args::{
+    BasicPratipadika, Dhatu, Gana, Lakara, Linga, Pratipadika, Prayoga, Purusha, SamasaType,
+    Slp1String, Vacana, Vibhakti,
+};
+use vidyut_sandhi::Kind;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn safe(s: &str) -> Slp1String {
+    Slp1String::from(s).expect("ok")
+}
+
+fn basic(text: &str) -> BasicPratipadika {
+    match Pratipadika::basic(safe(text)) {
+        Pratipadika::Basic(b) => b,
+        _ => unreachable!(),
+    }
+}
+
+fn write_kosha(path: &Path) -> Result<()> {
+    let ca = Pratipadika::avyaya(safe("ca"));
+    let iti = Pratipadika::avyaya(safe("iti"));
+    let deva = basic("deva");
+    let vana = basic("vana");
+    let rajan = basic("rAjan");
+    let gam = Dhatu::mula(safe("gam"), Gana::Bhvadi);
+
+    let ca_entry: PratipadikaEntry = (&ca).try_into()?;
+    let iti_entry: PratipadikaEntry = (&iti).try_into()?;
+    let deva_entry = PratipadikaEntry::basic(&deva, &[Linga::Pum]);
+    let vana_entry = PratipadikaEntry::basic(&vana, &[Linga::Napumsaka]);
+    let rajan_entry = PratipadikaEntry::basic(&rajan, &[Linga::Pum]);
+    let gam_entry = DhatuEntry::new(&gam);
+
+    let mut words: Vec<(&str, PadaEntry)> = vec![
+        ("ca", SubantaEntry::avyaya(ca_entry).into()),
+        ("iti", SubantaEntry::avyaya(iti_entry).into()),
+        ("rAja", SubantaEntry::purvapada(rajan_entry).into()),
+        (
+            "devaH",
+            SubantaEntry::new(deva_entry, Linga::Pum, Vibhakti::Prathama, Vacana::Eka).into(),
+        ),
+        (
+            "gacCati",
+            TinantaEntry::new(
+                gam_entry.clone(),
+                Prayoga::Kartari,
+                Lakara::Lat,
+                Purusha::Prathama,
+                Vacana::Eka,
+            )
+            .into(),
+        ),
+    ];
+    for vibhakti in [Vibhakti::Prathama, Vibhakti::Dvitiya] {
+        let entry = SubantaEntry::new(vana_entry.clone(), Linga::Napumsaka, vibhakti, Vacana::Eka);
+        words.push(("vanam", entry.into()));
+    }
+    words.sort_by_key(|(key, _)| *key);
+
+    let mut builder = Builder::new(path)?;
+    builder.register_dhatu_entry(&gam_entry);
+    for (key, entry) in &words {
+        builder.register_pada_entry(entry);
+        builder.insert(key, entry)?;
+    }
+    // As if *vanam* were more common as an object than as a subject.
+    let vanam_2 = SubantaEntry::new(vana_entry, Linga::Napumsaka, Vibhakti::Dvitiya, Vacana::Eka);
+    builder.set_pada_weight(&vanam_2.into(), 8.0)?;
+    builder.finish()?;
+    Ok(())
+}
+
+fn write_model(path: &Path) -> Result<()> {
+    let mut builder = ModelBuilder::new();
+    let deva = State::Subanta(Linga::Pum, Vibhakti::Prathama, Vacana::Eka);
+    let gacchati = State::Tinanta(Purusha::Prathama, Vacana::Eka);
+    builder.count_transition(State::Initial, deva);
+    builder.count_transition(deva, gacchati);
+    // A purvapada has the same state as `deva` in our training data.
+    builder.count_transition(deva, deva);
+    builder.count_transition(State::Initial, State::Avyaya);
+    builder.count_transition(State::Avyaya, State::Avyaya);
+    let vanam_1 = State::Subanta(Linga::Napumsaka, Vibhakti::Prathama, Vacana::Eka);
+    let vanam_2 = State::Subanta(Linga::Napumsaka, Vibhakti::Dvitiya, Vacana::Eka);
+    builder.count_transition(State::Initial, vanam_1);
+    builder.count_transition(State::Initial, vanam_2);
+    for (state, lemma) in [
+        (deva, "deva"),
+        (deva, "rAjan"),
+        (gacchati, "gam"),
+        (State::Avyaya, "ca"),
+        (State::Avyaya, "iti"),
+        (vanam_1, "vana"),
+        (vanam_2, "vana"),
+    ] {
+        builder.count_emission(state, lemma.to_string());
+    }
+    builder.write_model(path)?;
+    Ok(())
+}
+
+/// Creates a data directory with a small sandhi table, lexicon, and model.
+fn create_chedaka() -> Result<(TempDir, Chedaka)> {
+    let dir = tempdir()?;
+    fs::create_dir_all(dir.path().join("sandhi"))?;
+    fs::write(
+        dir.path().join("sandhi/rules.csv"),
+        "first,second,result\na,i,e\naH,g,o g\n",
+    )?;
+    write_kosha(&dir.path().join("kosha"))?;
+    write_model(dir.path())?;
+
+    let chedaka = Chedaka::new(dir.path())?;
+    Ok((dir, chedaka))
+}
+
+fn texts(tokens: &[Token]) -> Vec<String> {
+    tokens.iter().map(|t| t.text().to_string()).collect()
+}
+
+#[test]
+fn run() -> Result<()> {
+    let (_dir, chedaka) = create_chedaka()?;
+
+    let tokens = chedaka.run("devo gacCati")?;
+    assert_eq!(texts(&tokens), vec!["devaH", "gacCati"]);
+    assert_eq!(tokens[0].lemma(), "deva");
+    assert_eq!(tokens[0].span(), Some(0..4));
+    assert_eq!(tokens[1].span(), Some(5..12));
+    assert_eq!(tokens[0].surface(), Some("devo"));
+    assert_eq!(tokens[1].surface(), Some("gacCati"));
+
+    let sandhi = tokens[0].sandhi().expect("present");
+    assert_eq!(
+        (sandhi.first(), sandhi.second(), sandhi.text()),
+        ("devaH", "gacCati", "devo gacCati")
+    );
+    assert_eq!(sandhi.change(), ("aH", "", "o"));
+    assert_eq!(sandhi.kind(), Kind::Standard);
+    let sandhi = tokens[1].sandhi().expect("present");
+    assert_eq!(sandhi.kind(), Kind::Prefix);
+    assert_eq!(sandhi.change(), ("", "", ""));
+
+    let tokens = chedaka.run("ceti")?;
+    assert_eq!(texts(&tokens), vec!["ca", "iti"]);
+    Ok(())
+}
+
+#[test]
+fn run_n_best() -> Result<()> {
+    let (_dir, chedaka) = create_chedaka()?;
+
+    let results = chedaka.run_n_best("vanam", 5)?;
+    assert_eq!(results.len(), 3);
+    assert!(results.windows(2).all(|w| w[0].score() >= w[1].score()));
+
+    // Both analyses of `vanam` are better than skipping the word entirely.
+    let mut vibhaktis = Vec::new();
+    for result in &results[..2] {
+        assert_eq!(result.tokens().len(), 1);
+        match result.tokens()[0].data() {
+            Some(PadaEntry::Subanta(s)) => vibhaktis.push(s.vibhakti()),
+            _ => panic!("unexpected data"),
+        }
+    }
+    assert_ne!(vibhaktis[0], vibhaktis[1]);
+    assert!(results[2].tokens()[0].data().is_none());
+
+    // The best result is the same as `run`.
+    assert_eq!(results[0].tokens(), chedaka.run("vanam")?.as_slice());
+    assert_eq!(chedaka.run_n_best("vanam", 1)?.len(), 1);
+    assert!(chedaka.run_n_best("vanam", 0)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn lattice() -> Result<()> {
+    let (_dir, chedaka) = create_chedaka()?;
+
+    let lattice = chedaka.lattice("devo gacCati", 1)?;
+    assert_eq!(lattice.text(), "devo gacCati");
+
+    let spans: Vec<_> = lattice
+        .edges()
+        .iter()
+        .filter(|e| e.token().data().is_some())
+        .map(|e| (e.start(), e.end(), e.next(), e.token().text()))
+        .collect();
+    assert_eq!(spans, vec![(0, 4, 5, "devaH"), (5, 12, 12, "gacCati")]);
+
+    // Each edge leads to the start of the next, so we can walk a full path through the lattice.
+    let mut offset = 0;
+    let mut path = Vec::new();
+    while offset < lattice.text().len() {
+        let edge = lattice
+            .edges_from(offset)
+            .find(|e| e.token().data().is_some())
+            .expect("present");
+        path.push(edge.token().text());
+        offset = edge.next();
+    }
+    assert_eq!(offset, lattice.text().len());
+    assert_eq!(path, vec!["devaH", "gacCati"]);
+
+    // The best path through the lattice has the same score as the best segmentation.
+    let best = chedaka.run_n_best("devo gacCati", 1)?;
+    let last = lattice
+        .edges_from(5)
+        .find(|e| e.token().text() == "gacCati")
+        .expect("present");
+    assert_eq!(last.score(), best[0].score());
+    Ok(())
+}
+
+#[test]
+fn non_ascii_text() -> Result<()> {
+    let (_dir, chedaka) = create_chedaka()?;
+    assert!(chedaka.run("देवो गच्छति").is_err());
+    assert!(chedaka.run_n_best("देवो गच्छति", 2).is_err());
+    assert!(chedaka.lattice("देवो गच्छति", 2).is_err());
+    Ok(())
+}
+
+#[test]
+fn run_with_input_scheme() -> Result<()> {
+    let original = "देवो गच्छति";
+    for scheme in [Some(Scheme::Devanagari), None] {
+        let (_dir, chedaka) = create_chedaka()?;
+        let chedaka = chedaka.with_input_scheme(scheme);
+
+        let tokens = chedaka.run(original)?;
+        assert_eq!(texts(&tokens), vec!["devaH", "gacCati"]);
+
+        let spans: Vec<_> = tokens
+            .iter()
+            .map(|t| &original[t.span().expect("present")])
+            .collect();
+        assert_eq!(spans, vec!["देवो", "गच्छति"]);
+        let surfaces: Vec<_> = tokens.iter().map(|t| t.surface()).collect();
+        assert_eq!(surfaces, vec![Some("देवो"), Some("गच्छति")]);
+    }
+    Ok(())
+}
+
+#[test]
+fn run_with_feature_model() -> Result<()> {
+    let (dir, chedaka) = create_chedaka()?;
+
+    let deva = State::Subanta(Linga::Pum, Vibhakti::Prathama, Vacana::Eka);
+    let gacchati = State::Tinanta(Purusha::Prathama, Vacana::Eka);
+    let obs = |state, lemma: &str| Observation {
+        state,
+        lemma: lemma.to_string(),
+        ..Default::default()
+    };
+    let mut builder = FeatureModelBuilder::new();
+    builder.count_sentence(&[obs(deva, "deva"), obs(gacchati, "gam")]);
+    builder.count_sentence(&[obs(State::Avyaya, "ca"), obs(State::Avyaya, "iti")]);
+    builder.write_model(dir.path())?;
+
+    let model = FeatureModel::read(&Config::new(dir.path()).feature_model_path())?;
+    let chedaka = chedaka.with_scorer(model);
+    assert_eq!(
+        texts(&chedaka.run("devo gacCati")?),
+        vec!["devaH", "gacCati"]
+    );
+    assert_eq!(texts(&chedaka.run("ceti")?), vec!["ca", "iti"]);
+
+    let results = chedaka.run_n_best("devo gacCati", 2)?;
+    assert!(results[0].score() > results[1].score());
+    Ok(())
+}
+
+#[test]
+fn run_with_priors() -> Result<()> {
+    let (dir, chedaka) = create_chedaka()?;
+
+    let model = Model::read(&Config::new(dir.path()).model_path())?;
+    let chedaka = chedaka.with_scorer(PriorScorer::new(model));
+
+    // The model scores both analyses of `vanam` equally, so the kosha's weights decide.
+    let results = chedaka.run_n_best("vanam", 2)?;
+    assert!(results[0].score() > results[1].score());
+    match results[0].tokens()[0].data() {
+        Some(PadaEntry::Subanta(s)) => assert_eq!(s.vibhakti(), Vibhakti::Dvitiya),
+        _ => panic!("unexpected data"),
+    }
+
+    // Words without weights are unaffected.
+    assert_eq!(
+        texts(&chedaka.run("devo gacCati")?),
+        vec!["devaH", "gacCati"]
+    );
+    Ok(())
+}
+
+#[test]
+fn run_with_samasa_grouping() -> Result<()> {
+    let (_dir, chedaka) = create_chedaka()?;
+    let tokens = chedaka.run("rAjadevo gacCati")?;
+    assert_eq!(texts(&tokens), vec!["rAja", "devaH", "gacCati"]);
+    assert!(!tokens[0].is_samasa());
+
+    let chedaka = chedaka.with_samasa_grouping(true);
+    let tokens = chedaka.run("rAjadevo gacCati")?;
+    assert_eq!(texts(&tokens), vec!["rAja-devaH", "gacCati"]);
+
+    let samasa = &tokens[0];
+    assert!(samasa.is_samasa());
+    assert_eq!(texts(samasa.members()), vec!["rAja", "devaH"]);
+    assert_eq!(samasa.lemma(), "deva");
+    assert_eq!(samasa.span(), Some(0..8));
+    assert_eq!(samasa.surface(), Some("rAjadevo"));
+    assert_eq!(samasa.sandhi(), samasa.members()[1].sandhi());
+    match samasa.data() {
+        Some(PadaEntry::Subanta(s)) => {
+            assert_eq!(s.vibhakti(), Vibhakti::Prathama);
+            match s.pratipadika_entry() {
+                PratipadikaEntry::Samasa(x) => {
+                    assert_eq!(x.samasa_type(), SamasaType::Tatpurusha);
+                    assert!(x.padas()[0].is_purvapada());
+                }
+                _ => panic!("expected samasa"),
+            }
+        }
+        _ => panic!("expected subanta"),
+    }
+    Ok(())
+}
@@ -1,11 +1,14 @@
 //! Creates a kosha of Sanskrit words and writes the results to disk.
 use clap::Parser;
+use glob::glob;
 use log::info;
 use rayon::prelude::*;
 use serde::Deserialize;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process;
+use vidyut_data::conllu::Reader;
+use vidyut_data::dcs;
 use vidyut_kosha::entries::{
     DhatuEntry, DhatuMeta, PadaEntry, PratipadikaEntry, SubantaEntry, TinantaEntry,
 };
@@ -43,6 +46,11 @@ struct Args {
 
     #[arg(long, value_delimiter = ',')]
     filters: Vec<String>,
+
+    /// CoNLL-U files from the Digital Corpus of Sanskrit. If set, we weight each pada by the
+    /// number of times it appears in these files.
+    #[arg(long, num_args=1..)]
+    dcs: Vec<String>,
 }
 
 /// Defines all of the input data paths we need to construct the kosha.
@@ -729,6 +737,35 @@ fn create_basic_subantas(builder: &mut Builder, all_pratipadikas: &Pratipadikas)
     entries
 }
 
+/// Creates *pūrvapada* entries so that we can segment the members of a *samāsa*.
+///
+/// A *pūrvapada* has the form of a *pada*, so we drop a stem-final `n` per 8.2.7 (*nalopaḥ
+/// prātipadikāntasya*), e.g. `rAjan` -> `rAja`. We skip all other consonant stems, since their
+/// *pada* forms need rules that we don't apply here.
+fn create_purvapadas(builder: &Builder, all_pratipadikas: &Pratipadikas) -> Entries {
+    let mut ret = Vec::new();
+    for (_, phit) in all_pratipadikas {
+        let b = match phit {
+            Pratipadika::Basic(b) => b,
+            _ => continue,
+        };
+
+        let stem = b.text();
+        let text = if let Some(prefix) = stem.strip_suffix('n') {
+            prefix
+        } else if stem.ends_with(|c| "aAiIuUfFxeEoO".contains(c)) {
+            stem
+        } else {
+            continue;
+        };
+
+        let entry = SubantaEntry::purvapada(phit.try_into().expect("ok"));
+        let packed_entry = builder.pack(&entry.into()).expect("ok");
+        ret.push((text.to_string(), packed_entry));
+    }
+    ret
+}
+
 fn read_basic_avyayas(builder: &mut Builder, path: &Path) -> Result<Pratipadikas> {
     let mut ret = Pratipadikas::new();
 
@@ -873,6 +910,49 @@ fn read_gatis(builder: &mut Builder, path: &Path) -> Result<Pratipadikas> {
     Ok(ret)
 }
 
+/// Counts the padas in the given DCS files. Each key is a pada and whether it is a purvapada.
+fn count_dcs_padas(patterns: &[String]) -> Result<HashMap<(Pada, bool), u32>> {
+    let mut counts = HashMap::new();
+    for pattern in patterns {
+        for path in glob(pattern)?.flatten() {
+            let reader = Reader::from_path(&path)?;
+            for sentence in reader {
+                for token in &sentence.tokens {
+                    if let Ok((_, pada)) = dcs::standardize(token) {
+                        let key = (pada, dcs::is_purvapada(token));
+                        *counts.entry(key).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+    }
+    Ok(counts)
+}
+
+/// Sets the weight of each pada in the kosha to the number of times it appears in the DCS.
+///
+/// DCS padas whose lemmas are not in the kosha are skipped.
+fn add_dcs_weights(builder: &mut Builder, counts: &HashMap<(Pada, bool), u32>) -> usize {
+    let mut num_weights = 0;
+    for ((pada, is_purvapada), count) in counts {
+        // A purvapada has no ending, so the DCS's vibhakti and vacana don't apply.
+        let entry = match pada {
+            Pada::Subanta(s) if *is_purvapada => s
+                .pratipadika()
+                .try_into()
+                .map(|p| SubantaEntry::purvapada(p).into()),
+            _ => PadaEntry::try_from(pada),
+        };
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if builder.set_pada_weight(&entry, *count as f32).is_ok() {
+            num_weights += 1;
+        }
+    }
+    num_weights
+}
+
 fn run(args: Args) -> Result<()> {
     let paths = DataPaths::new(&args.input_dir);
 
@@ -942,6 +1022,10 @@ fn run(args: Args) -> Result<()> {
             );
             entries.extend(basic_subantas);
 
+            let purvapadas = create_purvapadas(&builder, &basic_pratipadikas);
+            info!("Created {} purvapadas.", purvapadas.len());
+            entries.extend(purvapadas);
+
             let sarvanamas = create_sarvanamas(&mut builder);
             entries.extend(sarvanamas);
         }
@@ -977,6 +1061,16 @@ fn run(args: Args) -> Result<()> {
         num_entries += 1;
     }
 
+    if !args.dcs.is_empty() {
+        info!("Counting DCS padas.");
+        let counts = count_dcs_padas(&args.dcs)?;
+        let num_weights = add_dcs_weights(&mut builder, &counts);
+        info!(
+            "Added weights for {num_weights} of {} DCS padas.",
+            counts.len()
+        );
+    }
+
     info!("Finishing build.");
     builder.finish()?;
 
@@ -152,6 +152,21 @@ fn create_pratipadika_entry_str(p: &PratipadikaEntry) -> String {
         PratipadikaEntry::Krdanta(k) => {
             format!("(Krdanta {})", create_krdanta_entry_str(&k))
         }
+        PratipadikaEntry::Taddhitanta(t) => {
+            format!(
+                "(Taddhitanta {} + {})",
+                create_pratipadika_entry_str(t.pratipadika_entry()),
+                t.taddhita().as_str()
+            )
+        }
+        PratipadikaEntry::Samasa(s) => {
+            let padas: Vec<_> = s
+                .padas()
+                .iter()
+                .map(|p| create_pratipadika_entry_str(p.pratipadika_entry()))
+                .collect();
+            format!("(Samasa {:?} {})", s.samasa_type(), padas.join(" + "))
+        }
     }
 }
 
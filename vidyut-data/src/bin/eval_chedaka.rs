@@ -1,12 +1,12 @@
 //! Evaluate our segmenter against some standard input data.
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use glob::glob;
 use std::error::Error;
 use std::ops::AddAssign;
 use std::path::{Path, PathBuf};
 
-use vidyut_cheda::{Chedaka, Token};
+use vidyut_cheda::{Chedaka, Config, FeatureModel, Token};
 use vidyut_data::conllu::Reader;
 use vidyut_data::dcs;
 use vidyut_kosha::entries::*;
@@ -16,6 +16,15 @@ use vidyut_prakriya::args::Pada;
 
 type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
+/// The kind of model to evaluate.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum ModelType {
+    /// A bigram model over coarse word states.
+    Bigram,
+    /// A model that combines relative-frequency estimates for a richer set of features.
+    Features,
+}
+
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
 struct Args {
@@ -29,6 +38,10 @@ struct Args {
 
     #[arg(short, long, default_value_t = false)]
     show_semantics: bool,
+
+    /// The kind of model to evaluate.
+    #[arg(long, value_enum, default_value_t = ModelType::Bigram)]
+    model_type: ModelType,
 }
 
 /// Summary statistics for our eval.
@@ -83,6 +96,7 @@ fn as_code(w: &Token) -> String {
                         vp::Krt::Base(vp::BaseKrt::tumun) => "tumun",
                         _ => "_",
                     },
+                    _ => "_",
                 };
                 val.to_string()
             } else {
@@ -201,7 +215,11 @@ fn eval_patterns(patterns: Vec<String>, chedaka: &Chedaka, show_semantics: &bool
 
 /// Runs an end-to-end evaluation over the given glob patterns.
 fn run_eval(args: Args) -> Result<()> {
-    let chedaka = Chedaka::new(&args.vidyut_dir)?;
+    let mut chedaka = Chedaka::new(&args.vidyut_dir)?;
+    if let ModelType::Features = args.model_type {
+        let config = Config::new(&args.vidyut_dir);
+        chedaka = chedaka.with_scorer(FeatureModel::read(&config.feature_model_path())?);
+    }
     let stats = eval_patterns(args.paths, &chedaka, &args.show_semantics)?;
 
     let pct = |x, y| 100_f32 * (x as f32) / (y as f32);
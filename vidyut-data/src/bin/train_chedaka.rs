@@ -1,24 +1,36 @@
 //! Train a model by collecting features from our dataset.
 
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use glob::glob;
 use rayon::prelude::*;
 use std::collections::HashSet;
 use std::error::Error;
 use std::path::{Path, PathBuf};
 
-use vidyut_cheda::ModelBuilder;
-use vidyut_cheda::State;
+use vidyut_cheda::{Config, FeatureModelBuilder, ModelBuilder, Observation, State};
 use vidyut_data::conllu::Reader;
 use vidyut_data::dcs;
 use vidyut_prakriya::args::Pada;
+use vidyut_sandhi::Splitter;
 
 type Result<T> = std::result::Result<T, Box<dyn Error>>;
 
+/// The kind of model to train.
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum ModelType {
+    /// A bigram model over coarse word states.
+    Bigram,
+    /// A model that combines relative-frequency estimates for a richer set of features.
+    Features,
+}
+
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
 struct Args {
     // The base directory to which we will write our training data.
+    //
+    // For `ModelType::Features`, this directory must also contain our sandhi rules, which we use
+    // to recover sandhi changes from the DCS text.
     #[arg(long)]
     model_dir: PathBuf,
 
@@ -31,6 +43,10 @@ struct Args {
     /// It's best to exclude any files that you will use to evaluate the model.
     #[arg(short, long)]
     exclude: Vec<String>,
+
+    /// The kind of model to train.
+    #[arg(long, value_enum, default_value_t = ModelType::Bigram)]
+    model_type: ModelType,
 }
 
 fn process_file(path: &Path) -> Result<ModelBuilder> {
@@ -59,6 +75,33 @@ fn process_file(path: &Path) -> Result<ModelBuilder> {
     Ok(builder)
 }
 
+fn process_file_features(path: &Path, splitter: &Splitter) -> Result<FeatureModelBuilder> {
+    println!("Processing: {:?}", path.display());
+    let mut builder = FeatureModelBuilder::new();
+
+    let reader = Reader::from_path(path)?;
+    for sentence in reader {
+        let changes = dcs::sandhi_changes(splitter, &sentence);
+        let observations: Vec<Observation> = sentence
+            .tokens
+            .iter()
+            .zip(changes)
+            .flat_map(|(t, sandhi)| {
+                let (lemma, artha) = dcs::standardize(t).ok()?;
+                Some(Observation {
+                    state: State::from_pada(&artha),
+                    lemma,
+                    is_purvapada: dcs::is_purvapada(t),
+                    sandhi,
+                })
+            })
+            .collect();
+        builder.count_sentence(&observations);
+    }
+
+    Ok(builder)
+}
+
 fn run(args: Args) -> Result<()> {
     let include_paths: Vec<_> = args
         .include
@@ -81,18 +124,35 @@ fn run(args: Args) -> Result<()> {
         }
     }
 
-    let all_stats: Vec<_> = paths
-        .par_iter()
-        .map(|path| process_file(&path).ok())
-        .collect();
-
-    let mut builder = ModelBuilder::new();
-    for stats in all_stats {
-        if let Some(stats) = stats {
-            builder.merge(stats);
+    match args.model_type {
+        ModelType::Bigram => {
+            let all_stats: Vec<_> = paths
+                .par_iter()
+                .map(|path| process_file(&path).ok())
+                .collect();
+
+            let mut builder = ModelBuilder::new();
+            for stats in all_stats {
+                if let Some(stats) = stats {
+                    builder.merge(stats);
+                }
+            }
+            builder.write_model(&args.model_dir)?;
+        }
+        ModelType::Features => {
+            let splitter = Splitter::from_csv(Config::new(&args.model_dir).sandhi_rules())?;
+            let all_stats: Vec<_> = paths
+                .par_iter()
+                .map(|path| process_file_features(path, &splitter).ok())
+                .collect();
+
+            let mut builder = FeatureModelBuilder::new();
+            for stats in all_stats.into_iter().flatten() {
+                builder.merge(stats);
+            }
+            builder.write_model(&args.model_dir)?;
         }
     }
-    builder.write_model(&args.model_dir)?;
 
     println!("Complete. Wrote model to {:?}", &args.model_dir);
 
@@ -13,6 +13,8 @@ pub type TokenFeatures = FxHashMap<String, String>;
 /// A token, usually representing a Sanskrit `pada`.
 #[derive(Debug)]
 pub struct Token {
+    /// The form of this token before sandhi is applied.
+    pub form: String,
     /// The lemma for this token.
     pub lemma: String,
     /// The universal part of speech (upos) tag for this token.
@@ -76,7 +78,7 @@ fn create_token(line: &str) -> Option<Token> {
     // Tokens
     let mut fields = line.split_terminator('\t');
     let _num = fields.next();
-    let _form = as_field(fields.next());
+    let form = as_field(fields.next());
     let lemma = as_field(fields.next());
     let upos = as_field(fields.next());
     let _xpos = as_field(fields.next());
@@ -84,6 +86,7 @@ fn create_token(line: &str) -> Option<Token> {
 
     if !lemma.is_empty() {
         Some(Token {
+            form,
             lemma,
             upos,
             features,
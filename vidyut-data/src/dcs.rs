@@ -1,10 +1,12 @@
 //! Utility functions for reading DCS data.
-use crate::conllu::{Token as EvalToken, TokenFeatures};
+use crate::conllu::{Sentence, Token as EvalToken, TokenFeatures};
 use core::cell::RefCell;
 use std::fmt;
+use vidyut_cheda::Sandhi;
 use vidyut_lipi::{Lipika, Scheme};
 use vidyut_prakriya::args::{self as vp, Slp1String};
 use vidyut_prakriya::args::{Linga, Pada, Pratipadika, Purusha, Subanta, Vacana, Vibhakti};
+use vidyut_sandhi::Splitter;
 
 pub type Result<T> = std::result::Result<T, DcsError>;
 
@@ -14,13 +16,16 @@ pub struct DcsError {
     value: String,
 }
 
-fn to_slp1(text: &str) -> Slp1String {
+fn transliterate(text: &str) -> String {
     thread_local! {
         static LIPIKA: RefCell<Lipika> = RefCell::new(Lipika::new());
     };
 
-    let slp1 =
-        LIPIKA.with_borrow_mut(|lipika| lipika.transliterate(text, Scheme::Iast, Scheme::Slp1));
+    LIPIKA.with_borrow_mut(|lipika| lipika.transliterate(text, Scheme::Iast, Scheme::Slp1))
+}
+
+fn to_slp1(text: &str) -> Slp1String {
+    let slp1 = transliterate(text);
     let clean: String = slp1.chars().filter(|c| c.is_ascii_alphabetic()).collect();
     Slp1String::from(clean).expect("ok")
 }
@@ -53,6 +58,70 @@ impl fmt::Display for DcsError {
     }
 }
 
+/// Returns whether the given token is the first member of a compound.
+pub fn is_purvapada(t: &EvalToken) -> bool {
+    parse_is_purvapada(&t.features)
+}
+
+/// Returns the sandhi change that joins each token in `sentence` to the token after it, as
+/// returned by `Sandhi::change`.
+///
+/// The DCS doesn't say how sandhi was applied, but it does have each token's form before sandhi
+/// and the sentence's text after sandhi. So we split the text with `splitter` and keep the split
+/// whose first part is the token's form, preferring splits that best match the next form. If no
+/// split matches, we can't align the rest of the sentence and return `None` for the remaining
+/// tokens.
+pub fn sandhi_changes(
+    splitter: &Splitter,
+    sentence: &Sentence,
+) -> Vec<Option<(String, String, String)>> {
+    let forms: Vec<Slp1String> = sentence.tokens.iter().map(|t| to_slp1(&t.form)).collect();
+
+    // Keep only words so that our text has the same shape as the input to `Chedaka`.
+    let text = transliterate(&sentence.text);
+    let words: Vec<_> = text
+        .split(|c: char| !(c.is_ascii_alphabetic() || c == '\''))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let mut remaining = words.join(" ");
+
+    let mut changes = Vec::with_capacity(forms.len());
+    for (i, form) in forms.iter().enumerate() {
+        let next = forms.get(i + 1).map_or("", |f| f.as_str());
+        let common_prefix = |s: &str| {
+            s.bytes()
+                .zip(next.bytes())
+                .take_while(|(x, y)| x == y)
+                .count()
+        };
+        let best = splitter
+            .split_all(&remaining)
+            .into_iter()
+            .filter(|s| is_same_form(s.first(), form))
+            .max_by_key(|s| common_prefix(s.second()));
+        let Some(split) = best else {
+            break;
+        };
+
+        let sandhi = Sandhi::new(&remaining, &split);
+        let (first, second, result) = sandhi.change();
+        changes.push(Some((first.into(), second.into(), result.into())));
+        remaining = split.second().to_string();
+    }
+    changes.resize(forms.len(), None);
+    changes
+}
+
+/// Returns whether `first`, the first part of a sandhi split, is the same as `form`.
+///
+/// Our splitter undoes visarga sandhi with a final `s` or `r`, whereas the DCS uses a final visarga.
+fn is_same_form(first: &str, form: &str) -> bool {
+    match (first.strip_suffix(['s', 'r']), form.strip_suffix('H')) {
+        (Some(x), Some(y)) => x == y,
+        _ => first == form,
+    }
+}
+
 /// Convert DCS semantics to Vidyut semantics.
 pub fn standardize(t: &EvalToken) -> Result<(String, Pada)> {
     let lemma = standardize_lemma(&t.lemma);
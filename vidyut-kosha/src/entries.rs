@@ -4,17 +4,21 @@
 //! To persist this data for your application, clone the specific fields you need.
 use crate::errors::{Error, Result};
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
 use vidyut_prakriya::args as vp;
 use vidyut_prakriya::args::{
     BasicPratipadika, Dhatu, Krdanta, Krt, Lakara, Linga, Pada, Pratipadika, Prayoga, Purusha,
-    Subanta, Tinanta, Vacana, Vibhakti,
+    Samasa, SamasaType, Subanta, Taddhita, TaddhitaArtha, Taddhitanta, Tinanta, Vacana, Vibhakti,
 };
 
 /// A dhatu with its metadata.
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug)]
 pub struct DhatuEntry<'a> {
     pub(crate) dhatu: &'a Dhatu,
     pub(crate) meta: Option<&'a DhatuMeta>,
+    pub(crate) metadata: Option<&'a Metadata>,
 }
 
 /// Metadata for some dhatu.
@@ -32,6 +36,41 @@ pub struct DhatuMeta {
     pub(crate) pada: Option<String>,
 }
 
+/// User-defined metadata for a dhatu, *prātipadika*, or *pada*.
+///
+/// Unlike `DhatuMeta`, which has a fixed set of fields, `Metadata` maps arbitrary keys to values.
+/// Use it to store data like dictionary glosses, corpus frequencies, source dictionary IDs, and
+/// custom tags. To attach metadata to an entry, use `Builder::set_dhatu_metadata` and related
+/// methods.
+///
+/// # Usage
+///
+/// ```
+/// use vidyut_kosha::entries::Metadata;
+///
+/// let metadata = Metadata::new()
+///     .with("gloss", "god")
+///     .with("frequency", 1024)
+///     .with("tags", vec!["vedic".to_string()]);
+///
+/// assert_eq!(metadata.get_text("gloss"), Some("god"));
+/// assert_eq!(metadata.get_int("frequency"), Some(1024));
+/// assert_eq!(metadata.get_tags("tags"), Some(&["vedic".to_string()][..]));
+/// ```
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Metadata(BTreeMap<String, MetaValue>);
+
+/// A value in some `Metadata`.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum MetaValue {
+    /// Text data, such as a gloss or an ID.
+    Text(String),
+    /// Integer data, such as a frequency.
+    Int(i64),
+    /// A list of strings, such as a set of tags.
+    Tags(Vec<String>),
+}
+
 /// A builder for a `DhatuEntry`.
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
 pub struct DhatuMetaBuilder {
@@ -47,21 +86,45 @@ pub struct DhatuMetaBuilder {
 /// A basic *prātipadika* with its metadata.
 ///
 /// Prefer working with `PratipadikaEntry` instead.
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug)]
 pub struct BasicPratipadikaEntry<'a> {
     pratipadika: &'a BasicPratipadika,
     lingas: &'a [Linga],
+    metadata: Option<&'a Metadata>,
 }
 
 /// A *kṛdanta* with its metadata.
 ///
 /// Prefer working with `PratipadikaEntry` instead.
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug)]
 pub struct KrdantaEntry<'a> {
     dhatu_entry: DhatuEntry<'a>,
     krt: Krt,
     prayoga: Option<Prayoga>,
     lakara: Option<Lakara>,
+    metadata: Option<&'a Metadata>,
+}
+
+/// A *taddhitānta* with its metadata.
+///
+/// Prefer working with `PratipadikaEntry` instead.
+#[derive(Clone, Debug)]
+pub struct TaddhitantaEntry<'a> {
+    pratipadika_entry: Box<PratipadikaEntry<'a>>,
+    taddhita: Taddhita,
+    artha: Option<TaddhitaArtha>,
+    metadata: Option<&'a Metadata>,
+}
+
+/// A *samāsa* with its metadata.
+///
+/// Prefer working with `PratipadikaEntry` instead.
+#[derive(Clone, Debug)]
+pub struct SamasaEntry<'a> {
+    padas: Vec<SubantaEntry<'a>>,
+    samasa_type: SamasaType,
+    stri: bool,
+    metadata: Option<&'a Metadata>,
 }
 
 /// A *prātipadika* with its metadata.
@@ -71,25 +134,32 @@ pub enum PratipadikaEntry<'a> {
     Basic(BasicPratipadikaEntry<'a>),
     /// A *kṛdanta prātipadika*.
     Krdanta(KrdantaEntry<'a>),
+    /// A *taddhitānta prātipadika*.
+    Taddhitanta(TaddhitantaEntry<'a>),
+    /// A *samāsa prātipadika*.
+    Samasa(SamasaEntry<'a>),
 }
 
 /// A *subanta* (nominal) entry.
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug)]
 pub struct SubantaEntry<'a> {
     pratipadika_entry: PratipadikaEntry<'a>,
     linga: Linga,
     vibhakti: Vibhakti,
     vacana: Vacana,
+    is_purvapada: bool,
+    metadata: Option<&'a Metadata>,
 }
 
 /// A *tinanta* (verb) entry.
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
+#[derive(Clone, Debug)]
 pub struct TinantaEntry<'a> {
     dhatu_entry: DhatuEntry<'a>,
     prayoga: Prayoga,
     lakara: Lakara,
     purusha: Purusha,
     vacana: Vacana,
+    metadata: Option<&'a Metadata>,
 }
 
 /// A Sanskrit *pada* (word).
@@ -101,10 +171,64 @@ pub enum PadaEntry<'a> {
     Tinanta(TinantaEntry<'a>),
 }
 
+/// Implements `PartialEq`, `Eq`, `Hash`, `PartialOrd`, and `Ord` for an entry with the given
+/// fields.
+///
+/// We leave out the entry's user-defined `metadata`, which describes the entry but doesn't
+/// identify it. So entries that differ only in their metadata are equal, and `KoshaStack` returns
+/// just one of them.
+macro_rules! impl_entry_cmp {
+    ($Entry:ident, [$( $field:ident ),*]) => {
+        impl PartialEq for $Entry<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                $( self.$field == other.$field )&&*
+            }
+        }
+
+        impl Eq for $Entry<'_> {}
+
+        impl Hash for $Entry<'_> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                $( self.$field.hash(state); )*
+            }
+        }
+
+        impl PartialOrd for $Entry<'_> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $Entry<'_> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                Ordering::Equal $( .then_with(|| self.$field.cmp(&other.$field)) )*
+            }
+        }
+    };
+}
+
+impl_entry_cmp!(DhatuEntry, [dhatu, meta]);
+impl_entry_cmp!(BasicPratipadikaEntry, [pratipadika, lingas]);
+impl_entry_cmp!(KrdantaEntry, [dhatu_entry, krt, prayoga, lakara]);
+impl_entry_cmp!(TaddhitantaEntry, [pratipadika_entry, taddhita, artha]);
+impl_entry_cmp!(SamasaEntry, [padas, samasa_type, stri]);
+impl_entry_cmp!(
+    SubantaEntry,
+    [pratipadika_entry, linga, vibhakti, vacana, is_purvapada]
+);
+impl_entry_cmp!(
+    TinantaEntry,
+    [dhatu_entry, prayoga, lakara, purusha, vacana]
+);
+
 impl<'a> DhatuEntry<'a> {
     /// Creates a new `DhatuEntry` with no metadata. To set metadata, use `builder()` instead.
     pub fn new(dhatu: &'a Dhatu) -> Self {
-        Self { dhatu, meta: None }
+        Self {
+            dhatu,
+            meta: None,
+            metadata: None,
+        }
     }
 
     /// Returns the dhatu that generates this entry.
@@ -161,6 +285,17 @@ impl<'a> DhatuEntry<'a> {
         self.meta = Some(meta);
         self
     }
+
+    /// Returns the user-defined metadata for this dhatu, if any.
+    pub fn metadata(&self) -> Option<&'a Metadata> {
+        self.metadata
+    }
+
+    /// Sets the user-defined metadata on this dhatu.
+    pub fn with_metadata(mut self, metadata: Option<&'a Metadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
 }
 
 impl<'a> From<&'a Dhatu> for DhatuEntry<'a> {
@@ -175,6 +310,96 @@ impl<'a> From<DhatuEntry<'a>> for Dhatu {
     }
 }
 
+impl Metadata {
+    /// Creates empty metadata.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets `key` to `value`.
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<MetaValue>) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    /// Sets `key` to `value` and returns the old value for `key`, if any.
+    pub fn insert(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<MetaValue>,
+    ) -> Option<MetaValue> {
+        self.0.insert(key.into(), value.into())
+    }
+
+    /// Returns the value for `key`, if any.
+    pub fn get(&self, key: &str) -> Option<&MetaValue> {
+        self.0.get(key)
+    }
+
+    /// Returns the value for `key` if it is text.
+    pub fn get_text(&self, key: &str) -> Option<&str> {
+        match self.get(key) {
+            Some(MetaValue::Text(x)) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Returns the value for `key` if it is an integer.
+    pub fn get_int(&self, key: &str) -> Option<i64> {
+        match self.get(key) {
+            Some(MetaValue::Int(x)) => Some(*x),
+            _ => None,
+        }
+    }
+
+    /// Returns the value for `key` if it is a list of tags.
+    pub fn get_tags(&self, key: &str) -> Option<&[String]> {
+        match self.get(key) {
+            Some(MetaValue::Tags(x)) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// Iterates over all keys and values in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &MetaValue)> {
+        self.0.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns whether there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for MetaValue {
+    fn from(val: String) -> Self {
+        MetaValue::Text(val)
+    }
+}
+
+impl From<&str> for MetaValue {
+    fn from(val: &str) -> Self {
+        MetaValue::Text(val.to_string())
+    }
+}
+
+impl From<i64> for MetaValue {
+    fn from(val: i64) -> Self {
+        MetaValue::Int(val)
+    }
+}
+
+impl From<Vec<String>> for MetaValue {
+    fn from(val: Vec<String>) -> Self {
+        MetaValue::Tags(val)
+    }
+}
+
 impl DhatuMeta {
     /// Returns a builder for some `DhatuMeta` struct.
     ///
@@ -251,6 +476,7 @@ impl<'a> BasicPratipadikaEntry<'a> {
         Self {
             pratipadika,
             lingas,
+            metadata: None,
         }
     }
 
@@ -270,6 +496,17 @@ impl<'a> BasicPratipadikaEntry<'a> {
     pub fn is_avyaya(&self) -> bool {
         self.pratipadika.is_avyaya()
     }
+
+    /// Returns the user-defined metadata for this *prātipadika*, if any.
+    pub fn metadata(&self) -> Option<&'a Metadata> {
+        self.metadata
+    }
+
+    /// Sets the user-defined metadata on this *prātipadika*.
+    pub fn with_metadata(mut self, metadata: Option<&'a Metadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
 }
 
 impl<'a> KrdantaEntry<'a> {
@@ -285,6 +522,7 @@ impl<'a> KrdantaEntry<'a> {
             krt,
             prayoga,
             lakara,
+            metadata: None,
         }
     }
 
@@ -322,6 +560,17 @@ impl<'a> KrdantaEntry<'a> {
     pub fn is_avyaya(&self) -> bool {
         self.krt.is_avyaya()
     }
+
+    /// Returns the user-defined metadata for this krdanta, if any.
+    pub fn metadata(&self) -> Option<&'a Metadata> {
+        self.metadata
+    }
+
+    /// Sets the user-defined metadata on this krdanta.
+    pub fn with_metadata(mut self, metadata: Option<&'a Metadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
 }
 
 impl<'a> From<KrdantaEntry<'a>> for Krdanta {
@@ -346,13 +595,138 @@ impl<'a> From<&KrdantaEntry<'a>> for Krdanta {
     }
 }
 
+impl<'a> TaddhitantaEntry<'a> {
+    /// Creates a new `TaddhitantaEntry`.
+    pub fn new(
+        pratipadika_entry: PratipadikaEntry<'a>,
+        taddhita: Taddhita,
+        artha: Option<TaddhitaArtha>,
+    ) -> Self {
+        Self {
+            pratipadika_entry: Box::new(pratipadika_entry),
+            taddhita,
+            artha,
+            metadata: None,
+        }
+    }
+
+    /// The *prātipadika* entry that this *taddhitānta* derives from.
+    pub fn pratipadika_entry(&self) -> &PratipadikaEntry<'a> {
+        &self.pratipadika_entry
+    }
+
+    /// Returns the *taddhita pratyaya* used by this *taddhitānta*.
+    pub fn taddhita(&self) -> Taddhita {
+        self.taddhita
+    }
+
+    /// Returns the meaning condition used by this *taddhitānta*, if known.
+    pub fn artha(&self) -> Option<TaddhitaArtha> {
+        self.artha
+    }
+
+    /// Returns whether this entry represents an *avyaya*.
+    pub fn is_avyaya(&self) -> bool {
+        // 1.1.38 taddhitaS cAsarvavibhaktiH
+        self.taddhita.is_vibhakti()
+    }
+
+    /// Returns the user-defined metadata for this *taddhitānta*, if any.
+    pub fn metadata(&self) -> Option<&'a Metadata> {
+        self.metadata
+    }
+
+    /// Sets the user-defined metadata on this *taddhitānta*.
+    pub fn with_metadata(mut self, metadata: Option<&'a Metadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+}
+
+impl<'a> From<&TaddhitantaEntry<'a>> for Taddhitanta {
+    fn from(val: &TaddhitantaEntry<'a>) -> Taddhitanta {
+        let mut builder = Taddhitanta::builder();
+        builder
+            .pratipadika(val.pratipadika_entry.as_ref().into())
+            .taddhita(val.taddhita);
+        if let Some(artha) = val.artha {
+            builder.artha(artha);
+        }
+
+        builder.build().expect("validated")
+    }
+}
+
+impl<'a> SamasaEntry<'a> {
+    /// Creates a new `SamasaEntry`.
+    ///
+    /// Returns an error if `padas` is empty.
+    pub fn new(padas: Vec<SubantaEntry<'a>>, samasa_type: SamasaType) -> Result<Self> {
+        if padas.is_empty() {
+            return Err(Error::UnsupportedType);
+        }
+        Ok(Self {
+            padas,
+            samasa_type,
+            stri: false,
+            metadata: None,
+        })
+    }
+
+    /// Sets whether this *samāsa* uses a *strī pratyaya*.
+    pub fn with_stri(mut self, stri: bool) -> Self {
+        self.stri = stri;
+        self
+    }
+
+    /// The members of this *samāsa*, in order.
+    pub fn padas(&self) -> &[SubantaEntry<'a>] {
+        &self.padas
+    }
+
+    /// Returns the type of this *samāsa*.
+    pub fn samasa_type(&self) -> SamasaType {
+        self.samasa_type
+    }
+
+    /// Returns whether this *samāsa* uses a *strī pratyaya*.
+    pub fn stri(&self) -> bool {
+        self.stri
+    }
+
+    /// Returns whether this entry represents an *avyaya*.
+    pub fn is_avyaya(&self) -> bool {
+        // 1.1.41 avyayIBAvaS ca
+        self.samasa_type == SamasaType::Avyayibhava
+    }
+
+    /// Returns the user-defined metadata for this *samāsa*, if any.
+    pub fn metadata(&self) -> Option<&'a Metadata> {
+        self.metadata
+    }
+
+    /// Sets the user-defined metadata on this *samāsa*.
+    pub fn with_metadata(mut self, metadata: Option<&'a Metadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
+}
+
+impl<'a> From<&SamasaEntry<'a>> for Samasa {
+    fn from(val: &SamasaEntry<'a>) -> Samasa {
+        Samasa::builder()
+            .padas(val.padas.iter().map(Subanta::from).collect())
+            .samasa_type(val.samasa_type)
+            .build()
+            .expect("validated")
+            .with_stri(val.stri)
+    }
+}
+
 impl<'a> PratipadikaEntry<'a> {
     /// Creates a new `PratipadikaEntry`.
     pub fn basic(pratipadika: &'a BasicPratipadika, lingas: &'a [Linga]) -> Self {
-        Self::Basic(BasicPratipadikaEntry {
-            pratipadika,
-            lingas,
-        })
+        Self::Basic(BasicPratipadikaEntry::new(pratipadika, lingas))
     }
 
     /// Returns the lemma that corresponds to this *prātipadika*.
@@ -360,6 +734,11 @@ impl<'a> PratipadikaEntry<'a> {
         match self {
             PratipadikaEntry::Basic(b) => b.pratipadika().text(),
             PratipadikaEntry::Krdanta(k) => k.dhatu_text(),
+            PratipadikaEntry::Taddhitanta(t) => t.pratipadika_entry.lemma(),
+            PratipadikaEntry::Samasa(s) => match s.padas.last() {
+                Some(last) => last.pratipadika_entry.lemma(),
+                None => "",
+            },
         }
     }
 
@@ -373,6 +752,7 @@ impl<'a> PratipadikaEntry<'a> {
                 Krt::Base(b) => b.lingas(),
                 _ => &[],
             },
+            Self::Taddhitanta(_) | Self::Samasa(_) => &[],
         }
     }
 
@@ -381,6 +761,28 @@ impl<'a> PratipadikaEntry<'a> {
         match self {
             PratipadikaEntry::Basic(b) => b.is_avyaya(),
             PratipadikaEntry::Krdanta(k) => k.is_avyaya(),
+            PratipadikaEntry::Taddhitanta(t) => t.is_avyaya(),
+            PratipadikaEntry::Samasa(s) => s.is_avyaya(),
+        }
+    }
+
+    /// Returns the user-defined metadata for this *prātipadika*, if any.
+    pub fn metadata(&self) -> Option<&'a Metadata> {
+        match self {
+            PratipadikaEntry::Basic(b) => b.metadata(),
+            PratipadikaEntry::Krdanta(k) => k.metadata(),
+            PratipadikaEntry::Taddhitanta(t) => t.metadata(),
+            PratipadikaEntry::Samasa(s) => s.metadata(),
+        }
+    }
+
+    /// Sets the user-defined metadata on this *prātipadika*.
+    pub fn with_metadata(self, metadata: Option<&'a Metadata>) -> Self {
+        match self {
+            Self::Basic(b) => Self::Basic(b.with_metadata(metadata)),
+            Self::Krdanta(k) => Self::Krdanta(k.with_metadata(metadata)),
+            Self::Taddhitanta(t) => Self::Taddhitanta(t.with_metadata(metadata)),
+            Self::Samasa(s) => Self::Samasa(s.with_metadata(metadata)),
         }
     }
 }
@@ -396,6 +798,10 @@ impl<'a> From<&PratipadikaEntry<'a>> for Pratipadika {
         match val {
             PratipadikaEntry::Basic(b) => Pratipadika::Basic(b.pratipadika.clone()),
             PratipadikaEntry::Krdanta(k) => Pratipadika::Krdanta(Krdanta::from(k).into()),
+            PratipadikaEntry::Taddhitanta(t) => {
+                Pratipadika::Taddhitanta(Taddhitanta::from(t).into())
+            }
+            PratipadikaEntry::Samasa(s) => Pratipadika::Samasa(Samasa::from(s).into()),
         }
     }
 }
@@ -406,10 +812,7 @@ impl<'a> TryFrom<&'a Pratipadika> for PratipadikaEntry<'a> {
     fn try_from(val: &'a Pratipadika) -> Result<PratipadikaEntry<'a>> {
         match val {
             Pratipadika::Basic(b) => {
-                let basic_entry = BasicPratipadikaEntry {
-                    pratipadika: b,
-                    lingas: &[],
-                };
+                let basic_entry = BasicPratipadikaEntry::new(b, &[]);
                 Ok(PratipadikaEntry::Basic(basic_entry))
             }
             Pratipadika::Krdanta(k) => {
@@ -418,7 +821,20 @@ impl<'a> TryFrom<&'a Pratipadika> for PratipadikaEntry<'a> {
                     KrdantaEntry::new(dhatu_entry, k.krt(), k.prayoga(), k.lakara());
                 Ok(PratipadikaEntry::Krdanta(krdanta_entry))
             }
-            _ => Err(Error::UnsupportedType),
+            Pratipadika::Taddhitanta(t) => {
+                let taddhitanta_entry =
+                    TaddhitantaEntry::new(t.pratipadika().try_into()?, t.taddhita(), t.artha());
+                Ok(PratipadikaEntry::Taddhitanta(taddhitanta_entry))
+            }
+            Pratipadika::Samasa(s) => {
+                let padas = s
+                    .padas()
+                    .iter()
+                    .map(SubantaEntry::try_from)
+                    .collect::<Result<Vec<_>>>()?;
+                let samasa_entry = SamasaEntry::new(padas, s.samasa_type())?.with_stri(s.stri());
+                Ok(PratipadikaEntry::Samasa(samasa_entry))
+            }
         }
     }
 }
@@ -436,6 +852,8 @@ impl<'a> SubantaEntry<'a> {
             linga,
             vibhakti,
             vacana,
+            is_purvapada: false,
+            metadata: None,
         }
     }
 
@@ -446,6 +864,23 @@ impl<'a> SubantaEntry<'a> {
             linga: Linga::Pum,
             vibhakti: Vibhakti::Prathama,
             vacana: Vacana::Eka,
+            is_purvapada: false,
+            metadata: None,
+        }
+    }
+
+    /// Creates a new `SubantaEntry` that is a *pūrvapada*, i.e. a non-final member of a *samāsa*.
+    ///
+    /// A *pūrvapada* has no ending of its own, so its linga, vibhakti, and vacana are
+    /// placeholders.
+    pub fn purvapada(pratipadika_entry: PratipadikaEntry<'a>) -> Self {
+        Self {
+            pratipadika_entry,
+            linga: Linga::Pum,
+            vibhakti: Vibhakti::Prathama,
+            vacana: Vacana::Eka,
+            is_purvapada: true,
+            metadata: None,
         }
     }
 
@@ -456,8 +891,7 @@ impl<'a> SubantaEntry<'a> {
 
     /// Returns whether this subanta must be the purvapada in a samasa.
     pub fn is_purvapada(&self) -> bool {
-        // TODO: implement
-        false
+        self.is_purvapada
     }
 
     /// The *liṅga* used by this *subanta*.
@@ -481,6 +915,17 @@ impl<'a> SubantaEntry<'a> {
     pub fn is_avyaya(&self) -> bool {
         self.pratipadika_entry.is_avyaya()
     }
+
+    /// Returns the user-defined metadata for this *subanta*, if any.
+    pub fn metadata(&self) -> Option<&'a Metadata> {
+        self.metadata
+    }
+
+    /// Sets the user-defined metadata on this *subanta*.
+    pub fn with_metadata(mut self, metadata: Option<&'a Metadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
 }
 
 impl<'a> From<SubantaEntry<'a>> for vp::Subanta {
@@ -532,6 +977,7 @@ impl<'a> TinantaEntry<'a> {
             lakara,
             purusha,
             vacana,
+            metadata: None,
         }
     }
 
@@ -569,6 +1015,17 @@ impl<'a> TinantaEntry<'a> {
     pub fn vacana(&self) -> Vacana {
         self.vacana
     }
+
+    /// Returns the user-defined metadata for this *tiṅanta*, if any.
+    pub fn metadata(&self) -> Option<&'a Metadata> {
+        self.metadata
+    }
+
+    /// Sets the user-defined metadata on this *tiṅanta*.
+    pub fn with_metadata(mut self, metadata: Option<&'a Metadata>) -> Self {
+        self.metadata = metadata;
+        self
+    }
 }
 
 impl<'a> From<&'a TinantaEntry<'a>> for vp::Tinanta {
@@ -615,6 +1072,17 @@ impl<'a> PadaEntry<'a> {
             PadaEntry::Subanta(s) => Some(s.pratipadika_entry.lemma()),
         }
     }
+
+    /// Returns the user-defined metadata for this *pada*, if any.
+    ///
+    /// This is metadata for the *pada* itself. For metadata on the underlying dhatu or
+    /// *prātipadika*, use `TinantaEntry::dhatu_entry` or `SubantaEntry::pratipadika_entry`.
+    pub fn metadata(&self) -> Option<&'a Metadata> {
+        match self {
+            PadaEntry::Tinanta(t) => t.metadata(),
+            PadaEntry::Subanta(s) => s.metadata(),
+        }
+    }
 }
 
 impl<'a> From<PadaEntry<'a>> for Pada {
@@ -752,4 +1220,127 @@ mod tests {
         let iti_pada = PadaEntry::Subanta(SubantaEntry::avyaya(iti_entry));
         assert_eq!(iti_pada.lemma(), Some("iti"));
     }
+
+    #[test]
+    fn taddhitanta_entry_round_trip() {
+        let dasharatha = Pratipadika::basic(safe("daSaraTa"));
+        let expected: Pratipadika = Taddhitanta::builder()
+            .pratipadika(dasharatha)
+            .taddhita(Taddhita::iY)
+            .artha(TaddhitaArtha::TasyaApatyam)
+            .build()
+            .expect("ok")
+            .into();
+        let entry: PratipadikaEntry = (&expected).try_into().expect("ok");
+        assert_eq!(entry.lemma(), "daSaraTa");
+        assert!(!entry.is_avyaya());
+
+        let actual: Pratipadika = entry.into();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn taddhitanta_entry_avyaya() {
+        let sarva = Pratipadika::basic(safe("sarva"));
+        let sarvatra = Pratipadika::from(Taddhitanta::new(sarva, Taddhita::tral));
+        let entry: PratipadikaEntry = (&sarvatra).try_into().expect("ok");
+        assert!(entry.is_avyaya());
+    }
+
+    #[test]
+    fn samasa_entry_round_trip() {
+        let raja = Subanta::new(
+            Pratipadika::basic(safe("rAjan")),
+            Linga::Pum,
+            Vibhakti::Sasthi,
+            Vacana::Eka,
+        );
+        let purusha = Subanta::new(
+            Pratipadika::basic(safe("puruza")),
+            Linga::Pum,
+            Vibhakti::Prathama,
+            Vacana::Eka,
+        );
+        let expected: Pratipadika = Samasa::builder()
+            .padas(vec![raja, purusha])
+            .samasa_type(SamasaType::Tatpurusha)
+            .build()
+            .expect("ok")
+            .into();
+        let entry: PratipadikaEntry = (&expected).try_into().expect("ok");
+        assert_eq!(entry.lemma(), "puruza");
+        assert!(!entry.is_avyaya());
+
+        let actual: Pratipadika = entry.into();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn samasa_entry_with_no_padas() {
+        assert!(SamasaEntry::new(Vec::new(), SamasaType::Dvandva).is_err());
+    }
+
+    #[test]
+    fn metadata() {
+        let mut metadata = Metadata::new().with("gloss", "god").with("frequency", 10);
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata.get_text("gloss"), Some("god"));
+        assert_eq!(metadata.get_int("frequency"), Some(10));
+
+        // Getters check the value type.
+        assert_eq!(metadata.get_int("gloss"), None);
+        assert_eq!(metadata.get_tags("gloss"), None);
+        assert_eq!(metadata.get("source"), None);
+
+        let old = metadata.insert("gloss", vec!["deity".to_string()]);
+        assert_eq!(old, Some(MetaValue::Text("god".to_string())));
+        let keys: Vec<_> = metadata.iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["frequency", "gloss"]);
+    }
+
+    #[test]
+    fn entry_metadata() {
+        let deva = Pratipadika::basic(safe("deva"));
+        let metadata = Metadata::new().with("gloss", "god");
+
+        let entry = PratipadikaEntry::try_from(&deva).expect("ok");
+        assert_eq!(entry.metadata(), None);
+        let entry = entry.with_metadata(Some(&metadata));
+        assert_eq!(entry.metadata(), Some(&metadata));
+
+        // Pada metadata is separate from lemma metadata.
+        let pada: PadaEntry = SubantaEntry::avyaya(entry).into();
+        assert_eq!(pada.metadata(), None);
+    }
+
+    #[test]
+    fn entry_eq_ignores_metadata() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash(pada: &PadaEntry) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            pada.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let deva = Pratipadika::basic(safe("deva"));
+        let god = Metadata::new().with("gloss", "god");
+        let deity = Metadata::new().with("gloss", "deity");
+
+        let entry = PratipadikaEntry::try_from(&deva).expect("ok");
+        let a = entry.clone().with_metadata(Some(&god));
+        let b = entry.clone().with_metadata(Some(&deity));
+        assert_eq!(a, b);
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+
+        let pada_a: PadaEntry = SubantaEntry::avyaya(a).with_metadata(Some(&god)).into();
+        let pada_b: PadaEntry = SubantaEntry::avyaya(b).into();
+        assert_eq!(pada_a, pada_b);
+        assert_eq!(hash(&pada_a), hash(&pada_b));
+
+        // Other fields still count.
+        let pada_c: PadaEntry =
+            SubantaEntry::new(entry, Linga::Pum, Vibhakti::Dvitiya, Vacana::Eka).into();
+        assert_ne!(pada_a, pada_c);
+    }
 }
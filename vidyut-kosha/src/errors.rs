@@ -22,6 +22,9 @@ pub enum Error {
     TryFromInt(num::TryFromIntError),
     /// Tried to insert too many duplicates into the kosha.
     TooManyDuplicates(String),
+    /// Tried to insert a key that ends like a duplicate of another key. This happens if the key is
+    /// some other key followed by two bytes below 65, e.g. `a` and `a'/`.
+    AmbiguousKey(String),
     /// The given int could not be mapped to a registry item.
     UnknownId(&'static str, usize),
     /// The given data type was not found in the registry.
@@ -30,6 +33,26 @@ pub enum Error {
     ParseEnum(&'static str, String),
     /// Data type is not yet supported in the kosha.
     UnsupportedType,
+    /// A search pattern could not be compiled.
+    Regex(String),
+    /// The kosha has a file format version that this crate can't read.
+    UnsupportedFormat(u32),
+    /// The kosha was built with an incompatible version of some crate. Contains the crate's name
+    /// and the version that built the kosha.
+    VersionMismatch(&'static str, String),
+    /// The kosha packs its data differently than this crate does. Contains the kosha's layout.
+    LayoutMismatch(String),
+    /// Some count in the kosha's manifest doesn't match the kosha's data. Contains the name of
+    /// the count, the value in the manifest, and the actual value.
+    CountMismatch(&'static str, usize, usize),
+    /// Some file in the kosha doesn't match the checksum in the kosha's manifest.
+    ChecksumMismatch(String),
+    /// Some data in the kosha could not be decoded. Contains a description of the data.
+    CorruptKosha(String),
+    /// A prior weight was negative, infinite, or NaN.
+    InvalidWeight(f32),
+    /// A record in a lexicon file could not be read. Contains the line number and the reason.
+    InvalidRecord(usize, String),
 }
 
 impl From<io::Error> for Error {
@@ -82,11 +105,36 @@ impl fmt::Display for Error {
             EncodeError(e) => e.fmt(f),
             Fst(e) => e.fmt(f),
             TooManyDuplicates(s) => write!(f, "Key `{}` has been inserted too many times.", s),
+            AmbiguousKey(s) => write!(f, "Key `{s}` looks like a duplicate of a shorter key."),
             UnknownId(name, id) => write!(f, "Unknown {name} ID: {}", id),
             NotRegistered(name) => write!(f, "Record of type {name} was not in the registry."),
             ParseEnum(name, value) => write!(f, "Enum `{name}` has no value `{value}`."),
             TryFromInt(e) => e.fmt(f),
             UnsupportedType => write!(f, "Data type not yet supported."),
+            Regex(e) => write!(f, "Invalid search pattern: {e}"),
+            UnsupportedFormat(v) => write!(
+                f,
+                "Kosha has format version {v}, but this crate supports only version {}.",
+                crate::manifest::FORMAT_VERSION
+            ),
+            VersionMismatch(name, v) => write!(
+                f,
+                "Kosha was built with {name} {v}, which is incompatible with this version. Please rebuild the kosha."
+            ),
+            LayoutMismatch(s) => write!(
+                f,
+                "Kosha was packed with an incompatible layout (`{s}`). Please rebuild the kosha."
+            ),
+            CountMismatch(name, expected, actual) => write!(
+                f,
+                "Kosha manifest expects {expected} {name}, but the kosha has {actual}."
+            ),
+            ChecksumMismatch(name) => {
+                write!(f, "Kosha file `{name}` does not match its checksum.")
+            }
+            CorruptKosha(s) => write!(f, "Kosha data is corrupt ({s}). Please rebuild the kosha."),
+            InvalidWeight(w) => write!(f, "Weight {w} must be finite and non-negative."),
+            InvalidRecord(line, reason) => write!(f, "Invalid record on line {line}: {reason}"),
         }
     }
 }
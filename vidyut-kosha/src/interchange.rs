@@ -0,0 +1,1041 @@
+//! Reads and writes kosha entries in standard lexicon formats.
+//!
+//! `Kosha::export` and `Builder::import` support three formats:
+//!
+//! - `LexiconFormat::Jsonl` writes one JSON object per word. Each object has a `key`, the full
+//!   `vidyut_prakriya::args::Pada` for the word, and optionally the `dhatu_meta` of its *dhātu*
+//!   and the `lingas` of its *prātipadika*. This format is lossless except for user metadata and
+//!   for the metadata of *dhātu*s nested inside *taddhitānta*s and *samāsa*s.
+//!
+//! - `LexiconFormat::Tsv` writes a header row and one row per word with the columns `form`,
+//!   `lemma`, `upos`, `feats`, and `misc`. These columns have the same meaning as in CoNLL-U.
+//!
+//! - `LexiconFormat::Conllu` writes each word as a single-token sentence that standard CoNLL-U
+//!   tools can read.
+//!
+//! In the TSV and CoNLL-U formats, `feats` uses [Universal Dependencies][ud] features with the
+//! same conventions as the Digital Corpus of Sanskrit:
+//!
+//! | Vidyut     | Feature         | Values                                                 |
+//! |------------|-----------------|--------------------------------------------------------|
+//! | `Linga`    | `Gender`        | `Masc`, `Fem`, `Neut`                                  |
+//! | `Vibhakti` | `Case`          | `Nom`, `Acc`, `Ins`, `Dat`, `Abl`, `Gen`, `Loc`, `Voc` |
+//! | `Vacana`   | `Number`        | `Sing`, `Dual`, `Plur`                                 |
+//! | `Purusha`  | `Person`        | `3`, `2`, `1`                                          |
+//! | `Prayoga`  | `Voice`         | `Act`, `Pass`                                          |
+//! | `Lakara`   | `Tense`, `Mood` | e.g. `Tense=Pres`, `Mood=Ind` for *laṭ*                |
+//!
+//! As in the DCS, a *pūrvapada* has `Case=Cpd` and no `Gender` or `Number`.
+//!
+//! Some of our values have no exact UD equivalent. For example, both *luṭ* and *lṛṭ* are
+//! `Tense=Fut|Mood=Ind`. So `misc` stores the values that `feats` can't express exactly:
+//!
+//! - `Dhatu`, `Gana`, `Antargana`, `Prefixes`, and `Sanadi` describe a *mūla dhātu*.
+//! - `Lakara` and `Prayoga` use our own labels, e.g. `Lakara=la~w`.
+//! - `Krt` or `Unadi` is the *pratyaya* of a *kṛdanta*.
+//! - `Lingas` lists the lingas that a basic *prātipadika* allows.
+//!
+//! When importing, we prefer `misc` if it's present and otherwise fall back to `feats`. This
+//! lets us ingest lexicons from other projects, which usually have only `feats`. Since those
+//! lexicons don't record a verb's *gaṇa*, we use `BvAdi` by default. *taddhitānta*s, *samāsa*s,
+//! and *nāmadhātu*s don't fit in these columns, so we export them with their lemma only and
+//! import them as basic *prātipadika*s and *mūla dhātu*s. For a lossless export, use JSON Lines.
+//!
+//! [ud]: https://universaldependencies.org/u/feat/index.html
+use crate::entries::{
+    BasicPratipadikaEntry, DhatuEntry, DhatuMeta, KrdantaEntry, PadaEntry, PratipadikaEntry,
+    SubantaEntry, TinantaEntry,
+};
+use crate::errors::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+use vidyut_prakriya::args::{
+    Antargana, BaseKrt, Dhatu, Gana, Krt, Lakara, Linga, Pada, Pratipadika, Prayoga, Purusha,
+    Sanadi, Slp1String, Subanta, Tinanta, Unadi, Vacana, Vibhakti,
+};
+
+/// The column names in our TSV format.
+const TSV_HEADER: &str = "form\tlemma\tupos\tfeats\tmisc";
+
+/// A lexicon format supported by `Kosha::export` and `Builder::import`.
+///
+/// For details on each format, see the `interchange` module.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub enum LexiconFormat {
+    /// Tab-separated values with UD-style morphological features.
+    Tsv,
+    /// JSON Lines, with one JSON object per word.
+    Jsonl,
+    /// A CoNLL-U file with one single-token sentence per word.
+    Conllu,
+}
+
+impl LexiconFormat {
+    /// Returns a string label for this format.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Tsv => "tsv",
+            Self::Jsonl => "jsonl",
+            Self::Conllu => "conllu",
+        }
+    }
+}
+
+impl FromStr for LexiconFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let ret = match s {
+            "tsv" => Self::Tsv,
+            "jsonl" => Self::Jsonl,
+            "conllu" => Self::Conllu,
+            _ => return Err(Error::ParseEnum("LexiconFormat", s.to_string())),
+        };
+        Ok(ret)
+    }
+}
+
+/// A word and its semantics as owned data.
+///
+/// `PadaEntry` borrows its data from a `Kosha`, so we use this struct to read and write words.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Record {
+    pub(crate) key: String,
+    pada: Pada,
+    /// Metadata for the *dhātu* of a *tiṅanta* or *kṛdanta*.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dhatu_meta: Option<DhatuMeta>,
+    /// The lingas of a basic *prātipadika*.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    lingas: Vec<Linga>,
+    /// Whether a *subanta* is a *pūrvapada*, which `Pada` can't express.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    is_purvapada: bool,
+}
+
+/// A word in the TSV or CoNLL-U formats.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Row {
+    form: String,
+    lemma: String,
+    upos: String,
+    feats: BTreeMap<String, String>,
+    misc: BTreeMap<String, String>,
+}
+
+impl Record {
+    /// Creates a record for the given key and entry.
+    pub(crate) fn new(key: &str, entry: &PadaEntry) -> Self {
+        let dhatu_entry = match entry {
+            PadaEntry::Tinanta(t) => Some(t.dhatu_entry()),
+            PadaEntry::Subanta(s) => match s.pratipadika_entry() {
+                PratipadikaEntry::Krdanta(k) => Some(k.dhatu_entry()),
+                _ => None,
+            },
+        };
+        // Unpacked dhatus always have metadata, so skip metadata that is empty.
+        let dhatu_meta = dhatu_entry
+            .and_then(|d| d.meta)
+            .filter(|m| **m != DhatuMeta::default())
+            .cloned();
+
+        let lingas = match entry {
+            PadaEntry::Subanta(s) => match s.pratipadika_entry() {
+                PratipadikaEntry::Basic(b) => b.lingas().to_vec(),
+                _ => Vec::new(),
+            },
+            PadaEntry::Tinanta(_) => Vec::new(),
+        };
+
+        // `Pada::from` doesn't keep whether a subanta is an avyaya, so set it explicitly.
+        let pada = match entry {
+            PadaEntry::Subanta(s) if s.is_avyaya() => {
+                Pada::Subanta(Subanta::avyaya(Pratipadika::from(s.pratipadika_entry())))
+            }
+            _ => entry.clone().into(),
+        };
+
+        let is_purvapada = matches!(entry, PadaEntry::Subanta(s) if s.is_purvapada());
+
+        Self {
+            key: key.to_string(),
+            pada,
+            dhatu_meta,
+            lingas,
+            is_purvapada,
+        }
+    }
+
+    /// Returns a `PadaEntry` that borrows from this record.
+    pub(crate) fn to_entry(&self) -> Result<PadaEntry<'_>> {
+        let to_dhatu_entry = |dhatu| {
+            let entry = DhatuEntry::new(dhatu);
+            match &self.dhatu_meta {
+                Some(m) => entry.with_meta(m),
+                None => entry,
+            }
+        };
+
+        match &self.pada {
+            Pada::Tinanta(t) => Ok(TinantaEntry::new(
+                to_dhatu_entry(t.dhatu()),
+                t.prayoga(),
+                t.lakara(),
+                t.purusha(),
+                t.vacana(),
+            )
+            .into()),
+            Pada::Subanta(s) => {
+                let pratipadika_entry = match s.pratipadika() {
+                    Pratipadika::Basic(b) => {
+                        PratipadikaEntry::Basic(BasicPratipadikaEntry::new(b, &self.lingas))
+                    }
+                    Pratipadika::Krdanta(k) => PratipadikaEntry::Krdanta(KrdantaEntry::new(
+                        to_dhatu_entry(k.dhatu()),
+                        k.krt(),
+                        k.prayoga(),
+                        k.lakara(),
+                    )),
+                    p => p.try_into()?,
+                };
+                let subanta = if self.is_purvapada {
+                    SubantaEntry::purvapada(pratipadika_entry)
+                } else if s.is_avyaya() {
+                    SubantaEntry::avyaya(pratipadika_entry)
+                } else {
+                    SubantaEntry::new(pratipadika_entry, s.linga(), s.vibhakti(), s.vacana())
+                };
+                Ok(subanta.into())
+            }
+            _ => Err(Error::UnsupportedType),
+        }
+    }
+
+    /// Returns the lemma of the given dhatu.
+    fn dhatu_lemma(&self, dhatu: &Dhatu) -> String {
+        match &self.dhatu_meta {
+            Some(m) if !m.clean_text.is_empty() => m.clean_text.clone(),
+            _ => match dhatu {
+                Dhatu::Mula(m) => m.aupadeshika().to_string(),
+                Dhatu::Nama(n) => pratipadika_lemma(n.pratipadika()),
+            },
+        }
+    }
+
+    /// Converts this record to a TSV or CoNLL-U row.
+    fn to_row(&self) -> Result<Row> {
+        let mut row = Row {
+            form: self.key.clone(),
+            ..Row::default()
+        };
+
+        match &self.pada {
+            Pada::Tinanta(t) => {
+                let (tense, mood) = lakara_to_ud(t.lakara());
+                row.lemma = self.dhatu_lemma(t.dhatu());
+                row.upos = "VERB".to_string();
+                row.set_feat("Mood", mood);
+                row.set_feat("Number", vacana_to_ud(t.vacana()));
+                row.set_feat("Person", purusha_to_ud(t.purusha()));
+                row.set_feat("Tense", tense);
+                row.set_feat("Voice", prayoga_to_ud(t.prayoga()));
+
+                write_dhatu_misc(&mut row, t.dhatu());
+                row.set_misc("Lakara", t.lakara().as_str());
+                row.set_misc("Prayoga", t.prayoga().as_str());
+            }
+            Pada::Subanta(s) => {
+                if self.is_purvapada {
+                    row.upos = "NOUN".to_string();
+                    row.set_feat("Case", "Cpd");
+                } else if s.is_avyaya() {
+                    row.upos = "ADV".to_string();
+                } else {
+                    row.upos = "NOUN".to_string();
+                    row.set_feat("Case", vibhakti_to_ud(s.vibhakti()));
+                    row.set_feat("Gender", linga_to_ud(s.linga()));
+                    row.set_feat("Number", vacana_to_ud(s.vacana()));
+                }
+
+                match s.pratipadika() {
+                    Pratipadika::Krdanta(k) => {
+                        row.lemma = self.dhatu_lemma(k.dhatu());
+                        write_dhatu_misc(&mut row, k.dhatu());
+                        match k.krt() {
+                            Krt::Base(x) => row.set_misc("Krt", x.as_str()),
+                            Krt::Unadi(x) => row.set_misc("Unadi", x.as_str()),
+                        }
+                        if let Some(la) = k.lakara() {
+                            row.set_misc("Lakara", la.as_str());
+                        }
+                        if let Some(pr) = k.prayoga() {
+                            row.set_misc("Prayoga", pr.as_str());
+                        }
+                    }
+                    p => {
+                        row.lemma = pratipadika_lemma(p);
+                        if !self.lingas.is_empty() {
+                            let lingas: Vec<_> = self.lingas.iter().map(|x| x.as_str()).collect();
+                            row.set_misc("Lingas", &lingas.join(","));
+                        }
+                    }
+                }
+            }
+            _ => return Err(Error::UnsupportedType),
+        }
+
+        Ok(row)
+    }
+
+    /// Creates a record from a TSV or CoNLL-U row.
+    fn from_row(row: &Row) -> std::result::Result<Self, String> {
+        let is_tinanta = row.upos == "VERB"
+            && !["Case", "Gender", "VerbForm"]
+                .iter()
+                .any(|f| row.feats.contains_key(*f));
+
+        let mut dhatu_meta = None;
+        let mut lingas = Vec::new();
+        let mut is_purvapada = false;
+        let pada = if is_tinanta {
+            dhatu_meta = Some(clean_text_meta(&row.lemma)?);
+            let lakara = match row.misc.get("Lakara") {
+                Some(s) => parse(s)?,
+                None => lakara_from_ud(row.feat("Tense")?, row.feat("Mood")?)?,
+            };
+            let prayoga = match row.misc.get("Prayoga") {
+                Some(s) => parse(s)?,
+                None => match row.feats.get("Voice") {
+                    Some(s) => prayoga_from_ud(s)?,
+                    None => Prayoga::Kartari,
+                },
+            };
+            Pada::Tinanta(Tinanta::new(
+                read_dhatu_misc(row)?,
+                prayoga,
+                lakara,
+                purusha_from_ud(row.feat("Person")?)?,
+                vacana_from_ud(row.feat("Number")?)?,
+            ))
+        } else {
+            let krt: Option<Krt> = match (row.misc.get("Krt"), row.misc.get("Unadi")) {
+                (Some(s), _) => Some(parse::<BaseKrt>(s)?.into()),
+                (None, Some(s)) => Some(parse::<Unadi>(s)?.into()),
+                (None, None) => None,
+            };
+            let is_avyaya = !row.feats.contains_key("Case");
+            is_purvapada = row.feats.get("Case").is_some_and(|s| s == "Cpd");
+
+            let pratipadika: Pratipadika = match krt {
+                Some(krt) => {
+                    dhatu_meta = Some(clean_text_meta(&row.lemma)?);
+                    let mut builder = vidyut_prakriya::args::Krdanta::builder()
+                        .dhatu(read_dhatu_misc(row)?)
+                        .krt(krt);
+                    if let Some(s) = row.misc.get("Lakara") {
+                        builder = builder.lakara(parse(s)?);
+                    }
+                    if let Some(s) = row.misc.get("Prayoga") {
+                        builder = builder.prayoga(parse(s)?);
+                    }
+                    builder.build().map_err(|e| e.to_string())?.into()
+                }
+                None => {
+                    if let Some(s) = row.misc.get("Lingas") {
+                        lingas = s
+                            .split(',')
+                            .map(parse)
+                            .collect::<std::result::Result<_, _>>()?;
+                    }
+                    let text = slp1(&row.lemma)?;
+                    if is_avyaya {
+                        Pratipadika::avyaya(text)
+                    } else {
+                        Pratipadika::basic(text)
+                    }
+                }
+            };
+
+            if is_avyaya {
+                Pada::Subanta(Subanta::avyaya(pratipadika))
+            } else if is_purvapada {
+                Pada::Subanta(Subanta::new(
+                    pratipadika,
+                    Linga::Pum,
+                    Vibhakti::Prathama,
+                    Vacana::Eka,
+                ))
+            } else {
+                Pada::Subanta(Subanta::new(
+                    pratipadika,
+                    linga_from_ud(row.feat("Gender")?)?,
+                    vibhakti_from_ud(row.feat("Case")?)?,
+                    vacana_from_ud(row.feat("Number")?)?,
+                ))
+            }
+        };
+
+        Ok(Self {
+            key: row.form.clone(),
+            pada,
+            dhatu_meta,
+            lingas,
+            is_purvapada,
+        })
+    }
+}
+
+impl Row {
+    fn set_feat(&mut self, key: &str, value: &str) {
+        self.feats.insert(key.to_string(), value.to_string());
+    }
+
+    fn set_misc(&mut self, key: &str, value: &str) {
+        self.misc.insert(key.to_string(), value.to_string());
+    }
+
+    /// Returns the value of a required feature.
+    fn feat(&self, key: &str) -> std::result::Result<&str, String> {
+        match self.feats.get(key) {
+            Some(s) => Ok(s),
+            None => Err(format!("missing feature `{key}`")),
+        }
+    }
+
+    /// Parses a row from the `form`, `lemma`, `upos`, `feats`, and `misc` columns.
+    fn from_fields(
+        form: &str,
+        lemma: &str,
+        upos: &str,
+        feats: &str,
+        misc: &str,
+    ) -> std::result::Result<Self, String> {
+        if form.is_empty() || form == "_" {
+            return Err("missing form".to_string());
+        }
+        Ok(Self {
+            form: form.to_string(),
+            lemma: from_field(lemma).to_string(),
+            upos: from_field(upos).to_string(),
+            feats: from_features(feats),
+            misc: from_features(misc),
+        })
+    }
+}
+
+/// Parses an SLP1 string.
+fn slp1(text: &str) -> std::result::Result<Slp1String, String> {
+    Slp1String::from(text).map_err(|e| e.to_string())
+}
+
+/// Parses one of our enum values.
+fn parse<T: FromStr>(text: &str) -> std::result::Result<T, String> {
+    text.parse()
+        .map_err(|_| format!("unknown {} `{text}`", std::any::type_name::<T>()))
+}
+
+/// Creates dhatu metadata with the given clean text.
+fn clean_text_meta(lemma: &str) -> std::result::Result<DhatuMeta, String> {
+    DhatuMeta::builder()
+        .clean_text(lemma.to_string())
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Returns the lemma of the given *prātipadika*.
+fn pratipadika_lemma(p: &Pratipadika) -> String {
+    match p {
+        Pratipadika::Basic(b) => b.text().to_string(),
+        Pratipadika::Krdanta(k) => match k.dhatu() {
+            Dhatu::Mula(m) => m.aupadeshika().to_string(),
+            Dhatu::Nama(n) => pratipadika_lemma(n.pratipadika()),
+        },
+        Pratipadika::Taddhitanta(t) => pratipadika_lemma(t.pratipadika()),
+        Pratipadika::Samasa(s) => match s.padas().last() {
+            Some(last) => pratipadika_lemma(last.pratipadika()),
+            None => String::new(),
+        },
+    }
+}
+
+/// Writes the fields of a *mūla dhātu* to `misc`.
+fn write_dhatu_misc(row: &mut Row, dhatu: &Dhatu) {
+    if let Dhatu::Mula(m) = dhatu {
+        row.set_misc("Dhatu", m.aupadeshika());
+        row.set_misc("Gana", m.gana().as_str());
+        if let Some(a) = m.antargana() {
+            row.set_misc("Antargana", a.as_str());
+        }
+        if !m.prefixes().is_empty() {
+            row.set_misc("Prefixes", &m.prefixes().join(","));
+        }
+        if !m.sanadi().is_empty() {
+            let sanadi: Vec<_> = m.sanadi().iter().map(|x| x.as_str()).collect();
+            row.set_misc("Sanadi", &sanadi.join(","));
+        }
+    }
+}
+
+/// Reads a *mūla dhātu* from `misc`, using the row's lemma if `misc` has no dhatu.
+fn read_dhatu_misc(row: &Row) -> std::result::Result<Dhatu, String> {
+    let aupadeshika = row.misc.get("Dhatu").unwrap_or(&row.lemma);
+    let gana = match row.misc.get("Gana") {
+        Some(s) => parse(s)?,
+        None => Gana::Bhvadi,
+    };
+    let mut builder = Dhatu::builder().aupadeshika(aupadeshika).gana(gana);
+    if let Some(s) = row.misc.get("Antargana") {
+        builder = builder.antargana(parse::<Antargana>(s)?);
+    }
+    if let Some(s) = row.misc.get("Prefixes") {
+        let prefixes: Vec<_> = s.split(',').collect();
+        builder = builder.prefixes(&prefixes);
+    }
+    if let Some(s) = row.misc.get("Sanadi") {
+        let sanadi = s
+            .split(',')
+            .map(parse::<Sanadi>)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        builder = builder.sanadi(&sanadi);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+/// Parses a CoNLL-U field. (`_` represents the empty string.)
+fn from_field(s: &str) -> &str {
+    if s == "_" {
+        ""
+    } else {
+        s
+    }
+}
+
+/// Formats a CoNLL-U field. (`_` represents the empty string.)
+fn to_field(s: &str) -> &str {
+    if s.is_empty() {
+        "_"
+    } else {
+        s
+    }
+}
+
+/// Parses a CoNLL-U feature list, e.g. `Case=Nom|Number=Sing`.
+fn from_features(s: &str) -> BTreeMap<String, String> {
+    from_field(s)
+        .split_terminator('|')
+        .filter_map(|item| item.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// Formats a CoNLL-U feature list, e.g. `Case=Nom|Number=Sing`.
+fn to_features(features: &BTreeMap<String, String>) -> String {
+    if features.is_empty() {
+        "_".to_string()
+    } else {
+        let items: Vec<_> = features.iter().map(|(k, v)| format!("{k}={v}")).collect();
+        items.join("|")
+    }
+}
+
+fn linga_to_ud(linga: Linga) -> &'static str {
+    match linga {
+        Linga::Pum => "Masc",
+        Linga::Stri => "Fem",
+        Linga::Napumsaka => "Neut",
+    }
+}
+
+fn linga_from_ud(s: &str) -> std::result::Result<Linga, String> {
+    let ret = match s {
+        "Masc" => Linga::Pum,
+        "Fem" => Linga::Stri,
+        "Neut" => Linga::Napumsaka,
+        _ => return Err(format!("unknown Gender `{s}`")),
+    };
+    Ok(ret)
+}
+
+fn vibhakti_to_ud(vibhakti: Vibhakti) -> &'static str {
+    use Vibhakti::*;
+    match vibhakti {
+        Prathama => "Nom",
+        Dvitiya => "Acc",
+        Trtiya => "Ins",
+        Caturthi => "Dat",
+        Panchami => "Abl",
+        Sasthi => "Gen",
+        Saptami => "Loc",
+        Sambodhana => "Voc",
+    }
+}
+
+fn vibhakti_from_ud(s: &str) -> std::result::Result<Vibhakti, String> {
+    use Vibhakti::*;
+    let ret = match s {
+        "Nom" => Prathama,
+        "Acc" => Dvitiya,
+        "Ins" => Trtiya,
+        "Dat" => Caturthi,
+        "Abl" => Panchami,
+        "Gen" => Sasthi,
+        "Loc" => Saptami,
+        "Voc" => Sambodhana,
+        _ => return Err(format!("unknown Case `{s}`")),
+    };
+    Ok(ret)
+}
+
+fn vacana_to_ud(vacana: Vacana) -> &'static str {
+    match vacana {
+        Vacana::Eka => "Sing",
+        Vacana::Dvi => "Dual",
+        Vacana::Bahu => "Plur",
+    }
+}
+
+fn vacana_from_ud(s: &str) -> std::result::Result<Vacana, String> {
+    let ret = match s {
+        "Sing" => Vacana::Eka,
+        "Dual" => Vacana::Dvi,
+        "Plur" => Vacana::Bahu,
+        _ => return Err(format!("unknown Number `{s}`")),
+    };
+    Ok(ret)
+}
+
+fn purusha_to_ud(purusha: Purusha) -> &'static str {
+    match purusha {
+        Purusha::Prathama => "3",
+        Purusha::Madhyama => "2",
+        Purusha::Uttama => "1",
+    }
+}
+
+fn purusha_from_ud(s: &str) -> std::result::Result<Purusha, String> {
+    let ret = match s {
+        "3" => Purusha::Prathama,
+        "2" => Purusha::Madhyama,
+        "1" => Purusha::Uttama,
+        _ => return Err(format!("unknown Person `{s}`")),
+    };
+    Ok(ret)
+}
+
+/// UD has no impersonal voice, so we treat *bhāve prayoga* as passive.
+fn prayoga_to_ud(prayoga: Prayoga) -> &'static str {
+    match prayoga {
+        Prayoga::Kartari => "Act",
+        Prayoga::Karmani | Prayoga::Bhave => "Pass",
+    }
+}
+
+fn prayoga_from_ud(s: &str) -> std::result::Result<Prayoga, String> {
+    let ret = match s {
+        "Act" => Prayoga::Kartari,
+        "Pass" => Prayoga::Karmani,
+        _ => return Err(format!("unknown Voice `{s}`")),
+    };
+    Ok(ret)
+}
+
+/// Returns the UD (`Tense`, `Mood`) for the given lakara.
+fn lakara_to_ud(lakara: Lakara) -> (&'static str, &'static str) {
+    use Lakara::*;
+    match lakara {
+        Lat => ("Pres", "Ind"),
+        Lit => ("Perf", "Ind"),
+        Lut | Lrt => ("Fut", "Ind"),
+        Let => ("Pres", "Sub"),
+        Lot => ("Pres", "Imp"),
+        Lan => ("Impf", "Ind"),
+        VidhiLin => ("Pres", "Opt"),
+        AshirLin => ("Aor", "Prec"),
+        Lun => ("Aor", "Ind"),
+        Lrn => ("Fut", "Cond"),
+    }
+}
+
+fn lakara_from_ud(tense: &str, mood: &str) -> std::result::Result<Lakara, String> {
+    use Lakara::*;
+    let ret = match (tense, mood) {
+        ("Pres", "Ind") => Lat,
+        ("Perf", "Ind") => Lit,
+        ("Fut", "Ind") => Lrt,
+        ("Pres", "Sub") => Let,
+        ("Pres", "Imp") => Lot,
+        ("Impf", "Ind") => Lan,
+        ("Pres", "Opt") => VidhiLin,
+        ("Aor", "Prec") => AshirLin,
+        ("Aor", "Ind") => Lun,
+        ("Fut", "Cond") => Lrn,
+        _ => return Err(format!("unknown Tense `{tense}` with Mood `{mood}`")),
+    };
+    Ok(ret)
+}
+
+/// Writes records in some `LexiconFormat`.
+pub(crate) struct Exporter<W: Write> {
+    format: LexiconFormat,
+    writer: W,
+    /// The number of records we've written so far.
+    count: usize,
+}
+
+impl<W: Write> Exporter<W> {
+    /// Creates an exporter and writes any header that `format` needs.
+    pub(crate) fn new(format: LexiconFormat, mut writer: W) -> Result<Self> {
+        if format == LexiconFormat::Tsv {
+            writeln!(writer, "{TSV_HEADER}")?;
+        }
+        Ok(Self {
+            format,
+            writer,
+            count: 0,
+        })
+    }
+
+    /// Writes the given record.
+    pub(crate) fn write(&mut self, record: &Record) -> Result<()> {
+        self.count += 1;
+        let w = &mut self.writer;
+        match self.format {
+            LexiconFormat::Jsonl => {
+                let line = serde_json::to_string(record).map_err(io_error)?;
+                writeln!(w, "{line}")?;
+            }
+            LexiconFormat::Tsv => {
+                let row = record.to_row()?;
+                writeln!(
+                    w,
+                    "{}\t{}\t{}\t{}\t{}",
+                    row.form,
+                    to_field(&row.lemma),
+                    to_field(&row.upos),
+                    to_features(&row.feats),
+                    to_features(&row.misc)
+                )?;
+            }
+            LexiconFormat::Conllu => {
+                let row = record.to_row()?;
+                writeln!(w, "# sent_id = {}", self.count)?;
+                writeln!(w, "# text = {}", row.form)?;
+                writeln!(
+                    w,
+                    "1\t{}\t{}\t{}\t_\t{}\t0\troot\t_\t{}",
+                    row.form,
+                    to_field(&row.lemma),
+                    to_field(&row.upos),
+                    to_features(&row.feats),
+                    to_features(&row.misc)
+                )?;
+                writeln!(w)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flushes all records to the underlying writer.
+    pub(crate) fn finish(mut self) -> Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+fn io_error(e: serde_json::Error) -> Error {
+    Error::Io(e.into())
+}
+
+/// Reads all records in `reader`, which has the given `format`.
+pub(crate) fn read_records(format: LexiconFormat, reader: impl BufRead) -> Result<Vec<Record>> {
+    let mut ret = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = i + 1;
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let record = match format {
+            LexiconFormat::Jsonl => serde_json::from_str(line).map_err(|e| e.to_string()),
+            LexiconFormat::Tsv => {
+                if line_number == 1 && line == TSV_HEADER {
+                    continue;
+                }
+                let fields: Vec<_> = line.split('\t').collect();
+                match fields.as_slice() {
+                    [form, lemma, upos, feats, misc] => {
+                        Row::from_fields(form, lemma, upos, feats, misc)
+                    }
+                    [form, lemma, upos, feats] => Row::from_fields(form, lemma, upos, feats, "_"),
+                    _ => Err(format!("expected 4 or 5 columns, found {}", fields.len())),
+                }
+                .and_then(|row| Record::from_row(&row))
+            }
+            LexiconFormat::Conllu => {
+                let fields: Vec<_> = line.split('\t').collect();
+                match fields.as_slice() {
+                    // Multiword tokens (`1-2`) and empty nodes (`1.1`) have no lemma of their own.
+                    [id, ..] if id.contains(['-', '.']) => continue,
+                    [_, form, lemma, upos, _, feats, _, _, _, misc] => {
+                        Row::from_fields(form, lemma, upos, feats, misc)
+                    }
+                    _ => Err(format!("expected 10 columns, found {}", fields.len())),
+                }
+                .and_then(|row| Record::from_row(&row))
+            }
+        };
+        ret.push(record.map_err(|e| Error::InvalidRecord(line_number, e))?);
+    }
+    Ok(ret)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn safe(s: &str) -> Slp1String {
+        Slp1String::from(s).expect("ok")
+    }
+
+    fn gam() -> Dhatu {
+        Dhatu::mula(safe("ga\\mx"), Gana::Bhvadi)
+    }
+
+    fn gam_meta() -> DhatuMeta {
+        DhatuMeta::builder()
+            .clean_text("gam".to_string())
+            .artha_en("go".to_string())
+            .build()
+            .unwrap()
+    }
+
+    fn read(format: LexiconFormat, text: &str) -> Result<Vec<Record>> {
+        read_records(format, text.as_bytes())
+    }
+
+    fn write(format: LexiconFormat, records: &[Record]) -> String {
+        let mut out = Vec::new();
+        let mut exporter = Exporter::new(format, &mut out).unwrap();
+        for r in records {
+            exporter.write(r).unwrap();
+        }
+        exporter.finish().unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn lexicon_format_from_str() {
+        for f in [
+            LexiconFormat::Tsv,
+            LexiconFormat::Jsonl,
+            LexiconFormat::Conllu,
+        ] {
+            assert_eq!(f.as_str().parse::<LexiconFormat>().unwrap(), f);
+        }
+        assert!("csv".parse::<LexiconFormat>().is_err());
+    }
+
+    #[test]
+    fn lakara_round_trip() {
+        for la in Lakara::iter() {
+            let (tense, mood) = lakara_to_ud(la);
+            let expected = if la == Lakara::Lut { Lakara::Lrt } else { la };
+            assert_eq!(lakara_from_ud(tense, mood).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn tinanta_row() {
+        let gam = gam();
+        let meta = gam_meta();
+        let entry: PadaEntry = TinantaEntry::new(
+            DhatuEntry::new(&gam).with_meta(&meta),
+            Prayoga::Kartari,
+            Lakara::Lut,
+            Purusha::Prathama,
+            Vacana::Eka,
+        )
+        .into();
+
+        let text = write(LexiconFormat::Tsv, &[Record::new("gantA", &entry)]);
+        assert_eq!(
+            text,
+            format!(
+                "{TSV_HEADER}\ngantA\tgam\tVERB\tMood=Ind|Number=Sing|Person=3|Tense=Fut|Voice=Act\t\
+                 Dhatu=ga\\mx|Gana=BvAdi|Lakara=lu~w|Prayoga=kartari\n"
+            )
+        );
+
+        // Dhatu metadata other than the lemma is lost.
+        let records = read(LexiconFormat::Tsv, &text).unwrap();
+        assert_eq!(records.len(), 1);
+        let meta = DhatuMeta::builder()
+            .clean_text("gam".to_string())
+            .build()
+            .unwrap();
+        let expected = TinantaEntry::new(
+            DhatuEntry::new(&gam).with_meta(&meta),
+            Prayoga::Kartari,
+            Lakara::Lut,
+            Purusha::Prathama,
+            Vacana::Eka,
+        );
+        assert_eq!(records[0].key, "gantA");
+        assert_eq!(records[0].to_entry().unwrap(), expected.into());
+    }
+
+    #[test]
+    fn subanta_row() {
+        let Pratipadika::Basic(deva) = Pratipadika::basic(safe("deva")) else {
+            unreachable!()
+        };
+        let lingas = [Linga::Pum];
+        let entry: PadaEntry = SubantaEntry::new(
+            PratipadikaEntry::Basic(BasicPratipadikaEntry::new(&deva, &lingas)),
+            Linga::Pum,
+            Vibhakti::Trtiya,
+            Vacana::Bahu,
+        )
+        .into();
+
+        let text = write(LexiconFormat::Conllu, &[Record::new("devEH", &entry)]);
+        assert_eq!(
+            text,
+            "# sent_id = 1\n# text = devEH\n\
+             1\tdevEH\tdeva\tNOUN\t_\tCase=Ins|Gender=Masc|Number=Plur\t0\troot\t_\tLingas=puM\n\n"
+        );
+
+        let records = read(LexiconFormat::Conllu, &text).unwrap();
+        assert_eq!(records, vec![Record::new("devEH", &entry)]);
+    }
+
+    #[test]
+    fn purvapada_row() {
+        let Pratipadika::Basic(deva) = Pratipadika::basic(safe("deva")) else {
+            unreachable!()
+        };
+        let lingas = [Linga::Pum];
+        let entry: PadaEntry = SubantaEntry::purvapada(PratipadikaEntry::Basic(
+            BasicPratipadikaEntry::new(&deva, &lingas),
+        ))
+        .into();
+        let record = Record::new("deva", &entry);
+
+        let text = write(LexiconFormat::Tsv, std::slice::from_ref(&record));
+        assert!(text.ends_with("deva\tdeva\tNOUN\tCase=Cpd\tLingas=puM\n"));
+        let records = read(LexiconFormat::Tsv, &text).unwrap();
+        assert_eq!(records, vec![record.clone()]);
+        assert_eq!(records[0].to_entry().unwrap(), entry);
+
+        let text = write(LexiconFormat::Jsonl, std::slice::from_ref(&record));
+        let records = read(LexiconFormat::Jsonl, &text).unwrap();
+        assert_eq!(records[0].to_entry().unwrap(), entry);
+    }
+
+    #[test]
+    fn krdanta_row() {
+        let gam = gam();
+        let meta = gam_meta();
+        let krdanta = KrdantaEntry::new(
+            DhatuEntry::new(&gam).with_meta(&meta),
+            BaseKrt::ktvA.into(),
+            None,
+            None,
+        );
+        let entry: PadaEntry = SubantaEntry::avyaya(PratipadikaEntry::Krdanta(krdanta)).into();
+
+        let text = write(LexiconFormat::Tsv, &[Record::new("gatvA", &entry)]);
+        assert!(text.ends_with("gatvA\tgam\tADV\t_\tDhatu=ga\\mx|Gana=BvAdi|Krt=ktvA\n"));
+
+        let records = read(LexiconFormat::Tsv, &text).unwrap();
+        let entry = records[0].to_entry().unwrap();
+        match entry {
+            PadaEntry::Subanta(s) => {
+                assert!(s.is_avyaya());
+                assert_eq!(s.pratipadika_entry().lemma(), "gam");
+            }
+            _ => panic!("expected subanta"),
+        }
+    }
+
+    #[test]
+    fn jsonl_is_lossless() {
+        let gam = gam();
+        let meta = gam_meta();
+        let entry: PadaEntry = TinantaEntry::new(
+            DhatuEntry::new(&gam).with_meta(&meta),
+            Prayoga::Bhave,
+            Lakara::Lut,
+            Purusha::Prathama,
+            Vacana::Eka,
+        )
+        .into();
+        let record = Record::new("gamyate", &entry);
+
+        let text = write(LexiconFormat::Jsonl, std::slice::from_ref(&record));
+        assert_eq!(text.lines().count(), 1);
+        let records = read(LexiconFormat::Jsonl, &text).unwrap();
+        assert_eq!(records, vec![record]);
+        assert_eq!(records[0].to_entry().unwrap(), entry);
+    }
+
+    #[test]
+    fn read_foreign_rows() {
+        // Rows from other projects have no `misc` column and might omit a header.
+        let text = "Bavati\tBU\tVERB\tMood=Ind|Number=Sing|Person=3|Tense=Pres|Voice=Act\n\
+                    ca\tca\tCCONJ\t_\n";
+        let records = read(LexiconFormat::Tsv, text).unwrap();
+        assert_eq!(records.len(), 2);
+
+        match records[0].to_entry().unwrap() {
+            PadaEntry::Tinanta(t) => {
+                assert_eq!(t.dhatu(), &Dhatu::mula(safe("BU"), Gana::Bhvadi));
+                assert_eq!(t.dhatu_text(), "BU");
+                assert_eq!(t.lakara(), Lakara::Lat);
+                assert_eq!(t.prayoga(), Prayoga::Kartari);
+            }
+            _ => panic!("expected tinanta"),
+        }
+        match records[1].to_entry().unwrap() {
+            PadaEntry::Subanta(s) => assert!(s.is_avyaya()),
+            _ => panic!("expected subanta"),
+        }
+    }
+
+    #[test]
+    fn read_conllu_with_multiword_tokens() {
+        let text = "# text = rAmaSca\n\
+                    1-2\trAmaSca\t_\t_\t_\t_\t_\t_\t_\t_\n\
+                    1\trAmaH\trAma\tNOUN\t_\tCase=Nom|Gender=Masc|Number=Sing\t0\troot\t_\t_\n\
+                    2\tca\tca\tCCONJ\t_\t_\t1\tcc\t_\t_\n\n";
+        let records = read(LexiconFormat::Conllu, text).unwrap();
+        let keys: Vec<_> = records.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(keys, ["rAmaH", "ca"]);
+    }
+
+    #[test]
+    fn read_invalid_rows() {
+        let text =
+            format!("{TSV_HEADER}\nrAmaH\trAma\tNOUN\tCase=Foo|Gender=Masc|Number=Sing\t_\n");
+        assert!(matches!(
+            read(LexiconFormat::Tsv, &text),
+            Err(Error::InvalidRecord(2, s)) if s == "unknown Case `Foo`"
+        ));
+
+        let text = "gacCati\tgam\tVERB\tMood=Ind|Number=Sing|Tense=Pres\t_\n";
+        assert!(matches!(
+            read(LexiconFormat::Tsv, text),
+            Err(Error::InvalidRecord(1, s)) if s == "missing feature `Person`"
+        ));
+
+        assert!(matches!(
+            read(LexiconFormat::Jsonl, "{\"key\": \"ca\"}\n"),
+            Err(Error::InvalidRecord(1, _))
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-//! A memory-efficient Sanskrit kosha (lexicon) with basic support for searching prefixes.
+//! A memory-efficient Sanskrit kosha (lexicon) with support for prefix, pattern, and fuzzy search.
 //!
 //!
 //! Implementation
@@ -33,22 +33,32 @@
 //! roughly 1.5x slower than a default `HashMap`. Our production kosha stores more than 29 million
 //! words in around 31MB of data with an average storage cost of 1 byte per word. Of course, the
 //! specific storage cost will vary depending on the words in the input list.
-use crate::entries::{DhatuEntry, PadaEntry, PratipadikaEntry};
+use crate::entries::{DhatuEntry, Metadata, PadaEntry, PratipadikaEntry};
 use crate::errors::{Error, Result};
+use crate::interchange::{self, Exporter, LexiconFormat};
+use crate::lazy::Bytes;
+use crate::lemmas::{LemmaIndex, Record};
+use crate::manifest::Manifest;
+use crate::metadata::MetadataTables;
 use crate::packing::{Id, PackedEntry, Packer, SubantaSuffixes, TinantaSuffixes};
+use crate::search::{Levenshtein, Regex};
+use fst::automaton::{Automaton, Str};
 use fst::map::Stream;
 use fst::raw::{Fst, Node, Output};
+use fst::Streamer;
 use fst::{Map, MapBuilder};
-use log::info;
+use log::{info, warn};
 use rustc_hash::FxHashMap;
+use std::collections::VecDeque;
 use std::fs::File;
-use std::io;
+use std::io::{self, BufRead, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 use vidyut_prakriya::args::{Lakara, Linga, Prayoga, Purusha, Vacana, Vibhakti};
 
 // Use the range [0, 64] to avoid confusion with the ASCII range, which starts at 65 (01000001,
 // i.e. uppercase `A`).
-const DUPES_PER_BYTE: u8 = 65;
+pub(crate) const DUPES_PER_BYTE: u8 = 65;
 const MAX_DUPLICATES: usize = (DUPES_PER_BYTE as usize) * (DUPES_PER_BYTE as usize);
 
 struct Paths {
@@ -73,32 +83,240 @@ impl Paths {
     fn registry(&self) -> PathBuf {
         self.base.join("registry.msgpack")
     }
+
+    /// Path to our reverse index from lemmas to keys.
+    fn lemmas(&self) -> PathBuf {
+        self.base.join("lemmas.msgpack")
+    }
+
+    /// Path to our user-defined metadata.
+    fn metadata(&self) -> PathBuf {
+        self.base.join("metadata.msgpack")
+    }
+
+    /// Path to the manifest that describes the files above.
+    fn manifest(&self) -> PathBuf {
+        self.base.join("manifest.msgpack")
+    }
+
+    /// Paths to all of the files that the manifest describes.
+    fn data_files(&self) -> Vec<PathBuf> {
+        vec![self.fst(), self.registry(), self.lemmas(), self.metadata()]
+    }
+
+    /// Reads the manifest, if one exists.
+    ///
+    /// Koshas created before we added manifests don't have this file, so we can't check them.
+    fn read_manifest(&self) -> Result<Option<Manifest>> {
+        if self.manifest().exists() {
+            info!("Loading manifest from `{:?}`", self.manifest());
+            Ok(Some(Manifest::read(&self.manifest())?))
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 fn to_packed_entry(output: Output) -> PackedEntry {
     PackedEntry::from_u32(output.value() as u32)
 }
 
+/// Sorts `results` from highest weight to lowest, with unweighted results last.
+///
+/// Since weights are never negative, we can treat a missing weight as negative infinity.
+pub(crate) fn sort_by_weight<T>(results: &mut [(T, Option<f32>)]) {
+    let key = |w: &Option<f32>| w.unwrap_or(f32::NEG_INFINITY);
+    results.sort_by(|(_, x), (_, y)| key(y).total_cmp(&key(x)));
+}
+
+/// A lemma ID in our registry.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum LemmaId {
+    Dhatu(Id),
+    Pratipadika(Id),
+}
+
 /// A compact Sanskrit kosha.
 pub struct Kosha {
     /// The underlying FST object.
-    fst: Map<Vec<u8>>,
+    pub(crate) fst: Map<Bytes>,
+    /// `fst` as an in-memory buffer, for `Kosha::fst`. If we read the kosha into memory, this
+    /// shares its buffer with `fst`. Otherwise, we copy `fst` on first use.
+    owned_fst: OnceLock<Arc<Map<Vec<u8>>>>,
     /// Maps indices to semantics objects.
-    packer: Packer,
+    pub(crate) packer: Packer,
+    /// Paths to the files that define this kosha.
+    paths: Paths,
+    /// The manifest that describes this kosha, if it has one.
+    manifest: Option<Manifest>,
+    /// Maps lemmas to the keys that use them. We load this index on first use.
+    lemmas: OnceLock<LemmaIndex>,
+    /// Maps lemma strings to their IDs. We create this map on first use.
+    lemma_ids: OnceLock<FxHashMap<String, Vec<LemmaId>>>,
 }
 
 impl Kosha {
     /// Reads the kosha from the given `base_path`.
+    ///
+    /// Before returning, we check the kosha against its manifest. If the kosha was built with an
+    /// incompatible version of this crate, or if its data doesn't match its manifest, we return
+    /// an error like `Error::UnsupportedFormat` or `Error::CountMismatch`. To also check the
+    /// kosha's files against their checksums, use `verify`.
     pub fn new(base_path: impl AsRef<Path>) -> Result<Self> {
         let paths = Paths::new(base_path);
+        let manifest = paths.read_manifest()?;
 
         info!("Loading fst from `{:?}`", paths.fst());
-        let fst = Map::new(std::fs::read(paths.fst())?)?;
+        let owned_fst = Arc::new(Map::new(std::fs::read(paths.fst())?)?);
+        let fst = Map::new(Bytes::from_map(owned_fst.clone()))?;
 
         info!("Loading registry from `{:?}`", paths.registry());
         let packer = Packer::read(&paths.registry())?;
 
-        Ok(Self { fst, packer })
+        let mut kosha = Self::from_parts(fst, packer, paths, manifest)?;
+        kosha.owned_fst = OnceLock::from(owned_fst);
+        Ok(kosha)
+    }
+
+    /// Opens the kosha at the given `base_path` by memory-mapping its files.
+    ///
+    /// Unlike `new`, this method doesn't read the kosha into memory. Instead, we read pages from
+    /// disk as we use them, and we decode dhatus and pratipadikas only when they are first used.
+    /// So this method uses much less memory than `new`, and all processes that open the same
+    /// kosha share the same pages through the OS page cache. Startup still takes time linear in
+    /// the size of the registry, since we scan it once to find where each group of items starts.
+    ///
+    /// Some methods need to see all dhatus and pratipadikas, so they are slow the first time they
+    /// are called. These are `pack`, `lemma_forms`, `dhatu_forms`, and `pratipadika_forms`.
+    ///
+    /// Like `new`, this method checks the kosha against its manifest.
+    ///
+    /// # Safety
+    ///
+    /// The kosha's files must not be modified or truncated while the kosha is open, since doing so
+    /// changes the memory that the kosha refers to. To update a kosha that might be open, write
+    /// the new kosha to a different directory then rename it into place.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// # use vidyut_kosha::*;
+    /// use vidyut_kosha::Kosha;
+    ///
+    /// // SAFETY: we never modify the kosha in place.
+    /// let kosha = unsafe { Kosha::mmap("/path/to/kosha/data")? };
+    ///
+    /// for entry in kosha.get_all("Bavati") {
+    ///     println!("{:#?}", entry);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    #[allow(unsafe_code)]
+    pub unsafe fn mmap(base_path: impl AsRef<Path>) -> Result<Self> {
+        let paths = Paths::new(base_path);
+        let manifest = paths.read_manifest()?;
+
+        info!("Mapping fst from `{:?}`", paths.fst());
+        let fst = Map::new(Bytes::mmap(&paths.fst())?)?;
+
+        info!("Mapping registry from `{:?}`", paths.registry());
+        let packer = Packer::read_lazy(Bytes::mmap(&paths.registry())?)?;
+
+        Self::from_parts(fst, packer, paths, manifest)
+    }
+
+    fn from_parts(
+        fst: Map<Bytes>,
+        mut packer: Packer,
+        paths: Paths,
+        manifest: Option<Manifest>,
+    ) -> Result<Self> {
+        if let Some(m) = &manifest {
+            m.check_versions()?;
+            m.check_data(&packer, fst.len())?;
+        }
+
+        // Koshas created before we added metadata don't have this file.
+        if paths.metadata().exists() {
+            info!("Loading metadata from `{:?}`", paths.metadata());
+            packer.metadata = MetadataTables::read(&paths.metadata())?;
+        }
+
+        Ok(Self {
+            fst,
+            owned_fst: OnceLock::new(),
+            packer,
+            paths,
+            manifest,
+            lemmas: OnceLock::new(),
+            lemma_ids: OnceLock::new(),
+        })
+    }
+
+    /// Checks the kosha's files against the checksums in its manifest.
+    ///
+    /// Since this method reads every byte of the kosha, it is slow for large koshas. So we don't
+    /// call it when loading a kosha. Instead, call it when you suspect that the kosha's files are
+    /// corrupt, e.g. after downloading or copying them.
+    ///
+    /// Koshas created before we added manifests have no checksums, so this method accepts them.
+    pub fn verify(&self) -> Result<()> {
+        match &self.manifest {
+            Some(m) => m.check_files(&self.paths.base),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns our reverse index from lemmas to keys, loading it if necessary.
+    fn lemmas(&self) -> Result<&LemmaIndex> {
+        if let Some(lemmas) = self.lemmas.get() {
+            return Ok(lemmas);
+        }
+
+        // Koshas created before we added the lemma index don't have this file, so rebuild the
+        // index from the FST instead.
+        let lemmas = if self.paths.lemmas().exists() {
+            info!("Loading lemma index from `{:?}`", self.paths.lemmas());
+            LemmaIndex::read(&self.paths.lemmas())?
+        } else {
+            warn!(
+                "Lemma index `{:?}` is missing, so we will rebuild it from the FST. \
+                 To avoid this cost, rebuild the kosha.",
+                self.paths.lemmas()
+            );
+            self.create_lemma_index()
+        };
+        Ok(self.lemmas.get_or_init(|| lemmas))
+    }
+
+    /// Creates our reverse index from lemmas to keys by reading the entire FST.
+    fn create_lemma_index(&self) -> LemmaIndex {
+        let mut ret = LemmaIndex::new();
+        let mut stream = self.fst.stream();
+        while let Some((raw_key, value)) = stream.next() {
+            // All keys were inserted as `&str`, so this should never fail.
+            if let Ok(key) = std::str::from_utf8(self.strip_dupe_marker(raw_key)) {
+                ret.add(key, &PackedEntry::from_u32(value as u32));
+            }
+        }
+        ret
+    }
+
+    fn lemma_ids(&self) -> &FxHashMap<String, Vec<LemmaId>> {
+        self.lemma_ids.get_or_init(|| self.create_lemma_ids())
+    }
+
+    fn create_lemma_ids(&self) -> FxHashMap<String, Vec<LemmaId>> {
+        let mut ret: FxHashMap<String, Vec<LemmaId>> = FxHashMap::default();
+        for (i, dhatu) in self.dhatus().enumerate() {
+            let ids = ret.entry(dhatu.clean_text().to_string()).or_default();
+            ids.push(LemmaId::Dhatu(Id(i)));
+        }
+        for (i, pratipadika) in self.pratipadikas().enumerate() {
+            let ids = ret.entry(pratipadika.lemma().to_string()).or_default();
+            ids.push(LemmaId::Pratipadika(Id(i)));
+        }
+        ret
     }
 
     /// Returns the number of words in the kosha.
@@ -191,7 +409,20 @@ impl Kosha {
     }
 
     /// Returns a reference to this kosha's underlying FST.
+    ///
+    /// If the kosha was opened with `mmap`, the first call to this method copies the FST into
+    /// memory. To avoid this copy, use `fst_ref`.
     pub fn fst(&self) -> &Map<Vec<u8>> {
+        self.owned_fst.get_or_init(|| {
+            let bytes = self.fst.as_fst().as_bytes().to_vec();
+            Arc::new(Map::new(bytes).expect("`self.fst` is a valid FST"))
+        })
+    }
+
+    /// Returns a reference to this kosha's underlying FST without copying it.
+    ///
+    /// If the kosha was opened with `mmap`, the FST refers to the kosha's memory-mapped file.
+    pub fn fst_ref(&self) -> &Map<impl AsRef<[u8]>> {
         &self.fst
     }
 
@@ -378,6 +609,56 @@ impl Kosha {
         ret
     }
 
+    /// Returns the prior weight of the given *pada*, or `None` if it has no weight.
+    ///
+    /// Weights are set with `Builder::set_pada_weight`. If the kosha has any weights, this
+    /// method packs `pada`, which is slow the first time it is called on a memory-mapped kosha.
+    pub fn weight(&self, pada: &PadaEntry) -> Option<f32> {
+        self.packer.pada_weight(pada)
+    }
+
+    /// Returns all results for the given `key` along with their weights, sorted from highest
+    /// weight to lowest.
+    ///
+    /// Results without a weight come after all results with a weight, even those with weight 0.
+    /// Results with the same weight are returned in the same order as `get_all`.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// # use vidyut_kosha::*;
+    /// use vidyut_kosha::Kosha;
+    ///
+    /// let kosha = Kosha::new("/path/to/kosha/data")?;
+    ///
+    /// for (entry, weight) in kosha.get_all_ranked("Bavati") {
+    ///     println!("{weight:?}: {:#?}", entry);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn get_all_ranked(&self, key: &str) -> Vec<(PadaEntry<'_>, Option<f32>)> {
+        let mut ret: Vec<_> = self
+            .get_all(key)
+            .into_iter()
+            .map(|entry| {
+                let weight = self.weight(&entry);
+                (entry, weight)
+            })
+            .collect();
+        sort_by_weight(&mut ret);
+        ret
+    }
+
+    /// Returns the `k` results for the given `key` with the highest weights, sorted from highest
+    /// weight to lowest.
+    ///
+    /// This is a shorthand for truncating the results of `get_all_ranked`.
+    pub fn get_top_k(&self, key: &str, k: usize) -> Vec<(PadaEntry<'_>, Option<f32>)> {
+        let mut ret = self.get_all_ranked(key);
+        ret.truncate(k);
+        ret
+    }
+
     fn get_all_for_suffix<'a>(
         &'a self,
         ret: &mut Vec<PadaEntry<'a>>,
@@ -434,7 +715,7 @@ impl Kosha {
         &'a self,
         node: Node,
         out: Output,
-        fst: &Fst<Vec<u8>>,
+        fst: &Fst<Bytes>,
         results: &mut Vec<PadaEntry<'a>>,
     ) {
         for c1 in 0..DUPES_PER_BYTE {
@@ -470,13 +751,381 @@ impl Kosha {
         }
     }
 
+    /// Returns all words in the kosha that use the given dhatu, along with their semantics.
+    ///
+    /// Words are returned in the order they were inserted, and each paradigm is returned in the
+    /// order it was registered. Words derived from the dhatu through a *kṛt pratyaya* use a
+    /// different *prātipadika*, so we don't return them here. To get them, use `lemma_forms` or
+    /// `pratipadika_forms`.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// # use vidyut_kosha::*;
+    /// use vidyut_kosha::Kosha;
+    ///
+    /// let kosha = Kosha::new("/path/to/kosha/data")?;
+    ///
+    /// for dhatu in kosha.dhatus().take(1) {
+    ///     for (key, entry) in kosha.dhatu_forms(&dhatu)? {
+    ///         println!("{key}: {:?}", entry);
+    ///     }
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn dhatu_forms(&self, dhatu: &DhatuEntry) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        let id = self.packer.pack_dhatu(dhatu.dhatu())?;
+        self.expand_records(self.lemmas()?.dhatu(id))
+    }
+
+    /// Returns all words in the kosha that use the given *prātipadika*, along with their
+    /// semantics.
+    ///
+    /// Words are returned in the order they were inserted, and each paradigm is returned in the
+    /// order it was registered.
+    pub fn pratipadika_forms(
+        &self,
+        pratipadika: &PratipadikaEntry,
+    ) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        let id = self.packer.pack_pratipadika_id(pratipadika)?;
+        self.expand_records(self.lemmas()?.pratipadika(id))
+    }
+
+    /// Returns all words in the kosha whose `PadaEntry::lemma` is `lemma`, along with their
+    /// semantics.
+    ///
+    /// For a dhatu, `lemma` is its `clean_text`. So `lemma_forms("gam")` returns both tinantas
+    /// like `gacCati` and krdantas like `gata`.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// # use vidyut_kosha::*;
+    /// use vidyut_kosha::Kosha;
+    ///
+    /// let kosha = Kosha::new("/path/to/kosha/data")?;
+    ///
+    /// for (key, entry) in kosha.lemma_forms("deva")? {
+    ///     println!("{key}: {:?}", entry);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn lemma_forms(&self, lemma: &str) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        let mut ret = Vec::new();
+        let lemmas = self.lemmas()?;
+        for id in self
+            .lemma_ids()
+            .get(lemma)
+            .map_or(&[][..], |x| x.as_slice())
+        {
+            let records = match id {
+                LemmaId::Dhatu(id) => lemmas.dhatu(*id),
+                LemmaId::Pratipadika(id) => lemmas.pratipadika(*id),
+            };
+            ret.extend(self.expand_records(records)?);
+        }
+        Ok(ret)
+    }
+
+    fn expand_records(&self, records: &[Record]) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        let mut ret = Vec::new();
+        for (key, value) in records {
+            self.packer
+                .expand(&mut ret, key, &PackedEntry::from_u32(*value))?;
+        }
+        Ok(ret)
+    }
+
+    /// Returns all words in the kosha that start with `prefix`, along with their semantics.
+    ///
+    /// Results are sorted by key. This is useful for autocomplete, but short prefixes can match
+    /// millions of words.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// # use vidyut_kosha::*;
+    /// use vidyut_kosha::Kosha;
+    ///
+    /// let kosha = Kosha::new("/path/to/kosha/data")?;
+    ///
+    /// for (key, entry) in kosha.prefix_search("gacCa")? {
+    ///     println!("{key}: {:?}", entry);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn prefix_search(&self, prefix: &str) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        self.search(Str::new(prefix).starts_with())
+    }
+
+    /// Returns all words in the kosha that fully match the regular expression `pattern`, along
+    /// with their semantics.
+    ///
+    /// For the supported syntax, see the [`regex`](https://docs.rs/regex) crate. Results are
+    /// sorted by key.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// # use vidyut_kosha::*;
+    /// use vidyut_kosha::Kosha;
+    ///
+    /// let kosha = Kosha::new("/path/to/kosha/data")?;
+    ///
+    /// for (key, entry) in kosha.regex_search("deva(s|m)")? {
+    ///     println!("{key}: {:?}", entry);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn regex_search(&self, pattern: &str) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        self.search(Regex::new(pattern)?)
+    }
+
+    /// Returns all words in the kosha that fully match the glob `pattern`, along with their
+    /// semantics.
+    ///
+    /// `*` matches any sequence of characters, `?` matches any single character, and `[...]`
+    /// and `[!...]` match any single character in or not in a set. Results are sorted by key.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// # use vidyut_kosha::*;
+    /// use vidyut_kosha::Kosha;
+    ///
+    /// let kosha = Kosha::new("/path/to/kosha/data")?;
+    ///
+    /// for (key, entry) in kosha.glob_search("*Ami")? {
+    ///     println!("{key}: {:?}", entry);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn glob_search(&self, pattern: &str) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        self.search(Regex::from_glob(pattern)?)
+    }
+
+    /// Returns all words in the kosha within `max_distance` edits of `query`, along with their
+    /// semantics.
+    ///
+    /// We measure edit distance in sounds rather than bytes: a sound is a single character along
+    /// with any SLP1 marks (`~`, `/`, `\`, `^`) that follow it. Results are sorted by key. This
+    /// is useful for correcting OCR and spelling errors, but distances above 2 are slow.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// # use vidyut_kosha::*;
+    /// use vidyut_kosha::Kosha;
+    ///
+    /// let kosha = Kosha::new("/path/to/kosha/data")?;
+    ///
+    /// for (key, entry) in kosha.fuzzy_search("gacCatu", 1)? {
+    ///     println!("{key}: {:?}", entry);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn fuzzy_search(
+        &self,
+        query: &str,
+        max_distance: usize,
+    ) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        self.search(Levenshtein::new(query, max_distance))
+    }
+
+    /// Returns all words in the kosha that `aut` matches, along with their semantics.
+    ///
+    /// Unlike `fst().search(aut)`, this method matches against complete words: we expand each
+    /// prefix with its suffix table and skip the bytes that mark duplicate keys. Results are
+    /// sorted by key.
+    pub fn search<A: Automaton>(&self, aut: A) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        let fst = self.fst.as_fst();
+        let mut ret = Vec::new();
+        let mut path = SearchPath {
+            key: Vec::new(),
+            states: vec![aut.start()],
+            is_key: Vec::new(),
+        };
+        self.search_node(&aut, fst.root(), Output::zero(), &mut path, &mut ret)?;
+        // Stable, so duplicates stay in insertion order.
+        ret.sort_by(|x, y| x.0.cmp(&y.0));
+        Ok(ret)
+    }
+
+    /// Adds all matches in the subtree rooted at `node` to `ret`.
+    ///
+    /// Args:
+    /// - `node`: the node we reach after reading `path.key`.
+    /// - `out`: the output corresponding to this node.
+    /// - `path`: the path from the root to `node`.
+    fn search_node<'a, A: Automaton>(
+        &'a self,
+        aut: &A,
+        node: Node,
+        out: Output,
+        path: &mut SearchPath<A::State>,
+        ret: &mut Vec<(String, PadaEntry<'a>)>,
+    ) -> Result<()> {
+        let d = path.key.len();
+        let can_match = aut.can_match(&path.states[d]);
+        // A duplicate of a key is the key followed by two marker bytes.
+        let is_dupe =
+            node.is_final() && d >= 2 && path.is_key[d - 2] && is_marker(&path.key[d - 2..]);
+        let is_key = node.is_final() && !is_dupe;
+
+        if node.is_final() {
+            let entry = to_packed_entry(out.cat(node.final_output()));
+            if is_dupe {
+                let (key, state) = (&path.key[..d - 2], &path.states[d - 2]);
+                if aut.can_match(state) {
+                    self.search_entry(aut, state, key, &entry, ret)?;
+                }
+            } else if can_match {
+                self.search_entry(aut, &path.states[d], &path.key, &entry, ret)?;
+            }
+        }
+
+        // Even if `aut` can't match any longer key, it might match a duplicate of this key or of
+        // the key before it, so keep reading marker bytes.
+        let in_marker = d >= 1 && path.is_key[d - 1] && path.key[d - 1] < DUPES_PER_BYTE;
+        if !can_match && !is_key && !in_marker {
+            return Ok(());
+        }
+
+        let fst = self.fst.as_fst();
+        path.is_key.push(is_key);
+        for t in node.transitions() {
+            if !can_match && t.inp >= DUPES_PER_BYTE {
+                continue;
+            }
+            let next = aut.accept(&path.states[d], t.inp);
+            path.key.push(t.inp);
+            path.states.push(next);
+            self.search_node(aut, fst.node(t.addr), out.cat(t.out), path, ret)?;
+            path.key.pop();
+            path.states.pop();
+        }
+        path.is_key.pop();
+        Ok(())
+    }
+
+    /// Adds the words for `key` and `entry` that `aut` matches to `ret`.
+    fn search_entry<'a, A: Automaton>(
+        &'a self,
+        aut: &A,
+        state: &A::State,
+        key: &[u8],
+        entry: &PackedEntry,
+        ret: &mut Vec<(String, PadaEntry<'a>)>,
+    ) -> Result<()> {
+        // All keys were inserted as `&str`, so this should never fail.
+        let Ok(key) = std::str::from_utf8(key) else {
+            return Ok(());
+        };
+        self.packer.expand_matching(ret, key, entry, |ending| {
+            let mut bytes = ending.bytes();
+            match bytes.next() {
+                Some(b) => {
+                    let state = bytes.fold(aut.accept(state, b), |s, b| aut.accept(&s, b));
+                    aut.is_match(&state)
+                }
+                None => aut.is_match(state),
+            }
+        })
+    }
+
     /// Iterates over all key-value pairs in the FST.
     ///
-    /// NOTE: this method currently has limited functionality.
+    /// NOTE: this method currently has limited functionality. To iterate over words, use `iter`.
     pub fn stream(&self) -> Stream<'_> {
         self.fst.stream()
     }
 
+    /// Iterates over all words in the kosha, along with their semantics.
+    ///
+    /// Unlike `stream`, this method expands each prefix with its suffix table and skips the bytes
+    /// that mark duplicate keys. We read the FST lazily, so this method uses little memory even
+    /// on large koshas. Words are returned in FST order, so the words in a paradigm are returned
+    /// together and might not be sorted with respect to other keys.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// # use vidyut_kosha::*;
+    /// use vidyut_kosha::Kosha;
+    ///
+    /// let kosha = Kosha::new("/path/to/kosha/data")?;
+    ///
+    /// for item in kosha.iter() {
+    ///     let (key, entry) = item?;
+    ///     println!("{key}: {:?}", entry);
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            kosha: self,
+            stream: self.fst.stream(),
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Returns `raw_key` without the bytes that mark it as a duplicate, if it has any.
+    ///
+    /// `raw_key` is a key from the underlying FST.
+    pub(crate) fn strip_dupe_marker<'k>(&self, raw_key: &'k [u8]) -> &'k [u8] {
+        match raw_key.len().checked_sub(2) {
+            Some(n) if is_marker(&raw_key[n..]) && self.is_key(&raw_key[..n]) => &raw_key[..n],
+            _ => raw_key,
+        }
+    }
+
+    /// Returns whether `raw_key` is a key in the underlying FST and not a duplicate of one.
+    fn is_key(&self, raw_key: &[u8]) -> bool {
+        self.fst.contains_key(raw_key) && self.strip_dupe_marker(raw_key).len() == raw_key.len()
+    }
+
+    /// Returns all words for the given FST entry, along with their semantics.
+    ///
+    /// `raw_key` and `value` are a key-value pair from the underlying FST, e.g. from `stream` or
+    /// `fst`. If `value` is a prefix, we return one word per ending in its paradigm. This method
+    /// is useful for iterating over the FST in custom ways, e.g. in batches.
+    pub fn expand_entry(&self, raw_key: &[u8], value: u64) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        let mut ret = Vec::new();
+        // All keys were inserted as `&str`, so this should never fail.
+        if let Ok(key) = std::str::from_utf8(self.strip_dupe_marker(raw_key)) {
+            let entry = PackedEntry::from_u32(value as u32);
+            self.packer.expand(&mut ret, key, &entry)?;
+        }
+        Ok(ret)
+    }
+
+    /// Writes all words in the kosha to `writer` in the given lexicon format.
+    ///
+    /// Words are written in the same order as `iter`. For details on each format, see
+    /// `LexiconFormat`. To read the output back into a kosha, use `Builder::import`.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// # use vidyut_kosha::*;
+    /// use std::fs::File;
+    /// use std::io::BufWriter;
+    /// use vidyut_kosha::{Kosha, LexiconFormat};
+    ///
+    /// let kosha = Kosha::new("/path/to/kosha/data")?;
+    /// let writer = BufWriter::new(File::create("lexicon.conllu")?);
+    /// kosha.export(LexiconFormat::Conllu, writer)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn export(&self, format: LexiconFormat, writer: impl Write) -> Result<()> {
+        let mut exporter = Exporter::new(format, writer)?;
+        for item in self.iter() {
+            let (key, entry) = item?;
+            exporter.write(&interchange::Record::new(&key, &entry))?;
+        }
+        exporter.finish()
+    }
+
     /// Packs the given `PadaEntry` into a simple integer code.
     pub fn pack(&self, value: &PadaEntry) -> Result<PackedEntry> {
         self.packer.pack(value)
@@ -488,16 +1137,62 @@ impl Kosha {
     }
 }
 
+/// An iterator over all words in a `Kosha`, along with their semantics.
+///
+/// For details, see `Kosha::iter`.
+pub struct Iter<'a> {
+    kosha: &'a Kosha,
+    stream: Stream<'a>,
+    /// Words from the current FST entry that we haven't returned yet.
+    buffer: VecDeque<(String, PadaEntry<'a>)>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Result<(String, PadaEntry<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffer.is_empty() {
+            let (raw_key, value) = self.stream.next()?;
+            match self.kosha.expand_entry(raw_key, value) {
+                Ok(words) => self.buffer.extend(words),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        self.buffer.pop_front().map(Ok)
+    }
+}
+
 /// Builder for a `Kosha`.
 ///
-/// Memory usage is linear in the number of unique lemmas (`Dhatu`s or `Pratipadika`s).
+/// Memory usage is linear in the number of unique lemmas (`Dhatu`s or `Pratipadika`s) and in the
+/// number of inserted keys, since we also build a reverse index from lemmas to keys.
 pub struct Builder {
     seen_keys: FxHashMap<String, usize>,
     fst_builder: MapBuilder<io::BufWriter<File>>,
-    packer: Packer,
+    pub(crate) packer: Packer,
+    lemmas: LemmaIndex,
     paths: Paths,
 }
 
+/// The path from the FST's root to some node, as used in `Kosha::search_node`.
+struct SearchPath<S> {
+    /// The bytes on the path.
+    key: Vec<u8>,
+    /// The automaton state at each node on the path, including the root.
+    states: Vec<S>,
+    /// Whether each node on the path ends a key (and not a duplicate), excluding the last node.
+    is_key: Vec<bool>,
+}
+
+/// Returns whether `bytes` could mark a duplicate key.
+///
+/// A marker is always two bytes below `DUPES_PER_BYTE`. Since valid keys can also contain these
+/// bytes (e.g. `'` and `/` in SLP1), a marker is a duplicate only if it follows a complete key.
+/// `Builder::insert_packed` rejects any key that would make this ambiguous.
+fn is_marker(bytes: &[u8]) -> bool {
+    bytes.len() == 2 && bytes.iter().all(|b| *b < DUPES_PER_BYTE)
+}
+
 /// Create an extended insertion key (for duplicates).
 ///
 /// The output of this function must respect the FST's insertion constraint on key orderings. That
@@ -532,6 +1227,7 @@ impl Builder {
             seen_keys: FxHashMap::default(),
             fst_builder: MapBuilder::new(writer)?,
             packer: Packer::new(),
+            lemmas: LemmaIndex::new(),
             paths,
         })
     }
@@ -563,6 +1259,12 @@ impl Builder {
             Some(c) => *c,
             None => 0,
         };
+        // A key that is another key followed by two marker bytes would look like a duplicate.
+        let n = key.len().saturating_sub(2);
+        if num_repeats == 0 && is_marker(&key.as_bytes()[n..]) && seen_keys.contains_key(&key[..n])
+        {
+            return Err(Error::AmbiguousKey(key.to_string()));
+        }
         seen_keys.insert(key.to_string(), num_repeats + 1);
 
         // For duplicates, add another u8 to make this key unique.
@@ -578,10 +1280,46 @@ impl Builder {
         // paradigm containing multiple suffixes. So instead, maintain our own count. We
         // keep this count on the packer so we can reuse its MessagePack serde.
         self.packer.increment(value)?;
+        self.lemmas.add(key, value);
 
         Ok(())
     }
 
+    /// Reads all words in `reader`, which has the given lexicon format, and inserts them.
+    ///
+    /// Unlike `insert`, this method accepts keys in any order. But since we sort keys only within
+    /// `reader`, all of its keys must come after any keys that were inserted beforehand. For
+    /// details on each format, see `LexiconFormat`. Returns the number of words inserted.
+    ///
+    /// If any record is invalid, we insert nothing and return `Error::InvalidRecord`.
+    ///
+    /// # Usage
+    ///
+    /// ```rust,no_run
+    /// # use vidyut_kosha::*;
+    /// use std::fs::File;
+    /// use std::io::BufReader;
+    /// use vidyut_kosha::{Builder, LexiconFormat};
+    ///
+    /// let mut builder = Builder::new("/path/to/kosha/data")?;
+    /// let reader = BufReader::new(File::open("lexicon.tsv")?);
+    /// builder.import(LexiconFormat::Tsv, reader)?;
+    /// builder.finish()?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn import(&mut self, format: LexiconFormat, reader: impl BufRead) -> Result<usize> {
+        let mut records = interchange::read_records(format, reader)?;
+        // Stable, so that duplicate keys keep their order.
+        records.sort_by(|x, y| x.key.cmp(&y.key));
+
+        for record in &records {
+            let entry = record.to_entry()?;
+            self.register_pada_entry(&entry);
+            self.insert(&record.key, &entry)?;
+        }
+        Ok(records.len())
+    }
+
     /// Registers the given dhatus on the internal packer. Duplicates are ignored.
     pub fn register_dhatu_entry(&mut self, dhatu: &DhatuEntry) {
         self.packer.register_dhatu_entry(dhatu);
@@ -623,11 +1361,57 @@ impl Builder {
         self.packer.pack(pada)
     }
 
+    /// Attaches `metadata` to the given dhatu, replacing any metadata it had before.
+    ///
+    /// `dhatu` must be registered beforehand. Its metadata is available through
+    /// `DhatuEntry::metadata` on all entries that use it.
+    pub fn set_dhatu_metadata(&mut self, dhatu: &DhatuEntry, metadata: Metadata) -> Result<()> {
+        self.packer.set_dhatu_metadata(dhatu, metadata)
+    }
+
+    /// Attaches `metadata` to the given *prātipadika*, replacing any metadata it had before.
+    ///
+    /// `pratipadika` must be registered beforehand. Its metadata is available through
+    /// `PratipadikaEntry::metadata` on all entries that use it.
+    pub fn set_pratipadika_metadata(
+        &mut self,
+        pratipadika: &PratipadikaEntry,
+        metadata: Metadata,
+    ) -> Result<()> {
+        self.packer.set_pratipadika_metadata(pratipadika, metadata)
+    }
+
+    /// Sets the prior weight of the given *pada*, replacing any weight it had before.
+    ///
+    /// Weights might be corpus counts or probabilities, and `Kosha::get_all_ranked` uses them to
+    /// sort the results for a key. So `weight` must be finite and non-negative. A weight of 0
+    /// means that the *pada* is known to be rare, which is different from having no weight.
+    ///
+    /// The lemma of `pada` must be registered beforehand.
+    pub fn set_pada_weight(&mut self, pada: &PadaEntry, weight: f32) -> Result<()> {
+        self.packer.set_pada_weight(pada, weight)
+    }
+
+    /// Attaches `metadata` to the given *pada*, replacing any metadata it had before.
+    ///
+    /// The lemma of `pada` must be registered beforehand. Its metadata is available through
+    /// `PadaEntry::metadata`, even if `pada` is stored as part of a paradigm.
+    pub fn set_pada_metadata(&mut self, pada: &PadaEntry, metadata: Metadata) -> Result<()> {
+        self.packer.set_pada_metadata(pada, metadata)
+    }
+
     /// Writes all kosha data to disk.
     pub fn finish(self) -> Result<()> {
         info!("Writing FST and packer data to {:?}.", self.paths.base);
-        self.fst_builder.finish()?;
+        self.fst_builder.into_inner()?.flush()?;
         self.packer.write(&self.paths.registry())?;
+        self.lemmas.write(&self.paths.lemmas())?;
+        self.packer.metadata.write(&self.paths.metadata())?;
+
+        // Write the manifest last, since it describes all of the files above.
+        let num_keys = self.seen_keys.values().sum();
+        let manifest = Manifest::new(&self.packer, num_keys, &self.paths.data_files())?;
+        manifest.write(&self.paths.manifest())?;
 
         Ok(())
     }
@@ -639,7 +1423,7 @@ mod tests {
     use super::*;
     use crate::entries::*;
     use vidyut_prakriya::args as vp;
-    use vidyut_prakriya::args::{Dhatu, Pratipadika, Slp1String};
+    use vidyut_prakriya::args::{Dhatu, Pada, Pratipadika, Slp1String};
 
     use tempfile::tempdir;
 
@@ -652,6 +1436,9 @@ mod tests {
         };
         assert!(paths.fst().starts_with("foo/"));
         assert!(paths.registry().starts_with("foo/"));
+        assert!(paths.lemmas().starts_with("foo/"));
+        assert!(paths.metadata().starts_with("foo/"));
+        assert!(paths.manifest().starts_with("foo/"));
     }
 
     fn safe(s: &str) -> Slp1String {
@@ -758,6 +1545,682 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn lemma_forms() -> TestResult {
+        let gam = Dhatu::mula(safe("ga\\mx~"), vp::Gana::Bhvadi);
+        let gam_meta = DhatuMeta::builder()
+            .clean_text("gam".to_string())
+            .build()
+            .unwrap();
+        let gam_entry = DhatuEntry::new(&gam).with_meta(&gam_meta);
+        let gacchan_entry = PratipadikaEntry::Krdanta(KrdantaEntry::new(
+            gam_entry.clone(),
+            vp::BaseKrt::Satf.into(),
+            Some(vp::Prayoga::Kartari),
+            Some(vp::Lakara::Lat),
+        ));
+        let deva = Pratipadika::basic(safe("deva"));
+        let deva_entry: PratipadikaEntry = (&deva).try_into().unwrap();
+
+        let dir = tempdir()?;
+        let mut builder = Builder::new(dir.path())?;
+        builder.register_dhatu_entry(&gam_entry);
+        builder.register_pratipadika_entry(&gacchan_entry);
+
+        use vp::{Linga as L, Vacana as V, Vibhakti as Vi};
+        let (key, value) = builder.register_subanta_paradigm(
+            &deva_entry,
+            &[
+                ("devas".to_string(), L::Pum, Vi::Prathama, V::Eka),
+                ("devam".to_string(), L::Pum, Vi::Dvitiya, V::Eka),
+                ("devena".to_string(), L::Pum, Vi::Trtiya, V::Eka),
+            ],
+        )?;
+        builder.insert_packed(&key, &value)?;
+
+        let gacchan_1s = SubantaEntry::new(gacchan_entry.clone(), L::Pum, Vi::Prathama, V::Eka);
+        builder.insert("gacCan", &gacchan_1s.into())?;
+        for (key, vacana) in [("gacCatas", V::Dvi), ("gacCati", V::Eka)] {
+            let tinanta = TinantaEntry::new(
+                gam_entry.clone(),
+                vp::Prayoga::Kartari,
+                vp::Lakara::Lat,
+                vp::Purusha::Prathama,
+                vacana,
+            );
+            builder.insert(key, &tinanta.into())?;
+        }
+        builder.finish()?;
+
+        let kosha = Kosha::new(dir.path())?;
+        let keys = |forms: Vec<(String, PadaEntry)>| -> Vec<String> {
+            forms.into_iter().map(|(k, _)| k).collect()
+        };
+
+        let forms = kosha.pratipadika_forms(&deva_entry)?;
+        assert_eq!(keys(forms.clone()), ["devas", "devam", "devena"]);
+        match &forms[2].1 {
+            PadaEntry::Subanta(s) => {
+                assert_eq!(s.pratipadika_entry().lemma(), "deva");
+                assert_eq!(s.vibhakti(), Vi::Trtiya);
+            }
+            _ => panic!("expected subanta"),
+        }
+
+        let forms = kosha.dhatu_forms(&gam_entry)?;
+        assert_eq!(keys(forms.clone()), ["gacCatas", "gacCati"]);
+        match &forms[0].1 {
+            PadaEntry::Tinanta(t) => assert_eq!(t.vacana(), V::Dvi),
+            _ => panic!("expected tinanta"),
+        }
+
+        assert_eq!(keys(kosha.pratipadika_forms(&gacchan_entry)?), ["gacCan"]);
+        assert_eq!(
+            keys(kosha.lemma_forms("gam")?),
+            ["gacCatas", "gacCati", "gacCan"]
+        );
+        assert_eq!(
+            keys(kosha.lemma_forms("deva")?),
+            ["devas", "devam", "devena"]
+        );
+        assert!(kosha.lemma_forms("agni")?.is_empty());
+
+        // Unregistered lemmas are an error.
+        let agni = Pratipadika::basic(safe("agni"));
+        let agni_entry: PratipadikaEntry = (&agni).try_into().unwrap();
+        assert!(kosha.pratipadika_forms(&agni_entry).is_err());
+
+        // Koshas built before we added the lemma index (and the manifest) don't have it, so we
+        // rebuild it from the FST.
+        std::fs::remove_file(dir.path().join("lemmas.msgpack"))?;
+        std::fs::remove_file(dir.path().join("manifest.msgpack"))?;
+        let old_kosha = Kosha::new(dir.path())?;
+        for lemma in ["gam", "deva"] {
+            assert_eq!(old_kosha.lemma_forms(lemma)?, kosha.lemma_forms(lemma)?);
+        }
+        assert_eq!(
+            old_kosha.pratipadika_forms(&gacchan_entry)?,
+            kosha.pratipadika_forms(&gacchan_entry)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[allow(unsafe_code)]
+    fn mmap() -> TestResult {
+        // Enough pratipadikas to fill several chunks in our lazy registry.
+        let names: Vec<String> = (0..150)
+            .map(|i| format!("deva{}{}", (b'a' + i / 10) as char, (b'a' + i % 10) as char))
+            .collect();
+        let pratipadikas: Vec<Pratipadika> =
+            names.iter().map(|x| Pratipadika::basic(safe(x))).collect();
+        let entries: Vec<PratipadikaEntry> =
+            pratipadikas.iter().map(|x| x.try_into().unwrap()).collect();
+
+        let dir = tempdir()?;
+        let mut builder = Builder::new(dir.path())?;
+        use vp::{Linga as L, Vacana as V, Vibhakti as Vi};
+        for (name, entry) in names.iter().zip(&entries) {
+            builder.register_pratipadika_entry(entry);
+            let subanta = SubantaEntry::new(entry.clone(), L::Pum, Vi::Prathama, V::Eka);
+            builder.insert(&format!("{name}s"), &subanta.into())?;
+        }
+        builder.finish()?;
+
+        let eager = Kosha::new(dir.path())?;
+        // SAFETY: we don't modify the kosha while it is open.
+        let lazy = unsafe { Kosha::mmap(dir.path())? };
+
+        assert_eq!(lazy.len(), eager.len());
+        assert_eq!(
+            lazy.fst().as_fst().as_bytes(),
+            eager.fst_ref().as_fst().as_bytes()
+        );
+        // `new` reads the FST into memory once, so `fst` doesn't copy it.
+        assert!(std::ptr::eq(
+            eager.fst().as_fst().as_bytes(),
+            eager.fst_ref().as_fst().as_bytes()
+        ));
+        for key in ["devaaas", "devaojs", "devaoj", "nara"] {
+            assert_eq!(lazy.get_all(key), eager.get_all(key));
+        }
+        assert_eq!(lazy.prefix_search("devab")?, eager.prefix_search("devab")?);
+        assert_eq!(lazy.pratipadikas().count(), 150);
+        assert_eq!(lazy.lemma_forms("devaoj")?, eager.lemma_forms("devaoj")?);
+
+        // Packing needs an index over all pratipadikas, which we create on first use.
+        let subanta = SubantaEntry::new(entries[149].clone(), L::Pum, Vi::Prathama, V::Eka);
+        let pada = subanta.into();
+        assert_eq!(lazy.pack(&pada)?, eager.pack(&pada)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn search() -> TestResult {
+        let deva = Pratipadika::basic(safe("deva"));
+        let deva_entry: PratipadikaEntry = (&deva).try_into().unwrap();
+
+        let dir = tempdir()?;
+        let mut builder = Builder::new(dir.path())?;
+        builder.register_pratipadika_entry(&deva_entry);
+
+        use vp::{Linga as L, Vacana as V, Vibhakti as Vi};
+        let (key, value) = builder.register_subanta_paradigm(
+            &deva_entry,
+            &[
+                ("devas".to_string(), L::Pum, Vi::Prathama, V::Eka),
+                ("devam".to_string(), L::Pum, Vi::Dvitiya, V::Eka),
+                ("devena".to_string(), L::Pum, Vi::Trtiya, V::Eka),
+            ],
+        )?;
+        // The prefix is shorter than some of our queries below.
+        assert_eq!(key, "dev");
+        builder.insert_packed(&key, &value)?;
+
+        // Duplicate keys.
+        for vibhakti in [Vi::Prathama, Vi::Sambodhana] {
+            let subanta = SubantaEntry::new(deva_entry.clone(), L::Pum, vibhakti, V::Bahu);
+            builder.insert("devAs", &subanta.into())?;
+        }
+        builder.finish()?;
+
+        let kosha = Kosha::new(dir.path())?;
+        let keys = |forms: Vec<(String, PadaEntry)>| -> Vec<String> {
+            forms.into_iter().map(|(k, _)| k).collect()
+        };
+
+        assert_eq!(keys(kosha.prefix_search("deva")?), ["devam", "devas"]);
+        assert_eq!(
+            keys(kosha.prefix_search("dev")?),
+            ["devAs", "devAs", "devam", "devas", "devena"]
+        );
+        assert!(kosha.prefix_search("nara")?.is_empty());
+
+        let forms = kosha.regex_search("dev(a|A)s")?;
+        assert_eq!(keys(forms.clone()), ["devAs", "devAs", "devas"]);
+        match (&forms[0].1, &forms[1].1) {
+            (PadaEntry::Subanta(x), PadaEntry::Subanta(y)) => {
+                assert_eq!(x.vibhakti(), Vi::Prathama);
+                assert_eq!(y.vibhakti(), Vi::Sambodhana);
+            }
+            _ => panic!("expected subantas"),
+        }
+        assert!(kosha.regex_search("dev(").is_err());
+
+        assert_eq!(keys(kosha.glob_search("*na")?), ["devena"]);
+        assert_eq!(keys(kosha.glob_search("dev?[!s]")?), ["devam"]);
+
+        assert_eq!(
+            keys(kosha.fuzzy_search("devos", 1)?),
+            ["devAs", "devAs", "devas"]
+        );
+        assert_eq!(keys(kosha.fuzzy_search("devena", 0)?), ["devena"]);
+        assert!(kosha.fuzzy_search("deva", 0)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn keys_with_low_bytes() -> TestResult {
+        use vp::{Linga as L, Vacana as V, Vibhakti as Vi};
+        let a = Pratipadika::basic(safe("a"));
+        let a_entry: PratipadikaEntry = (&a).try_into().unwrap();
+        let a_1s: PadaEntry =
+            SubantaEntry::new(a_entry.clone(), L::Pum, Vi::Prathama, V::Eka).into();
+        let a_8s: PadaEntry =
+            SubantaEntry::new(a_entry.clone(), L::Pum, Vi::Sambodhana, V::Eka).into();
+
+        // In SLP1, `'` is avagraha and `/` is udatta. Both are below `DUPES_PER_BYTE`.
+        let dir = tempdir()?;
+        let mut builder = Builder::new(dir.path())?;
+        builder.register_pratipadika_entry(&a_entry);
+        builder.insert("a", &a_1s)?;
+        builder.insert("a", &a_8s)?;
+        builder.insert("a'", &a_1s)?;
+        builder.insert("a'b", &a_1s)?;
+        builder.insert("a/", &a_1s)?;
+        builder.finish()?;
+
+        let kosha = Kosha::new(dir.path())?;
+        let keys = |forms: Vec<(String, PadaEntry)>| -> Vec<String> {
+            forms.into_iter().map(|(k, _)| k).collect()
+        };
+
+        assert_eq!(kosha.get_all("a"), [a_1s.clone(), a_8s.clone()]);
+        assert_eq!(kosha.get_all("a'"), std::slice::from_ref(&a_1s));
+        assert_eq!(kosha.get_all("a'b"), std::slice::from_ref(&a_1s));
+        assert_eq!(kosha.get_all("a/"), std::slice::from_ref(&a_1s));
+        assert!(kosha.contains_key("a'"));
+
+        let all = ["a", "a", "a'", "a'b", "a/"];
+        assert_eq!(keys(kosha.prefix_search("a")?), all);
+        assert_eq!(keys(kosha.prefix_search("a'")?), ["a'", "a'b"]);
+        assert_eq!(keys(kosha.regex_search("a.")?), ["a'", "a/"]);
+        let items = kosha.iter().collect::<Result<Vec<_>>>()?;
+        assert_eq!(keys(items), all);
+
+        let mut stream = kosha.stream();
+        let mut expanded = Vec::new();
+        while let Some((k, v)) = stream.next() {
+            expanded.extend(kosha.expand_entry(k, v)?);
+        }
+        assert_eq!(keys(expanded), all);
+
+        // A key that looks like a duplicate of an earlier key is ambiguous.
+        let dir = tempdir()?;
+        let mut builder = Builder::new(dir.path())?;
+        builder.register_pratipadika_entry(&a_entry);
+        builder.insert("a", &a_1s)?;
+        assert!(matches!(
+            builder.insert("a'/", &a_1s),
+            Err(Error::AmbiguousKey(_))
+        ));
+        builder.insert("a'/b", &a_1s)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn taddhitantas_and_samasas() -> TestResult {
+        use vp::{Linga as L, Vacana as V, Vibhakti as Vi};
+
+        let dasharathi: Pratipadika = vp::Taddhitanta::builder()
+            .pratipadika(Pratipadika::basic(safe("daSaraTa")))
+            .taddhita(vp::Taddhita::iY)
+            .artha(vp::TaddhitaArtha::TasyaApatyam)
+            .build()
+            .unwrap()
+            .into();
+        let rajapurusha: Pratipadika = vp::Samasa::builder()
+            .padas(vec![
+                vp::Subanta::new(
+                    Pratipadika::basic(safe("rAjan")),
+                    L::Pum,
+                    Vi::Sasthi,
+                    V::Eka,
+                ),
+                vp::Subanta::new(dasharathi.clone(), L::Pum, Vi::Prathama, V::Eka),
+            ])
+            .samasa_type(vp::SamasaType::Tatpurusha)
+            .build()
+            .unwrap()
+            .into();
+        let dasharathi_entry: PratipadikaEntry = (&dasharathi).try_into().unwrap();
+        let rajapurusha_entry: PratipadikaEntry = (&rajapurusha).try_into().unwrap();
+
+        let dir = tempdir()?;
+        let mut builder = Builder::new(dir.path())?;
+        // Registering the samasa also registers its members.
+        builder.register_pratipadika_entry(&rajapurusha_entry);
+
+        let dasharathih = SubantaEntry::new(dasharathi_entry.clone(), L::Pum, Vi::Prathama, V::Eka);
+        let rajapurushah =
+            SubantaEntry::new(rajapurusha_entry.clone(), L::Pum, Vi::Prathama, V::Eka);
+        builder.insert("dASaraTiH", &dasharathih.clone().into())?;
+        builder.insert("rAjadASaraTiH", &rajapurushah.clone().into())?;
+        builder.finish()?;
+
+        let kosha = Kosha::new(dir.path())?;
+        assert_eq!(kosha.get_all("dASaraTiH"), vec![dasharathih.into()]);
+        assert_eq!(kosha.get_all("rAjadASaraTiH"), vec![rajapurushah.into()]);
+
+        match &kosha.get_all("rAjadASaraTiH")[0] {
+            PadaEntry::Subanta(s) => match s.pratipadika_entry() {
+                PratipadikaEntry::Samasa(samasa) => {
+                    assert_eq!(samasa.samasa_type(), vp::SamasaType::Tatpurusha);
+                    assert_eq!(samasa.padas().len(), 2);
+                    assert_eq!(samasa.padas()[0].vibhakti(), Vi::Sasthi);
+                    assert_eq!(samasa.padas()[1].pratipadika_entry(), &dasharathi_entry);
+                }
+                _ => panic!("expected samasa"),
+            },
+            _ => panic!("expected subanta"),
+        }
+
+        assert_eq!(
+            kosha.pratipadika_forms(&dasharathi_entry)?.len(),
+            1,
+            "taddhitantas have their own lemma ID"
+        );
+        // A samasa uses the lemma of its last member.
+        let keys: Vec<_> = kosha
+            .lemma_forms("daSaraTa")?
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(keys, ["dASaraTiH", "rAjadASaraTiH"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn purvapadas() -> TestResult {
+        use vp::{Linga as L, Vacana as V, Vibhakti as Vi};
+
+        let deva = Pratipadika::basic(safe("deva"));
+        let deva_entry: PratipadikaEntry = (&deva).try_into().unwrap();
+        let deva_purvapada = SubantaEntry::purvapada(deva_entry.clone());
+        let devah = SubantaEntry::new(deva_entry.clone(), L::Pum, Vi::Prathama, V::Eka);
+        assert!(deva_purvapada.is_purvapada());
+        assert!(!devah.is_purvapada());
+
+        let dir = tempdir()?;
+        let mut builder = Builder::new(dir.path())?;
+        builder.register_pratipadika_entry(&deva_entry);
+        builder.insert("deva", &deva_purvapada.clone().into())?;
+        builder.insert("devaH", &devah.clone().into())?;
+        builder.finish()?;
+
+        let kosha = Kosha::new(dir.path())?;
+        assert_eq!(kosha.get_all("deva"), vec![deva_purvapada.into()]);
+        assert_eq!(kosha.get_all("devaH"), vec![devah.into()]);
+        Ok(())
+    }
+
+    #[test]
+    #[allow(unsafe_code)]
+    fn metadata() -> TestResult {
+        let gam = Dhatu::mula(safe("gam"), vp::Gana::Bhvadi);
+        let gam_entry = DhatuEntry::new(&gam);
+        let deva = Pratipadika::basic(safe("deva"));
+        let deva_entry: PratipadikaEntry = (&deva).try_into().unwrap();
+        let agni = Pratipadika::basic(safe("agni"));
+        let agni_entry: PratipadikaEntry = (&agni).try_into().unwrap();
+
+        use vp::{Linga as L, Vacana as V, Vibhakti as Vi};
+        let devena = SubantaEntry::new(deva_entry.clone(), L::Pum, Vi::Trtiya, V::Eka);
+        let gacchati = TinantaEntry::new(
+            gam_entry.clone(),
+            vp::Prayoga::Kartari,
+            vp::Lakara::Lat,
+            vp::Purusha::Prathama,
+            V::Eka,
+        );
+
+        let dir = tempdir()?;
+        let mut builder = Builder::new(dir.path())?;
+        builder.register_dhatu_entry(&gam_entry);
+        builder.register_pratipadika_entry(&agni_entry);
+
+        // Metadata needs a registered lemma.
+        assert!(builder
+            .set_pratipadika_metadata(&deva_entry, Metadata::new())
+            .is_err());
+
+        let (key, value) = builder.register_subanta_paradigm(
+            &deva_entry,
+            &[
+                ("devas".to_string(), L::Pum, Vi::Prathama, V::Eka),
+                ("devena".to_string(), L::Pum, Vi::Trtiya, V::Eka),
+            ],
+        )?;
+        builder.set_dhatu_metadata(&gam_entry, Metadata::new().with("gloss", "go"))?;
+        builder.set_pratipadika_metadata(&deva_entry, Metadata::new().with("gloss", "god"))?;
+        builder.set_pada_metadata(&devena.clone().into(), Metadata::new().with("frequency", 7))?;
+        builder.set_pada_metadata(
+            &gacchati.clone().into(),
+            Metadata::new().with("tags", vec!["common".to_string()]),
+        )?;
+
+        builder.insert(
+            "agnis",
+            &SubantaEntry::new(agni_entry, L::Pum, Vi::Prathama, V::Eka).into(),
+        )?;
+        builder.insert_packed(&key, &value)?;
+        builder.insert("gacCati", &gacchati.into())?;
+        builder.finish()?;
+
+        let check = |kosha: &Kosha| {
+            match &kosha.get_all("devena")[0] {
+                PadaEntry::Subanta(s) => {
+                    let pratipadika = s.pratipadika_entry().metadata().unwrap();
+                    assert_eq!(pratipadika.get_text("gloss"), Some("god"));
+                    assert_eq!(s.metadata().unwrap().get_int("frequency"), Some(7));
+                }
+                _ => panic!("expected subanta"),
+            }
+            match &kosha.get_all("devas")[0] {
+                PadaEntry::Subanta(s) => {
+                    assert!(s.pratipadika_entry().metadata().is_some());
+                    assert!(s.metadata().is_none());
+                }
+                _ => panic!("expected subanta"),
+            }
+            match &kosha.get_all("gacCati")[0] {
+                PadaEntry::Tinanta(t) => {
+                    let dhatu = t.dhatu_entry().metadata().unwrap();
+                    assert_eq!(dhatu.get_text("gloss"), Some("go"));
+                    let tags = t.metadata().unwrap().get_tags("tags").unwrap();
+                    assert_eq!(tags, ["common"]);
+                }
+                _ => panic!("expected tinanta"),
+            }
+            assert!(kosha.get_all("agnis")[0].metadata().is_none());
+        };
+
+        check(&Kosha::new(dir.path())?);
+        // SAFETY: we don't modify the kosha while it is open.
+        check(&unsafe { Kosha::mmap(dir.path())? });
+
+        // Metadata also appears on expanded paradigms.
+        let kosha = Kosha::new(dir.path())?;
+        let forms = kosha.pratipadika_forms(&deva_entry)?;
+        let metadata: Vec<_> = forms.iter().map(|(_, p)| p.metadata().is_some()).collect();
+        assert_eq!(metadata, [false, true]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn iter() -> TestResult {
+        use vp::{Linga as L, Vacana as V, Vibhakti as Vi};
+        let deva = Pratipadika::basic(safe("deva"));
+        let deva_entry: PratipadikaEntry = (&deva).try_into().unwrap();
+        let devi = Pratipadika::basic(safe("devI"));
+        let devi_entry: PratipadikaEntry = (&devi).try_into().unwrap();
+        let devi_1s: PadaEntry =
+            SubantaEntry::new(devi_entry.clone(), L::Stri, Vi::Prathama, V::Eka).into();
+        let devi_8s: PadaEntry =
+            SubantaEntry::new(devi_entry.clone(), L::Stri, Vi::Sambodhana, V::Eka).into();
+
+        let dir = tempdir()?;
+        let mut builder = Builder::new(dir.path())?;
+        builder.register_pratipadika_entry(&devi_entry);
+        let (key, value) = builder.register_subanta_paradigm(
+            &deva_entry,
+            &[
+                ("devas".to_string(), L::Pum, Vi::Prathama, V::Eka),
+                ("devam".to_string(), L::Pum, Vi::Dvitiya, V::Eka),
+            ],
+        )?;
+        builder.insert("devI", &devi_1s)?;
+        builder.insert("devI", &devi_8s)?;
+        builder.insert_packed(&key, &value)?;
+        builder.finish()?;
+
+        let kosha = Kosha::new(dir.path())?;
+        let items = kosha.iter().collect::<Result<Vec<_>>>()?;
+        let keys: Vec<_> = items.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(keys, ["devI", "devI", "devas", "devam"]);
+        assert_eq!(items[0].1, devi_1s);
+        assert_eq!(items[1].1, devi_8s);
+        assert_eq!(items.len(), kosha.len());
+
+        // `expand_entry` skips the bytes that mark duplicates.
+        let mut stream = kosha.stream();
+        let mut raw = Vec::new();
+        while let Some((k, v)) = stream.next() {
+            raw.push((k.to_vec(), v));
+        }
+        assert_eq!(raw.len(), 3);
+        let (raw_key, value) = &raw[1];
+        assert_ne!(raw_key.as_slice(), b"devI");
+        assert_eq!(
+            kosha.expand_entry(raw_key, *value)?,
+            [("devI".to_string(), devi_8s)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn export_and_import() -> TestResult {
+        use vp::{Gana, Lakara, Linga as L, Prayoga, Purusha, Vacana as V, Vibhakti as Vi};
+        let ca = Pratipadika::avyaya(safe("ca"));
+        let ca_entry: PratipadikaEntry = (&ca).try_into().unwrap();
+        let vp::Pratipadika::Basic(deva) = Pratipadika::basic(safe("deva")) else {
+            unreachable!()
+        };
+        let deva_entry = PratipadikaEntry::basic(&deva, &[L::Pum]);
+        let gam = Dhatu::mula(safe("ga\\mx"), Gana::Bhvadi);
+        let gam_meta = DhatuMeta::builder().clean_text("gam".to_string()).build()?;
+        let gam_entry = DhatuEntry::new(&gam).with_meta(&gam_meta);
+        let gacchati: PadaEntry = TinantaEntry::new(
+            gam_entry.clone(),
+            Prayoga::Kartari,
+            Lakara::Lat,
+            Purusha::Prathama,
+            V::Eka,
+        )
+        .into();
+
+        let dir = tempdir()?;
+        let mut builder = Builder::new(dir.path())?;
+        builder.register_pratipadika_entry(&ca_entry);
+        builder.register_dhatu_entry(&gam_entry);
+        let (key, value) = builder.register_subanta_paradigm(
+            &deva_entry,
+            &[
+                ("devas".to_string(), L::Pum, Vi::Prathama, V::Eka),
+                ("devam".to_string(), L::Pum, Vi::Dvitiya, V::Eka),
+            ],
+        )?;
+        builder.insert("ca", &SubantaEntry::avyaya(ca_entry.clone()).into())?;
+        builder.insert_packed(&key, &value)?;
+        builder.insert("gacCati", &gacchati)?;
+        builder.finish()?;
+        let kosha = Kosha::new(dir.path())?;
+
+        // Paradigms are imported as separate keys, so compare words in sorted order.
+        let sorted_items = |kosha: &Kosha| -> Result<Vec<(String, Pada)>> {
+            let mut items = Vec::new();
+            for item in kosha.iter() {
+                let (key, entry) = item?;
+                items.push((key, entry.into()));
+            }
+            items.sort_by(|x, y| x.0.cmp(&y.0));
+            Ok(items)
+        };
+        let expected = sorted_items(&kosha)?;
+        assert_eq!(expected.len(), 4);
+
+        for format in [
+            LexiconFormat::Tsv,
+            LexiconFormat::Jsonl,
+            LexiconFormat::Conllu,
+        ] {
+            let mut buf = Vec::new();
+            kosha.export(format, &mut buf)?;
+
+            let out_dir = tempdir()?;
+            let mut builder = Builder::new(out_dir.path())?;
+            assert_eq!(builder.import(format, buf.as_slice())?, 4);
+            builder.finish()?;
+
+            let imported = Kosha::new(out_dir.path())?;
+            assert_eq!(sorted_items(&imported)?, expected, "{format:?}");
+            assert_eq!(
+                imported.get_all("devas"),
+                kosha.get_all("devas"),
+                "{format:?}"
+            );
+            assert_eq!(
+                imported.get_all("gacCati"),
+                kosha.get_all("gacCati"),
+                "{format:?}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn ranking() -> TestResult {
+        use vp::{Linga as L, Vacana as V, Vibhakti as Vi};
+        let tad = Pratipadika::basic(safe("tad"));
+        let tad_entry: PratipadikaEntry = (&tad).try_into().unwrap();
+        let yusmad = Pratipadika::basic(safe("yuzmad"));
+        let yusmad_entry: PratipadikaEntry = (&yusmad).try_into().unwrap();
+        let deva = Pratipadika::basic(safe("deva"));
+        let deva_entry: PratipadikaEntry = (&deva).try_into().unwrap();
+
+        let te_1: PadaEntry =
+            SubantaEntry::new(tad_entry.clone(), L::Pum, Vi::Prathama, V::Bahu).into();
+        let te_2: PadaEntry =
+            SubantaEntry::new(tad_entry.clone(), L::Stri, Vi::Prathama, V::Dvi).into();
+        let te_3: PadaEntry =
+            SubantaEntry::new(yusmad_entry.clone(), L::Pum, Vi::Caturthi, V::Eka).into();
+        let devam: PadaEntry =
+            SubantaEntry::new(deva_entry.clone(), L::Pum, Vi::Dvitiya, V::Eka).into();
+
+        let dir = tempdir()?;
+        let mut builder = Builder::new(dir.path())?;
+        builder.register_pratipadika_entry(&tad_entry);
+        builder.register_pratipadika_entry(&yusmad_entry);
+        let (key, value) = builder.register_subanta_paradigm(
+            &deva_entry,
+            &[
+                ("devas".to_string(), L::Pum, Vi::Prathama, V::Eka),
+                ("devam".to_string(), L::Pum, Vi::Dvitiya, V::Eka),
+            ],
+        )?;
+
+        builder.set_pada_weight(&te_2, 10.0)?;
+        builder.set_pada_weight(&te_3, 5.0)?;
+        builder.set_pada_weight(&devam, 2.5)?;
+        builder.set_pada_weight(&te_1, 0.0)?;
+        assert!(builder.set_pada_weight(&te_1, -1.0).is_err());
+        assert!(builder.set_pada_weight(&te_1, f32::NAN).is_err());
+
+        builder.insert_packed(&key, &value)?;
+        for pada in [&te_1, &te_2, &te_3] {
+            builder.insert("te", pada)?;
+        }
+        builder.finish()?;
+
+        let kosha = Kosha::new(dir.path())?;
+        assert_eq!(
+            kosha.get_all("te"),
+            [te_1.clone(), te_2.clone(), te_3.clone()]
+        );
+        assert_eq!(
+            kosha.get_all_ranked("te"),
+            [
+                (te_2.clone(), Some(10.0)),
+                (te_3, Some(5.0)),
+                (te_1.clone(), Some(0.0))
+            ]
+        );
+        assert_eq!(kosha.get_top_k("te", 1), [(te_2, Some(10.0))]);
+        assert_eq!(kosha.weight(&te_1), Some(0.0));
+
+        // Weights also apply to padas in a paradigm, and padas without a weight come last.
+        let devas: PadaEntry = SubantaEntry::new(deva_entry, L::Pum, Vi::Prathama, V::Eka).into();
+        assert_eq!(kosha.weight(&devas), None);
+        assert_eq!(kosha.get_all_ranked("devam"), [(devam, Some(2.5))]);
+        let mut ranked = vec![("a", None), ("b", Some(0.0)), ("c", None), ("d", Some(1.0))];
+        sort_by_weight(&mut ranked);
+        assert_eq!(
+            ranked,
+            [("d", Some(1.0)), ("b", Some(0.0)), ("a", None), ("c", None)]
+        );
+        assert!(kosha.get_top_k("nara", 3).is_empty());
+
+        Ok(())
+    }
+
     #[test]
     fn test_create_extended_key() -> TestResult {
         let cases = vec![
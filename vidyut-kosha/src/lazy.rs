@@ -0,0 +1,352 @@
+//! Data structures for loading a kosha lazily.
+//!
+//! A production kosha has a large registry, and decoding all of it takes seconds and hundreds of
+//! megabytes of memory. If we memory-map the registry instead, we can decode only the parts we
+//! actually use, and all processes that open the same kosha can share its pages through the OS
+//! page cache.
+use crate::errors::{Error, Result};
+use fst::Map;
+use memmap2::Mmap;
+use rmp_serde::decode::Error as DecodeError;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::Deserialize;
+use std::fmt;
+use std::fs::File;
+use std::ops::Index;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+/// The number of items that `LazyVec` decodes at once.
+///
+/// Larger chunks use less memory for bookkeeping but decode more items that we don't need.
+const CHUNK_SIZE: usize = 64;
+
+/// The contents of a file, either read into memory or memory-mapped.
+///
+/// Clones share the same underlying data.
+#[derive(Clone)]
+pub(crate) struct Bytes(Arc<dyn AsRef<[u8]> + Send + Sync>);
+
+impl Bytes {
+    /// Memory-maps the file at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified while it is mapped. See `memmap2::Mmap::map` for details.
+    #[allow(unsafe_code)]
+    pub(crate) unsafe fn mmap(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+        let mmap = Mmap::map(&file)?;
+        Ok(Self(Arc::new(mmap)))
+    }
+
+    /// Shares the buffer of `map`, which we have already read into memory, without copying it.
+    pub(crate) fn from_map(map: Arc<Map<Vec<u8>>>) -> Self {
+        Self(Arc::new(MapBuffer(map)))
+    }
+}
+
+/// The buffer of an in-memory FST.
+struct MapBuffer(Arc<Map<Vec<u8>>>);
+
+impl AsRef<[u8]> for MapBuffer {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_fst().as_bytes()
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(Arc::new(bytes))
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        (*self.0).as_ref()
+    }
+}
+
+impl fmt::Debug for Bytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bytes({} bytes)", self.as_ref().len())
+    }
+}
+
+/// Decodes a single msgpack value from the start of `rd` and advances `rd` past it.
+pub(crate) fn decode<T: DeserializeOwned>(rd: &mut &[u8]) -> Result<T> {
+    Ok(T::deserialize(&mut rmp_serde::Deserializer::new(rd))?)
+}
+
+/// A list of msgpack values that we decode only when they are first accessed.
+///
+/// We decode values in chunks of `CHUNK_SIZE` so that our bookkeeping stays small.
+pub(crate) struct LazyVec<T> {
+    bytes: Bytes,
+    /// The byte offset of the first value in each chunk.
+    offsets: Vec<usize>,
+    /// The decoded values for each chunk.
+    chunks: Vec<OnceLock<Vec<T>>>,
+    len: usize,
+}
+
+impl<T: DeserializeOwned> LazyVec<T> {
+    /// Skims the msgpack array at the start of `rd` and advances `rd` past it.
+    ///
+    /// `rd` must be a suffix of `bytes`. We check that each value is valid msgpack but don't
+    /// decode it. Since we still read every value to find where each chunk starts, this takes
+    /// time linear in the size of the array.
+    pub(crate) fn skim(bytes: &Bytes, rd: &mut &[u8]) -> Result<Self> {
+        let total = bytes.as_ref().len();
+        let len = rmp::decode::read_array_len(rd).map_err(DecodeError::from)? as usize;
+
+        let mut offsets = Vec::with_capacity((len + CHUNK_SIZE - 1) / CHUNK_SIZE);
+        for i in 0..len {
+            if i % CHUNK_SIZE == 0 {
+                offsets.push(total - rd.len());
+            }
+            IgnoredAny::deserialize(&mut rmp_serde::Deserializer::new(&mut *rd))?;
+        }
+
+        Ok(Self {
+            bytes: bytes.clone(),
+            chunks: offsets.iter().map(|_| OnceLock::new()).collect(),
+            offsets,
+            len,
+        })
+    }
+
+    /// Returns the value at `index`, decoding it if necessary.
+    ///
+    /// If `index` is out of bounds, we return `None`. If the value's chunk can't be decoded, we
+    /// return `Error::CorruptKosha`.
+    pub(crate) fn get(&self, index: usize) -> Result<Option<&T>> {
+        let c = index / CHUNK_SIZE;
+        let chunk = match self.chunks.get(c) {
+            Some(chunk) => chunk,
+            None => return Ok(None),
+        };
+        // `OnceLock::get_or_try_init` is unstable, so decode the chunk ourselves. If two threads
+        // race here, one of them decodes the chunk for nothing.
+        let values = match chunk.get() {
+            Some(values) => values,
+            None => {
+                let values = self.decode_chunk(c)?;
+                chunk.get_or_init(|| values)
+            }
+        };
+        Ok(values.get(index % CHUNK_SIZE))
+    }
+
+    fn decode_chunk(&self, c: usize) -> Result<Vec<T>> {
+        let n = CHUNK_SIZE.min(self.len - c * CHUNK_SIZE);
+        let mut rd = &self.bytes.as_ref()[self.offsets[c]..];
+        let mut ret = Vec::with_capacity(n);
+        for i in 0..n {
+            match decode(&mut rd) {
+                Ok(x) => ret.push(x),
+                Err(e) => {
+                    let index = c * CHUNK_SIZE + i;
+                    return Err(Error::CorruptKosha(format!("item {index}: {e}")));
+                }
+            }
+        }
+        Ok(ret)
+    }
+}
+
+impl<T: Clone> Clone for LazyVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            offsets: self.offsets.clone(),
+            chunks: self.chunks.clone(),
+            len: self.len,
+        }
+    }
+}
+
+/// A list of values that is either fully decoded or decoded lazily.
+#[derive(Clone)]
+pub(crate) enum Items<T> {
+    /// Values that are fully decoded.
+    Owned(Vec<T>),
+    /// Values that are decoded on first access.
+    Lazy(LazyVec<T>),
+}
+
+impl<T: Clone + DeserializeOwned> Items<T> {
+    /// Returns the number of values in this list.
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Items::Owned(v) => v.len(),
+            Items::Lazy(v) => v.len,
+        }
+    }
+
+    /// Returns the value at `index`, if it exists.
+    ///
+    /// If the list is lazy and the value can't be decoded, we return `Error::CorruptKosha`.
+    pub(crate) fn get(&self, index: usize) -> Result<Option<&T>> {
+        match self {
+            Items::Owned(v) => Ok(v.get(index)),
+            Items::Lazy(v) => v.get(index),
+        }
+    }
+
+    /// Appends `value` to the list.
+    ///
+    /// Lazy lists are read-only. Only builders append values, and a builder's lists are never
+    /// lazy.
+    pub(crate) fn push(&mut self, value: T) {
+        match self {
+            Items::Owned(v) => v.push(value),
+            Items::Lazy(_) => panic!("Lazy lists are read-only."),
+        }
+    }
+
+    /// Returns all values in an owned list.
+    ///
+    /// Lazy lists are read-only, so only builders should call this method.
+    pub(crate) fn owned(&self) -> &[T] {
+        match self {
+            Items::Owned(v) => v,
+            Items::Lazy(_) => panic!("Lazy lists are read-only."),
+        }
+    }
+
+    /// Returns all values in the list, decoding them if necessary.
+    pub(crate) fn to_vec(&self) -> Result<Vec<T>> {
+        self.iter().map(|x| x.map(|(_, x)| x.clone())).collect()
+    }
+
+    /// Iterates over all values in the list, along with their indices.
+    ///
+    /// If the list is lazy and some value can't be decoded, we yield `Error::CorruptKosha`.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Result<(usize, &T)>> {
+        (0..self.len()).filter_map(|i| self.get(i).map(|x| x.map(|x| (i, x))).transpose())
+    }
+}
+
+impl<T> Default for Items<T> {
+    fn default() -> Self {
+        Items::Owned(Vec::new())
+    }
+}
+
+impl<T: Clone + DeserializeOwned> Index<usize> for Items<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        match self.get(index) {
+            Ok(Some(x)) => x,
+            _ => panic!("index {index} is out of bounds or could not be decoded"),
+        }
+    }
+}
+
+impl<T: Clone + DeserializeOwned + PartialEq> PartialEq for Items<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && (0..self.len()).all(|i| match (self.get(i), other.get(i)) {
+                (Ok(x), Ok(y)) => x == y,
+                _ => false,
+            })
+    }
+}
+
+impl<T: Clone + DeserializeOwned + Eq> Eq for Items<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for Items<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Items::Owned(v) => v.fmt(f),
+            Items::Lazy(v) => write!(f, "Lazy({} items)", v.len),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn to_bytes(values: &[String]) -> Bytes {
+        Bytes(Arc::new(rmp_serde::to_vec(values).unwrap()))
+    }
+
+    #[test]
+    fn lazy_vec() {
+        let values: Vec<String> = (0..200).map(|i| format!("value-{i}")).collect();
+        let bytes = to_bytes(&values);
+        let mut rd = bytes.as_ref();
+        let lazy: LazyVec<String> = LazyVec::skim(&bytes, &mut rd).unwrap();
+
+        assert!(rd.is_empty());
+        assert_eq!(lazy.len, 200);
+        assert_eq!(lazy.offsets.len(), 4);
+        assert!(lazy.chunks.iter().all(|c| c.get().is_none()));
+
+        assert_eq!(lazy.get(130).unwrap().unwrap(), "value-130");
+        assert_eq!(lazy.get(199).unwrap().unwrap(), "value-199");
+        assert_eq!(lazy.get(0).unwrap().unwrap(), "value-0");
+        assert!(lazy.get(200).unwrap().is_none());
+        // We decoded only the chunks that we used.
+        assert!(lazy.chunks[1].get().is_none());
+    }
+
+    #[test]
+    fn lazy_vec_with_invalid_data() {
+        let bytes = Bytes(Arc::new(vec![0x92, 0xc1]));
+        let mut rd = bytes.as_ref();
+        assert!(LazyVec::<String>::skim(&bytes, &mut rd).is_err());
+    }
+
+    #[test]
+    fn lazy_vec_with_wrong_type() {
+        // Valid msgpack, but the last value is an int and not a string.
+        let mut bytes = vec![0xdc, 0, 100];
+        for i in 0..99 {
+            bytes.extend(rmp_serde::to_vec(&i.to_string()).unwrap());
+        }
+        bytes.push(0x01);
+
+        let bytes = Bytes(Arc::new(bytes));
+        let mut rd = bytes.as_ref();
+        let lazy: LazyVec<String> = LazyVec::skim(&bytes, &mut rd).unwrap();
+        assert_eq!(lazy.get(0).unwrap().unwrap(), "0");
+        assert!(matches!(lazy.get(99), Err(Error::CorruptKosha(_))));
+        assert!(matches!(lazy.get(64), Err(Error::CorruptKosha(_))));
+
+        let items = Items::Lazy(lazy);
+        assert!(items.iter().any(|x| x.is_err()));
+    }
+
+    #[test]
+    fn items() {
+        let values: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+        let bytes = to_bytes(&values);
+        let mut rd = bytes.as_ref();
+        let lazy = Items::Lazy(LazyVec::skim(&bytes, &mut rd).unwrap());
+        let mut owned = Items::Owned(values);
+
+        assert_eq!(lazy.len(), 100);
+        assert_eq!(lazy[70], "70");
+        assert_eq!(lazy, owned);
+        assert_eq!(lazy.iter().count(), 100);
+
+        owned.push("100".to_string());
+        assert_eq!(owned.len(), 101);
+        assert_eq!(owned[100], "100");
+        assert_ne!(lazy, owned);
+    }
+
+    #[test]
+    #[should_panic]
+    fn items_lazy_push() {
+        let bytes = to_bytes(&["a".to_string()]);
+        let mut rd = bytes.as_ref();
+        let mut lazy = Items::Lazy(LazyVec::<String>::skim(&bytes, &mut rd).unwrap());
+        lazy.push("b".to_string());
+    }
+}
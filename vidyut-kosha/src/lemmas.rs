@@ -0,0 +1,119 @@
+//! A reverse index from lemmas to the keys that use them.
+//!
+//! Our FST maps keys to packed entries, but it cannot efficiently answer the reverse question:
+//! which keys use a given lemma? So as we build the kosha, we also record each key under the
+//! lemma that its packed entry refers to.
+//!
+//! We store prefixes as-is and expand them with their suffix tables only when they are read. Since
+//! a single prefix usually stands for dozens of words, this approach keeps the index small.
+use crate::errors::Result;
+use crate::packing::{Id, PackedEntry, PartOfSpeech};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// A key and its packed value, as it was inserted into the FST.
+pub(crate) type Record = (String, u32);
+
+/// Maps each lemma ID to the keys that use it.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct LemmaIndex {
+    dhatus: Vec<Vec<Record>>,
+    pratipadikas: Vec<Vec<Record>>,
+}
+
+impl LemmaIndex {
+    /// Creates an empty index.
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads an index from disk.
+    pub(crate) fn read(path: &Path) -> Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(rmp_serde::from_read(reader)?)
+    }
+
+    /// Writes the index to disk.
+    pub(crate) fn write(&self, path: &Path) -> Result<()> {
+        let out = rmp_serde::to_vec(self)?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&out)?;
+        Ok(())
+    }
+
+    /// Records that `key` was inserted with the value `entry`.
+    pub(crate) fn add(&mut self, key: &str, entry: &PackedEntry) {
+        let (records, id) = match entry.pos() {
+            PartOfSpeech::Subanta => (
+                &mut self.pratipadikas,
+                entry.as_packed_subanta().pratipadika_id() as usize,
+            ),
+            PartOfSpeech::SubantaPrefix => (
+                &mut self.pratipadikas,
+                entry.as_packed_subanta_prefix().pratipadika_id() as usize,
+            ),
+            PartOfSpeech::Tinanta => (
+                &mut self.dhatus,
+                entry.as_packed_tinanta().dhatu_id() as usize,
+            ),
+            PartOfSpeech::TinantaPrefix => (
+                &mut self.dhatus,
+                entry.as_packed_tinanta_prefix().dhatu_id() as usize,
+            ),
+        };
+        if records.len() <= id {
+            records.resize(id + 1, Vec::new());
+        }
+        records[id].push((key.to_string(), entry.to_u32()));
+    }
+
+    /// Returns all records for the dhatu with the given ID.
+    pub(crate) fn dhatu(&self, id: Id) -> &[Record] {
+        self.dhatus.get(id.0).map_or(&[], |x| x.as_slice())
+    }
+
+    /// Returns all records for the pratipadika with the given ID.
+    pub(crate) fn pratipadika(&self, id: Id) -> &[Record] {
+        self.pratipadikas.get(id.0).map_or(&[], |x| x.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packing::{PackedSubanta, PackedTinantaPrefix};
+
+    #[test]
+    fn add_and_get() {
+        let subanta = PackedEntry::new()
+            .with_pos(PartOfSpeech::Subanta)
+            .with_payload(u32::from_le_bytes(
+                PackedSubanta::new()
+                    .with_pratipadika_id(2)
+                    .with_sup_id(1)
+                    .into_bytes(),
+            ));
+        let prefix = PackedEntry::new()
+            .with_pos(PartOfSpeech::TinantaPrefix)
+            .with_payload(u32::from_le_bytes(
+                PackedTinantaPrefix::new()
+                    .with_dhatu_id(1)
+                    .with_paradigm_id(3)
+                    .into_bytes(),
+            ));
+
+        let mut index = LemmaIndex::new();
+        index.add("devam", &subanta);
+        index.add("Bava", &prefix);
+
+        assert_eq!(
+            index.pratipadika(Id(2)),
+            &[("devam".to_string(), subanta.to_u32())]
+        );
+        assert_eq!(index.dhatu(Id(1)), &[("Bava".to_string(), prefix.to_u32())]);
+        assert!(index.pratipadika(Id(0)).is_empty());
+        assert!(index.dhatu(Id(5)).is_empty());
+    }
+}
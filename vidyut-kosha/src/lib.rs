@@ -4,10 +4,19 @@
 #![deny(unsafe_code)]
 
 pub use errors::Error;
-pub use kosha::{Builder, Kosha};
+pub use interchange::LexiconFormat;
+pub use kosha::{Builder, Iter, Kosha};
+pub use stack::KoshaStack;
 
 pub mod entries;
 pub mod packing;
 
 mod errors;
+mod interchange;
 mod kosha;
+mod lazy;
+mod lemmas;
+mod manifest;
+mod metadata;
+mod search;
+mod stack;
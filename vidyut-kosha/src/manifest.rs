@@ -0,0 +1,294 @@
+//! A manifest that describes the files in a kosha.
+//!
+//! A kosha is a set of files that only make sense together and only make sense to a compatible
+//! version of this crate. So when we build a kosha, we also write a small manifest that records
+//! how the kosha was built and what its files contain. When we load a kosha, we check the manifest
+//! against the kosha's data and against this crate so that we fail early instead of returning
+//! garbage. Checking the files' checksums means reading every byte of the kosha, so we do so only
+//! on request through `Kosha::verify`.
+//!
+//! We encode the manifest as a MessagePack map with named fields so that it stays readable by
+//! generic tools and so that future versions can add fields without breaking older readers.
+use crate::errors::{Error, Result};
+use crate::packing::Packer;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// The version of our file format.
+///
+/// Increment this whenever a change to the kosha's files would prevent older versions of this
+/// crate from reading them.
+///
+/// Version 2 added `Sup::Purvapada` to our sup table.
+pub(crate) const FORMAT_VERSION: u32 = 2;
+
+/// Just the version of some manifest, which we check before decoding anything else.
+#[derive(Deserialize)]
+struct Version {
+    format_version: u32,
+}
+
+/// The sizes of various parts of the kosha.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+struct Counts {
+    /// The number of keys in the FST, including duplicates.
+    keys: usize,
+    /// The number of words in the kosha, as returned by `Kosha::len`.
+    entries: usize,
+    dhatus: usize,
+    pratipadikas: usize,
+}
+
+/// Describes a kosha's files and how they were built.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Manifest {
+    format_version: u32,
+    /// The version of `vidyut-kosha` that built this kosha.
+    kosha_version: String,
+    /// The version of `vidyut-prakriya` that built this kosha.
+    prakriya_version: String,
+    /// How we packed the kosha's entries. See `Packer::layout`.
+    layout: String,
+    counts: Counts,
+    /// Maps each file name to its CRC-32 checksum.
+    checksums: BTreeMap<String, u32>,
+}
+
+/// Returns the CRC-32 checksum of the file at `path`.
+fn checksum(path: &Path) -> Result<u32> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buf = vec![0; 1 << 16];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher.finalize())
+}
+
+/// Returns whether a kosha built with version `built` of some crate can be read with version
+/// `current` of that crate.
+///
+/// We follow Cargo's rules for semver compatibility: versions are compatible if they have the same
+/// major version and, if the major version is 0, the same minor version.
+fn is_compatible(built: &str, current: &str) -> bool {
+    let release = |v: &str| -> Option<(u64, u64)> {
+        let mut parts = v.split(['.', '-', '+']);
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts.next()?.parse().ok()?;
+        Some((major, minor))
+    };
+    match (release(built), release(current)) {
+        (Some((0, a)), Some((0, b))) => a == b,
+        (Some((a, _)), Some((b, _))) => a == b,
+        _ => false,
+    }
+}
+
+/// Returns the name of the file at `path`.
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+impl Manifest {
+    /// Creates a manifest for a kosha with the given data and files.
+    pub(crate) fn new(packer: &Packer, num_keys: usize, files: &[PathBuf]) -> Result<Self> {
+        let mut checksums = BTreeMap::new();
+        for path in files {
+            checksums.insert(file_name(path), checksum(path)?);
+        }
+
+        Ok(Self {
+            format_version: FORMAT_VERSION,
+            kosha_version: env!("CARGO_PKG_VERSION").to_string(),
+            prakriya_version: vidyut_prakriya::VERSION.to_string(),
+            layout: packer.layout(),
+            counts: Counts {
+                keys: num_keys,
+                entries: packer.count,
+                dhatus: packer.dhatus.len(),
+                pratipadikas: packer.pratipadikas.len(),
+            },
+            checksums,
+        })
+    }
+
+    /// Loads a manifest from disk.
+    ///
+    /// If the manifest has a format version we don't support, we return an error without reading
+    /// the rest of the manifest.
+    pub(crate) fn read(path: &Path) -> Result<Self> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+
+        let version: Version = rmp_serde::from_slice(&bytes)?;
+        if version.format_version != FORMAT_VERSION {
+            return Err(Error::UnsupportedFormat(version.format_version));
+        }
+        Ok(rmp_serde::from_slice(&bytes)?)
+    }
+
+    /// Writes the manifest to disk.
+    pub(crate) fn write(&self, path: &Path) -> Result<()> {
+        let out = rmp_serde::to_vec_named(self)?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&out)?;
+        Ok(())
+    }
+
+    /// Checks that the files in `base_path` match our checksums.
+    pub(crate) fn check_files(&self, base_path: &Path) -> Result<()> {
+        for (name, expected) in &self.checksums {
+            if checksum(&base_path.join(name))? != *expected {
+                return Err(Error::ChecksumMismatch(name.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the kosha was built with versions of our crates that are compatible with the
+    /// ones we use now.
+    pub(crate) fn check_versions(&self) -> Result<()> {
+        let versions = [
+            (
+                "vidyut-kosha",
+                &self.kosha_version,
+                env!("CARGO_PKG_VERSION"),
+            ),
+            (
+                "vidyut-prakriya",
+                &self.prakriya_version,
+                vidyut_prakriya::VERSION,
+            ),
+        ];
+        for (name, built, current) in versions {
+            if !is_compatible(built, current) {
+                return Err(Error::VersionMismatch(name, built.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that `packer` and an FST with `num_keys` keys match this manifest.
+    pub(crate) fn check_data(&self, packer: &Packer, num_keys: usize) -> Result<()> {
+        let layout = packer.layout();
+        if layout != self.layout {
+            return Err(Error::LayoutMismatch(self.layout.clone()));
+        }
+
+        let counts = [
+            ("keys", self.counts.keys, num_keys),
+            ("entries", self.counts.entries, packer.count),
+            ("dhatus", self.counts.dhatus, packer.dhatus.len()),
+            (
+                "pratipadikas",
+                self.counts.pratipadikas,
+                packer.pratipadikas.len(),
+            ),
+        ];
+        for (name, expected, actual) in counts {
+            if expected != actual {
+                return Err(Error::CountMismatch(name, expected, actual));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn read_and_write() -> Result<()> {
+        let dir = tempdir()?;
+        let data = dir.path().join("data.bin");
+        File::create(&data)?.write_all(b"hello")?;
+
+        let packer = Packer::new();
+        let manifest = Manifest::new(&packer, 0, std::slice::from_ref(&data))?;
+        assert_eq!(manifest.checksums["data.bin"], crc32fast::hash(b"hello"));
+
+        let path = dir.path().join("manifest.msgpack");
+        manifest.write(&path)?;
+        let loaded = Manifest::read(&path)?;
+        assert_eq!(loaded, manifest);
+        loaded.check_files(dir.path())?;
+        loaded.check_data(&packer, 0)?;
+
+        assert!(matches!(
+            loaded.check_data(&packer, 1),
+            Err(Error::CountMismatch("keys", 0, 1))
+        ));
+
+        File::create(&data)?.write_all(b"world")?;
+        assert!(matches!(
+            loaded.check_files(dir.path()),
+            Err(Error::ChecksumMismatch(name)) if name == "data.bin"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn check_layout() -> Result<()> {
+        let packer = Packer::new();
+        let mut manifest = Manifest::new(&packer, 0, &[])?;
+        manifest.check_data(&packer, 0)?;
+
+        manifest.layout = "pos:3,payload:29".to_string();
+        assert!(matches!(
+            manifest.check_data(&packer, 0),
+            Err(Error::LayoutMismatch(s)) if s == "pos:3,payload:29"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn check_versions() -> Result<()> {
+        assert!(is_compatible("0.4.0", "0.4.2"));
+        assert!(is_compatible("1.2.0", "1.5.1"));
+        assert!(is_compatible("0.4.0-dev", "0.4.0"));
+        assert!(!is_compatible("0.3.9", "0.4.0"));
+        assert!(!is_compatible("1.0.0", "2.0.0"));
+        assert!(!is_compatible("garbage", "0.4.0"));
+
+        let packer = Packer::new();
+        let mut manifest = Manifest::new(&packer, 0, &[])?;
+        manifest.check_versions()?;
+
+        manifest.prakriya_version = "0.0.1".to_string();
+        assert!(matches!(
+            manifest.check_versions(),
+            Err(Error::VersionMismatch("vidyut-prakriya", v)) if v == "0.0.1"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn read_unsupported_version() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("manifest.msgpack");
+
+        // A future manifest might have other fields, but it will always have a version.
+        let mut future = BTreeMap::new();
+        future.insert("format_version", FORMAT_VERSION + 1);
+        future.insert("some_new_field", 0);
+        File::create(&path)?.write_all(&rmp_serde::to_vec_named(&future)?)?;
+
+        assert!(matches!(
+            Manifest::read(&path),
+            Err(Error::UnsupportedFormat(v)) if v == FORMAT_VERSION + 1
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+//! Side tables of user-defined metadata and weights.
+//!
+//! Most entries have no metadata, and users might attach metadata to lemmas, to specific *pada*s,
+//! or both. So we store metadata in sparse maps keyed by the same IDs that our packed entries
+//! use, and we keep these maps outside of the registry so that koshas without metadata pay
+//! nothing for them.
+use crate::entries::Metadata;
+use crate::errors::Result;
+use crate::packing::{Id, IdKind, IdMap, PackedEntry};
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Maps dhatu, pratipadika, and pada IDs to their metadata.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MetadataTables {
+    pub(crate) dhatus: FxHashMap<Id, Metadata>,
+    pub(crate) pratipadikas: FxHashMap<Id, Metadata>,
+    /// Keyed by the `u32` value of a packed subanta or tinanta.
+    pub(crate) padas: FxHashMap<u32, Metadata>,
+    /// Prior weights for ranking *pada*s, keyed like `padas`.
+    #[serde(default)]
+    pub(crate) weights: FxHashMap<u32, f32>,
+}
+
+impl MetadataTables {
+    /// Loads metadata from disk.
+    pub(crate) fn read(path: &Path) -> Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(rmp_serde::from_read(reader)?)
+    }
+
+    /// Writes metadata to disk.
+    pub(crate) fn write(&self, path: &Path) -> Result<()> {
+        let out = rmp_serde::to_vec(self)?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(&out)?;
+        Ok(())
+    }
+
+    /// Adds all metadata in `other`, whose IDs are mapped to ours with `id_map`.
+    ///
+    /// If we already have metadata for some ID, we keep our metadata.
+    pub(crate) fn import(&mut self, other: &MetadataTables, id_map: &IdMap) -> Result<()> {
+        for (id, metadata) in &other.dhatus {
+            let id = id_map.get(IdKind::Dhatu, *id)?;
+            self.dhatus.entry(id).or_insert_with(|| metadata.clone());
+        }
+        for (id, metadata) in &other.pratipadikas {
+            let id = id_map.get(IdKind::Pratipadika, *id)?;
+            self.pratipadikas
+                .entry(id)
+                .or_insert_with(|| metadata.clone());
+        }
+        for (packed, metadata) in &other.padas {
+            let packed = id_map.remap(PackedEntry::from_u32(*packed))?.to_u32();
+            self.padas.entry(packed).or_insert_with(|| metadata.clone());
+        }
+        for (packed, weight) in &other.weights {
+            let packed = id_map.remap(PackedEntry::from_u32(*packed))?.to_u32();
+            self.weights.entry(packed).or_insert(*weight);
+        }
+        Ok(())
+    }
+}
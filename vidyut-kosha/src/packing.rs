@@ -67,19 +67,27 @@ TODO: investigate different packing orders to see if we can reduce the size of t
 #![allow(dead_code)]
 
 use crate::entries::{
-    DhatuEntry, DhatuMeta, KrdantaEntry, PadaEntry, PratipadikaEntry, SubantaEntry, TinantaEntry,
+    DhatuEntry, DhatuMeta, KrdantaEntry, Metadata, PadaEntry, PratipadikaEntry, SamasaEntry,
+    SubantaEntry, TaddhitantaEntry, TinantaEntry,
 };
 use crate::errors::{Error, Result};
+use crate::lazy::{self, Bytes, Items, LazyVec};
+use crate::metadata::MetadataTables;
 use modular_bitfield::prelude::*;
 use rustc_hash::FxHashMap;
 use vidyut_prakriya::args::{
-    BasicPratipadika, Dhatu, Krt, Lakara, Linga, Prayoga, Purusha, Vacana, Vibhakti,
+    BasicPratipadika, Dhatu, Krt, Lakara, Linga, Prayoga, Purusha, SamasaType, Taddhita,
+    TaddhitaArtha, Vacana, Vibhakti,
 };
 
+use rmp_serde::decode::Error as DecodeError;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
+use std::hash::Hash;
 use std::io::{BufReader, BufWriter, Write};
 use std::path::Path;
+use std::sync::OnceLock;
 
 /// An ID for interned linguistic data.
 #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
@@ -145,6 +153,21 @@ impl SubantaSuffix {
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    /// Returns the linga of this suffix.
+    pub fn linga(&self) -> Linga {
+        self.linga
+    }
+
+    /// Returns the vibhakti of this suffix.
+    pub fn vibhakti(&self) -> Vibhakti {
+        self.vibhakti
+    }
+
+    /// Returns the vacana of this suffix.
+    pub fn vacana(&self) -> Vacana {
+        self.vacana
+    }
 }
 
 impl SubantaSuffixes {
@@ -156,17 +179,7 @@ impl SubantaSuffixes {
     fn from_padas(padas: &[(String, Linga, Vibhakti, Vacana)]) -> Self {
         assert!(!padas.is_empty());
 
-        let mut common_prefix = String::from(&padas[0].0);
-        for pada in &padas[1..] {
-            for ((i, x), y) in common_prefix.char_indices().zip(pada.0.chars()) {
-                if x != y {
-                    common_prefix.truncate(i);
-                    break;
-                }
-            }
-        }
-
-        let offset = common_prefix.len();
+        let offset = common_prefix_len(padas.iter().map(|p| p.0.as_str()));
         let endings: Vec<_> = padas
             .iter()
             .map(|p| {
@@ -201,6 +214,26 @@ impl TinantaSuffix {
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    /// Returns the prayoga of this suffix.
+    pub fn prayoga(&self) -> Prayoga {
+        self.prayoga
+    }
+
+    /// Returns the lakara of this suffix.
+    pub fn lakara(&self) -> Lakara {
+        self.lakara
+    }
+
+    /// Returns the purusha of this suffix.
+    pub fn purusha(&self) -> Purusha {
+        self.purusha
+    }
+
+    /// Returns the vacana of this suffix.
+    pub fn vacana(&self) -> Vacana {
+        self.vacana
+    }
 }
 
 impl TinantaSuffixes {
@@ -212,17 +245,7 @@ impl TinantaSuffixes {
     fn from_padas(padas: &[(String, Prayoga, Lakara, Purusha, Vacana)]) -> Self {
         assert!(!padas.is_empty());
 
-        let mut common_prefix = String::from(&padas[0].0);
-        for pada in &padas[1..] {
-            for ((i, x), y) in common_prefix.char_indices().zip(pada.0.chars()) {
-                if x != y {
-                    common_prefix.truncate(i);
-                    break;
-                }
-            }
-        }
-
-        let offset = common_prefix.len();
+        let offset = common_prefix_len(padas.iter().map(|p| p.0.as_str()));
         let endings: Vec<_> = padas
             .iter()
             .map(|p| {
@@ -236,6 +259,27 @@ impl TinantaSuffixes {
     }
 }
 
+/// Returns the length in bytes of the longest prefix that all of `words` share.
+fn common_prefix_len<'a>(mut words: impl Iterator<Item = &'a str>) -> usize {
+    let first = match words.next() {
+        Some(w) => w,
+        None => return 0,
+    };
+    let mut len = first.len();
+    for word in words {
+        let mismatch = first[..len]
+            .char_indices()
+            .zip(word.chars())
+            .find(|((_, x), y)| x != y);
+        len = match mismatch {
+            Some(((i, _), _)) => i,
+            // `word` might be shorter than our current prefix.
+            None => len.min(word.len()),
+        };
+    }
+    len
+}
+
 #[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
 struct PratipadikaMeta {
     lingas: Vec<Linga>,
@@ -249,6 +293,7 @@ enum Sup {
         vacana: Vacana,
     },
     Avyaya,
+    Purvapada,
 }
 
 #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
@@ -270,6 +315,9 @@ struct RichKrt {
 pub(crate) enum SmallPratipadika {
     Basic(BasicPratipadika),
     Krdanta(SmallKrdanta),
+    // New variants must go at the end so that existing registries still decode.
+    Taddhitanta(SmallTaddhitanta),
+    Samasa(SmallSamasa),
 }
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
@@ -278,6 +326,21 @@ pub(crate) struct SmallKrdanta {
     krt_id: Id,
 }
 
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub(crate) struct SmallTaddhitanta {
+    pratipadika_id: Id,
+    taddhita: Taddhita,
+    artha: Option<TaddhitaArtha>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub(crate) struct SmallSamasa {
+    /// The pratipadika ID and sup for each member of the samasa.
+    padas: Vec<(Id, Sup)>,
+    samasa_type: SamasaType,
+    stri: bool,
+}
+
 /// A prefix to some paradigm of sup endings.
 #[bitfield(bits = 30)]
 #[derive(Debug)]
@@ -310,7 +373,7 @@ pub struct PackedTinanta {
 
 /// Semantics for a *pada*.
 #[bitfield]
-#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, Ord, PartialOrd)]
 pub struct PackedEntry {
     /// The part of speech for these semantics. We use this value to decide how to interpret the
     /// `payload` field.
@@ -320,6 +383,16 @@ pub struct PackedEntry {
     pub(crate) payload: B30,
 }
 
+/// The bit layout of the packed types above.
+///
+/// We record this layout in each kosha's manifest so that we can reject koshas that were packed
+/// differently. So if you change any of the layouts above, update this string too.
+const BIT_LAYOUT: &str = "pos:2,payload:30 \
+    subanta_prefix:pratipadika_id:21,paradigm_id:9 \
+    subanta:sup_id:7,pratipadika_id:23 \
+    tinanta_prefix:dhatu_id:18,paradigm_id:12 \
+    tinanta:tin_id:8,dhatu_id:22";
+
 /// Semantics for an unknown term.
 #[bitfield(bits = 30)]
 struct PackedUnknown {
@@ -335,6 +408,17 @@ impl Sup {
             vacana,
         }
     }
+
+    /// Returns the sup used by the given subanta.
+    fn from_subanta(s: &SubantaEntry) -> Self {
+        if s.is_purvapada() {
+            Sup::Purvapada
+        } else if s.is_avyaya() {
+            Sup::Avyaya
+        } else {
+            Sup::new(s.linga(), s.vibhakti(), s.vacana())
+        }
+    }
 }
 
 impl Tin {
@@ -370,7 +454,7 @@ impl PackedEntry {
     }
 
     /// Unwraps the bitfield as an ordinary `u32`.
-    pub(crate) fn to_u32(self) -> u32 {
+    pub fn to_u32(self) -> u32 {
         u32::from_le_bytes(self.into_bytes())
     }
 
@@ -382,6 +466,80 @@ impl PackedEntry {
     pub(crate) fn is_prefix(&self) -> bool {
         self.pos() == PartOfSpeech::SubantaPrefix || self.pos() == PartOfSpeech::TinantaPrefix
     }
+
+    /// Returns a copy of this entry whose registry IDs have been replaced by `f`.
+    ///
+    /// We call `f` with the kind of each ID and its value.
+    pub(crate) fn map_ids(self, mut f: impl FnMut(IdKind, Id) -> Result<Id>) -> Result<Self> {
+        let mut map =
+            |kind, id: u32| -> Result<u32> { Ok(f(kind, Id(id as usize))?.0.try_into()?) };
+
+        let payload = match self.pos() {
+            PartOfSpeech::Subanta => {
+                let s = self.as_packed_subanta();
+                let pratipadika_id = map(IdKind::Pratipadika, s.pratipadika_id())?;
+                s.with_pratipadika_id(pratipadika_id).into_bytes()
+            }
+            PartOfSpeech::SubantaPrefix => {
+                let s = self.as_packed_subanta_prefix();
+                let pratipadika_id = map(IdKind::Pratipadika, s.pratipadika_id())?;
+                let paradigm_id = map(IdKind::SubantaSuffixes, s.paradigm_id().into())?;
+                s.with_pratipadika_id(pratipadika_id)
+                    .with_paradigm_id(paradigm_id.try_into()?)
+                    .into_bytes()
+            }
+            PartOfSpeech::Tinanta => {
+                let t = self.as_packed_tinanta();
+                let dhatu_id = map(IdKind::Dhatu, t.dhatu_id())?;
+                t.with_dhatu_id(dhatu_id).into_bytes()
+            }
+            PartOfSpeech::TinantaPrefix => {
+                let t = self.as_packed_tinanta_prefix();
+                let dhatu_id = map(IdKind::Dhatu, t.dhatu_id())?;
+                let paradigm_id = map(IdKind::TinantaSuffixes, t.paradigm_id().into())?;
+                t.with_dhatu_id(dhatu_id)
+                    .with_paradigm_id(paradigm_id.try_into()?)
+                    .into_bytes()
+            }
+        };
+        Ok(self.with_payload(u32::from_le_bytes(payload)))
+    }
+}
+
+/// The kinds of registry IDs that a `PackedEntry` can contain.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub(crate) enum IdKind {
+    Dhatu,
+    Pratipadika,
+    SubantaSuffixes,
+    TinantaSuffixes,
+}
+
+/// Maps the registry IDs of one `Packer` to the registry IDs of another.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct IdMap {
+    dhatus: Vec<Id>,
+    pratipadikas: Vec<Id>,
+    subanta_suffixes: Vec<Id>,
+    tinanta_suffixes: Vec<Id>,
+}
+
+impl IdMap {
+    /// Returns the new ID for the old ID `id`.
+    pub(crate) fn get(&self, kind: IdKind, id: Id) -> Result<Id> {
+        let (ids, name) = match kind {
+            IdKind::Dhatu => (&self.dhatus, "dhatu"),
+            IdKind::Pratipadika => (&self.pratipadikas, "pratipadika"),
+            IdKind::SubantaSuffixes => (&self.subanta_suffixes, "subanta suffixes"),
+            IdKind::TinantaSuffixes => (&self.tinanta_suffixes, "tinanta suffixes"),
+        };
+        ids.get(id.0).copied().ok_or(Error::UnknownId(name, id.0))
+    }
+
+    /// Returns a copy of `entry` that uses the new IDs.
+    pub(crate) fn remap(&self, entry: PackedEntry) -> Result<PackedEntry> {
+        entry.map_ids(|kind, id| self.get(kind, id))
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -396,8 +554,27 @@ struct Registry {
     tinanta_suffixes: Vec<TinantaSuffixes>,
 }
 
+/// Maps each item to its index.
+fn create_index<'a, T: 'a + Clone + Eq + Hash>(
+    items: impl Iterator<Item = (usize, &'a T)>,
+) -> FxHashMap<T, Id> {
+    items.map(|(i, x)| (x.clone(), Id(i))).collect()
+}
+
+/// Maps each item in `items` to its index, decoding `items` if necessary.
+fn create_items_index<'a, T: 'a + Clone + Eq + Hash + DeserializeOwned>(
+    index: &'a OnceLock<FxHashMap<T, Id>>,
+    items: &'a Items<T>,
+) -> Result<&'a FxHashMap<T, Id>> {
+    if let Some(index) = index.get() {
+        return Ok(index);
+    }
+    let values = items.iter().collect::<Result<Vec<_>>>()?;
+    Ok(index.get_or_init(|| create_index(values.into_iter())))
+}
+
 /// Packs and unpacks linguistic data.
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub(crate) struct Packer {
     pub(crate) count: usize,
 
@@ -410,11 +587,13 @@ pub(crate) struct Packer {
     krts: Vec<RichKrt>,
     krt_to_index: FxHashMap<RichKrt, Id>,
 
-    pub(crate) dhatus: Vec<Dhatu>,
-    dhatu_to_index: FxHashMap<Dhatu, Id>,
+    // These tables are large, so we decode them lazily if we can and create their indices only
+    // when we first need them.
+    pub(crate) dhatus: Items<Dhatu>,
+    dhatu_to_index: OnceLock<FxHashMap<Dhatu, Id>>,
 
-    pub(crate) pratipadikas: Vec<SmallPratipadika>,
-    pratipadika_to_index: FxHashMap<SmallPratipadika, Id>,
+    pub(crate) pratipadikas: Items<SmallPratipadika>,
+    pratipadika_to_index: OnceLock<FxHashMap<SmallPratipadika, Id>>,
 
     pub(crate) subanta_suffixes: Vec<SubantaSuffixes>,
     subanta_suffixes_to_index: FxHashMap<SubantaSuffixes, Id>,
@@ -422,9 +601,12 @@ pub(crate) struct Packer {
     pub(crate) tinanta_suffixes: Vec<TinantaSuffixes>,
     tinanta_suffixes_to_index: FxHashMap<TinantaSuffixes, Id>,
 
-    dhatu_meta: Vec<DhatuMeta>,
+    dhatu_meta: Items<DhatuMeta>,
     // This is a Map because not all pratipadikas have metadata.
     pratipadika_meta: FxHashMap<Id, PratipadikaMeta>,
+
+    // User-defined metadata, which we store separately from the registry.
+    pub(crate) metadata: MetadataTables,
 }
 
 impl Packer {
@@ -447,6 +629,11 @@ impl Packer {
             ret.sups.push(Sup::Avyaya);
             ret.sup_to_index.insert(Sup::Avyaya, id);
         }
+        {
+            let id = Id(ret.sups.len());
+            ret.sups.push(Sup::Purvapada);
+            ret.sup_to_index.insert(Sup::Purvapada, id);
+        }
         assert!(ret.sups.len() < 1 << 7);
 
         for prayoga in Prayoga::iter() {
@@ -491,55 +678,80 @@ impl Packer {
 
         ret.count = count;
         ret.krts = krts;
-        ret.dhatus = dhatus;
-        ret.pratipadikas = pratipadikas;
+        ret.dhatus = Items::Owned(dhatus);
+        ret.pratipadikas = Items::Owned(pratipadikas);
         ret.subanta_suffixes = subanta_suffixes;
         ret.tinanta_suffixes = tinanta_suffixes;
-        ret.dhatu_meta = dhatu_meta;
+        ret.dhatu_meta = Items::Owned(dhatu_meta);
         ret.pratipadika_meta = pratipadika_meta;
+        ret.create_small_indices();
 
-        ret.krt_to_index = ret
-            .krts
-            .iter()
-            .enumerate()
-            .map(|(i, x)| (x.clone(), Id(i)))
-            .collect();
-
-        ret.dhatu_to_index = ret
-            .dhatus
-            .iter()
-            .enumerate()
-            .map(|(i, x)| (x.clone(), Id(i)))
-            .collect();
+        Ok(ret)
+    }
 
-        ret.pratipadika_to_index = ret
-            .pratipadikas
-            .iter()
-            .enumerate()
-            .map(|(i, x)| (x.clone(), Id(i)))
-            .collect();
+    /// Loads a packer from the registry in `bytes`.
+    ///
+    /// Unlike `read`, this method decodes dhatus and pratipadikas only when they are first used,
+    /// which is much faster and uses much less memory if `bytes` is memory-mapped.
+    pub(crate) fn read_lazy(bytes: Bytes) -> Result<Self> {
+        let mut ret = Self::new();
+        let mut rd = bytes.as_ref();
 
-        ret.subanta_suffixes_to_index = ret
-            .subanta_suffixes
-            .iter()
-            .enumerate()
-            .map(|(i, x)| (x.clone(), Id(i)))
-            .collect();
+        // `rmp_serde` encodes structs as arrays, so we read the fields of `Registry` in the order
+        // they are declared.
+        let num_fields = rmp::decode::read_array_len(&mut rd).map_err(DecodeError::from)?;
+        if num_fields != 8 {
+            return Err(DecodeError::LengthMismatch(num_fields).into());
+        }
+        ret.count = lazy::decode(&mut rd)?;
+        ret.krts = lazy::decode(&mut rd)?;
+        ret.dhatus = Items::Lazy(LazyVec::skim(&bytes, &mut rd)?);
+        ret.dhatu_meta = Items::Lazy(LazyVec::skim(&bytes, &mut rd)?);
+        ret.pratipadikas = Items::Lazy(LazyVec::skim(&bytes, &mut rd)?);
+        ret.pratipadika_meta = lazy::decode(&mut rd)?;
+        ret.subanta_suffixes = lazy::decode(&mut rd)?;
+        ret.tinanta_suffixes = lazy::decode(&mut rd)?;
+        ret.create_small_indices();
 
         Ok(ret)
     }
 
+    /// Describes how this packer packs entries.
+    ///
+    /// Sup and tin IDs depend on the order in which `vidyut-prakriya` defines its enums, so we
+    /// include a checksum of our sup and tin tables as well.
+    pub(crate) fn layout(&self) -> String {
+        let checksum = |x: &str| crc32fast::hash(x.as_bytes());
+        let sups = checksum(&format!("{:?}", self.sups));
+        let tins = checksum(&format!("{:?}", self.tins));
+        format!("{BIT_LAYOUT} sups:{sups:08x} tins:{tins:08x}")
+    }
+
+    /// Creates the indices that we always need and that are cheap to create.
+    fn create_small_indices(&mut self) {
+        self.krt_to_index = create_index(self.krts.iter().enumerate());
+        self.subanta_suffixes_to_index = create_index(self.subanta_suffixes.iter().enumerate());
+    }
+
+    fn dhatu_to_index(&self) -> Result<&FxHashMap<Dhatu, Id>> {
+        create_items_index(&self.dhatu_to_index, &self.dhatus)
+    }
+
+    fn pratipadika_to_index(&self) -> Result<&FxHashMap<SmallPratipadika, Id>> {
+        create_items_index(&self.pratipadika_to_index, &self.pratipadikas)
+    }
+
     /// Writes the registry to disk.
     pub(crate) fn write(&self, registry_path: &Path) -> Result<()> {
         let registry = Registry {
             count: self.count,
             krts: self.krts.clone(),
-            dhatus: self.dhatus.clone(),
-            pratipadikas: self.pratipadikas.clone(),
+            dhatus: self.dhatus.to_vec()?,
+            pratipadikas: self.pratipadikas.to_vec()?,
             subanta_suffixes: self.subanta_suffixes.clone(),
             tinanta_suffixes: self.tinanta_suffixes.clone(),
 
-            dhatu_meta: self.dhatu_meta.clone(),
+            dhatu_meta: self.dhatu_meta.to_vec()?,
             pratipadika_meta: self.pratipadika_meta.clone(),
         };
 
@@ -589,13 +801,17 @@ impl Packer {
 
             for ending in &paradigm.endings {
                 if ending.text == suffix {
-                    ret.push(PadaEntry::Subanta(SubantaEntry::new(
-                        // Stack-allocated, clones are cheap.
-                        phit.clone(),
-                        ending.linga,
-                        ending.vibhakti,
-                        ending.vacana,
-                    )));
+                    let metadata = self.subanta_metadata(entry.pratipadika_id(), ending);
+                    ret.push(PadaEntry::Subanta(
+                        SubantaEntry::new(
+                            // Stack-allocated, clones are cheap.
+                            phit.clone(),
+                            ending.linga,
+                            ending.vibhakti,
+                            ending.vacana,
+                        )
+                        .with_metadata(metadata),
+                    ));
                 }
             }
         } else if base_entry.pos() == PartOfSpeech::TinantaPrefix {
@@ -617,21 +833,178 @@ impl Packer {
 
             for ending in &paradigm.endings {
                 if ending.text == suffix {
-                    ret.push(PadaEntry::Tinanta(TinantaEntry::new(
-                        // Stack-allocated, clones are cheap.
+                    let metadata = self.tinanta_metadata(entry.dhatu_id(), ending);
+                    ret.push(PadaEntry::Tinanta(
+                        TinantaEntry::new(
+                            // Stack-allocated, clones are cheap.
+                            dhatu.clone(),
+                            ending.prayoga,
+                            ending.lakara,
+                            ending.purusha,
+                            ending.vacana,
+                        )
+                        .with_metadata(metadata),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends all of the words that `key` and `entry` represent to `ret`.
+    ///
+    /// If `entry` is a prefix, we append one word per ending in its paradigm. Otherwise, we append
+    /// just `key` and its unpacked entry.
+    pub(crate) fn expand<'a>(
+        &'a self,
+        ret: &mut Vec<(String, PadaEntry<'a>)>,
+        key: &str,
+        entry: &PackedEntry,
+    ) -> Result<()> {
+        self.expand_matching(ret, key, entry, |_| true)
+    }
+
+    /// Like `expand`, but appends only the words whose ending satisfies `is_match`.
+    ///
+    /// If `entry` is not a prefix, its ending is the empty string.
+    pub(crate) fn expand_matching<'a>(
+        &'a self,
+        ret: &mut Vec<(String, PadaEntry<'a>)>,
+        key: &str,
+        entry: &PackedEntry,
+        is_match: impl Fn(&str) -> bool,
+    ) -> Result<()> {
+        match entry.pos() {
+            PartOfSpeech::SubantaPrefix => {
+                let entry = entry.as_packed_subanta_prefix();
+                let paradigm = self
+                    .subanta_suffixes
+                    .get(entry.paradigm_id() as usize)
+                    .ok_or_else(|| Error::UnknownId("paradigm", entry.paradigm_id() as usize))?;
+                let phit = self.unpack_pratipadika(Id(entry.pratipadika_id() as usize))?;
+                for ending in paradigm.endings.iter().filter(|x| is_match(&x.text)) {
+                    let pada = SubantaEntry::new(
+                        phit.clone(),
+                        ending.linga,
+                        ending.vibhakti,
+                        ending.vacana,
+                    )
+                    .with_metadata(self.subanta_metadata(entry.pratipadika_id(), ending));
+                    ret.push((format!("{key}{}", ending.text), pada.into()));
+                }
+            }
+            PartOfSpeech::TinantaPrefix => {
+                let entry = entry.as_packed_tinanta_prefix();
+                let paradigm = self
+                    .tinanta_suffixes
+                    .get(entry.paradigm_id() as usize)
+                    .ok_or_else(|| Error::UnknownId("paradigm", entry.paradigm_id() as usize))?;
+                let dhatu = self.unpack_dhatu(Id(entry.dhatu_id() as usize))?;
+                for ending in paradigm.endings.iter().filter(|x| is_match(&x.text)) {
+                    let pada = TinantaEntry::new(
                         dhatu.clone(),
                         ending.prayoga,
                         ending.lakara,
                         ending.purusha,
                         ending.vacana,
-                    )));
+                    )
+                    .with_metadata(self.tinanta_metadata(entry.dhatu_id(), ending));
+                    ret.push((format!("{key}{}", ending.text), pada.into()));
+                }
+            }
+            _ => {
+                if is_match("") {
+                    ret.push((key.to_string(), self.unpack(entry)?));
                 }
             }
         }
+        Ok(())
+    }
+
+    /// Returns the metadata for the subanta that `pratipadika_id` and `ending` define, if any.
+    fn subanta_metadata(&self, pratipadika_id: u32, ending: &SubantaSuffix) -> Option<&Metadata> {
+        // Most koshas have no pada metadata, so skip the lookup below if we can.
+        if self.metadata.padas.is_empty() {
+            return None;
+        }
+        let sup = Sup::new(ending.linga, ending.vibhakti, ending.vacana);
+        let sup_id = self.sup_to_index.get(&sup)?;
+        let payload = PackedSubanta::new()
+            .with_pratipadika_id(pratipadika_id)
+            .with_sup_id(sup_id.0 as u8);
+        let packed = PackedEntry::new()
+            .with_pos(PartOfSpeech::Subanta)
+            .with_payload(u32::from_le_bytes(payload.into_bytes()));
+        self.metadata.padas.get(&packed.to_u32())
+    }
 
+    /// Returns the metadata for the tinanta that `dhatu_id` and `ending` define, if any.
+    fn tinanta_metadata(&self, dhatu_id: u32, ending: &TinantaSuffix) -> Option<&Metadata> {
+        // Most koshas have no pada metadata, so skip the lookup below if we can.
+        if self.metadata.padas.is_empty() {
+            return None;
+        }
+        let tin = Tin::new(ending.prayoga, ending.lakara, ending.purusha, ending.vacana);
+        let tin_id = self.tin_to_index.get(&tin)?;
+        let payload = PackedTinanta::new()
+            .with_dhatu_id(dhatu_id)
+            .with_tin_id(tin_id.0 as u8);
+        let packed = PackedEntry::new()
+            .with_pos(PartOfSpeech::Tinanta)
+            .with_payload(u32::from_le_bytes(payload.into_bytes()));
+        self.metadata.padas.get(&packed.to_u32())
+    }
+
+    /// Sets the metadata for the given dhatu, which must already be registered.
+    pub(crate) fn set_dhatu_metadata(
+        &mut self,
+        dhatu: &DhatuEntry,
+        metadata: Metadata,
+    ) -> Result<()> {
+        let id = self.pack_dhatu(dhatu.dhatu())?;
+        self.metadata.dhatus.insert(id, metadata);
+        Ok(())
+    }
+
+    /// Sets the metadata for the given pratipadika, which must already be registered.
+    pub(crate) fn set_pratipadika_metadata(
+        &mut self,
+        pratipadika: &PratipadikaEntry,
+        metadata: Metadata,
+    ) -> Result<()> {
+        let id = self.pack_pratipadika_id(pratipadika)?;
+        self.metadata.pratipadikas.insert(id, metadata);
+        Ok(())
+    }
+
+    /// Sets the metadata for the given pada, whose lemma must already be registered.
+    pub(crate) fn set_pada_metadata(&mut self, pada: &PadaEntry, metadata: Metadata) -> Result<()> {
+        let packed = self.pack(pada)?;
+        self.metadata.padas.insert(packed.to_u32(), metadata);
         Ok(())
     }
 
+    /// Sets the prior weight for the given pada, whose lemma must already be registered.
+    pub(crate) fn set_pada_weight(&mut self, pada: &PadaEntry, weight: f32) -> Result<()> {
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(Error::InvalidWeight(weight));
+        }
+        let packed = self.pack(pada)?;
+        self.metadata.weights.insert(packed.to_u32(), weight);
+        Ok(())
+    }
+
+    /// Returns the prior weight for the given pada, if it has one.
+    pub(crate) fn pada_weight(&self, pada: &PadaEntry) -> Option<f32> {
+        // Packing might create large indices, so avoid it if we can.
+        if self.metadata.weights.is_empty() {
+            return None;
+        }
+        let packed = self.pack(pada).ok()?;
+        self.metadata.weights.get(&packed.to_u32()).copied()
+    }
+
     /// Increments the count of entries stored in the packer.
     pub(crate) fn increment(&mut self, entry: &PackedEntry) -> Result<()> {
         if entry.pos() == PartOfSpeech::SubantaPrefix {
@@ -656,12 +1029,18 @@ impl Packer {
     pub(crate) fn register_dhatu_entry(&mut self, entry: &DhatuEntry) -> Id {
         let dhatu = entry.dhatu();
 
-        if let Some(id) = self.dhatu_to_index.get(dhatu) {
+        // Only builders register data, and a builder's lists are never lazy.
+        let index = self
+            .dhatu_to_index
+            .get_or_init(|| create_index(self.dhatus.owned().iter().enumerate()));
+        if let Some(id) = index.get(dhatu) {
             *id
         } else {
             let id = Id(self.dhatus.len());
             self.dhatus.push(dhatu.clone());
-            self.dhatu_to_index.insert(dhatu.clone(), id);
+            let mut index = self.dhatu_to_index.take().unwrap_or_default();
+            index.insert(dhatu.clone(), id);
+            self.dhatu_to_index = OnceLock::from(index);
             let meta = if let Some(m) = entry.meta {
                 m.clone()
             } else {
@@ -670,7 +1049,10 @@ impl Packer {
             self.dhatu_meta.push(meta);
 
             assert_eq!(self.dhatus.len(), self.dhatu_meta.len());
-            assert_eq!(self.dhatus.len(), self.dhatu_to_index.len());
+            assert_eq!(
+                Some(self.dhatus.len()),
+                self.dhatu_to_index.get().map(|x| x.len())
+            );
 
             id
         }
@@ -703,14 +1085,43 @@ impl Packer {
                 let krt_id = self.register_krt(&k);
                 SmallPratipadika::Krdanta(SmallKrdanta { dhatu_id, krt_id })
             }
+            PE::Taddhitanta(t) => {
+                let pratipadika_id = self.register_pratipadika_entry(t.pratipadika_entry());
+                SmallPratipadika::Taddhitanta(SmallTaddhitanta {
+                    pratipadika_id,
+                    taddhita: t.taddhita(),
+                    artha: t.artha(),
+                })
+            }
+            PE::Samasa(s) => {
+                let padas = s
+                    .padas()
+                    .iter()
+                    .map(|p| {
+                        let id = self.register_pratipadika_entry(p.pratipadika_entry());
+                        (id, Sup::from_subanta(p))
+                    })
+                    .collect();
+                SmallPratipadika::Samasa(SmallSamasa {
+                    padas,
+                    samasa_type: s.samasa_type(),
+                    stri: s.stri(),
+                })
+            }
         };
 
-        if let Some(i) = self.pratipadika_to_index.get(&small) {
+        // Only builders register data, and a builder's lists are never lazy.
+        let index = self
+            .pratipadika_to_index
+            .get_or_init(|| create_index(self.pratipadikas.owned().iter().enumerate()));
+        if let Some(i) = index.get(&small) {
             *i
         } else {
             let id = Id(self.pratipadikas.len());
             self.pratipadikas.push(small.clone());
-            self.pratipadika_to_index.insert(small.clone(), id);
+            let mut index = self.pratipadika_to_index.take().unwrap_or_default();
+            index.insert(small.clone(), id);
+            self.pratipadika_to_index = OnceLock::from(index);
 
             if let PE::Basic(b) = entry {
                 self.pratipadika_meta.insert(
@@ -721,7 +1132,10 @@ impl Packer {
                 );
             }
 
-            assert!(self.pratipadikas.len() == self.pratipadika_to_index.len());
+            assert_eq!(
+                Some(self.pratipadikas.len()),
+                self.pratipadika_to_index.get().map(|x| x.len())
+            );
 
             id
         }
@@ -745,15 +1159,7 @@ impl Packer {
         };
 
         let pratipadika_id = self.register_pratipadika_entry(pratipadika);
-        let paradigm_id = match self.subanta_suffixes_to_index.get(&paradigm) {
-            Some(id) => *id,
-            None => {
-                let id = Id(self.subanta_suffixes.len());
-                self.subanta_suffixes.push(paradigm.clone());
-                self.subanta_suffixes_to_index.insert(paradigm, id);
-                id
-            }
-        };
+        let paradigm_id = self.intern_subanta_suffixes(paradigm);
 
         let value = {
             let prefix = PackedSubantaPrefix::new()
@@ -783,7 +1189,37 @@ impl Packer {
         };
 
         let dhatu_id = self.register_dhatu_entry(dhatu);
-        let paradigm_id = match self.tinanta_suffixes_to_index.get(&suffixes) {
+        let paradigm_id = self.intern_tinanta_suffixes(suffixes);
+
+        let value = {
+            let prefix = PackedTinantaPrefix::new()
+                .with_dhatu_id(dhatu_id.0.try_into()?)
+                .with_paradigm_id(paradigm_id.0.try_into()?);
+
+            PackedEntry::new()
+                .with_pos(PartOfSpeech::TinantaPrefix)
+                .with_payload(u32::from_le_bytes(prefix.into_bytes()))
+        };
+
+        Ok((key, value))
+    }
+
+    /// Registers the given subanta suffix table and returns its interned ID.
+    fn intern_subanta_suffixes(&mut self, paradigm: SubantaSuffixes) -> Id {
+        match self.subanta_suffixes_to_index.get(&paradigm) {
+            Some(id) => *id,
+            None => {
+                let id = Id(self.subanta_suffixes.len());
+                self.subanta_suffixes.push(paradigm.clone());
+                self.subanta_suffixes_to_index.insert(paradigm, id);
+                id
+            }
+        }
+    }
+
+    /// Registers the given tinanta suffix table and returns its interned ID.
+    fn intern_tinanta_suffixes(&mut self, suffixes: TinantaSuffixes) -> Id {
+        match self.tinanta_suffixes_to_index.get(&suffixes) {
             Some(id) => *id,
             None => {
                 let id = Id(self.tinanta_suffixes.len());
@@ -797,23 +1233,37 @@ impl Packer {
 
                 id
             }
-        };
-
-        let value = {
-            let prefix = PackedTinantaPrefix::new()
-                .with_dhatu_id(dhatu_id.0.try_into()?)
-                .with_paradigm_id(paradigm_id.0.try_into()?);
-
-            PackedEntry::new()
-                .with_pos(PartOfSpeech::TinantaPrefix)
-                .with_payload(u32::from_le_bytes(prefix.into_bytes()))
-        };
+        }
+    }
 
-        Ok((key, value))
+    /// Registers all data in `other` and returns how `other`'s IDs map to ours.
+    ///
+    /// If `other` has metadata for a lemma we have already registered, we keep our metadata.
+    pub(crate) fn import(&mut self, other: &Packer) -> Result<IdMap> {
+        let mut ret = IdMap::default();
+        for i in 0..other.dhatus.len() {
+            let dhatu = other.unpack_dhatu(Id(i))?;
+            ret.dhatus.push(self.register_dhatu_entry(&dhatu));
+        }
+        for i in 0..other.pratipadikas.len() {
+            let pratipadika = other.unpack_pratipadika(Id(i))?;
+            ret.pratipadikas
+                .push(self.register_pratipadika_entry(&pratipadika));
+        }
+        for paradigm in &other.subanta_suffixes {
+            ret.subanta_suffixes
+                .push(self.intern_subanta_suffixes(paradigm.clone()));
+        }
+        for suffixes in &other.tinanta_suffixes {
+            ret.tinanta_suffixes
+                .push(self.intern_tinanta_suffixes(suffixes.clone()));
+        }
+        self.metadata.import(&other.metadata, &ret)?;
+        Ok(ret)
     }
 
-    fn pack_dhatu(&self, dhatu: &Dhatu) -> Result<Id> {
-        self.dhatu_to_index
+    pub(crate) fn pack_dhatu(&self, dhatu: &Dhatu) -> Result<Id> {
+        self.dhatu_to_index()?
             .get(dhatu)
             .copied()
             .ok_or_else(|| Error::NotRegistered("dhatu"))
@@ -829,9 +1279,38 @@ impl Packer {
                 let krt_id = self.pack_krt(k)?;
                 Ok(SmallPratipadika::Krdanta(SmallKrdanta { dhatu_id, krt_id }))
             }
+            PE::Taddhitanta(t) => Ok(SmallPratipadika::Taddhitanta(SmallTaddhitanta {
+                pratipadika_id: self.pack_pratipadika_id(t.pratipadika_entry())?,
+                taddhita: t.taddhita(),
+                artha: t.artha(),
+            })),
+            PE::Samasa(s) => {
+                let padas = s
+                    .padas()
+                    .iter()
+                    .map(|p| {
+                        let id = self.pack_pratipadika_id(p.pratipadika_entry())?;
+                        Ok((id, Sup::from_subanta(p)))
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(SmallPratipadika::Samasa(SmallSamasa {
+                    padas,
+                    samasa_type: s.samasa_type(),
+                    stri: s.stri(),
+                }))
+            }
         }
     }
 
+    /// Returns the interned ID of the given pratipadika.
+    pub(crate) fn pack_pratipadika_id(&self, pratipadika: &PratipadikaEntry) -> Result<Id> {
+        let small = self.pack_pratipadika(pratipadika)?;
+        self.pratipadika_to_index()?
+            .get(&small)
+            .copied()
+            .ok_or(Error::NotRegistered("pratipadika"))
+    }
+
     fn pack_krt(&self, krdanta: &KrdantaEntry) -> Result<Id> {
         assert!(!self.krt_to_index.is_empty());
         let krt = RichKrt {
@@ -847,17 +1326,12 @@ impl Packer {
 
     fn pack_subanta(&self, s: &SubantaEntry) -> Result<PackedSubanta> {
         let packed_pratipadika = self.pack_pratipadika(s.pratipadika_entry())?;
-        match self.pratipadika_to_index.get(&packed_pratipadika) {
+        match self.pratipadika_to_index()?.get(&packed_pratipadika) {
             Some(pratipadika_id) => {
-                let sup_id = if s.pratipadika_entry().is_avyaya() {
-                    self.sup_to_index
-                        .get(&Sup::Avyaya)
-                        .ok_or_else(|| Error::NotRegistered("sup"))?
-                } else {
-                    self.sup_to_index
-                        .get(&Sup::new(s.linga(), s.vibhakti(), s.vacana()))
-                        .ok_or_else(|| Error::NotRegistered("sup"))?
-                };
+                let sup_id = self
+                    .sup_to_index
+                    .get(&Sup::from_subanta(s))
+                    .ok_or_else(|| Error::NotRegistered("sup"))?;
                 let ret = {
                     PackedSubanta::new()
                         .with_pratipadika_id(pratipadika_id.0.try_into()?)
@@ -923,10 +1397,11 @@ impl Packer {
     }
 
     pub(crate) fn unpack_dhatu(&self, id: Id) -> Result<DhatuEntry> {
-        match (self.dhatus.get(id.0), self.dhatu_meta.get(id.0)) {
+        match (self.dhatus.get(id.0)?, self.dhatu_meta.get(id.0)?) {
             (Some(dhatu), Some(meta)) => {
                 let mut entry = DhatuEntry::new(dhatu);
                 entry.meta = Some(meta);
+                entry.metadata = self.metadata.dhatus.get(&id);
                 Ok(entry)
             }
             _ => Err(Error::UnknownId("dhatu", id.0)),
@@ -934,7 +1409,12 @@ impl Packer {
     }
 
     pub(crate) fn unpack_pratipadika(&self, id: Id) -> Result<PratipadikaEntry> {
-        match self.pratipadikas.get(id.0) {
+        let entry = self.unpack_pratipadika_inner(id)?;
+        Ok(entry.with_metadata(self.metadata.pratipadikas.get(&id)))
+    }
+
+    fn unpack_pratipadika_inner(&self, id: Id) -> Result<PratipadikaEntry<'_>> {
+        match self.pratipadikas.get(id.0)? {
             Some(p) => match p {
                 SmallPratipadika::Basic(b) => {
                     let lingas = match self.pratipadika_meta.get(&id) {
@@ -954,11 +1434,46 @@ impl Packer {
                         krt.lakara,
                     )))
                 }
+                SmallPratipadika::Taddhitanta(t) => {
+                    let base = self.unpack_member(t.pratipadika_id, id)?;
+                    Ok(PratipadikaEntry::Taddhitanta(TaddhitantaEntry::new(
+                        base, t.taddhita, t.artha,
+                    )))
+                }
+                SmallPratipadika::Samasa(s) => {
+                    let mut padas = Vec::with_capacity(s.padas.len());
+                    for (member_id, sup) in &s.padas {
+                        let member = self.unpack_member(*member_id, id)?;
+                        padas.push(match sup {
+                            Sup::Basic {
+                                linga,
+                                vibhakti,
+                                vacana,
+                            } => SubantaEntry::new(member, *linga, *vibhakti, *vacana),
+                            Sup::Avyaya => SubantaEntry::avyaya(member),
+                            Sup::Purvapada => SubantaEntry::purvapada(member),
+                        });
+                    }
+                    let samasa = SamasaEntry::new(padas, s.samasa_type)?.with_stri(s.stri);
+                    Ok(PratipadikaEntry::Samasa(samasa))
+                }
             },
             None => Err(Error::UnknownId("pratipadika", id.0)),
         }
     }
 
+    /// Unpacks a pratipadika used within the pratipadika `parent_id`.
+    ///
+    /// We always register members before the pratipadikas that use them. So if a member ID is
+    /// not smaller than its parent's, the registry is corrupt and unpacking it would never end.
+    fn unpack_member(&self, id: Id, parent_id: Id) -> Result<PratipadikaEntry<'_>> {
+        if id.0 < parent_id.0 {
+            self.unpack_pratipadika(id)
+        } else {
+            Err(Error::UnknownId("pratipadika", id.0))
+        }
+    }
+
     fn unpack_subanta(&self, packed: PackedSubanta) -> Result<SubantaEntry> {
         let pratipadika = self.unpack_pratipadika(Id(packed.pratipadika_id() as usize))?;
         let sup = self.unpack_sup(Id(packed.sup_id() as usize))?;
@@ -969,6 +1484,7 @@ impl Packer {
                 vacana,
             } => Ok(SubantaEntry::new(pratipadika, *linga, *vibhakti, *vacana)),
             Sup::Avyaya => Ok(SubantaEntry::avyaya(pratipadika)),
+            Sup::Purvapada => Ok(SubantaEntry::purvapada(pratipadika)),
         }
     }
 
@@ -990,11 +1506,13 @@ impl Packer {
         match pada.pos() {
             PartOfSpeech::Subanta => {
                 let subanta = self.unpack_subanta(pada.as_packed_subanta())?;
-                Ok(PadaEntry::Subanta(subanta))
+                let metadata = self.metadata.padas.get(&pada.to_u32());
+                Ok(PadaEntry::Subanta(subanta.with_metadata(metadata)))
             }
             PartOfSpeech::Tinanta => {
                 let tinanta = self.unpack_tinanta(pada.as_packed_tinanta())?;
-                Ok(PadaEntry::Tinanta(tinanta))
+                let metadata = self.metadata.padas.get(&pada.to_u32());
+                Ok(PadaEntry::Tinanta(tinanta.with_metadata(metadata)))
             }
             _ => Err(Error::NotRegistered("Prefix")),
         }
@@ -1043,6 +1561,13 @@ impl Packer {
             SmallPratipadika::Krdanta(k) => {
                 format!("(Krdanta {})", create_krdanta_entry_str(&k))
             }
+            SmallPratipadika::Taddhitanta(t) => {
+                format!("(Taddhitanta {} + {:?})", t.pratipadika_id.0, t.taddhita)
+            }
+            SmallPratipadika::Samasa(s) => {
+                let ids: Vec<_> = s.padas.iter().map(|(id, _)| id.0.to_string()).collect();
+                format!("(Samasa {:?} {})", s.samasa_type, ids.join(" + "))
+            }
         };
 
         let str = match entry.pos() {
@@ -1171,6 +1696,72 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn read_lazy_matches_read() -> TestResult {
+        // `read_lazy` reads the fields of `Registry` by hand. So if we add, remove, or reorder
+        // fields, this test should fail until we update `read_lazy`.
+        let gam = Dhatu::mula(safe("ga\\mx~"), vp::Gana::Bhvadi);
+        let gam_meta = DhatuMeta::builder()
+            .clean_text("gam".to_string())
+            .build()
+            .expect("ok");
+        let gam_entry = DhatuEntry::new(&gam).with_meta(&gam_meta);
+        let gata: Pratipadika = vp::Krdanta::new(gam.clone(), vp::BaseKrt::kta).into();
+        let deva = Pratipadika::basic(safe("deva"));
+
+        let mut packer = Packer::new();
+        packer.register_pratipadika_entry(&(&gata).try_into()?);
+        let (_, subanta) = packer.register_subanta_paradigm(
+            &(&deva).try_into()?,
+            &[(
+                "devas".to_string(),
+                Linga::Pum,
+                Vibhakti::Prathama,
+                Vacana::Eka,
+            )],
+        )?;
+        let (_, tinanta) = packer.register_tinanta_suffixes(
+            &gam_entry,
+            &[(
+                "gacCati".to_string(),
+                Prayoga::Kartari,
+                Lakara::Lat,
+                Purusha::Prathama,
+                Vacana::Eka,
+            )],
+        )?;
+        packer.increment(&subanta)?;
+        packer.increment(&tinanta)?;
+
+        let registry = Registry {
+            count: packer.count,
+            krts: packer.krts.clone(),
+            dhatus: packer.dhatus.to_vec()?,
+            dhatu_meta: packer.dhatu_meta.to_vec()?,
+            pratipadikas: packer.pratipadikas.to_vec()?,
+            pratipadika_meta: packer.pratipadika_meta.clone(),
+            subanta_suffixes: packer.subanta_suffixes.clone(),
+            tinanta_suffixes: packer.tinanta_suffixes.clone(),
+        };
+        assert!(registry.count > 0);
+        assert!(!registry.krts.is_empty());
+        assert!(!registry.dhatus.is_empty());
+        assert!(!registry.dhatu_meta.is_empty());
+        assert!(!registry.pratipadikas.is_empty());
+        assert!(!registry.pratipadika_meta.is_empty());
+        assert!(!registry.subanta_suffixes.is_empty());
+        assert!(!registry.tinanta_suffixes.is_empty());
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("registry.msgpack");
+        packer.write(&path)?;
+        let eager = Packer::read(&path)?;
+        let lazy = Packer::read_lazy(Bytes::from(std::fs::read(&path)?))?;
+        assert_eq!(lazy, eager);
+
+        Ok(())
+    }
+
     #[test]
     fn test_avyaya_packing() -> TestResult {
         let iti_stem = Pratipadika::basic(safe("iti"));
@@ -1192,4 +1783,47 @@ mod tests {
         assert_eq!(packer.unpack(&iti_code)?, iti);
         Ok(())
     }
+
+    #[test]
+    fn test_common_prefix_len() {
+        let len = |words: &[&str]| common_prefix_len(words.iter().copied());
+        assert_eq!(len(&[]), 0);
+        assert_eq!(len(&["devaH"]), 5);
+        assert_eq!(len(&["devaH", "devam"]), 4);
+        // A later word is shorter than the prefix so far.
+        assert_eq!(len(&["devas", "deva"]), 4);
+        assert_eq!(len(&["devas", "deva", "devAn"]), 3);
+        assert_eq!(len(&["rAma", "kAma"]), 0);
+        assert_eq!(len(&["ā", "āa", "ab"]), 0);
+    }
+
+    #[test]
+    fn test_subanta_paradigm_with_shorter_pada() -> TestResult {
+        let deva = Pratipadika::basic(safe("deva"));
+        let deva_entry = (&deva).try_into().expect("ok");
+        let mut packer = Packer::new();
+        let padas = [
+            (
+                "devas".to_string(),
+                Linga::Pum,
+                Vibhakti::Prathama,
+                Vacana::Eka,
+            ),
+            (
+                "deva".to_string(),
+                Linga::Pum,
+                Vibhakti::Sambodhana,
+                Vacana::Eka,
+            ),
+        ];
+        let (key, _) = packer.register_subanta_paradigm(&deva_entry, &padas)?;
+        assert_eq!(key, "deva");
+        let endings: Vec<_> = packer.subanta_suffixes[0]
+            .endings()
+            .iter()
+            .map(|e| e.text())
+            .collect();
+        assert_eq!(endings, ["s", ""]);
+        Ok(())
+    }
 }
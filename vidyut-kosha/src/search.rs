@@ -0,0 +1,299 @@
+//! Automata for searching the kosha.
+//!
+//! `Kosha::search` walks the FST with any `fst::Automaton`. This module defines the automata that
+//! the FST crate doesn't provide on its own: regular expressions, globs, and a Levenshtein
+//! automaton that measures edit distance in sounds rather than bytes.
+use crate::errors::{Error, Result};
+use fst::Automaton;
+use regex_automata::dfa::{dense, Automaton as _, StartKind};
+use regex_automata::util::primitives::StateID;
+use regex_automata::util::start;
+use regex_automata::Anchored;
+
+/// SLP1 marks that modify the sound before them (*candrabindu* and Vedic accents).
+const SLP1_MARKS: &[u8] = b"~/\\^";
+
+/// Returns whether `b` continues the sound that the previous byte started.
+///
+/// A sound is a single character followed by any number of SLP1 marks. For non-ASCII keys, bytes
+/// after the first byte of a UTF-8 character continue that character.
+fn continues_sound(b: u8) -> bool {
+    (b & 0xC0) == 0x80 || SLP1_MARKS.contains(&b)
+}
+
+/// Splits `text` into sounds.
+fn to_sounds(text: &str) -> Vec<&[u8]> {
+    let bytes = text.as_bytes();
+    let mut ret = Vec::new();
+    let mut start = 0;
+    for (i, b) in bytes.iter().enumerate() {
+        if i > start && !continues_sound(*b) {
+            ret.push(&bytes[start..i]);
+            start = i;
+        }
+    }
+    if start < bytes.len() {
+        ret.push(&bytes[start..]);
+    }
+    ret
+}
+
+/// Matches all keys within some edit distance of a query.
+///
+/// We measure edit distance in sounds rather than bytes. So `a` and `a/` are one edit apart in
+/// SLP1, and replacing one Devanagari character with another is a single edit.
+pub(crate) struct Levenshtein<'a> {
+    query: Vec<&'a [u8]>,
+    max_distance: usize,
+}
+
+/// The state of a `Levenshtein` automaton.
+#[derive(Clone, Debug)]
+pub(crate) struct LevenshteinState {
+    /// The edit distance from each prefix of the query to all complete sounds read so far.
+    row: Vec<usize>,
+    /// The bytes of the sound we are reading now, which might be incomplete.
+    sound: Vec<u8>,
+}
+
+impl<'a> Levenshtein<'a> {
+    /// Creates an automaton that matches keys at most `max_distance` sounds away from `query`.
+    pub(crate) fn new(query: &'a str, max_distance: usize) -> Self {
+        Self {
+            query: to_sounds(query),
+            max_distance,
+        }
+    }
+
+    /// Returns the row we get after reading `sound`.
+    fn next_row(&self, row: &[usize], sound: &[u8]) -> Vec<usize> {
+        let mut ret = Vec::with_capacity(row.len());
+        ret.push(row[0] + 1);
+        for (i, q) in self.query.iter().enumerate() {
+            let cost = usize::from(*q != sound);
+            let value = (row[i] + cost).min(row[i + 1] + 1).min(ret[i] + 1);
+            ret.push(value);
+        }
+        ret
+    }
+}
+
+impl Automaton for Levenshtein<'_> {
+    type State = LevenshteinState;
+
+    fn start(&self) -> LevenshteinState {
+        LevenshteinState {
+            row: (0..=self.query.len()).collect(),
+            sound: Vec::new(),
+        }
+    }
+
+    fn is_match(&self, state: &LevenshteinState) -> bool {
+        let distance = if state.sound.is_empty() {
+            state.row[self.query.len()]
+        } else {
+            self.next_row(&state.row, &state.sound)[self.query.len()]
+        };
+        distance <= self.max_distance
+    }
+
+    fn can_match(&self, state: &LevenshteinState) -> bool {
+        // Reading more sounds can never decrease the smallest value in the row.
+        state.row.iter().any(|x| *x <= self.max_distance)
+    }
+
+    fn accept(&self, state: &LevenshteinState, byte: u8) -> LevenshteinState {
+        if state.sound.is_empty() || continues_sound(byte) {
+            let mut sound = state.sound.clone();
+            sound.push(byte);
+            LevenshteinState {
+                row: state.row.clone(),
+                sound,
+            }
+        } else {
+            LevenshteinState {
+                row: self.next_row(&state.row, &state.sound),
+                sound: vec![byte],
+            }
+        }
+    }
+}
+
+/// Matches all keys that fully match a regular expression.
+pub(crate) struct Regex {
+    dfa: dense::DFA<Vec<u32>>,
+    start: StateID,
+}
+
+impl Regex {
+    /// Compiles `pattern`, which must match the entire key.
+    pub(crate) fn new(pattern: &str) -> Result<Self> {
+        let dfa = dense::Builder::new()
+            .configure(dense::Config::new().start_kind(StartKind::Anchored))
+            .build(&format!("(?:{pattern})$"))
+            .map_err(|e| Error::Regex(e.to_string()))?;
+        let start = dfa
+            .start_state(&start::Config::new().anchored(Anchored::Yes))
+            .map_err(|e| Error::Regex(e.to_string()))?;
+        Ok(Self { dfa, start })
+    }
+
+    /// Compiles a glob `pattern`, which must match the entire key.
+    ///
+    /// `*` matches any sequence of characters, `?` matches a single character, and `[...]`
+    /// matches a single character in a set. As with shell globs, `[!...]` matches a single
+    /// character not in a set.
+    pub(crate) fn from_glob(pattern: &str) -> Result<Self> {
+        Self::new(&glob_to_regex(pattern))
+    }
+}
+
+impl Automaton for Regex {
+    type State = StateID;
+
+    fn start(&self) -> StateID {
+        self.start
+    }
+
+    fn is_match(&self, state: &StateID) -> bool {
+        self.dfa.is_match_state(self.dfa.next_eoi_state(*state))
+    }
+
+    fn can_match(&self, state: &StateID) -> bool {
+        !self.dfa.is_dead_state(*state)
+    }
+
+    fn accept(&self, state: &StateID, byte: u8) -> StateID {
+        self.dfa.next_state(*state, byte)
+    }
+}
+
+/// Converts a glob pattern to a regular expression.
+fn glob_to_regex(glob: &str) -> String {
+    let mut ret = String::with_capacity(glob.len() * 2);
+    let mut in_class = false;
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_class {
+            match c {
+                ']' => {
+                    in_class = false;
+                    ret.push(c);
+                }
+                '\\' | '[' | '&' | '~' => {
+                    ret.push('\\');
+                    ret.push(c);
+                }
+                _ => ret.push(c),
+            }
+            continue;
+        }
+        match c {
+            '*' => ret.push_str(".*"),
+            '?' => ret.push('.'),
+            '[' => {
+                in_class = true;
+                ret.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    ret.push('^');
+                }
+            }
+            _ => {
+                if "\\.+()|]{}^$#&-~".contains(c) {
+                    ret.push('\\');
+                }
+                ret.push(c);
+            }
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn matches(aut: &impl Automaton, text: &str) -> bool {
+        let mut state = aut.start();
+        for b in text.bytes() {
+            if !aut.can_match(&state) {
+                return false;
+            }
+            state = aut.accept(&state, b);
+        }
+        aut.is_match(&state)
+    }
+
+    #[test]
+    fn test_to_sounds() {
+        assert_eq!(to_sounds("a/gni~"), vec![&b"a/"[..], b"g", b"n", b"i~"]);
+        assert_eq!(
+            to_sounds("देव"),
+            vec!["द".as_bytes(), "े".as_bytes(), "व".as_bytes()]
+        );
+        assert!(to_sounds("").is_empty());
+    }
+
+    #[test]
+    fn levenshtein() {
+        let aut = Levenshtein::new("devas", 1);
+        assert!(matches(&aut, "devas"));
+        assert!(matches(&aut, "devAs"));
+        assert!(matches(&aut, "deva"));
+        assert!(matches(&aut, "devasa"));
+        assert!(!matches(&aut, "dAvAs"));
+        assert!(!matches(&aut, "nara"));
+
+        // Marks are part of the sound before them.
+        let aut = Levenshtein::new("a/gni", 1);
+        assert!(matches(&aut, "agni"));
+        assert!(matches(&aut, "a\\gni"));
+        assert!(!matches(&aut, "a/gni/s"));
+
+        // Non-ASCII characters count as a single sound.
+        let aut = Levenshtein::new("देवः", 1);
+        assert!(matches(&aut, "देवा"));
+        assert!(!matches(&aut, "दवा"));
+    }
+
+    #[test]
+    fn levenshtein_with_zero_distance() {
+        let aut = Levenshtein::new("rAma", 0);
+        assert!(matches(&aut, "rAma"));
+        assert!(!matches(&aut, "rama"));
+        assert!(!matches(&aut, "rAm"));
+    }
+
+    #[test]
+    fn regex() {
+        let aut = Regex::new("deva(s|m)").unwrap();
+        assert!(matches(&aut, "devas"));
+        assert!(matches(&aut, "devam"));
+        assert!(!matches(&aut, "deva"));
+        assert!(!matches(&aut, "devasya"));
+        assert!(!matches(&aut, "adevas"));
+
+        assert!(Regex::new("deva(").is_err());
+    }
+
+    #[test]
+    fn glob() {
+        let aut = Regex::from_glob("*Ami").unwrap();
+        assert!(matches(&aut, "gacCAmi"));
+        assert!(!matches(&aut, "gacCati"));
+
+        let aut = Regex::from_glob("dev?.[!s]").unwrap();
+        assert!(matches(&aut, "deva.m"));
+        assert!(!matches(&aut, "deva.s"));
+        assert!(!matches(&aut, "devaXm"));
+    }
+
+    #[test]
+    fn test_glob_to_regex() {
+        assert_eq!(glob_to_regex("a*b?c"), "a.*b.c");
+        assert_eq!(glob_to_regex("[!ab]"), "[^ab]");
+        assert_eq!(glob_to_regex("a.b"), "a\\.b");
+    }
+}
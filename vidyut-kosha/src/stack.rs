@@ -0,0 +1,662 @@
+//! A stack of koshas that we query as a single kosha.
+//!
+//! Building a production kosha takes hours, so rebuilding it just to add a few hundred rare words
+//! is impractical. Instead, we can build those words into a small *overlay* kosha and stack it on
+//! top of the *base* kosha. A `KoshaStack` answers queries by consulting every kosha in the stack,
+//! and `KoshaStack::merge` combines the stack into a single kosha on disk.
+//!
+//! Each kosha in the stack numbers its dhatus and pratipadikas independently. So that packed
+//! entries stay meaningful across the stack, we give each kosha a range of IDs that starts after
+//! the ranges of the koshas below it. The base kosha therefore keeps its original IDs.
+use crate::entries::{DhatuEntry, PadaEntry, PratipadikaEntry};
+use crate::errors::{Error, Result};
+use crate::kosha::{sort_by_weight, Builder, Kosha};
+use crate::packing::{Id, IdKind, PackedEntry, PartOfSpeech};
+use fst::automaton::Automaton;
+use fst::map::OpBuilder;
+use fst::Streamer;
+use log::info;
+use rustc_hash::FxHashSet;
+use std::hash::Hash;
+use std::path::Path;
+
+/// Several koshas that we query as a single kosha.
+///
+/// The first kosha in the stack is the *base* kosha, and the koshas above it are *overlays*.
+/// Queries return results from all koshas in order from the base upward, and we skip results that
+/// a lower kosha has already returned.
+///
+/// # Usage
+///
+/// ```rust,no_run
+/// # use vidyut_kosha::*;
+/// use vidyut_kosha::{Kosha, KoshaStack};
+///
+/// let base = Kosha::new("/path/to/kosha/data")?;
+/// let overlay = Kosha::new("/path/to/rare/words")?;
+/// let stack = KoshaStack::new(vec![base, overlay]);
+///
+/// for entry in stack.get_all("Bavati") {
+///     println!("{:#?}", entry);
+/// }
+///
+/// // Combine the stack into a single kosha.
+/// stack.merge("/path/to/merged/data")?;
+/// # Ok::<(), Error>(())
+/// ```
+pub struct KoshaStack {
+    layers: Vec<Kosha>,
+    /// The first stack-wide dhatu ID for each layer.
+    dhatu_offsets: Vec<usize>,
+    /// The first stack-wide pratipadika ID for each layer.
+    pratipadika_offsets: Vec<usize>,
+}
+
+/// Removes all repeated items from `items` but otherwise keeps its order.
+fn dedup<T: Clone + Eq + Hash>(items: Vec<T>) -> Vec<T> {
+    let mut seen = FxHashSet::default();
+    items
+        .into_iter()
+        .filter(|x| seen.insert(x.clone()))
+        .collect()
+}
+
+/// Returns the start of each layer's range, given the size of each layer.
+fn range_starts(sizes: impl Iterator<Item = usize>) -> Vec<usize> {
+    let mut total = 0;
+    sizes
+        .map(|n| {
+            let start = total;
+            total += n;
+            start
+        })
+        .collect()
+}
+
+impl KoshaStack {
+    /// Creates a stack from `layers`. `layers[0]` is the base kosha, and each following kosha is
+    /// an overlay on the ones before it.
+    pub fn new(layers: Vec<Kosha>) -> Self {
+        let dhatu_offsets = range_starts(layers.iter().map(|k| k.packer.dhatus.len()));
+        let pratipadika_offsets = range_starts(layers.iter().map(|k| k.packer.pratipadikas.len()));
+        Self {
+            layers,
+            dhatu_offsets,
+            pratipadika_offsets,
+        }
+    }
+
+    /// Returns the koshas in this stack, starting with the base kosha.
+    pub fn layers(&self) -> &[Kosha] {
+        &self.layers
+    }
+
+    /// Returns the total number of words in all koshas in the stack.
+    ///
+    /// A word stored in more than one kosha is counted more than once.
+    pub fn len(&self) -> usize {
+        self.layers.iter().map(|k| k.len()).sum()
+    }
+
+    /// Returns whether the stack contains no words.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over all dhatus in the stack.
+    ///
+    /// A dhatu stored in more than one kosha is returned only once, with the metadata of the
+    /// lowest kosha that contains it.
+    pub fn dhatus(&self) -> impl Iterator<Item = DhatuEntry<'_>> {
+        self.layers.iter().enumerate().flat_map(move |(i, layer)| {
+            layer.dhatus().filter(move |d| {
+                let lower = &self.layers[..i];
+                !lower.iter().any(|k| k.packer.pack_dhatu(d.dhatu()).is_ok())
+            })
+        })
+    }
+
+    /// Returns an iterator over all pratipadikas in the stack.
+    ///
+    /// A pratipadika stored in more than one kosha is returned only once, with the metadata of
+    /// the lowest kosha that contains it.
+    pub fn pratipadikas(&self) -> impl Iterator<Item = PratipadikaEntry<'_>> {
+        self.layers.iter().enumerate().flat_map(move |(i, layer)| {
+            layer.pratipadikas().filter(move |p| {
+                let lower = &self.layers[..i];
+                !lower
+                    .iter()
+                    .any(|k| k.packer.pack_pratipadika_id(p).is_ok())
+            })
+        })
+    }
+
+    /// Returns whether any kosha in the stack contains at least one entry with the exact value
+    /// `key`.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.layers.iter().any(|k| k.contains_key(key))
+    }
+
+    /// Returns all results for the given `key` from all koshas in the stack.
+    ///
+    /// If several koshas contain the same entry, we return it once. Metadata doesn't make two
+    /// entries different, so if the copies have different metadata, we keep the one from the
+    /// lowest kosha.
+    pub fn get_all(&self, key: &str) -> Vec<PadaEntry<'_>> {
+        dedup(self.layers.iter().flat_map(|k| k.get_all(key)).collect())
+    }
+
+    /// Returns the prior weight of the given *pada* in the lowest kosha that gives it a weight,
+    /// or `None` if no kosha does.
+    pub fn weight(&self, pada: &PadaEntry) -> Option<f32> {
+        self.layers.iter().find_map(|k| k.weight(pada))
+    }
+
+    /// Returns all results for the given `key` along with their weights, sorted from highest
+    /// weight to lowest.
+    ///
+    /// For details, see `Kosha::get_all_ranked`.
+    pub fn get_all_ranked(&self, key: &str) -> Vec<(PadaEntry<'_>, Option<f32>)> {
+        let mut ret: Vec<_> = self
+            .get_all(key)
+            .into_iter()
+            .map(|entry| {
+                let weight = self.weight(&entry);
+                (entry, weight)
+            })
+            .collect();
+        sort_by_weight(&mut ret);
+        ret
+    }
+
+    /// Returns the `k` results for the given `key` with the highest weights.
+    ///
+    /// For details, see `Kosha::get_top_k`.
+    pub fn get_top_k(&self, key: &str, k: usize) -> Vec<(PadaEntry<'_>, Option<f32>)> {
+        let mut ret = self.get_all_ranked(key);
+        ret.truncate(k);
+        ret
+    }
+
+    /// Returns all words in the stack that use the given dhatu, along with their semantics.
+    ///
+    /// For details, see `Kosha::dhatu_forms`.
+    pub fn dhatu_forms(&self, dhatu: &DhatuEntry) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        self.forms(|k| k.dhatu_forms(dhatu))
+    }
+
+    /// Returns all words in the stack that use the given *prātipadika*, along with their
+    /// semantics.
+    ///
+    /// For details, see `Kosha::pratipadika_forms`.
+    pub fn pratipadika_forms(
+        &self,
+        pratipadika: &PratipadikaEntry,
+    ) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        self.forms(|k| k.pratipadika_forms(pratipadika))
+    }
+
+    /// Returns all words in the stack whose `PadaEntry::lemma` is `lemma`, along with their
+    /// semantics.
+    ///
+    /// For details, see `Kosha::lemma_forms`.
+    pub fn lemma_forms(&self, lemma: &str) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        let mut ret = Vec::new();
+        for layer in &self.layers {
+            ret.extend(layer.lemma_forms(lemma)?);
+        }
+        Ok(dedup(ret))
+    }
+
+    /// Collects the forms of some lemma from each kosha that registers it.
+    fn forms<'a>(
+        &'a self,
+        f: impl Fn(&'a Kosha) -> Result<Vec<(String, PadaEntry<'a>)>>,
+    ) -> Result<Vec<(String, PadaEntry<'a>)>> {
+        let mut ret = Vec::new();
+        let mut found = false;
+        for layer in &self.layers {
+            match f(layer) {
+                Ok(forms) => {
+                    ret.extend(forms);
+                    found = true;
+                }
+                Err(Error::NotRegistered(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        if found {
+            Ok(dedup(ret))
+        } else {
+            Err(Error::NotRegistered("lemma"))
+        }
+    }
+
+    /// Returns all words in the stack that start with `prefix`, along with their semantics.
+    ///
+    /// For details, see `Kosha::prefix_search`.
+    pub fn prefix_search(&self, prefix: &str) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        self.merge_results(|k| k.prefix_search(prefix))
+    }
+
+    /// Returns all words in the stack that fully match the regular expression `pattern`, along
+    /// with their semantics.
+    ///
+    /// For details, see `Kosha::regex_search`.
+    pub fn regex_search(&self, pattern: &str) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        self.merge_results(|k| k.regex_search(pattern))
+    }
+
+    /// Returns all words in the stack that fully match the glob `pattern`, along with their
+    /// semantics.
+    ///
+    /// For details, see `Kosha::glob_search`.
+    pub fn glob_search(&self, pattern: &str) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        self.merge_results(|k| k.glob_search(pattern))
+    }
+
+    /// Returns all words in the stack within `max_distance` edits of `query`, along with their
+    /// semantics.
+    ///
+    /// For details, see `Kosha::fuzzy_search`.
+    pub fn fuzzy_search(
+        &self,
+        query: &str,
+        max_distance: usize,
+    ) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        self.merge_results(|k| k.fuzzy_search(query, max_distance))
+    }
+
+    /// Returns all words in the stack that `aut` matches, along with their semantics.
+    ///
+    /// For details, see `Kosha::search`.
+    pub fn search<A: Automaton>(&self, aut: A) -> Result<Vec<(String, PadaEntry<'_>)>> {
+        self.merge_results(|k| k.search(&aut))
+    }
+
+    /// Combines the sorted results from each kosha into a single sorted list.
+    fn merge_results<'a>(
+        &'a self,
+        f: impl Fn(&'a Kosha) -> Result<Vec<(String, PadaEntry<'a>)>>,
+    ) -> Result<Vec<(String, PadaEntry<'a>)>> {
+        let mut ret = Vec::new();
+        for layer in &self.layers {
+            ret.extend(f(layer)?);
+        }
+        // Stable, so results for the same key stay in stack order.
+        ret.sort_by(|x, y| x.0.cmp(&y.0));
+        Ok(dedup(ret))
+    }
+
+    /// Packs the given `PadaEntry` into a simple integer code.
+    ///
+    /// If more than one kosha contains the entry's lemma, we use the ID from the lowest one.
+    pub fn pack(&self, value: &PadaEntry) -> Result<PackedEntry> {
+        for (i, layer) in self.layers.iter().enumerate() {
+            if let Ok(packed) = layer.pack(value) {
+                return packed.map_ids(|kind, id| match self.offsets(kind).get(i) {
+                    Some(offset) => Ok(Id(offset + id.0)),
+                    None => Err(Error::UnknownId("layer", i)),
+                });
+            }
+        }
+        Err(Error::NotRegistered("pada"))
+    }
+
+    /// Unpacks the given `PackedEntry` into a full `PadaEntry`.
+    ///
+    /// `value` must have been created with `KoshaStack::pack` on a stack with the same koshas.
+    pub fn unpack(&self, value: PackedEntry) -> Result<PadaEntry<'_>> {
+        let (kind, id) = match value.pos() {
+            PartOfSpeech::Subanta => (
+                IdKind::Pratipadika,
+                value.as_packed_subanta().pratipadika_id(),
+            ),
+            PartOfSpeech::Tinanta => (IdKind::Dhatu, value.as_packed_tinanta().dhatu_id()),
+            _ => return Err(Error::NotRegistered("Prefix")),
+        };
+
+        // The last layer whose range starts at or before `id`. Empty layers have empty ranges,
+        // so we skip them.
+        let offsets = self.offsets(kind);
+        let i = offsets
+            .partition_point(|x| *x <= id as usize)
+            .checked_sub(1)
+            .ok_or(Error::UnknownId("layer", id as usize))?;
+        let local = value.map_ids(|_, id| Ok(Id(id.0 - offsets[i])))?;
+        self.layers[i].unpack(local)
+    }
+
+    fn offsets(&self, kind: IdKind) -> &[usize] {
+        match kind {
+            IdKind::Dhatu => &self.dhatu_offsets,
+            IdKind::Pratipadika => &self.pratipadika_offsets,
+            // Packed padas never refer to suffix tables.
+            IdKind::SubantaSuffixes | IdKind::TinantaSuffixes => &[],
+        }
+    }
+
+    /// Writes all koshas in the stack to a single new kosha at `base_path`.
+    ///
+    /// The new kosha contains every word in the stack exactly once. Its dhatus and pratipadikas
+    /// are renumbered, so packed entries from the stack are not valid for the new kosha.
+    pub fn merge(&self, base_path: impl AsRef<Path>) -> Result<()> {
+        let mut builder = Builder::new(base_path)?;
+
+        info!("Merging the registries of {} koshas.", self.layers.len());
+        let id_maps = self
+            .layers
+            .iter()
+            .map(|k| builder.packer.import(&k.packer))
+            .collect::<Result<Vec<_>>>()?;
+
+        // The FST stores duplicates as separate keys, so a word and its duplicates are adjacent
+        // in a union of all FSTs. So we collect all values for a word before inserting them.
+        info!("Merging the FSTs of {} koshas.", self.layers.len());
+        let mut op = OpBuilder::new();
+        for layer in &self.layers {
+            op = op.add(layer.fst.stream());
+        }
+        let mut union = op.union();
+        let mut key: Vec<u8> = Vec::new();
+        let mut values: Vec<(usize, PackedEntry)> = Vec::new();
+        while let Some((extended_key, indexed_values)) = union.next() {
+            // Each layer decides for itself whether this key is a duplicate.
+            let mut n = extended_key.len();
+            for (i, iv) in indexed_values.iter().enumerate() {
+                let m = self.layers[iv.index].strip_dupe_marker(extended_key).len();
+                if i > 0 && m != n {
+                    let key = String::from_utf8_lossy(extended_key).to_string();
+                    return Err(Error::AmbiguousKey(key));
+                }
+                n = m;
+            }
+            if extended_key[..n] != key[..] {
+                insert_all(&mut builder, &key, &mut values)?;
+                key.clear();
+                key.extend(&extended_key[..n]);
+            }
+            for iv in indexed_values {
+                let value = PackedEntry::from_u32(iv.value as u32);
+                values.push((iv.index, id_maps[iv.index].remap(value)?));
+            }
+        }
+        insert_all(&mut builder, &key, &mut values)?;
+
+        builder.finish()
+    }
+}
+
+/// Inserts all of the `values` for `key`, with lower koshas first.
+fn insert_all(
+    builder: &mut Builder,
+    key: &[u8],
+    values: &mut Vec<(usize, PackedEntry)>,
+) -> Result<()> {
+    if values.is_empty() {
+        return Ok(());
+    }
+    // All keys were inserted as `&str`, so this should never fail.
+    let key = std::str::from_utf8(key).map_err(|_| Error::UnsupportedType)?;
+
+    // Stable, so each kosha's duplicates stay in order.
+    values.sort_by_key(|(i, _)| *i);
+    let unique = dedup(values.drain(..).map(|(_, v)| v).collect());
+    for value in unique {
+        builder.insert_packed(key, &value)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::entries::*;
+    use tempfile::tempdir;
+    use vidyut_prakriya::args as vp;
+    use vidyut_prakriya::args::{Pratipadika, Slp1String};
+
+    type TestResult = Result<()>;
+
+    fn safe(s: &str) -> Slp1String {
+        Slp1String::from(s).expect("ok")
+    }
+
+    /// Builds a kosha at `path` with the given subantas, which must be sorted by key.
+    fn build(path: &Path, words: &[(&str, &PratipadikaEntry, vp::Vibhakti)]) -> Result<Kosha> {
+        let mut builder = Builder::new(path)?;
+        for (key, entry, vibhakti) in words {
+            builder.register_pratipadika_entry(entry);
+            let subanta =
+                SubantaEntry::new((*entry).clone(), vp::Linga::Pum, *vibhakti, vp::Vacana::Eka);
+            builder.insert(key, &subanta.into())?;
+        }
+        builder.finish()?;
+        Kosha::new(path)
+    }
+
+    #[test]
+    fn stack_and_merge() -> TestResult {
+        use vp::Vibhakti as Vi;
+
+        let pratipadikas: Vec<Pratipadika> = ["deva", "nara", "vAnara"]
+            .iter()
+            .map(|x| Pratipadika::basic(safe(x)))
+            .collect();
+        let entries: Vec<PratipadikaEntry> =
+            pratipadikas.iter().map(|x| x.try_into().unwrap()).collect();
+        let (deva, nara, vanara) = (&entries[0], &entries[1], &entries[2]);
+
+        let dir = tempdir()?;
+        let base = build(
+            &dir.path().join("base"),
+            &[
+                ("devas", deva, Vi::Prathama),
+                ("naras", nara, Vi::Prathama),
+                ("naras", nara, Vi::Sambodhana),
+            ],
+        )?;
+        let overlay = build(
+            &dir.path().join("overlay"),
+            &[
+                ("naras", nara, Vi::Prathama),
+                ("vAnaram", vanara, Vi::Dvitiya),
+                ("vAnaras", vanara, Vi::Prathama),
+            ],
+        )?;
+        let stack = KoshaStack::new(vec![base, overlay]);
+
+        assert_eq!(stack.len(), 6);
+        assert!(stack.contains_key("devas"));
+        assert!(stack.contains_key("vAnaram"));
+        assert!(!stack.contains_key("vAnara"));
+        assert_eq!(stack.dhatus().count(), 0);
+        assert_eq!(stack.pratipadikas().count(), 3);
+
+        // Entries in more than one kosha are returned once.
+        let naras = stack.get_all("naras");
+        assert_eq!(naras.len(), 2);
+        let keys = |forms: Vec<(String, PadaEntry)>| -> Vec<String> {
+            forms.into_iter().map(|(k, _)| k).collect()
+        };
+        assert_eq!(
+            keys(stack.prefix_search("")?),
+            ["devas", "naras", "naras", "vAnaram", "vAnaras"]
+        );
+        assert_eq!(keys(stack.pratipadika_forms(nara)?), ["naras", "naras"]);
+        assert_eq!(
+            keys(stack.pratipadika_forms(vanara)?),
+            ["vAnaram", "vAnaras"]
+        );
+        assert_eq!(keys(stack.lemma_forms("vAnara")?), ["vAnaram", "vAnaras"]);
+
+        // IDs are consistent across the stack.
+        for entry in stack.get_all("vAnaras").iter().chain(&naras) {
+            let packed = stack.pack(entry)?;
+            assert_eq!(&stack.unpack(packed)?, entry);
+        }
+        // `nara` is in both koshas, so it uses its ID from the base.
+        let base_packed = stack.layers()[0].pack(&naras[0])?;
+        assert_eq!(stack.pack(&naras[0])?, base_packed);
+
+        // Merge
+        let merged_path = dir.path().join("merged");
+        stack.merge(&merged_path)?;
+        let merged = Kosha::new(&merged_path)?;
+        assert_eq!(merged.len(), 5);
+        assert_eq!(merged.pratipadikas().count(), 3);
+        for key in ["devas", "naras", "vAnaram", "vAnaras", "vAnara"] {
+            assert_eq!(merged.get_all(key), stack.get_all(key), "{key}");
+        }
+        assert_eq!(
+            keys(merged.lemma_forms("nara")?),
+            keys(stack.lemma_forms("nara")?)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_all_ignores_metadata() -> TestResult {
+        let deva = Pratipadika::basic(safe("deva"));
+        let deva: PratipadikaEntry = (&deva).try_into().unwrap();
+        let devas: PadaEntry = SubantaEntry::new(
+            deva.clone(),
+            vp::Linga::Pum,
+            vp::Vibhakti::Prathama,
+            vp::Vacana::Eka,
+        )
+        .into();
+
+        let dir = tempdir()?;
+        let mut layers = Vec::new();
+        for (name, gloss) in [("base", "god"), ("overlay", "deity")] {
+            let path = dir.path().join(name);
+            let mut builder = Builder::new(&path)?;
+            builder.register_pratipadika_entry(&deva);
+            builder.set_pratipadika_metadata(&deva, Metadata::new().with("gloss", gloss))?;
+            builder.insert("devas", &devas)?;
+            builder.finish()?;
+            layers.push(Kosha::new(&path)?);
+        }
+        let stack = KoshaStack::new(layers);
+
+        // Both koshas have the same entry, so we return it once with the base's metadata.
+        let results = stack.get_all("devas");
+        assert_eq!(results, [devas]);
+        let PadaEntry::Subanta(s) = &results[0] else {
+            panic!("not a subanta");
+        };
+        assert_eq!(
+            s.pratipadika_entry()
+                .metadata()
+                .and_then(|m| m.get_text("gloss")),
+            Some("god")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge_keys_with_low_bytes() -> TestResult {
+        use vp::Vibhakti as Vi;
+
+        let deva = Pratipadika::basic(safe("deva"));
+        let deva: PratipadikaEntry = (&deva).try_into().unwrap();
+
+        // `'` and `/` are below `DUPES_PER_BYTE`, but these keys are not duplicates.
+        let dir = tempdir()?;
+        let base = build(
+            &dir.path().join("base"),
+            &[
+                ("deva", &deva, Vi::Prathama),
+                ("deva", &deva, Vi::Sambodhana),
+                ("deva'", &deva, Vi::Prathama),
+            ],
+        )?;
+        let overlay = build(
+            &dir.path().join("overlay"),
+            &[("deva", &deva, Vi::Dvitiya), ("deva/", &deva, Vi::Prathama)],
+        )?;
+        let stack = KoshaStack::new(vec![base, overlay]);
+
+        let merged_path = dir.path().join("merged");
+        stack.merge(&merged_path)?;
+        let merged = Kosha::new(&merged_path)?;
+        assert_eq!(merged.len(), 5);
+        for key in ["deva", "deva'", "deva/"] {
+            assert_eq!(merged.get_all(key), stack.get_all(key), "{key}");
+        }
+        assert_eq!(merged.get_all("deva").len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn merge_paradigms() -> TestResult {
+        use vp::{Linga as L, Vacana as V, Vibhakti as Vi};
+
+        let deva = Pratipadika::basic(safe("deva"));
+        let deva_entry: PratipadikaEntry = (&deva).try_into().unwrap();
+        let rama = Pratipadika::basic(safe("rAma"));
+        let rama_entry: PratipadikaEntry = (&rama).try_into().unwrap();
+
+        let dir = tempdir()?;
+        let mut layers = Vec::new();
+        for (name, entry, stem) in [("a", &deva_entry, "dev"), ("b", &rama_entry, "rAm")] {
+            let path = dir.path().join(name);
+            let mut builder = Builder::new(&path)?;
+            // Register a throwaway paradigm first so that paradigm IDs differ across koshas.
+            if name == "b" {
+                builder.register_subanta_paradigm(
+                    entry,
+                    &[("rAmAt".to_string(), L::Pum, Vi::Panchami, V::Eka)],
+                )?;
+            }
+            let (key, value) = builder.register_subanta_paradigm(
+                entry,
+                &[
+                    (format!("{stem}as"), L::Pum, Vi::Prathama, V::Eka),
+                    (format!("{stem}am"), L::Pum, Vi::Dvitiya, V::Eka),
+                ],
+            )?;
+            builder.insert_packed(&key, &value)?;
+            if name == "b" {
+                // Metadata keys use IDs that change when we merge.
+                let rama_2s = SubantaEntry::new(entry.clone(), L::Pum, Vi::Dvitiya, V::Eka);
+                builder.set_pratipadika_metadata(entry, Metadata::new().with("gloss", "Rama"))?;
+                builder.set_pada_metadata(
+                    &rama_2s.clone().into(),
+                    Metadata::new().with("frequency", 3),
+                )?;
+                builder.set_pada_weight(&rama_2s.into(), 3.0)?;
+            }
+            builder.finish()?;
+            layers.push(Kosha::new(&path)?);
+        }
+        let stack = KoshaStack::new(layers);
+
+        let merged_path = dir.path().join("merged");
+        stack.merge(&merged_path)?;
+        let merged = Kosha::new(&merged_path)?;
+        assert_eq!(merged.len(), 4);
+        for key in ["devas", "devam", "rAmas", "rAmam"] {
+            assert_eq!(merged.get_all(key), stack.get_all(key), "{key}");
+            assert_eq!(merged.get_all(key).len(), 1);
+        }
+        match &merged.get_all("rAmam")[0] {
+            PadaEntry::Subanta(s) => {
+                let pratipadika = s.pratipadika_entry().metadata().unwrap();
+                assert_eq!(pratipadika.get_text("gloss"), Some("Rama"));
+                assert_eq!(s.metadata().unwrap().get_int("frequency"), Some(3));
+            }
+            _ => panic!("expected subanta"),
+        }
+        assert!(merged.get_all("devam")[0].metadata().is_none());
+        assert_eq!(merged.get_all_ranked("rAmam")[0].1, Some(3.0));
+        assert_eq!(stack.get_all_ranked("rAmam")[0].1, Some(3.0));
+
+        Ok(())
+    }
+}
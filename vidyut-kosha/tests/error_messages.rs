@@ -1,7 +1,10 @@
-use std::fs::File;
+use std::fs::{self, File};
+use std::path::Path;
 use tempfile::{tempdir, NamedTempFile};
+use vidyut_kosha::entries::{PratipadikaEntry, SubantaEntry};
 use vidyut_kosha::packing::PackedEntry;
 use vidyut_kosha::{Builder, Error, Kosha};
+use vidyut_prakriya::args::{Pratipadika, Slp1String};
 
 fn assert_is_fst_error<T>(ret: Result<T, Error>) {
     if let Err(e) = ret {
@@ -15,6 +18,20 @@ fn assert_is_fst_error<T>(ret: Result<T, Error>) {
     }
 }
 
+/// Builds a simple kosha with the given `keys`, which must be sorted.
+fn build_kosha(path: &Path, keys: &[&str]) {
+    let ca = Pratipadika::basic(Slp1String::from("ca").unwrap());
+    let entry = PratipadikaEntry::try_from(&ca).unwrap();
+
+    let mut builder = Builder::new(path).unwrap();
+    builder.register_pratipadika_entry(&entry);
+    for key in keys {
+        let pada = SubantaEntry::avyaya(entry.clone()).into();
+        builder.insert(key, &pada).unwrap();
+    }
+    builder.finish().unwrap();
+}
+
 #[test]
 fn build_with_existing_file() {
     let file = NamedTempFile::new().unwrap();
@@ -89,3 +106,116 @@ fn read_invalid_fst_file() {
     let k = Kosha::new(dir.path());
     assert_is_fst_error(k);
 }
+
+#[test]
+fn read_unsupported_format() {
+    let dir = tempdir().unwrap();
+    build_kosha(dir.path(), &["ca"]);
+
+    // A manifest from some future version of the crate.
+    let mut manifest = std::collections::BTreeMap::new();
+    manifest.insert("format_version", u32::MAX);
+    let bytes = rmp_serde::to_vec_named(&manifest).unwrap();
+    fs::write(dir.path().join("manifest.msgpack"), bytes).unwrap();
+
+    match Kosha::new(dir.path()) {
+        Err(Error::UnsupportedFormat(v)) => assert_eq!(v, u32::MAX),
+        k => panic!("Failed: {:?}", k.err()),
+    }
+}
+
+#[test]
+fn read_corrupt_file() {
+    let dir = tempdir().unwrap();
+    build_kosha(dir.path(), &["ca"]);
+
+    let lemmas = dir.path().join("lemmas.msgpack");
+    let mut bytes = fs::read(&lemmas).unwrap();
+    bytes.push(0);
+    fs::write(&lemmas, bytes).unwrap();
+
+    // We check checksums only on request.
+    let kosha = Kosha::new(dir.path()).unwrap();
+    match kosha.verify() {
+        Err(Error::ChecksumMismatch(name)) => assert_eq!(name, "lemmas.msgpack"),
+        k => panic!("Failed: {:?}", k.err()),
+    }
+}
+
+#[test]
+fn verify_kosha() {
+    let dir = tempdir().unwrap();
+    build_kosha(dir.path(), &["ca"]);
+
+    let kosha = Kosha::new(dir.path()).unwrap();
+    kosha.verify().unwrap();
+}
+
+#[test]
+fn read_incompatible_version() {
+    let dir = tempdir().unwrap();
+    build_kosha(dir.path(), &["ca"]);
+
+    // Change the version in the manifest to one from a much older crate. Each version is a short
+    // MessagePack string, which starts with `0xa0` plus its length.
+    let path = dir.path().join("manifest.msgpack");
+    let bytes = fs::read(&path).unwrap();
+    let field = |v: &str| {
+        [
+            b"prakriya_version".as_slice(),
+            &[0xa0 + v.len() as u8],
+            v.as_bytes(),
+        ]
+        .concat()
+    };
+    let old = field(vidyut_prakriya::VERSION);
+    let new = field("0.0.1");
+    let i = bytes.windows(old.len()).position(|w| w == old).unwrap();
+    let bytes = [&bytes[..i], &new, &bytes[i + old.len()..]].concat();
+    fs::write(&path, bytes).unwrap();
+
+    match Kosha::new(dir.path()) {
+        Err(Error::VersionMismatch(name, v)) => {
+            assert_eq!((name, v.as_str()), ("vidyut-prakriya", "0.0.1"));
+        }
+        k => panic!("Failed: {:?}", k.err()),
+    }
+}
+
+#[test]
+fn read_mismatched_files() {
+    let dir = tempdir().unwrap();
+    let a = dir.path().join("a");
+    let b = dir.path().join("b");
+    build_kosha(&a, &["ca"]);
+    build_kosha(&b, &["ca", "cet"]);
+
+    // Mix files from two different koshas.
+    fs::copy(b.join("padas.fst"), a.join("padas.fst")).unwrap();
+
+    match Kosha::new(&a) {
+        Err(Error::CountMismatch(name, expected, actual)) => {
+            assert_eq!((name, expected, actual), ("keys", 1, 2));
+        }
+        k => panic!("Failed: {:?}", k.err()),
+    }
+
+    // SAFETY: we don't modify the kosha while it is open.
+    match unsafe { Kosha::mmap(&a) } {
+        Err(Error::CountMismatch(name, expected, actual)) => {
+            assert_eq!((name, expected, actual), ("keys", 1, 2));
+        }
+        k => panic!("Failed: {:?}", k.err()),
+    }
+}
+
+#[test]
+fn read_kosha_without_manifest() {
+    let dir = tempdir().unwrap();
+    build_kosha(dir.path(), &["ca"]);
+
+    // Koshas created before we added manifests are still readable.
+    fs::remove_file(dir.path().join("manifest.msgpack")).unwrap();
+    let kosha = Kosha::new(dir.path()).unwrap();
+    assert_eq!(kosha.len(), 1);
+}
@@ -252,6 +252,19 @@ OVERRIDES = {
         C.COMBINING_DIGIT_2: None,
         C.COMBINING_DIGIT_3: None,
     },
+    "IAST": {
+        # The grave accent composes with vowels under NFC (a + \u0300 = \u00e0), so
+        # it cannot survive a round trip. Leave this accent unmapped.
+        "\u1ce1": None,
+    },
+    "ISO": {
+        # Vedic accents, per ISO 15919: vertical line above and macron below.
+        C.SVARITA: "\u030d",
+        C.ANUDATTA: "\u0331",
+        # The grave accent composes with vowels under NFC (a + \u0300 = \u00e0), so
+        # it cannot survive a round trip. Leave this accent unmapped.
+        "\u1ce1": None,
+    },
     "GRANTHA": {
         # vowel sign AU
         C.SIGN_AU: "\U0001134c",
@@ -451,6 +464,34 @@ OVERRIDES = {
 }
 
 
+# Vedic accents that we support consistently across schemes. Every Brahmic
+# scheme defines all of these, and Roman schemes define those that fit their
+# conventions. For the list of accents, see `VedicAccent` in `scheme.rs`.
+VEDIC_ACCENTS = [
+    C.SVARITA,
+    C.ANUDATTA,
+    C.DIRGHA_SVARITA,
+    C.ATHARVAVEDA_INDEPENDENT_SVARITA,
+    C.PRENKHA,
+    C.VEDIC_DOT_BELOW,
+]
+
+# Brahmic scripts with their own accent marks. All other Brahmic scripts reuse
+# the Devanagari marks, which Unicode intends to be shared across scripts.
+BRAHMIC_ACCENTS = {
+    "GRANTHA": {
+        # Svarita (use chandra symbol)
+        C.SVARITA: "\u1cf4",
+        # Dirgha svarita (use Devanagari svarita)
+        C.DIRGHA_SVARITA: "\u0951",
+    },
+    "GURMUKHI": {
+        # Udaat
+        C.SVARITA: "\u0a51",
+    },
+}
+
+
 # Additional characters not present in common_map (or deleted in OVERRIDES)
 EXTENSIONS = {
     "ASSAMESE": [
@@ -486,9 +527,6 @@ EXTENSIONS = {
         ("\u094b", "\u093e\u094e"),
         (C.SIGN_AU, "\u094b\u094e"),
 
-        (C.PRENKHA, C.PRENKHA),
-        (C.DIRGHA_SVARITA, C.DIRGHA_SVARITA),
-        (C.VEDIC_DOT_BELOW, C.VEDIC_DOT_BELOW),
         (C.COMBINING_DIGIT_0, C.COMBINING_DIGIT_0),
         (C.DOUBLE_CANDRABINDU_VIRAMA, C.DOUBLE_CANDRABINDU_VIRAMA),
         # Punctuation
@@ -535,6 +573,14 @@ EXTENSIONS = {
     ],
     "IAST": [
         (C.CANDRABINDU, "\u0303"),
+        # Vedic accents. For output, keep the Devanagari dirgha svarita as-is.
+        # On input, also accept the ISO 15919 marks: vertical line above for
+        # svarita, macron below for anudatta, and double vertical line above
+        # for dirgha svarita.
+        (C.DIRGHA_SVARITA, C.DIRGHA_SVARITA),
+        (C.DIRGHA_SVARITA, "\u030e"),
+        (C.SVARITA, "\u030d"),
+        (C.ANUDATTA, "\u0331"),
     ],
     "ITRANS": [
         # Vedic anusvara (just render as candrabindu)
@@ -556,6 +602,12 @@ EXTENSIONS = {
         (C.DA + C.VIRAMA + C.HA, "d:h"),
         (C.PA + C.VIRAMA + C.HA, "p:h"),
         (C.BA + C.VIRAMA + C.HA, "b:h"),
+        # Vedic accents, per ISO 15919: double vertical line above for dirgha
+        # svarita. On input, also accept the Devanagari dirgha svarita and the
+        # circumflex below that we used for svarita before.
+        (C.DIRGHA_SVARITA, "\u030e"),
+        (C.DIRGHA_SVARITA, C.DIRGHA_SVARITA),
+        (C.SVARITA, "\u032d"),
     ],
     "KANNADA": [
         (C.JIHVAMULIYA, "\u0cf1"),
@@ -748,17 +800,6 @@ def main():
         "",
     ]
 
-    BRAHMIC_WITH_DEVA_ACCENTS = {
-        "ASSAMESE",
-        "BENGALI",
-        "GUJARATI",
-        "KANNADA",
-        "MALAYALAM",
-        "ORIYA",
-        "TELUGU",
-        "SHARADA",
-    }
-
     for path in sorted(glob("/Users/arun/temp/common_maps/**/*.toml")):
         with open(path, "rb") as f:
             data = tomllib.load(f)
@@ -782,10 +823,6 @@ def main():
                 # TODO: support these
                 continue
 
-            if category == "accents":
-                if scheme_name in BRAHMIC_WITH_DEVA_ACCENTS or scheme_name == "GRANTHA":
-                    continue
-
             if category.endswith("alternates"):
                 for raw_main, alts in data[category].items():
                     deva = raw_to_deva.get(raw_main)
@@ -809,6 +846,9 @@ def main():
                     deva = unicodedata.normalize("NFC", _sanitize(deva))
                     assert isinstance(deva, str)
                     assert isinstance(raw, str)
+                    if scheme_type == "brahmic" and deva in VEDIC_ACCENTS:
+                        # Added below.
+                        continue
                     raw = _maybe_override(scheme_name, deva, raw)
                     if raw is not None:
                         raw_to_deva[raw] = deva
@@ -825,26 +865,10 @@ def main():
                             assert isinstance(raw, str)
                             scheme_items.append((mark, raw))
 
-        # Add svarita and anudatta for Brahmic scripts that use Devanagari accent marks.
-        if scheme_name in BRAHMIC_WITH_DEVA_ACCENTS:
-            scheme_items.extend(
-                [
-                    (C.SVARITA, "\u0951"),
-                    (C.ANUDATTA, "\u0952"),
-                    (C.DIRGHA_SVARITA, C.DIRGHA_SVARITA),
-                ]
-            )
-        elif scheme_name == "GRANTHA":
-            scheme_items.extend(
-                [
-                    # Svarita (use chandra symbol)
-                    (C.SVARITA, "\u1cf4"),
-                    # Dirgha svarita (use Devanagari svarita)
-                    (C.DIRGHA_SVARITA, "\u0951"),
-                    # Anudatta (use Devanagari)
-                    (C.ANUDATTA, "\u0952"),
-                ]
-            )
+        if scheme_type == "brahmic":
+            own_accents = BRAHMIC_ACCENTS.get(scheme_name, {})
+            for accent in VEDIC_ACCENTS:
+                scheme_items.append((accent, own_accents.get(accent, accent)))
 
         scheme_items.extend(EXTENSIONS.get(scheme_name, []))
 
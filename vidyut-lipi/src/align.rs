@@ -0,0 +1,244 @@
+//! Utilities for tracking which parts of the input produced which parts of the output.
+
+use std::ops::Range;
+
+/// Maps a span of the input string to the span of the output string that it produced.
+///
+/// Both spans are byte ranges. Spans are as small as our transliteration logic allows, which
+/// usually means a single token or character. If a scheme reorders or merges characters, such as
+/// a consonant and its vowel sign, then the span covers the whole group.
+///
+/// Either span might be empty. For example, a character that we delete during transliteration has
+/// an empty `target`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Alignment {
+    /// A byte range in the input string.
+    pub source: Range<usize>,
+    /// The byte range in the output string that `source` produced.
+    pub target: Range<usize>,
+}
+
+impl Alignment {
+    fn new(source: Range<usize>, target: Range<usize>) -> Self {
+        Self { source, target }
+    }
+}
+
+/// Records alignments for a single stage of transliteration.
+///
+/// A stage reads its input from left to right and appends to its output. Occasionally, a stage
+/// will also remove some text from the end of its output before appending new text, as when we
+/// pop an implicit vowel after seeing a vowel sign. `Tracker` handles this case by merging the
+/// affected alignments into a single alignment.
+///
+/// The alignments we produce are contiguous and cover the entire input and output.
+///
+/// Tracking is optional, and a disabled `Tracker` does no work.
+pub(crate) struct Tracker {
+    alignments: Option<Vec<Alignment>>,
+}
+
+impl Tracker {
+    /// Creates a new tracker. If `enabled` is false, the tracker ignores all calls to `push`.
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            alignments: if enabled { Some(Vec::new()) } else { None },
+        }
+    }
+
+    /// Returns whether this tracker records alignments.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.alignments.is_some()
+    }
+
+    /// Records that all input up to `source_end` has produced all output up to `target_end`.
+    ///
+    /// `low` is the smallest length of the output since the last call to `push`. If `low` is less
+    /// than the length at the last call, then we merge this alignment with the previous ones.
+    pub(crate) fn push(&mut self, source_end: usize, low: usize, target_end: usize) {
+        if let Some(alignments) = &mut self.alignments {
+            while alignments.last().is_some_and(|a| a.target.end > low) {
+                alignments.pop();
+            }
+            let (source_start, target_start) = alignments
+                .last()
+                .map_or((0, 0), |a| (a.source.end, a.target.end));
+            alignments.push(Alignment::new(
+                source_start..source_end,
+                target_start..target_end,
+            ));
+        }
+    }
+
+    /// Records that the input `text` has been copied to the output as-is.
+    pub(crate) fn push_copy(&mut self, text: &str) {
+        if self.alignments.is_some() {
+            let (mut source_end, mut target_end) = self.ends();
+            for c in text.chars() {
+                source_end += c.len_utf8();
+                target_end += c.len_utf8();
+                self.push(source_end, target_end, target_end);
+            }
+        }
+    }
+
+    /// Returns the ends of the input and output covered so far.
+    pub(crate) fn ends(&self) -> (usize, usize) {
+        self.alignments
+            .as_ref()
+            .and_then(|xs| xs.last())
+            .map_or((0, 0), |a| (a.source.end, a.target.end))
+    }
+
+    /// Returns the recorded alignments, or `None` if this tracker is disabled.
+    pub(crate) fn finish(self) -> Option<Vec<Alignment>> {
+        self.alignments
+    }
+}
+
+/// Combines two alignments `first` (from A to B) and `second` (from B to C) into a single
+/// alignment from A to C.
+///
+/// Both arguments must be contiguous and must agree on the length of B.
+pub(crate) fn compose(first: &[Alignment], second: &[Alignment]) -> Vec<Alignment> {
+    let mut ret = Vec::new();
+    let mut i = 0;
+    let mut j = 0;
+    // Our current position in A, B, and C, respectively.
+    let mut a = 0;
+    let mut b = 0;
+    let mut c = 0;
+
+    while i < first.len() || j < second.len() {
+        let mut a_end = a;
+        let mut c_end = c;
+        // The end of the current group in B, as seen by `first` and `second` respectively.
+        let mut b_first = b;
+        let mut b_second = b;
+
+        if let Some(x) = first.get(i) {
+            a_end = x.source.end;
+            b_first = x.target.end;
+            i += 1;
+        }
+        if let Some(y) = second.get(j) {
+            b_second = y.source.end;
+            c_end = y.target.end;
+            j += 1;
+        }
+
+        // Extend the group until both alignments agree on where it ends in B.
+        while b_first != b_second {
+            if b_first < b_second {
+                match first.get(i) {
+                    Some(x) => {
+                        a_end = x.source.end;
+                        b_first = x.target.end;
+                        i += 1;
+                    }
+                    None => break,
+                }
+            } else {
+                match second.get(j) {
+                    Some(y) => {
+                        b_second = y.source.end;
+                        c_end = y.target.end;
+                        j += 1;
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        if a < a_end || c < c_end {
+            ret.push(Alignment::new(a..a_end, c..c_end));
+        }
+        a = a_end;
+        b = std::cmp::max(b_first, b_second);
+        c = c_end;
+    }
+
+    ret
+}
+
+/// Like `compose`, but for alignments that might not have been recorded.
+///
+/// Returns `None` if either argument is `None`.
+pub(crate) fn compose_opt(
+    first: Option<Vec<Alignment>>,
+    second: Option<Vec<Alignment>>,
+) -> Option<Vec<Alignment>> {
+    Some(compose(&first?, &second?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn al(source: Range<usize>, target: Range<usize>) -> Alignment {
+        Alignment::new(source, target)
+    }
+
+    #[test]
+    fn tracker_push() {
+        let mut t = Tracker::new(true);
+        t.push(1, 0, 2);
+        t.push(2, 2, 3);
+        assert_eq!(t.finish(), Some(vec![al(0..1, 0..2), al(1..2, 2..3)]));
+    }
+
+    #[test]
+    fn tracker_push_after_pop() {
+        let mut t = Tracker::new(true);
+        t.push(1, 0, 2);
+        t.push(2, 2, 3);
+        // Pop one byte from the output, then push two more.
+        t.push(3, 2, 4);
+        assert_eq!(t.finish(), Some(vec![al(0..1, 0..2), al(1..3, 2..4)]));
+    }
+
+    #[test]
+    fn tracker_push_copy() {
+        let mut t = Tracker::new(true);
+        t.push(1, 0, 0);
+        t.push_copy("aक");
+        assert_eq!(
+            t.finish(),
+            Some(vec![al(0..1, 0..0), al(1..2, 0..1), al(2..5, 1..4)])
+        );
+    }
+
+    #[test]
+    fn tracker_disabled() {
+        let mut t = Tracker::new(false);
+        t.push(1, 0, 2);
+        t.push_copy("abc");
+        assert_eq!(t.finish(), None);
+    }
+
+    #[test]
+    fn compose_one_to_one() {
+        let x = vec![al(0..1, 0..2), al(1..2, 2..4)];
+        let y = vec![al(0..2, 0..1), al(2..4, 1..2)];
+        assert_eq!(compose(&x, &y), vec![al(0..1, 0..1), al(1..2, 1..2)]);
+    }
+
+    #[test]
+    fn compose_with_merge() {
+        // `first` splits B as [0, 1, 2], but `second` treats B[0..2] as a single unit.
+        let x = vec![al(0..1, 0..1), al(1..2, 1..2)];
+        let y = vec![al(0..2, 0..3)];
+        assert_eq!(compose(&x, &y), vec![al(0..2, 0..3)]);
+    }
+
+    #[test]
+    fn compose_with_empty_spans() {
+        // `first` deletes A[1..2], and `second` inserts C[1..2].
+        let x = vec![al(0..1, 0..1), al(1..2, 1..1), al(2..3, 1..2)];
+        let y = vec![al(0..1, 0..1), al(1..1, 1..2), al(1..2, 2..3)];
+        assert_eq!(
+            compose(&x, &y),
+            vec![al(0..1, 0..1), al(1..2, 1..2), al(2..3, 2..3)]
+        );
+    }
+}
@@ -226,8 +226,6 @@ pub const AHOM: &[(&str, &str)] = &[
     (DOUBLE_DANDA, "𑜽"),
     (ZERO_WIDTH_JOINER, "‍"),
     ("", ""),
-    (SVARITA, "॑"),
-    (ANUDATTA, "॒"),
     (SIGN_CANDRA_E, "𑜦"),
     (QA, "𑜀"),
     (KHHA, "𑜁"),
@@ -238,6 +236,12 @@ pub const AHOM: &[(&str, &str)] = &[
     (FA, "𑜇"),
     (YYA, "𑜊"),
     (RRA, "𑜍"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "𑜒𑜦"),
     (O, "𑜒𑜨"),
     (SIGN_E, "𑜦"),
@@ -342,6 +346,9 @@ pub const ASSAMESE: &[(&str, &str)] = &[
     (SVARITA, "॑"),
     (ANUDATTA, "॒"),
     (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (CANDRABINDU_VIRAMA, "ৼ"),
     (ABBREVIATION_SIGN, "৽"),
     (E, "ऎ"),
@@ -434,8 +441,6 @@ pub const BALINESE: &[(&str, &str)] = &[
     (DOUBLE_DANDA, "᭟"),
     (ZERO_WIDTH_JOINER, "‍"),
     ("", ""),
-    (SVARITA, "॑"),
-    (ANUDATTA, "॒"),
     (SIGN_CANDRA_E, "ᬾ"),
     (QA, "ᬓ᬴"),
     (KHHA, "ᬔ᬴"),
@@ -446,6 +451,12 @@ pub const BALINESE: &[(&str, &str)] = &[
     (FA, "ᬧ᬴"),
     (YYA, "ᬬ᬴"),
     (RRA, "ᬭ᬴"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "ᬏ"),
     (O, "ᬑ"),
     (SIGN_E, "ᬾ"),
@@ -550,6 +561,9 @@ pub const BENGALI: &[(&str, &str)] = &[
     (SVARITA, "॑"),
     (ANUDATTA, "॒"),
     (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (CANDRABINDU_VIRAMA, "ৼ"),
     (ABBREVIATION_SIGN, "৽"),
     (E, "ऎ"),
@@ -642,8 +656,6 @@ pub const BHAIKSUKI: &[(&str, &str)] = &[
     (DOUBLE_DANDA, "𑱂"),
     (ZERO_WIDTH_JOINER, "‍"),
     ("", ""),
-    (SVARITA, "॑"),
-    (ANUDATTA, "॒"),
     (SIGN_CANDRA_E, "𑰸"),
     (QA, "𑰎"),
     (KHHA, "𑰏"),
@@ -654,6 +666,12 @@ pub const BHAIKSUKI: &[(&str, &str)] = &[
     (FA, "𑰣"),
     (YYA, "𑰧"),
     (RRA, "𑰨"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "𑰊"),
     (O, "𑰌"),
     (SIGN_E, "𑰸"),
@@ -744,8 +762,6 @@ pub const BRAHMI: &[(&str, &str)] = &[
     (DOUBLE_DANDA, "𑁈"),
     (ZERO_WIDTH_JOINER, "‍"),
     ("", ""),
-    (SVARITA, "॑"),
-    (ANUDATTA, "॒"),
     (SIGN_CANDRA_E, "𑁂"),
     (QA, "𑀓"),
     (KHHA, "𑀔"),
@@ -756,6 +772,12 @@ pub const BRAHMI: &[(&str, &str)] = &[
     (FA, "𑀨"),
     (YYA, "𑀬"),
     (RRA, "𑀭"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (JIHVAMULIYA, "𑀃"),
     (UPADHMANIYA, "𑀄"),
     (E, "𑀏𑁆"),
@@ -848,8 +870,6 @@ pub const BURMESE: &[(&str, &str)] = &[
     (DOUBLE_DANDA, "။"),
     (ZERO_WIDTH_JOINER, "‍"),
     ("", ""),
-    (SVARITA, "॑"),
-    (ANUDATTA, "॒"),
     (SIGN_CANDRA_E, "ေ"),
     (QA, "က"),
     (KHHA, "ခ"),
@@ -860,6 +880,12 @@ pub const BURMESE: &[(&str, &str)] = &[
     (FA, "ဖ"),
     (YYA, "ယ"),
     (RRA, "ရ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "ဧ"),
     (O, "ဩ"),
     (SIGN_E, "ေ"),
@@ -950,8 +976,6 @@ pub const CHAM: &[(&str, &str)] = &[
     (DOUBLE_DANDA, "꩞"),
     (ZERO_WIDTH_JOINER, "‍"),
     ("", ""),
-    (SVARITA, "॑"),
-    (ANUDATTA, "॒"),
     (SIGN_CANDRA_E, "ꨯꨮ"),
     (QA, "ꨆ"),
     (KHHA, "ꨇ"),
@@ -962,6 +986,12 @@ pub const CHAM: &[(&str, &str)] = &[
     (FA, "ꨜ"),
     (YYA, "ꨥ"),
     (RRA, "ꨣ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "ꨃ"),
     (O, "ꨅ"),
     (SIGN_E, "ꨯꨮ"),
@@ -1060,11 +1090,6 @@ pub const DEVANAGARI: &[(&str, &str)] = &[
     (ZERO_WIDTH_JOINER, "‍"),
     (ZERO_WIDTH_NON_JOINER, "‌"),
     ("", ""),
-    (SVARITA, "॑"),
-    (DIRGHA_SVARITA, "᳚"),
-    (ANUDATTA, "॒"),
-    (PRENKHA, "᳒"),
-    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
     (COMBINING_DIGIT_1, "꣡"),
     (COMBINING_DIGIT_2, "꣢"),
     (COMBINING_DIGIT_3, "꣣"),
@@ -1082,7 +1107,6 @@ pub const DEVANAGARI: &[(&str, &str)] = &[
     (COMBINING_RA, "꣯"),
     (COMBINING_VI, "꣰"),
     (COMBINING_AVAGRAHA, "꣱"),
-    (VEDIC_DOT_BELOW, "᳝"),
     (VEDIC_RING_ABOVE, "᳸"),
     (QA, "क़"),
     (KHHA, "ख़"),
@@ -1095,6 +1119,12 @@ pub const DEVANAGARI: &[(&str, &str)] = &[
     (RRA, "ऱ"),
     (LLLA, "ऴ"),
     (NNNA, "ऩ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (SIGN_AI, "ेॎ"),
     (SIGN_OO, "ाॎ"),
     (SIGN_AU, "ोॎ"),
@@ -1192,8 +1222,6 @@ pub const DOGRA: &[(&str, &str)] = &[
     (DOUBLE_DANDA, "॥"),
     (ZERO_WIDTH_JOINER, "‍"),
     ("", ""),
-    (SVARITA, "॑"),
-    (ANUDATTA, "॒"),
     (SIGN_CANDRA_E, "𑠳"),
     (QA, "𑠊𑠺"),
     (KHHA, "𑠋𑠺"),
@@ -1204,6 +1232,12 @@ pub const DOGRA: &[(&str, &str)] = &[
     (FA, "𑠟𑠺"),
     (YYA, "𑠣𑠺"),
     (RRA, "𑠤𑠺"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (ABBREVIATION_SIGN, "𑠻"),
     (E, "𑠆"),
     (O, "𑠈"),
@@ -1295,8 +1329,6 @@ pub const GUNJALA_GONDI: &[(&str, &str)] = &[
     (DOUBLE_DANDA, ".."),
     (ZERO_WIDTH_JOINER, "‍"),
     ("", ""),
-    (SVARITA, "॑"),
-    (ANUDATTA, "॒"),
     (SIGN_CANDRA_E, "𑶐"),
     (QA, "𑵱"),
     (KHHA, "𑵲"),
@@ -1307,6 +1339,12 @@ pub const GUNJALA_GONDI: &[(&str, &str)] = &[
     (FA, "𑶆"),
     (YYA, "𑵬"),
     (RRA, "𑶈"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "𑵧"),
     (O, "𑵪"),
     (SIGN_E, "𑶐"),
@@ -1397,8 +1435,6 @@ pub const MASARAM_GONDI: &[(&str, &str)] = &[
     (DOUBLE_DANDA, "॥"),
     (ZERO_WIDTH_JOINER, "‍"),
     ("", ""),
-    (SVARITA, "॑"),
-    (ANUDATTA, "॒"),
     (SIGN_CANDRA_E, "𑴺"),
     (QA, "𑴌𑵂"),
     (KHHA, "𑴍𑵂"),
@@ -1409,6 +1445,12 @@ pub const MASARAM_GONDI: &[(&str, &str)] = &[
     (FA, "𑴡𑵂"),
     (YYA, "𑴥𑵂"),
     (RRA, "𑴦𑵂"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (TRA, "𑴰"),
     (E, "𑴆"),
     (O, "𑴉"),
@@ -1509,8 +1551,11 @@ pub const GRANTHA: &[(&str, &str)] = &[
     (YYA, "𑌯𑌼"),
     (RRA, "𑌰𑌼"),
     (SVARITA, "᳴"),
-    (DIRGHA_SVARITA, "॑"),
     (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "॑"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (SIGN_AU, "𑍗"),
     (COMBINING_DIGIT_0, "𑍦"),
     (COMBINING_DIGIT_1, "𑍧"),
@@ -1631,6 +1676,9 @@ pub const GUJARATI: &[(&str, &str)] = &[
     (SVARITA, "॑"),
     (ANUDATTA, "॒"),
     (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (ABBREVIATION_SIGN, "૰"),
     (E, "ऎ"),
     (O, "ऒ"),
@@ -1734,6 +1782,10 @@ pub const GURMUKHI: &[(&str, &str)] = &[
     (NNNA, "ਨ਼"),
     (SVARITA, "ੑ"),
     (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (ABBREVIATION_SIGN, "੶"),
     (E, "ਏ"),
     (O, "ਓ"),
@@ -1824,6 +1876,12 @@ pub const JAVANESE: &[(&str, &str)] = &[
     (DANDA, "꧈"),
     (DOUBLE_DANDA, "꧉"),
     (SIGN_CANDRA_E, "ꦼ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, ""),
     (O, ""),
     (SIGN_E, ""),
@@ -1924,6 +1982,12 @@ pub const KAITHI: &[(&str, &str)] = &[
     (RHA, "𑂜"),
     (FA, "𑂤𑂺"),
     (YYA, "𑂨𑂺"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (NUKTA, "𑂺"),
     (RRA, "𑂩𑂺"),
     (ABBREVIATION_SIGN, "𑂻"),
@@ -2032,6 +2096,9 @@ pub const KANNADA: &[(&str, &str)] = &[
     (SVARITA, "॑"),
     (ANUDATTA, "॒"),
     (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "ಎ"),
     (O, "ಒ"),
     (SIGN_E, "ೆ"),
@@ -2125,6 +2192,12 @@ pub const KHAROSHTHI: &[(&str, &str)] = &[
     (SIGN_E, ""),
     (SIGN_O, ""),
     (NGA, "𐨣"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
 ];
 
 pub const KHMER: &[(&str, &str)] = &[
@@ -2210,6 +2283,12 @@ pub const KHMER: &[(&str, &str)] = &[
     (DANDA, "។"),
     (DOUBLE_DANDA, "៕"),
     (SIGN_CANDRA_E, "េ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, ""),
     (O, ""),
     (SIGN_E, ""),
@@ -2299,6 +2378,12 @@ pub const KHUDAWADI: &[(&str, &str)] = &[
     (DANDA, "।"),
     (DOUBLE_DANDA, "॥"),
     (SIGN_CANDRA_E, "𑋥"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (NUKTA, "𑋩"),
     (DDDHA, "𑋊"),
     (RRA, "𑋙𑋩"),
@@ -2391,6 +2476,12 @@ pub const LAO: &[(&str, &str)] = &[
     (DANDA, "।"),
     (DOUBLE_DANDA, "॥"),
     (SIGN_CANDRA_E, "ແະ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, ""),
     (O, ""),
     (SIGN_E, ""),
@@ -2480,6 +2571,12 @@ pub const LEPCHA: &[(&str, &str)] = &[
     (DANDA, "।"),
     (DOUBLE_DANDA, "॥"),
     (SIGN_CANDRA_E, "ᰬ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, ""),
     (O, ""),
     (SIGN_E, ""),
@@ -2569,6 +2666,12 @@ pub const LIMBU: &[(&str, &str)] = &[
     (DANDA, "।"),
     (DOUBLE_DANDA, "॥"),
     (SIGN_CANDRA_E, "ᤧ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "ᤀᤧ"),
     (O, "ᤀᤨ"),
     (SIGN_E, "ᤧ"),
@@ -2670,6 +2773,9 @@ pub const MALAYALAM: &[(&str, &str)] = &[
     (SVARITA, "॑"),
     (ANUDATTA, "॒"),
     (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (SIGN_AU, "ൌ"),
     (CANDRABINDU_VIRAMA, "ഄ"),
     (E, "എ"),
@@ -2761,6 +2867,12 @@ pub const MEETEI_MAYEK: &[(&str, &str)] = &[
     (DANDA, "꯫"),
     (DOUBLE_DANDA, "꯫꯫"),
     (SIGN_CANDRA_E, "ꯦ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, ""),
     (O, ""),
     (SIGN_E, ""),
@@ -2850,6 +2962,12 @@ pub const MODI: &[(&str, &str)] = &[
     (DANDA, "𑙁"),
     (DOUBLE_DANDA, "𑙂"),
     (SIGN_CANDRA_E, "𑙀"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (ABBREVIATION_SIGN, "𑙃"),
     (E, ""),
     (O, ""),
@@ -2940,6 +3058,12 @@ pub const MON: &[(&str, &str)] = &[
     (DANDA, "၊"),
     (DOUBLE_DANDA, "။"),
     (SIGN_CANDRA_E, "ေ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, ""),
     (O, ""),
     (SIGN_E, ""),
@@ -3028,6 +3152,12 @@ pub const NANDINAGARI: &[(&str, &str)] = &[
     (AVAGRAHA, "𑧡"),
     (DANDA, "।"),
     (DOUBLE_DANDA, "॥"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "𑦪"),
     (O, "𑦬"),
     (SIGN_E, "𑧚"),
@@ -3117,6 +3247,12 @@ pub const NEWA: &[(&str, &str)] = &[
     (DANDA, "𑑋"),
     (DOUBLE_DANDA, "𑑌"),
     (SIGN_CANDRA_E, "𑐾"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (JIHVAMULIYA, "𑑠"),
     (UPADHMANIYA, "𑑡"),
     (NUKTA, "𑑆"),
@@ -3221,6 +3357,12 @@ pub const OL_CHIKI: &[(&str, &str)] = &[
     (FA, "ᱯ"),
     (YYA, "ᱭ"),
     (RRA, "ᱨ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "ᱮᱹ"),
     (O, "ᱳ"),
     (SIGN_E, "ᱮᱹ"),
@@ -3325,6 +3467,9 @@ pub const ORIYA: &[(&str, &str)] = &[
     (SVARITA, "॑"),
     (ANUDATTA, "॒"),
     (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "ऎ"),
     (O, "ऒ"),
     (SIGN_E, "ॆ"),
@@ -3425,6 +3570,12 @@ pub const SAURASHTRA: &[(&str, &str)] = &[
     (RHA, "ꢟ"),
     (FA, "ꢧ"),
     (YYA, "ꢫ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "ꢌ"),
     (O, "ꢏ"),
     (SIGN_E, "ꢾ"),
@@ -3529,6 +3680,9 @@ pub const SHARADA: &[(&str, &str)] = &[
     (SVARITA, "॑"),
     (ANUDATTA, "॒"),
     (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (JIHVAMULIYA, "𑇂"),
     (UPADHMANIYA, "𑇃"),
     (ABBREVIATION_SIGN, "𑇇"),
@@ -3631,6 +3785,12 @@ pub const SIDDHAM: &[(&str, &str)] = &[
     (FA, "𑖣𑗀"),
     (YYA, "𑖧𑗀"),
     (RRA, "𑖨𑗀"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "𑖊"),
     (O, "𑖌"),
     (SIGN_E, "𑖸"),
@@ -3719,6 +3879,12 @@ pub const SINHALA: &[(&str, &str)] = &[
     (DANDA, "."),
     (DOUBLE_DANDA, ".."),
     (SIGN_CANDRA_E, "ැ"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (CANDRABINDU, "ං"),
     (E, "එ"),
     (O, "ඔ"),
@@ -3806,6 +3972,12 @@ pub const SOYOMBO: &[(&str, &str)] = &[
     (DIGIT_9, "9"),
     (OM, "𑩐𑩖𑪖"),
     (AVAGRAHA, ""),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (DANDA, "𑪛"),
     (DOUBLE_DANDA, "𑪜"),
     (JIHVAMULIYA, "𑪄"),
@@ -3898,6 +4070,12 @@ pub const TAI_THAM: &[(&str, &str)] = &[
     (AVAGRAHA, "'"),
     (DANDA, "᪨"),
     (DOUBLE_DANDA, "᪩"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "ᩑ"),
     (O, "ᩒ"),
     (SIGN_E, "ᩮ"),
@@ -3996,6 +4174,12 @@ pub const TAKRI: &[(&str, &str)] = &[
     (FA, "𑚟𑚷"),
     (YYA, "𑚣𑚷"),
     (RRA, "𑚤𑚷"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (ABBREVIATION_SIGN, "𑚹"),
     (E, "𑚆"),
     (O, "𑚈"),
@@ -4097,6 +4281,12 @@ pub const TAMIL: &[(&str, &str)] = &[
     (RRA, "ற"),
     (LLLA, "ழ"),
     (NNNA, "ன"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (TAMIL_AYTHAM, "ஃ"),
     (E, "எ"),
     (O, "ஒ"),
@@ -4192,6 +4382,9 @@ pub const TELUGU: &[(&str, &str)] = &[
     (SVARITA, "॑"),
     (ANUDATTA, "॒"),
     (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (NUKTA, "఼"),
     (E, "ఎ"),
     (O, "ఒ"),
@@ -4291,6 +4484,12 @@ pub const THAI: &[(&str, &str)] = &[
     (FA, "ฟ"),
     (YYA, "ย"),
     (RRA, "ร"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "เอะ"),
     (O, "โอะ"),
     (SIGN_E, "เะ"),
@@ -4389,6 +4588,12 @@ pub const TIBETAN: &[(&str, &str)] = &[
     (FA, "ཕ༹"),
     (YYA, "ཡ༹"),
     (RRA, "ར༹"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (JIHVAMULIYA, "ྈ"),
     (UPADHMANIYA, "ྉ"),
     (E, "ཨེ"),
@@ -4489,6 +4694,12 @@ pub const TIRHUTA: &[(&str, &str)] = &[
     (FA, "𑒤𑓃"),
     (YYA, "𑒨𑓃"),
     (RRA, "𑒩𑓃"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (ABBREVIATION_SIGN, "𑓆"),
     (E, "𑒁𑒺"),
     (O, "𑒁𑒽"),
@@ -4588,6 +4799,12 @@ pub const ZANABAZAR_SQUARE: &[(&str, &str)] = &[
     (FA, "𑨟"),
     (YYA, "𑨪"),
     (RRA, "𑨫"),
+    (SVARITA, "॑"),
+    (ANUDATTA, "॒"),
+    (DIRGHA_SVARITA, "᳚"),
+    (ATHARVAVEDA_INDEPENDENT_SVARITA, "᳡"),
+    (PRENKHA, "᳒"),
+    (VEDIC_DOT_BELOW, "᳝"),
     (E, "𑨀𑨄"),
     (O, "𑨀𑨆"),
     (SIGN_E, "𑨄"),
@@ -4865,7 +5082,6 @@ pub const IAST: &[(&str, &str)] = &[
     (VIRAMA, ""),
     (SVARITA, "̭"),
     (ANUDATTA, "॒"),
-    (ATHARVAVEDA_INDEPENDENT_SVARITA, "̀"),
     (COMBINING_DIGIT_1, "́"),
     (COMBINING_DIGIT_2, "²"),
     (COMBINING_DIGIT_3, "³"),
@@ -4952,6 +5168,10 @@ pub const IAST: &[(&str, &str)] = &[
     (SIGN_RR, "r̥̄"),
     (COMBINING_DIGIT_1, "¹"),
     (CANDRABINDU, "̃"),
+    (DIRGHA_SVARITA, "᳚"),
+    (DIRGHA_SVARITA, "̎"),
+    (SVARITA, "̍"),
+    (ANUDATTA, "̱"),
     (E, "è"),
     (O, "ò"),
     (SIGN_E, "è"),
@@ -4994,9 +5214,8 @@ pub const ISO_15919: &[(&str, &str)] = &[
     (CANDRABINDU, "m̐"),
     (VIRAMA, ""),
     (ZERO_WIDTH_JOINER, "{}"),
-    (SVARITA, "̭"),
-    (ANUDATTA, "॒"),
-    (ATHARVAVEDA_INDEPENDENT_SVARITA, "̀"),
+    (SVARITA, "̍"),
+    (ANUDATTA, "̱"),
     (COMBINING_DIGIT_1, "́"),
     (COMBINING_DIGIT_2, "²"),
     (COMBINING_DIGIT_3, "³"),
@@ -5106,6 +5325,9 @@ pub const ISO_15919: &[(&str, &str)] = &[
     ("द्ह", "d:h"),
     ("प्ह", "p:h"),
     ("ब्ह", "b:h"),
+    (DIRGHA_SVARITA, "̎"),
+    (DIRGHA_SVARITA, "᳚"),
+    (SVARITA, "̭"),
     (E, "e"),
     (O, "o"),
     (SIGN_E, "e"),
@@ -1,93 +1,234 @@
+//! Transliterates text from the command line, from files, or from stdin.
 use clap::Parser;
-use vidyut_lipi::{Lipika, Scheme};
-
-#[derive(clap::ValueEnum, Clone, Debug)]
-enum ClapScheme {
-    Balinese,
-    Bengali,
-    Brahmi,
-    Burmese,
-    Devanagari,
-    Gujarati,
-    Grantha,
-    Gurmukhi,
-    Javanese,
-    Kannada,
-    Malayalam,
-    Odia,
-    Sharada,
-    Siddham,
-    Sinhala,
-    Tamil,
-    Telugu,
-    BarahaSouth,
-
-    HarvardKyoto,
-    Iast,
-    Iso15919,
-    Itrans,
-    Slp1,
-    Velthuis,
-    Wx,
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use vidyut_lipi::{detect, transliterate, transliterate_stream, Mapping, Scheme};
+
+type Result<T> = std::result::Result<T, Box<dyn Error + Send + Sync>>;
+
+/// The value of `--from` that asks us to detect the input scheme.
+const AUTO: &str = "auto";
+
+/// The path that stands for stdin or stdout.
+const STDIO: &str = "-";
+
+/// The scheme to transliterate from.
+#[derive(Clone, Copy, Debug)]
+enum Source {
+    /// Detect the scheme separately for each input.
+    Auto,
+    /// Use the given scheme.
+    Scheme(Scheme),
 }
 
-impl From<ClapScheme> for Scheme {
-    fn from(x: ClapScheme) -> Scheme {
-        use ClapScheme as C;
-        use Scheme::*;
-        match x {
-            C::Balinese => Balinese,
-            C::Bengali => Bengali,
-            C::Brahmi => Brahmi,
-            C::Burmese => Burmese,
-            C::Devanagari => Devanagari,
-            C::Gujarati => Gujarati,
-            C::Grantha => Grantha,
-            C::Gurmukhi => Gurmukhi,
-            C::Javanese => Javanese,
-            C::Kannada => Kannada,
-            C::Malayalam => Malayalam,
-            C::Odia => Odia,
-            C::Sharada => Sharada,
-            C::Siddham => Siddham,
-            C::Sinhala => Sinhala,
-            C::Tamil => Tamil,
-            C::Telugu => Telugu,
-            C::BarahaSouth => BarahaSouth,
-
-            C::HarvardKyoto => HarvardKyoto,
-            C::Iast => Iast,
-            C::Iso15919 => Iso15919,
-            C::Itrans => Itrans,
-            C::Slp1 => Slp1,
-            C::Velthuis => Velthuis,
-            C::Wx => Wx,
-        }
+/// Parses a scheme name.
+///
+/// We ignore case, `-`, and `_`, so `HarvardKyoto`, `harvard-kyoto`, and `harvard_kyoto` are all
+/// valid.
+fn parse_scheme(s: &str) -> std::result::Result<Scheme, String> {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| *c != '-' && *c != '_')
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let key = normalize(s);
+    Scheme::iter()
+        .find(|x| normalize(x.as_str()) == key)
+        .copied()
+        .ok_or_else(|| {
+            let mut names: Vec<_> = Scheme::iter().map(|x| x.as_str()).collect();
+            names.sort();
+            format!("unknown scheme `{s}`. Valid schemes: {}", names.join(", "))
+        })
+}
+
+fn parse_from(s: &str) -> std::result::Result<Source, String> {
+    if s.eq_ignore_ascii_case(AUTO) {
+        Ok(Source::Auto)
+    } else {
+        parse_scheme(s).map(Source::Scheme)
     }
 }
 
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
 struct Args {
-    /// The scheme to transliterate from.
-    #[arg(short, long)]
-    from: ClapScheme,
+    /// The scheme to transliterate from, or `auto` to detect it.
+    #[arg(short, long, value_parser = parse_from)]
+    from: Source,
 
     /// The scheme to transliterate to.
+    #[arg(short, long, value_parser = parse_scheme)]
+    to: Scheme,
+
+    /// The text to transliterate. If not set, we read from `--input` instead.
+    text: Option<String>,
+
+    /// The file or directory to read from, or `-` for stdin.
+    ///
+    /// If this is a directory, we transliterate all files within it recursively and in parallel,
+    /// and `--output` must also be a directory.
+    #[arg(short, long, default_value = STDIO, conflicts_with = "text")]
+    input: PathBuf,
+
+    /// The file or directory to write to, or `-` for stdout.
+    #[arg(short, long, default_value = STDIO)]
+    output: PathBuf,
+
+    /// The number of threads to use for directories. Defaults to the number of CPUs.
     #[arg(short, long)]
-    to: ClapScheme,
+    jobs: Option<usize>,
+}
+
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO
+}
+
+/// Returns the mapping for `input`, detecting its scheme if necessary.
+fn mapping_for(input: &str, from: Source, to: Scheme) -> Result<Mapping> {
+    let from = match from {
+        Source::Scheme(s) => s,
+        Source::Auto => detect(input).ok_or("could not detect the input scheme")?,
+    };
+    Ok(Mapping::new(from, to))
+}
+
+/// Opens `path` for writing, or stdout if `path` is `-`.
+fn create_writer(path: &Path) -> Result<Box<dyn Write>> {
+    if is_stdio(path) {
+        Ok(Box::new(BufWriter::new(io::stdout().lock())))
+    } else {
+        Ok(Box::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+/// Transliterates a single file, or stdin if `input` is `-`.
+fn transliterate_file(input: &Path, output: &Path, from: Source, to: Scheme) -> Result<()> {
+    let mut reader: Box<dyn Read> = if is_stdio(input) {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(input)?))
+    };
+    let mut writer = create_writer(output)?;
 
-    /// The text to transliterate.
-    text: String,
+    match from {
+        Source::Scheme(from) => {
+            // We know the scheme up front, so we can stream the input.
+            let mapping = Mapping::new(from, to);
+            transliterate_stream(reader, &mut writer, &mapping)?;
+        }
+        Source::Auto => {
+            // Detection needs to see the input, so read all of it first.
+            let mut text = String::new();
+            reader.read_to_string(&mut text)?;
+            let mapping = mapping_for(&text, from, to)?;
+            writer.write_all(transliterate(&text, &mapping).as_bytes())?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
 }
 
-fn run(args: Args) {
-    let mut lipika = Lipika::new();
-    let result = lipika.transliterate(args.text, args.from.into(), args.to.into());
-    println!("{result}");
+/// Returns all files in `dir` and its subdirectories, sorted by path.
+///
+/// We follow symlinks to files but not symlinks to directories, since the latter can form a
+/// cycle.
+fn find_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut ret = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            // Unlike `Path::is_dir`, `file_type` does not follow symlinks.
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                dirs.push(path);
+            } else if file_type.is_symlink() && path.is_dir() {
+                continue;
+            } else {
+                ret.push(path);
+            }
+        }
+    }
+    ret.sort();
+    Ok(ret)
+}
+
+/// Transliterates all files in `input` into the same relative paths in `output`.
+fn transliterate_dir(args: &Args) -> Result<()> {
+    if is_stdio(&args.output) {
+        return Err("`--output` must be a directory if `--input` is a directory".into());
+    }
+
+    let files = find_files(&args.input)?;
+    let num_jobs = args
+        .jobs
+        .or_else(|| thread::available_parallelism().ok().map(|n| n.get()))
+        .unwrap_or(1)
+        .clamp(1, files.len().max(1));
+
+    // Each thread claims the next unprocessed file until none are left.
+    let next = AtomicUsize::new(0);
+    let errors = Mutex::new(Vec::new());
+    thread::scope(|s| {
+        for _ in 0..num_jobs {
+            s.spawn(|| {
+                while let Some(path) = files.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let result = path
+                        .strip_prefix(&args.input)
+                        .map_err(|e| e.into())
+                        .map(|rel| args.output.join(rel))
+                        .and_then(|out| {
+                            if let Some(parent) = out.parent() {
+                                fs::create_dir_all(parent)?;
+                            }
+                            transliterate_file(path, &out, args.from, args.to)
+                        });
+                    if let Err(e) = result {
+                        if let Ok(mut errors) = errors.lock() {
+                            errors.push(format!("{}: {e}", path.display()));
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    let mut errors = errors.into_inner().unwrap_or_default();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        errors.sort();
+        Err(errors.join("\n").into())
+    }
+}
+
+fn run(args: Args) -> Result<()> {
+    if let Some(text) = &args.text {
+        let mapping = mapping_for(text, args.from, args.to)?;
+        let mut writer = create_writer(&args.output)?;
+        writeln!(writer, "{}", transliterate(text, &mapping))?;
+        writer.flush()?;
+        Ok(())
+    } else if args.input.is_dir() {
+        transliterate_dir(&args)
+    } else {
+        transliterate_file(&args.input, &args.output, args.from, args.to)
+    }
 }
 
 fn main() {
     let args = Args::parse();
-    run(args)
+    if let Err(err) = run(args) {
+        eprintln!("{err}");
+        process::exit(1);
+    }
 }
@@ -0,0 +1,507 @@
+//! Sorts Sanskrit text in traditional varṇa order.
+//!
+//! Most scripts do not store their letters in varṇa order, and most schemes spell some sounds with
+//! more than one character. So we transliterate all text to Devanagari, split it into sounds, and
+//! give each sound a weight.
+
+use crate::mapping::Mapping;
+use crate::scheme::Scheme;
+use crate::transliterate::transliterate;
+use std::cmp::Ordering;
+
+/// All consonants in varṇa order.
+const CONSONANTS: &str = "कखगघङचछजझञटठडढणतथदधनपफबभमयरलवशषसहळ";
+
+/// The number of consonants in each row of stops (क to म).
+const VARGA_LEN: u32 = 5;
+
+// Primary weights. `0` separates the primary and secondary parts of a `SortKey`.
+const WEIGHT_SPACE: u32 = 1;
+const WEIGHT_DIGIT_0: u32 = 2;
+const WEIGHT_A: u32 = 20;
+const WEIGHT_ANUSVARA: u32 = WEIGHT_A + 14;
+const WEIGHT_VISARGA: u32 = WEIGHT_ANUSVARA + 1;
+const WEIGHT_JIHVAMULIYA: u32 = WEIGHT_VISARGA + 1;
+const WEIGHT_UPADHMANIYA: u32 = WEIGHT_JIHVAMULIYA + 1;
+const WEIGHT_KA: u32 = WEIGHT_UPADHMANIYA + 1;
+const WEIGHT_OTHER: u32 = 0x1000;
+
+// Secondary weights. `1` ends the secondary weights of a single sound.
+const MARK_NUKTA: u32 = 2;
+const MARK_CANDRABINDU: u32 = 3;
+const MARK_SHORT: u32 = 4;
+const MARK_ACCENT: u32 = 0x100;
+
+/// Decides where we sort the anusvāra.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum AnusvaraPlacement {
+    /// Sort the anusvāra after all vowels and before the visarga, as most dictionaries do. Then
+    /// `saṃkalpa` sorts before `sakala`.
+    #[default]
+    AfterVowels,
+
+    /// Sort the anusvāra as the nasal of the stop that follows it. Then `saṃkalpa` sorts with
+    /// `saṅkalpa`. Before other sounds, we sort the anusvāra as in `AfterVowels`.
+    Homorganic,
+}
+
+/// Decides where we sort the visarga.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum VisargaPlacement {
+    /// Sort the visarga after the anusvāra and before all consonants.
+    #[default]
+    AfterAnusvara,
+
+    /// Sort the visarga as the sibilant it usually stands for: `ś` before `c` and `ch`, `ṣ` before
+    /// `ṭ` and `ṭh`, and `s` elsewhere. Then `namaḥ` sorts with `namas`.
+    Sibilant,
+}
+
+/// A key that sorts text in varṇa order.
+///
+/// Keys from the same `Collator` compare in the same order as the text that produced them.
+/// `to_bytes` encodes a key as bytes that also compare in this order, which is useful for storing
+/// keys in a database.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SortKey(Vec<u32>);
+
+impl SortKey {
+    /// Returns this key as a sequence of bytes whose lexicographic order matches the key's order.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.iter().flat_map(|w| w.to_be_bytes()).collect()
+    }
+}
+
+/// Sorts text in traditional varṇa order (`a ā i ī …`, `k kh g gh …`).
+///
+/// A `Collator` accepts text in any `Scheme`. We compare text first by its sounds, then by any
+/// marks on those sounds, such as nuktas and Vedic accents. Characters that are not Sanskrit
+/// sounds sort after all Sanskrit sounds, in code point order.
+///
+/// ### Usage
+///
+/// ```
+/// use vidyut_lipi::{Collator, Scheme};
+///
+/// let collator = Collator::new(Scheme::Iast);
+/// let mut words = vec!["kha", "ka", "aṃśa", "ākāśa", "aśva"];
+/// collator.sort(&mut words);
+/// assert_eq!(words, vec!["aṃśa", "aśva", "ākāśa", "ka", "kha"]);
+/// ```
+pub struct Collator {
+    mapping: Mapping,
+    anusvara: AnusvaraPlacement,
+    visarga: VisargaPlacement,
+    ignore_accents: bool,
+}
+
+/// A single sound and the marks that modify it.
+struct Unit {
+    weight: u32,
+    marks: Vec<u32>,
+}
+
+impl Collator {
+    /// Creates a collator for text in `scheme`.
+    pub fn new(scheme: Scheme) -> Self {
+        Self {
+            mapping: Mapping::new(scheme, Scheme::Devanagari),
+            anusvara: AnusvaraPlacement::default(),
+            visarga: VisargaPlacement::default(),
+            ignore_accents: false,
+        }
+    }
+
+    /// Sets where we sort the anusvāra.
+    pub fn with_anusvara(mut self, placement: AnusvaraPlacement) -> Self {
+        self.anusvara = placement;
+        self
+    }
+
+    /// Sets where we sort the visarga.
+    pub fn with_visarga(mut self, placement: VisargaPlacement) -> Self {
+        self.visarga = placement;
+        self
+    }
+
+    /// Sets whether we ignore Vedic accents.
+    ///
+    /// By default, accents break ties between text that is otherwise the same, and text without
+    /// accents sorts first.
+    pub fn with_ignore_accents(mut self, ignore_accents: bool) -> Self {
+        self.ignore_accents = ignore_accents;
+        self
+    }
+
+    /// Returns the sort key for `text`.
+    pub fn sort_key(&self, text: impl AsRef<str>) -> SortKey {
+        let deva = transliterate(text, &self.mapping);
+        let mut units = self.units(&deva);
+        self.place_ayogavahas(&mut units);
+
+        let mut key: Vec<u32> = units.iter().map(|u| u.weight).collect();
+        key.push(0);
+        for unit in &units {
+            key.extend(&unit.marks);
+            key.push(1);
+        }
+        SortKey(key)
+    }
+
+    /// Compares `a` and `b` in varṇa order.
+    pub fn compare(&self, a: impl AsRef<str>, b: impl AsRef<str>) -> Ordering {
+        self.sort_key(a).cmp(&self.sort_key(b))
+    }
+
+    /// Sorts `items` in varṇa order.
+    pub fn sort<S: AsRef<str>>(&self, items: &mut [S]) {
+        items.sort_by_cached_key(|x| self.sort_key(x));
+    }
+
+    /// Splits Devanagari text into weighted units.
+    fn units(&self, deva: &str) -> Vec<Unit> {
+        let mut units: Vec<Unit> = Vec::new();
+        // Whether the last unit is the implicit vowel of a consonant.
+        let mut implicit_a = false;
+
+        for c in deva.chars() {
+            let (consonant, nukta) = split_nukta(c);
+            if let Some(i) = CONSONANTS.chars().position(|x| x == consonant) {
+                let mut marks = Vec::new();
+                if nukta {
+                    marks.push(MARK_NUKTA);
+                }
+                units.push(Unit {
+                    weight: WEIGHT_KA + i as u32,
+                    marks,
+                });
+                units.push(Unit {
+                    weight: WEIGHT_A,
+                    marks: Vec::new(),
+                });
+                implicit_a = true;
+                continue;
+            }
+
+            if let Some((offset, short)) = vowel_sign(c) {
+                if implicit_a {
+                    units.pop();
+                }
+                let marks = if short { vec![MARK_SHORT] } else { Vec::new() };
+                units.push(Unit {
+                    weight: WEIGHT_A + offset,
+                    marks,
+                });
+            } else if c == '\u{094d}' {
+                // Virama
+                if implicit_a {
+                    units.pop();
+                }
+            } else if c == '\u{093c}' {
+                // Nukta: attach to the consonant, which precedes its implicit vowel.
+                let i = units.len().saturating_sub(if implicit_a { 2 } else { 1 });
+                if let Some(u) = units.get_mut(i) {
+                    u.marks.push(MARK_NUKTA);
+                }
+                continue;
+            } else if is_accent(c) {
+                if !self.ignore_accents {
+                    if let Some(u) = units.last_mut() {
+                        u.marks.push(MARK_ACCENT + c as u32);
+                    }
+                }
+                continue;
+            } else if c == '\u{093d}' {
+                // Avagraha marks an elided vowel and does not affect the sort order.
+                continue;
+            } else if c == '\u{0950}' {
+                // Om
+                units.push(Unit {
+                    weight: WEIGHT_A + 12,
+                    marks: Vec::new(),
+                });
+                units.push(Unit {
+                    weight: consonant_weight('म'),
+                    marks: Vec::new(),
+                });
+            } else {
+                let (weight, marks) = match c {
+                    '\u{0901}' => (WEIGHT_ANUSVARA, vec![MARK_CANDRABINDU]),
+                    '\u{0902}' => (WEIGHT_ANUSVARA, Vec::new()),
+                    '\u{0903}' => (WEIGHT_VISARGA, Vec::new()),
+                    '\u{1cf5}' => (WEIGHT_JIHVAMULIYA, Vec::new()),
+                    '\u{1cf6}' => (WEIGHT_UPADHMANIYA, Vec::new()),
+                    _ => match independent_vowel(c) {
+                        Some((offset, short)) => {
+                            let marks = if short { vec![MARK_SHORT] } else { Vec::new() };
+                            (WEIGHT_A + offset, marks)
+                        }
+                        None => (other_weight(c), Vec::new()),
+                    },
+                };
+                units.push(Unit { weight, marks });
+            }
+            implicit_a = false;
+        }
+        units
+    }
+
+    /// Reweights the anusvāra and visarga according to our placement settings.
+    fn place_ayogavahas(&self, units: &mut [Unit]) {
+        for i in 0..units.len() {
+            let next = units.get(i + 1).map(|u| u.weight);
+            let unit = &units[i];
+            // Candrabindu is a nasal vowel, so `Homorganic` does not apply to it.
+            if unit.weight == WEIGHT_ANUSVARA
+                && unit.marks.is_empty()
+                && self.anusvara == AnusvaraPlacement::Homorganic
+            {
+                if let Some(varga) = next.and_then(varga) {
+                    units[i].weight = WEIGHT_KA + varga * VARGA_LEN + (VARGA_LEN - 1);
+                }
+            } else if unit.weight == WEIGHT_VISARGA && self.visarga == VisargaPlacement::Sibilant {
+                let sibilant = match next.and_then(varga) {
+                    Some(1) => 'श',
+                    Some(2) => 'ष',
+                    _ => 'स',
+                };
+                units[i].weight = consonant_weight(sibilant);
+            }
+        }
+    }
+}
+
+/// Returns the weight of the consonant `c`.
+fn consonant_weight(c: char) -> u32 {
+    let i = CONSONANTS.chars().position(|x| x == c).unwrap_or_default();
+    WEIGHT_KA + i as u32
+}
+
+/// Returns the row of the stop with weight `weight`, or `None` if `weight` is not a stop.
+fn varga(weight: u32) -> Option<u32> {
+    let i = weight.checked_sub(WEIGHT_KA)?;
+    if i < 5 * VARGA_LEN {
+        Some(i / VARGA_LEN)
+    } else {
+        None
+    }
+}
+
+/// Splits a consonant with a precomposed nukta into the consonant and whether it has a nukta.
+fn split_nukta(c: char) -> (char, bool) {
+    match c {
+        '\u{0929}' => ('न', true),
+        '\u{0931}' => ('र', true),
+        '\u{0934}' => ('ळ', true),
+        '\u{0958}' => ('क', true),
+        '\u{0959}' => ('ख', true),
+        '\u{095a}' => ('ग', true),
+        '\u{095b}' => ('ज', true),
+        '\u{095c}' => ('ड', true),
+        '\u{095d}' => ('ढ', true),
+        '\u{095e}' => ('फ', true),
+        '\u{095f}' => ('य', true),
+        _ => (c, false),
+    }
+}
+
+/// Returns the offset of the independent vowel `c` from `a`, and whether it is a short `e` or
+/// `o`.
+fn independent_vowel(c: char) -> Option<(u32, bool)> {
+    let ret = match c {
+        'अ' => (0, false),
+        'आ' => (1, false),
+        'इ' => (2, false),
+        'ई' => (3, false),
+        'उ' => (4, false),
+        'ऊ' => (5, false),
+        'ऋ' => (6, false),
+        'ॠ' => (7, false),
+        'ऌ' => (8, false),
+        'ॡ' => (9, false),
+        'ऎ' => (10, true),
+        'ए' => (10, false),
+        'ऐ' => (11, false),
+        'ऒ' => (12, true),
+        'ओ' => (12, false),
+        'औ' => (13, false),
+        _ => return None,
+    };
+    Some(ret)
+}
+
+/// Returns the offset of the vowel sign `c` from `a`, and whether it is a short `e` or `o`.
+fn vowel_sign(c: char) -> Option<(u32, bool)> {
+    let ret = match c {
+        '\u{093e}' => (1, false),
+        '\u{093f}' => (2, false),
+        '\u{0940}' => (3, false),
+        '\u{0941}' => (4, false),
+        '\u{0942}' => (5, false),
+        '\u{0943}' => (6, false),
+        '\u{0944}' => (7, false),
+        '\u{0962}' => (8, false),
+        '\u{0963}' => (9, false),
+        '\u{0946}' => (10, true),
+        '\u{0947}' => (10, false),
+        '\u{0948}' => (11, false),
+        '\u{094a}' => (12, true),
+        '\u{094b}' => (12, false),
+        '\u{094c}' => (13, false),
+        _ => return None,
+    };
+    Some(ret)
+}
+
+/// Returns whether `c` is a Vedic accent or a similar mark.
+fn is_accent(c: char) -> bool {
+    matches!(c,
+        '\u{0951}'..='\u{0954}'
+        | '\u{1cd0}'..='\u{1ce8}'
+        | '\u{1ced}'
+        | '\u{1cf4}'
+        | '\u{1cf7}'..='\u{1cf9}'
+        | '\u{a8e0}'..='\u{a8f1}'
+    )
+}
+
+/// Returns the weight of a character that is not a Sanskrit sound.
+fn other_weight(c: char) -> u32 {
+    if c.is_whitespace() {
+        WEIGHT_SPACE
+    } else if let Some(d) = c.to_digit(10) {
+        WEIGHT_DIGIT_0 + d
+    } else if let Some(d) = ('०'..='९').position(|x| x == c) {
+        WEIGHT_DIGIT_0 + d as u32
+    } else {
+        WEIGHT_OTHER + c as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::Scheme::*;
+
+    fn sorted(collator: &Collator, words: &[&'static str]) -> Vec<&'static str> {
+        let mut ret = words.to_vec();
+        collator.sort(&mut ret);
+        ret
+    }
+
+    #[test]
+    fn sorts_in_varna_order() {
+        let c = Collator::new(Slp1);
+        let expected = vec![
+            "a", "aMSa", "aHkaH", "akza", "aSva", "A", "i", "I", "u", "U", "f", "F", "x", "X", "e",
+            "E", "o", "O", "ka", "kA", "ki", "kza", "Ka", "ga", "Na", "ca", "Ya", "wa", "Ra", "ta",
+            "na", "pa", "ma", "ya", "ra", "la", "va", "Sa", "za", "sa", "ha", "La",
+        ];
+        let mut words = expected.clone();
+        words.reverse();
+        c.sort(&mut words);
+        assert_eq!(words, expected);
+    }
+
+    #[test]
+    fn sorts_any_scheme() {
+        let words = ["kṛṣṇa", "kaṭu", "kṣetra", "kāla", "kukkuṭa", "khaga"];
+        let expected = ["kaṭu", "kāla", "kukkuṭa", "kṛṣṇa", "kṣetra", "khaga"];
+        assert_eq!(sorted(&Collator::new(Iast), &words), expected);
+
+        let to_deva = Mapping::new(Iast, Devanagari);
+        let deva: Vec<String> = words.iter().map(|w| transliterate(w, &to_deva)).collect();
+        let mut deva_sorted = deva.clone();
+        Collator::new(Devanagari).sort(&mut deva_sorted);
+        let expected: Vec<String> = expected
+            .iter()
+            .map(|w| transliterate(w, &to_deva))
+            .collect();
+        assert_eq!(deva_sorted, expected);
+
+        // Keys are the same in every scheme.
+        let to_telugu = Mapping::new(Iast, Telugu);
+        for w in words {
+            assert_eq!(
+                Collator::new(Iast).sort_key(w),
+                Collator::new(Telugu).sort_key(transliterate(w, &to_telugu))
+            );
+        }
+    }
+
+    #[test]
+    fn sorts_spaces_and_digits_first() {
+        let c = Collator::new(HarvardKyoto);
+        assert_eq!(
+            sorted(&c, &["ab", "a ba", "a1", "a"]),
+            ["a", "a ba", "a1", "ab"]
+        );
+    }
+
+    #[test]
+    fn anusvara_placement() {
+        let words = ["saMkalpa", "saGkara", "sakala", "saMvAda"];
+
+        let c = Collator::new(HarvardKyoto);
+        assert_eq!(
+            sorted(&c, &words),
+            ["saMkalpa", "saMvAda", "sakala", "saGkara"]
+        );
+
+        let c = c.with_anusvara(AnusvaraPlacement::Homorganic);
+        assert_eq!(
+            sorted(&c, &words),
+            ["saMvAda", "sakala", "saGkara", "saMkalpa"]
+        );
+        assert_eq!(c.compare("saMkalpa", "saGkalpa"), Ordering::Equal);
+        assert_ne!(c.compare("saMvAda", "samvAda"), Ordering::Equal);
+    }
+
+    #[test]
+    fn visarga_placement() {
+        let c = Collator::new(HarvardKyoto);
+        assert_eq!(c.compare("namaH", "namas"), Ordering::Less);
+        assert_eq!(c.compare("namaH", "namaka"), Ordering::Less);
+
+        let c = c.with_visarga(VisargaPlacement::Sibilant);
+        assert_eq!(c.compare("namaH", "namas"), Ordering::Equal);
+        assert_eq!(c.compare("namaH", "namaka"), Ordering::Greater);
+        assert_eq!(c.compare("duHca", "duzca"), Ordering::Equal);
+        assert_eq!(c.compare("duHca", "duSca"), Ordering::Less);
+    }
+
+    #[test]
+    fn accents() {
+        let c = Collator::new(Devanagari);
+        assert_eq!(c.compare("अ॒ग्निम्", "अग्निम्"), Ordering::Greater);
+        assert_eq!(c.compare("अ॒ग्निम्", "आग्निम्"), Ordering::Less);
+        assert_ne!(c.compare("अ॒ग्निम्", "अ॑ग्निम्"), Ordering::Equal);
+
+        let c = c.with_ignore_accents(true);
+        assert_eq!(c.compare("अ॒ग्निम्", "अग्निम्"), Ordering::Equal);
+        assert_eq!(c.compare("अ॒ग्निम्", "अ॑ग्निम्"), Ordering::Equal);
+    }
+
+    #[test]
+    fn marks_break_ties() {
+        let c = Collator::new(Devanagari);
+        assert_eq!(c.compare("क", "क़"), Ordering::Less);
+        assert_eq!(c.compare("क़", "कि"), Ordering::Less);
+        assert_eq!(c.compare("क़", "क\u{093c}"), Ordering::Equal);
+        assert_eq!(c.compare("अं", "अँ"), Ordering::Less);
+        // Avagraha is ignored.
+        assert_eq!(c.compare("सोऽहम्", "सोहम्"), Ordering::Equal);
+    }
+
+    #[test]
+    fn bytes_match_key_order() {
+        let c = Collator::new(Iast);
+        let words = ["a", "ā", "ka", "kha", "ṃ", "x", "अ"];
+        for a in words {
+            for b in words {
+                let (x, y) = (c.sort_key(a), c.sort_key(b));
+                assert_eq!(x.cmp(&y), x.to_bytes().cmp(&y.to_bytes()), "{a} {b}");
+            }
+        }
+    }
+}
@@ -0,0 +1,558 @@
+//! User-defined schemes that are loaded at runtime.
+
+use crate::errors::SchemeError;
+use crate::scheme::Scheme;
+use rustc_hash::{FxHashMap, FxHashSet};
+use unicode_normalization::UnicodeNormalization;
+
+type Result<T> = std::result::Result<T, SchemeError>;
+
+const VIRAMA: &str = "\u{094d}";
+const LETTER_A: &str = "\u{0905}";
+
+/// A scheme whose tokens are defined at runtime.
+///
+/// A `CustomScheme` is useful for formats that `Scheme` doesn't support, such as a publisher's
+/// house style or a legacy font encoding. Like a `Scheme`, a `CustomScheme` is a list of tokens
+/// that maps Devanagari strings to the scheme's own format. If a key is listed more than once,
+/// the first value is the one we use as output and the other values are accepted as alternates on
+/// input.
+///
+/// Every `CustomScheme` has a *base*, which is the `Scheme` whose other behavior the custom scheme
+/// inherits. The base determines whether the custom scheme is an alphabet or an abugida, and it
+/// also determines any special handling of the scheme (e.g. reordering of characters in Tamil or
+/// Grantha-style numerals). For most custom schemes, `Iast` (for alphabets) and `Devanagari` (for
+/// abugidas) are sensible defaults.
+///
+/// ### Usage
+///
+/// ```
+/// use vidyut_lipi::{transliterate, CustomScheme, Mapping, Scheme};
+///
+/// let tokens = vec![
+///     ("अ", "a"), ("आ", "aa"), ("ा", "aa"), ("इ", "i"), ("ि", "i"), ("्", ""),
+///     ("क", "k"), ("म", "m"), ("र", "r"), ("ल", "l"), ("ं", "ṁ"),
+/// ];
+/// let house = CustomScheme::new("HouseStyle", Scheme::Iast, tokens).unwrap();
+///
+/// let mapping = Mapping::new_custom(&Scheme::Devanagari.into(), &house);
+/// assert_eq!(transliterate("कमलं", &mapping), "kamalaṁ");
+/// assert_eq!(transliterate("रामा", &mapping), "raamaa");
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CustomScheme {
+    name: String,
+    base: Scheme,
+    tokens: Vec<(String, String)>,
+}
+
+impl CustomScheme {
+    /// Creates a new scheme from the given `tokens`, which map Devanagari keys to values in the
+    /// new scheme.
+    ///
+    /// All keys and values are converted to NFC. This function returns an error if `tokens` is
+    /// not a valid scheme definition, for example if two different keys share the same value.
+    /// The error names the offending keys so that the definition is easy to fix.
+    pub fn new<K, V>(
+        name: impl Into<String>,
+        base: Scheme,
+        tokens: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<Self>
+    where
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let tokens = tokens
+            .into_iter()
+            .map(|(k, v)| (k.as_ref().nfc().collect(), v.as_ref().nfc().collect()))
+            .collect();
+        let ret = Self {
+            name: name.into(),
+            base,
+            tokens,
+        };
+        ret.validate()?;
+        Ok(ret)
+    }
+
+    /// Creates a new scheme from a JSON definition.
+    ///
+    /// The definition should be an object with three fields:
+    ///
+    /// - `name`, a string that names the scheme.
+    /// - `base`, a string that names the scheme's base (e.g. `"Iast"`).
+    /// - `tokens`, a list of `[key, value]` pairs. We use a list instead of an object so that
+    ///   the scheme can list the same key more than once.
+    ///
+    /// ### Usage
+    ///
+    /// ```
+    /// use vidyut_lipi::CustomScheme;
+    ///
+    /// let json = r#"{
+    ///     "name": "Tiny",
+    ///     "base": "Iast",
+    ///     "tokens": [["अ", "a"], ["्", ""], ["क", "k"]]
+    /// }"#;
+    /// let scheme = CustomScheme::from_json(json).unwrap();
+    /// assert_eq!(scheme.name(), "Tiny");
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self> {
+        let value = serde_json::from_str(json).map_err(|e| {
+            // serde_json adds the location to its message, so strip it here.
+            let message = e.to_string();
+            let message = match message.rfind(" at line ") {
+                Some(i) => message[..i].to_string(),
+                None => message,
+            };
+            SchemeError::Syntax {
+                message,
+                line: e.line(),
+                column: e.column(),
+            }
+        })?;
+        Self::from_value(&value)
+    }
+
+    /// Creates a new scheme from a TOML definition.
+    ///
+    /// The definition has the same fields as the one that `from_json` accepts.
+    ///
+    /// ### Usage
+    ///
+    /// ```
+    /// use vidyut_lipi::CustomScheme;
+    ///
+    /// let toml = r#"
+    /// name = "Tiny"
+    /// base = "Iast"
+    /// tokens = [["अ", "a"], ["्", ""], ["क", "k"]]
+    /// "#;
+    /// let scheme = CustomScheme::from_toml(toml).unwrap();
+    /// assert_eq!(scheme.name(), "Tiny");
+    /// ```
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let table: toml::Table = toml.parse().map_err(|e: toml::de::Error| {
+            let offset = e.span().map_or(0, |s| s.start);
+            let (line, column) = line_and_column(toml, offset);
+            SchemeError::Syntax {
+                message: e.message().to_string(),
+                line,
+                column,
+            }
+        })?;
+        // Our definition uses only strings, lists, and tables, all of which convert to JSON
+        // without loss.
+        let value = serde_json::to_value(table)
+            .map_err(|_| SchemeError::InvalidDefinition("not valid TOML"))?;
+        Self::from_value(&value)
+    }
+
+    /// Creates a new scheme from a parsed definition.
+    fn from_value(value: &serde_json::Value) -> Result<Self> {
+        use serde_json::Value;
+        let invalid = SchemeError::InvalidDefinition;
+
+        let obj = value.as_object().ok_or(invalid("expected an object"))?;
+
+        let name = obj
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or(invalid("`name` must be a string"))?;
+        let base: Scheme = obj
+            .get("base")
+            .and_then(Value::as_str)
+            .ok_or(invalid("`base` must be a string"))?
+            .parse()
+            .map_err(|_| invalid("`base` must be the name of a built-in scheme"))?;
+
+        let raw_tokens = obj
+            .get("tokens")
+            .and_then(Value::as_array)
+            .ok_or(invalid("`tokens` must be a list"))?;
+        let mut tokens = Vec::with_capacity(raw_tokens.len());
+        for raw in raw_tokens {
+            match raw.as_array().map(|a| a.as_slice()) {
+                Some([Value::String(key), Value::String(value)]) => {
+                    tokens.push((key.as_str(), value.as_str()))
+                }
+                _ => {
+                    return Err(invalid(
+                        "each token must be a `[key, value]` pair of strings",
+                    ))
+                }
+            }
+        }
+
+        Self::new(name, base, tokens)
+    }
+
+    /// Returns the name of this scheme.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the base of this scheme.
+    pub fn base(&self) -> Scheme {
+        self.base
+    }
+
+    /// Returns the (key, value) pairs that define this scheme, in their original order.
+    pub fn tokens(&self) -> &[(String, String)] {
+        &self.tokens
+    }
+
+    /// Checks that this scheme's tokens are well-formed.
+    fn validate(&self) -> Result<()> {
+        let mut seen_pairs = FxHashSet::default();
+        let mut first_for_value: FxHashMap<&str, usize> = FxHashMap::default();
+
+        for (index, (key, value)) in self.tokens.iter().enumerate() {
+            if key.is_empty() {
+                return Err(SchemeError::EmptyKey { index });
+            }
+            if !key.chars().all(is_devanagari_key_char) {
+                return Err(SchemeError::NonDevanagariKey {
+                    index,
+                    key: key.clone(),
+                });
+            }
+            if value.is_empty() {
+                // Alphabets write the virama implicitly, so the virama can map to "".
+                if key == VIRAMA {
+                    continue;
+                }
+                return Err(SchemeError::EmptyValue {
+                    index,
+                    key: key.clone(),
+                });
+            }
+            if !seen_pairs.insert((key, value)) {
+                return Err(SchemeError::DuplicateToken {
+                    index,
+                    key: key.clone(),
+                    value: value.clone(),
+                });
+            }
+
+            match first_for_value.get(value.as_str()) {
+                Some(&first) => {
+                    let first_key = &self.tokens[first].0;
+                    if first_key != key && !is_vowel_and_mark(first_key, key) {
+                        return Err(SchemeError::AmbiguousValue {
+                            index,
+                            value: value.clone(),
+                            first: first_key.clone(),
+                            second: key.clone(),
+                        });
+                    }
+                }
+                None => {
+                    first_for_value.insert(value, index);
+                }
+            }
+        }
+
+        let has_key = |k: &str| self.tokens.iter().any(|(key, _)| key == k);
+        if !has_key(LETTER_A) {
+            return Err(SchemeError::MissingToken { key: LETTER_A });
+        }
+        if self.base.is_abugida() && !has_key(VIRAMA) {
+            return Err(SchemeError::MissingToken { key: VIRAMA });
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Scheme> for CustomScheme {
+    /// Creates a custom scheme with the same tokens as `scheme`.
+    fn from(scheme: Scheme) -> Self {
+        Self {
+            name: scheme.as_str().to_string(),
+            base: scheme,
+            tokens: scheme
+                .token_pairs()
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+}
+
+/// Returns the line and column of the byte `offset` in `text`, both starting from 1.
+fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+/// Returns whether `c` can be used in the key of a custom scheme.
+fn is_devanagari_key_char(c: char) -> bool {
+    matches!(c,
+        // Devanagari
+        '\u{0900}'..='\u{097f}'
+        // Vedic Extensions
+        | '\u{1cd0}'..='\u{1cff}'
+        // Devanagari Extended
+        | '\u{a8e0}'..='\u{a8ff}'
+        // Devanagari Extended-A
+        | '\u{11b00}'..='\u{11b5f}'
+        // ZWNJ, ZWJ
+        | '\u{200c}' | '\u{200d}'
+    )
+}
+
+/// Returns whether `x` and `y` are an independent vowel and its corresponding vowel sign, in
+/// either order.
+///
+/// Alphabets use the same value for both, so this is not an ambiguity.
+fn is_vowel_and_mark(x: &str, y: &str) -> bool {
+    let vowel_for_mark = |mark: &str| -> Option<&str> {
+        let vowel = match mark {
+            "\u{093e}" => "आ",
+            "\u{093f}" => "इ",
+            "\u{0940}" => "ई",
+            "\u{0941}" => "उ",
+            "\u{0942}" => "ऊ",
+            "\u{0943}" => "ऋ",
+            "\u{0944}" => "ॠ",
+            "\u{0962}" => "ऌ",
+            "\u{0963}" => "ॡ",
+            "\u{0945}" => "ऍ",
+            "\u{0946}" => "ऎ",
+            "\u{0947}" => "ए",
+            "\u{0948}" => "ऐ",
+            "\u{0949}" => "ऑ",
+            "\u{094a}" => "ऒ",
+            "\u{094b}" => "ओ",
+            "\u{094c}" => "औ",
+            "\u{094f}" => "ॵ",
+            _ => return None,
+        };
+        Some(vowel)
+    };
+    vowel_for_mark(x) == Some(y) || vowel_for_mark(y) == Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mapping::Mapping;
+    use crate::transliterate::transliterate;
+
+    fn new_scheme(base: Scheme, tokens: &[(&str, &str)]) -> Result<CustomScheme> {
+        CustomScheme::new("Test", base, tokens.iter().copied())
+    }
+
+    #[test]
+    fn new_with_builtin_tokens() {
+        // Most built-in schemes are lossy by design (e.g. Bengali uses the same letter for "ba"
+        // and "va"), so only some of them are valid custom schemes.
+        for scheme in [
+            Scheme::HarvardKyoto,
+            Scheme::Slp1,
+            Scheme::Velthuis,
+            Scheme::Wx,
+        ] {
+            let custom = CustomScheme::from(scheme);
+            CustomScheme::new(custom.name(), scheme, custom.tokens().iter().cloned())
+                .expect("valid");
+        }
+    }
+
+    #[test]
+    fn new_normalizes_to_nfc() {
+        // "ā" as "a" + combining macron.
+        let s = new_scheme(Scheme::Iast, &[("अ", "a"), ("आ", "a\u{0304}")]).expect("valid");
+        assert_eq!(s.tokens()[1].1, "\u{0101}");
+    }
+
+    #[test]
+    fn new_with_errors() {
+        use SchemeError::*;
+
+        assert_eq!(
+            new_scheme(Scheme::Iast, &[("अ", "a"), ("", "a")]),
+            Err(EmptyKey { index: 1 })
+        );
+        assert_eq!(
+            new_scheme(Scheme::Iast, &[("अ", "a"), ("क", "")]),
+            Err(EmptyValue {
+                index: 1,
+                key: "क".to_string()
+            })
+        );
+        assert_eq!(
+            new_scheme(Scheme::Iast, &[("a", "a")]),
+            Err(NonDevanagariKey {
+                index: 0,
+                key: "a".to_string()
+            })
+        );
+        assert_eq!(
+            new_scheme(Scheme::Iast, &[("अ", "a"), ("अ", "a")]),
+            Err(DuplicateToken {
+                index: 1,
+                key: "अ".to_string(),
+                value: "a".to_string()
+            })
+        );
+        let err = new_scheme(Scheme::Iast, &[("अ", "a"), ("श", "s"), ("स", "s")]);
+        assert_eq!(
+            err,
+            Err(AmbiguousValue {
+                index: 2,
+                value: "s".to_string(),
+                first: "श".to_string(),
+                second: "स".to_string(),
+            })
+        );
+        assert_eq!(
+            err.map_err(|e| e.to_string()),
+            Err("token 2: value `s` is used by both `श` and `स`".to_string())
+        );
+        assert_eq!(
+            new_scheme(Scheme::Iast, &[("क", "k")]),
+            Err(MissingToken { key: "अ" })
+        );
+        assert_eq!(
+            new_scheme(Scheme::Devanagari, &[("अ", "अ"), ("क", "क")]),
+            Err(MissingToken { key: VIRAMA })
+        );
+    }
+
+    #[test]
+    fn new_allows_vowels_and_marks_with_same_value() {
+        assert!(new_scheme(Scheme::Iast, &[("अ", "a"), ("ए", "e"), ("\u{0947}", "e")]).is_ok());
+        assert!(new_scheme(Scheme::Iast, &[("अ", "a"), ("ए", "e"), ("\u{0948}", "e")]).is_err());
+    }
+
+    #[test]
+    fn from_json_with_errors() {
+        let err = |json| match CustomScheme::from_json(json) {
+            Err(SchemeError::InvalidDefinition(_)) => (),
+            r => panic!("{json}: {r:?}"),
+        };
+        err("[]");
+        err(r#"{"base": "Iast", "tokens": []}"#);
+        err(r#"{"name": "x", "base": "Foo", "tokens": []}"#);
+        err(r#"{"name": "x", "base": "Iast", "tokens": {"अ": "a"}}"#);
+        err(r#"{"name": "x", "base": "Iast", "tokens": [["अ"]]}"#);
+        err(r#"{"name": "x", "base": "Iast", "tokens": [["अ", 1]]}"#);
+
+        // Validation errors are reported as usual.
+        assert_eq!(
+            CustomScheme::from_json(r#"{"name": "x", "base": "Iast", "tokens": [["क", "k"]]}"#),
+            Err(SchemeError::MissingToken { key: "अ" })
+        );
+    }
+
+    #[test]
+    fn from_json_with_syntax_error() {
+        let json = "{\n  \"name\": \"x\",\n  \"base\" \"Iast\"\n}";
+        match CustomScheme::from_json(json) {
+            Err(SchemeError::Syntax {
+                message,
+                line,
+                column,
+            }) => {
+                assert_eq!(message, "expected `:`");
+                assert_eq!((line, column), (3, 10));
+            }
+            r => panic!("{r:?}"),
+        }
+    }
+
+    #[test]
+    fn from_toml() {
+        let toml = r#"
+            name = "Tiny"
+            base = "Iast"
+            tokens = [["अ", "a"], ["्", ""], ["क", "k"], ["अ", "A"]]
+        "#;
+        let scheme = CustomScheme::from_toml(toml).expect("valid");
+        assert_eq!(scheme.name(), "Tiny");
+        assert_eq!(scheme.base(), Scheme::Iast);
+        assert_eq!(scheme.tokens().len(), 4);
+
+        let json = r#"{
+            "name": "Tiny",
+            "base": "Iast",
+            "tokens": [["अ", "a"], ["्", ""], ["क", "k"], ["अ", "A"]]
+        }"#;
+        assert_eq!(CustomScheme::from_json(json), Ok(scheme));
+    }
+
+    #[test]
+    fn from_toml_with_errors() {
+        let err = |toml| match CustomScheme::from_toml(toml) {
+            Err(SchemeError::InvalidDefinition(_)) => (),
+            r => panic!("{toml}: {r:?}"),
+        };
+        err(r#"base = "Iast""#);
+        err("name = \"x\"\nbase = \"Iast\"\ntokens = [[\"अ\", 1]]");
+        err("name = \"x\"\nbase = \"Iast\"\ntokens = { a = \"b\" }");
+
+        assert_eq!(
+            CustomScheme::from_toml("name = \"x\"\nbase = \"Iast\"\ntokens = [[\"क\", \"k\"]]"),
+            Err(SchemeError::MissingToken { key: "अ" })
+        );
+    }
+
+    #[test]
+    fn from_toml_with_syntax_error() {
+        let toml = "name = \"x\"\nbase = Iast\ntokens = [[\"अ\", \"a\"]]\n";
+        match CustomScheme::from_toml(toml) {
+            Err(SchemeError::Syntax { line, column, .. }) => assert_eq!((line, column), (2, 8)),
+            r => panic!("{r:?}"),
+        }
+    }
+
+    #[test]
+    fn mapping_with_custom_alphabet() {
+        // SLP1, but with "ṁ" for anusvara and "sh" for "ś".
+        let mut tokens: Vec<_> = Scheme::Slp1
+            .token_pairs()
+            .iter()
+            .filter(|(k, _)| *k != "ं" && *k != "श")
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        tokens.push(("ं".to_string(), "ṁ".to_string()));
+        tokens.push(("श".to_string(), "sh".to_string()));
+        let house = CustomScheme::new("House", Scheme::Slp1, tokens).expect("valid");
+        let deva = CustomScheme::from(Scheme::Devanagari);
+
+        let to_house = Mapping::new_custom(&deva, &house);
+        assert_eq!(transliterate("शंकरः", &to_house), "shaṁkaraH");
+        assert_eq!(to_house.from(), Scheme::Devanagari);
+        assert_eq!(to_house.to(), Scheme::Slp1);
+
+        let from_house = Mapping::new_custom(&house, &deva);
+        assert_eq!(transliterate("shaṁkaraH", &from_house), "शंकरः");
+    }
+
+    #[test]
+    fn mapping_with_custom_abugida() {
+        // A toy legacy encoding that uses ASCII punctuation for Devanagari sounds.
+        let tokens = [
+            ("अ", "@"),
+            ("आ", "@="),
+            ("\u{093e}", "="),
+            ("्", "~"),
+            ("क", "K"),
+            ("र", "R"),
+            ("म", "M"),
+        ];
+        let legacy = new_scheme(Scheme::Devanagari, &tokens).expect("valid");
+
+        let m = Mapping::new_custom(&Scheme::Slp1.into(), &legacy);
+        assert_eq!(transliterate("rAma", &m), "R=M");
+        assert_eq!(transliterate("karm", &m), "KR~M~");
+
+        let m = Mapping::new_custom(&legacy, &Scheme::Slp1.into());
+        assert_eq!(transliterate("R=M", &m), "rAma");
+    }
+}
@@ -0,0 +1,525 @@
+//! A statistical model for detecting the `Scheme` used by some text.
+//!
+//! `detect` uses hand-written rules and returns a single best guess, which works well for most
+//! scripts but is brittle for short strings in ASCII schemes like `HarvardKyoto`, `Itrans`,
+//! `Velthuis`, and `Slp1`. `Detector` instead scores its input against a character n-gram model for
+//! each scheme and returns a ranked list of candidates.
+
+use crate::mapping::Mapping;
+use crate::scheme::Scheme;
+use crate::transliterate::transliterate;
+use crate::unicode_norm;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::ops::Range;
+
+/// A short sample of Sanskrit text in SLP1.
+///
+/// We transliterate this text into each scheme to learn which character sequences are typical of
+/// that scheme. The text is a mix of verse and prose and uses most Sanskrit sounds.
+const SEED_CORPUS_SLP1: &str = "
+Darmakzetre kurukzetre samavetA yuyutsavaH . mAmakAH pARqavAS cEva kim akurvata saMjaya ..
+karmaRy evADikAras te mA Palezu kadAcana . mA karmaPalahetur BUr mA te saNgo 'stv akarmaRi ..
+yadA yadA hi Darmasya glAnir Bavati BArata . aByutTAnam aDarmasya tadAtmAnaM sfjAmy aham ..
+vAgarTAv iva saMpfktO vAgarTapratipattaye . jagataH pitarO vande pArvatIparameSvarO ..
+nArAyaRaM namaskftya naraM cEva narottamam . devIM sarasvatIM cEva tato jayam udIrayet ..
+tapaHsvADyAyanirataM tapasvI vAgvidAM varam . nAradaM paripapracCa vAlmIkir munipuMgavam ..
+ko nv asmin sAmprataM loke guRavAn kaS ca vIryavAn . DarmajYaS ca kftajYaS ca satyavAkyo dfQavrataH ..
+vasudevasutaM devaM kaMsacARUramardanam . devakIparamAnandaM kfzRaM vande jagadgurum ..
+agnim Ile purohitaM yajYasya devam ftvijam . hotAraM ratnaDAtamam ..
+atha yogAnuSAsanam . yogaS cittavfttiniroDaH . tadA drazwuH svarUpe 'vasTAnam .
+vftti sArUpyam itaratra . vfttayaH paYcatayyaH klizwAklizwAH .
+kxptaM pitFRAM kftyam OzaDInAM ca EkyaM SaNkaraH KaqgaM GawaH Cattram JazaH
+wIkA WakkuraH qamaruH QakkA RakAraH TUtkAraH PalaM BUmiH hlAdaH
+";
+
+/// Mixes the trigram, bigram, unigram, and uniform estimates of our model.
+///
+/// These weights must sum to 1.
+const LAMBDAS: [f64; 4] = [0.6, 0.25, 0.1, 0.05];
+
+/// The number of distinct characters we assume for our uniform estimate.
+///
+/// A character that a scheme has never seen receives a probability close to `LAMBDAS[3] /
+/// ALPHABET_SIZE`.
+const ALPHABET_SIZE: f64 = 512.0;
+
+/// The log-probability cost of switching from one scheme to another between adjacent words.
+///
+/// This value is hand-picked, not fitted. On the held-out sentences in `tests/detector.rs`, 12 is
+/// the smallest cost at which no single-scheme sentence splits into several spans. Larger costs
+/// keep that property but miss more real switches between adjacent sentences. Both effects are
+/// pinned by the tests there.
+const SWITCH_COST: f64 = 12.0;
+
+/// Character n-gram counts for a single scheme.
+#[derive(Clone, Debug, Default)]
+struct NgramModel {
+    unigrams: FxHashMap<char, u32>,
+    bigrams: FxHashMap<(char, char), u32>,
+    trigrams: FxHashMap<(char, char, char), u32>,
+    total: u32,
+}
+
+impl NgramModel {
+    /// Adds the n-grams of `text` to the model.
+    fn add(&mut self, text: &str) {
+        let mut prev = (' ', ' ');
+        for c in text.chars().chain(std::iter::once(' ')) {
+            *self.unigrams.entry(c).or_default() += 1;
+            *self.bigrams.entry((prev.1, c)).or_default() += 1;
+            *self.trigrams.entry((prev.0, prev.1, c)).or_default() += 1;
+            self.total += 1;
+            prev = (prev.1, c);
+        }
+    }
+
+    /// Returns the log-probability of `c` given the two characters before it.
+    fn log_prob(&self, a: char, b: char, c: char) -> f64 {
+        let ratio = |num: Option<&u32>, denom: Option<&u32>| match (num, denom) {
+            (Some(n), Some(d)) if *d > 0 => *n as f64 / *d as f64,
+            _ => 0.0,
+        };
+
+        let p3 = ratio(self.trigrams.get(&(a, b, c)), self.bigrams.get(&(a, b)));
+        let p2 = ratio(self.bigrams.get(&(b, c)), self.unigrams.get(&b));
+        let p1 = ratio(self.unigrams.get(&c), Some(&self.total));
+        let p = LAMBDAS[0] * p3.min(1.0)
+            + LAMBDAS[1] * p2.min(1.0)
+            + LAMBDAS[2] * p1
+            + LAMBDAS[3] / ALPHABET_SIZE;
+        p.ln()
+    }
+}
+
+/// A span of text that uses a single scheme.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SchemeSpan {
+    /// The byte range of the span in the original text.
+    pub range: Range<usize>,
+    /// The scheme used in this span.
+    pub scheme: Scheme,
+}
+
+/// Detects the scheme used by some text with a statistical model.
+///
+/// Unlike `detect`, which returns a single best guess, `Detector` returns a ranked list of
+/// candidate schemes with a confidence score for each. It can also split text that uses more than
+/// one scheme into spans.
+///
+/// Each `Detector` holds a character n-gram model for every `Scheme`. We create these models from
+/// the token tables for each scheme and from a small built-in sample of Sanskrit text. If you
+/// have sample text in a specific scheme (e.g. a representative corpus from your application), add
+/// it with `add_sample` to improve accuracy. Since creating a `Detector` is relatively slow,
+/// we recommend creating it once and reusing it.
+///
+/// ### Usage
+///
+/// ```
+/// use vidyut_lipi::{Detector, Scheme};
+///
+/// let detector = Detector::new();
+///
+/// let ranked = detector.rank("pARqavAH");
+/// assert_eq!(ranked[0].0, Scheme::Slp1);
+///
+/// let spans = detector.segments("rāmaḥ vanaṃ gacchati. रामः वनं गच्छति।");
+/// assert_eq!(spans.len(), 2);
+/// assert_eq!(spans[0].scheme, Scheme::Iast);
+/// assert_eq!(spans[1].scheme, Scheme::Devanagari);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Detector {
+    models: Vec<(Scheme, NgramModel)>,
+    /// All characters known to at least one model. Characters not in this set carry no
+    /// information about the scheme, so we ignore them.
+    known_chars: FxHashSet<char>,
+}
+
+impl Detector {
+    /// Creates a new detector with our default models.
+    pub fn new() -> Self {
+        let mut ret = Self {
+            models: Vec::new(),
+            known_chars: FxHashSet::default(),
+        };
+
+        let mut schemes: Vec<Scheme> = Scheme::iter().copied().collect();
+        schemes.sort();
+        let words = syllable_words();
+        for scheme in schemes {
+            let mut model = NgramModel::default();
+
+            let mapping = Mapping::new(Scheme::Slp1, scheme);
+            model.add(&transliterate(SEED_CORPUS_SLP1, &mapping));
+            for word in &words {
+                model.add(&transliterate(word, &mapping));
+            }
+
+            // Our mappings always prefer the first value for a key. So that we also learn
+            // alternate spellings (e.g. `aa` for `A` in ITRANS), transliterate the seed corpus again
+            // with a mapping that prefers the last value instead.
+            let mut alt_pairs: Vec<(String, String)> = scheme
+                .token_pairs()
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            alt_pairs.reverse();
+            let alt_mapping = Mapping::with_target_pairs(Scheme::Slp1, scheme, alt_pairs);
+            model.add(&transliterate(SEED_CORPUS_SLP1, &alt_mapping));
+
+            for (_, value) in scheme.token_pairs() {
+                model.add(value);
+            }
+
+            ret.known_chars.extend(model.unigrams.keys());
+            ret.models.push((scheme, model));
+        }
+        ret
+    }
+
+    /// Adds `text`, which uses the given `scheme`, to the detector's model.
+    ///
+    /// Use this method to adapt the detector to your own data. For example, if your users tend to
+    /// write ITRANS with certain spellings, add a few sentences of their text here.
+    pub fn add_sample(&mut self, scheme: Scheme, text: &str) {
+        let text = unicode_norm::to_nfc(text);
+        if let Some((_, model)) = self.models.iter_mut().find(|(s, _)| *s == scheme) {
+            model.add(&text);
+            self.known_chars.extend(model.unigrams.keys());
+        }
+    }
+
+    /// Returns all schemes ranked by how likely they are to have produced `input`.
+    ///
+    /// Each scheme is paired with a confidence score between 0 and 1, and the scores sum to 1.
+    /// Schemes with equal scores are ordered by `Scheme`'s `Ord` implementation.
+    ///
+    /// If `input` contains no characters that are meaningful to any scheme (e.g. if it is empty or
+    /// contains only punctuation), this method returns an empty list.
+    pub fn rank(&self, input: impl AsRef<str>) -> Vec<(Scheme, f64)> {
+        let input = unicode_norm::to_nfc(input.as_ref());
+        match self.log_likelihoods(&input) {
+            Some(scores) => {
+                let mut ret = softmax(&scores);
+                ret.sort_by(|x, y| y.1.total_cmp(&x.1).then(x.0.cmp(&y.0)));
+                ret
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Splits `input` into spans that each use a single scheme.
+    ///
+    /// We classify `input` word by word. Since short words are often ambiguous, we prefer to keep
+    /// adjacent words in the same scheme unless the evidence for a switch is strong. Whitespace
+    /// between two spans is not part of either span, and words that are meaningful to no scheme
+    /// (e.g. punctuation or numbers) join the span before them.
+    ///
+    /// If `input` is not NFC-normalized, we still return byte ranges into the original `input`.
+    pub fn segments(&self, input: impl AsRef<str>) -> Vec<SchemeSpan> {
+        let input = input.as_ref();
+
+        let words: Vec<Range<usize>> = split_words(input);
+        let scores: Vec<Option<Vec<(Scheme, f64)>>> = words
+            .iter()
+            .map(|w| self.log_likelihoods(&unicode_norm::to_nfc(&input[w.clone()])))
+            .collect();
+
+        if scores.iter().all(|x| x.is_none()) {
+            return Vec::new();
+        }
+        let labels = self.viterbi(&scores);
+
+        let mut ret: Vec<SchemeSpan> = Vec::new();
+        for (word, scheme) in words.into_iter().zip(labels) {
+            match ret.last_mut() {
+                Some(last) if last.scheme == scheme => last.range.end = word.end,
+                _ => ret.push(SchemeSpan {
+                    range: word,
+                    scheme,
+                }),
+            }
+        }
+        ret
+    }
+
+    /// Returns the log-likelihood of `input` under each scheme's model, or `None` if `input` has
+    /// no meaningful characters.
+    fn log_likelihoods(&self, input: &str) -> Option<Vec<(Scheme, f64)>> {
+        // Ignore characters that no scheme knows about, as they don't help us choose a scheme.
+        let chars: Vec<char> = input
+            .chars()
+            .filter(|c| self.known_chars.contains(c))
+            .collect();
+        if chars.iter().all(|c| c.is_whitespace()) {
+            return None;
+        }
+
+        let scores = self
+            .models
+            .iter()
+            .map(|(scheme, model)| {
+                let mut score = 0.0;
+                let mut prev = (' ', ' ');
+                for c in chars.iter().copied().chain(std::iter::once(' ')) {
+                    score += model.log_prob(prev.0, prev.1, c);
+                    prev = (prev.1, c);
+                }
+                (*scheme, score + prior(*scheme).ln())
+            })
+            .collect();
+        Some(scores)
+    }
+
+    /// Labels each word with its most likely scheme.
+    ///
+    /// `scores` contains the log-likelihoods for each word, or `None` if the word is not
+    /// meaningful to any scheme.
+    fn viterbi(&self, scores: &[Option<Vec<(Scheme, f64)>>]) -> Vec<Scheme> {
+        let n = self.models.len();
+        let mut best = vec![0.0; n];
+        // backpointers[i][s] is the best label for word i - 1 given that word i has label s.
+        let mut backpointers: Vec<Vec<usize>> = Vec::with_capacity(scores.len());
+
+        for (i, word_scores) in scores.iter().enumerate() {
+            let (i_prev_best, prev_best) = argmax(&best);
+            let mut next = vec![0.0; n];
+            let mut pointers = vec![0; n];
+            for s in 0..n {
+                let stay = best[s];
+                let switch = prev_best - SWITCH_COST;
+                let (from, score) = if i == 0 || stay >= switch {
+                    (s, stay)
+                } else {
+                    (i_prev_best, switch)
+                };
+                let emission = word_scores.as_ref().map_or(0.0, |xs| xs[s].1);
+                next[s] = score + emission;
+                pointers[s] = from;
+            }
+            best = next;
+            backpointers.push(pointers);
+        }
+
+        let mut labels = Vec::with_capacity(scores.len());
+        let (mut s, _) = argmax(&best);
+        for pointers in backpointers.iter().rev() {
+            labels.push(self.models[s].0);
+            s = pointers[s];
+        }
+        labels.reverse();
+        labels
+    }
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns short SLP1 words of the form vowel-consonant-vowel.
+///
+/// These words are not real Sanskrit, but they ensure that every model sees every consonant with
+/// a variety of vowel signs, which our seed corpus alone does not guarantee.
+fn syllable_words() -> Vec<String> {
+    let mut ret = Vec::new();
+    for v1 in "aAiIueo".chars() {
+        for c in "kKgGNcCjJYwWqQRtTdDnpPbBmyrlvSzsh".chars() {
+            for v2 in "aAiIuUfeEoO".chars() {
+                ret.push(format!("{v1}{c}{v2}"));
+            }
+        }
+    }
+    ret
+}
+
+/// Returns our prior belief that some text uses `scheme`.
+///
+/// Short strings are often valid in several schemes at once. For example, `kOsalya` is valid in
+/// both SLP1 and Baraha. In these cases, we prefer schemes that are more widely used.
+///
+/// These values are hand-picked, not fitted, and only their ratios matter. They encode a rough
+/// ranking of how often each scheme is used:
+///
+/// - Harvard-Kyoto, IAST, and Devanagari are the most common inputs.
+/// - ISO 15919 is just below IAST, with which it shares most of its text.
+/// - SLP1 and ITRANS are common but less so. Bengali and Burmese share their scripts with
+///   Assamese and Mon, so they must score above those schemes to win ties on shared letters.
+/// - Velthuis, WX, and Baraha reuse most ASCII letters that Harvard-Kyoto and SLP1 use, and they
+///   are rarely used. Their low priors stop them from winning ties on plain ASCII text.
+///
+/// `tests/detector.rs` pins our accuracy with these values on held-out text. On that text, flat
+/// priors read a few more ambiguous ASCII words correctly, but they cannot break ties between
+/// scripts that share letters.
+fn prior(scheme: Scheme) -> f64 {
+    use Scheme::*;
+    match scheme {
+        HarvardKyoto | Iast | Devanagari => 1.0,
+        Slp1 | Itrans | Bengali | Burmese => 0.5,
+        Iso15919 => 0.7,
+        Velthuis => 0.03,
+        Wx | BarahaSouth => 0.05,
+        _ => 0.2,
+    }
+}
+
+/// Returns the index and value of the largest item in `xs`.
+///
+/// For ties, we return the first index.
+fn argmax(xs: &[f64]) -> (usize, f64) {
+    let mut ret = (0, f64::NEG_INFINITY);
+    for (i, x) in xs.iter().enumerate() {
+        if *x > ret.1 {
+            ret = (i, *x);
+        }
+    }
+    ret
+}
+
+/// Converts log-likelihoods to probabilities.
+fn softmax(scores: &[(Scheme, f64)]) -> Vec<(Scheme, f64)> {
+    let max = scores
+        .iter()
+        .map(|(_, x)| *x)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let total: f64 = scores.iter().map(|(_, x)| (x - max).exp()).sum();
+    scores
+        .iter()
+        .map(|(s, x)| (*s, (x - max).exp() / total))
+        .collect()
+}
+
+/// Returns the byte ranges of all whitespace-separated words in `input`.
+fn split_words(input: &str) -> Vec<Range<usize>> {
+    let mut ret = Vec::new();
+    let mut start = None;
+    for (i, c) in input.char_indices() {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                ret.push(s..i);
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => (),
+        }
+    }
+    if let Some(s) = start {
+        ret.push(s..input.len());
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Scheme::*;
+
+    fn top(detector: &Detector, input: &str) -> Scheme {
+        detector.rank(input)[0].0
+    }
+
+    #[test]
+    fn rank_is_normalized_and_sorted() {
+        let d = Detector::new();
+        let ranked = d.rank("saMskRtam");
+        assert_eq!(ranked.len(), Scheme::iter().count());
+
+        let total: f64 = ranked.iter().map(|(_, x)| x).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        for pair in ranked.windows(2) {
+            assert!(pair[0].1 >= pair[1].1);
+        }
+    }
+
+    #[test]
+    fn rank_with_no_evidence() {
+        let d = Detector::new();
+        assert!(d.rank("").is_empty());
+        assert!(d.rank("   ").is_empty());
+    }
+
+    #[test]
+    fn rank_short_ascii_strings() {
+        let d = Detector::new();
+        let cases = [
+            (HarvardKyoto, "kRSNa"),
+            (HarvardKyoto, "zaSThI"),
+            (Itrans, "kRRiShNa"),
+            (Itrans, "shaShThii"),
+            (Velthuis, "k.r.s.na"),
+            (Velthuis, "\"sa.s.thii"),
+            (Slp1, "kfzRa"),
+            (Slp1, "zazWI"),
+            (Wx, "kqRNa"),
+        ];
+        for (expected, input) in cases {
+            assert_eq!(top(&d, input), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn rank_abugidas() {
+        let d = Detector::new();
+        for scheme in Scheme::iter() {
+            if !scheme.is_abugida() || matches!(scheme, Assamese | Mon) {
+                // Assamese and Bengali share a script, as do Mon and Burmese.
+                continue;
+            }
+            let text = transliterate("saMskftam", &Mapping::new(Slp1, *scheme));
+            assert_eq!(top(&d, &text), *scheme, "{text}");
+        }
+    }
+
+    #[test]
+    fn add_sample_changes_ranking() {
+        let mut d = Detector::new();
+        // "xyzzy" is meaningless in every scheme, but samples can teach the model otherwise.
+        d.add_sample(Velthuis, "xyzzy xyzzy xyzzy");
+        assert_eq!(top(&d, "xyzzy"), Velthuis);
+    }
+
+    #[test]
+    fn segments_with_mixed_input() {
+        let d = Detector::new();
+        let input = "The word धर्म (dharma) means duty.";
+        let spans = d.segments(input);
+        let schemes: Vec<_> = spans.iter().map(|s| s.scheme).collect();
+        assert_eq!(schemes.len(), 3, "{spans:?}");
+        assert_eq!(schemes[1], Devanagari);
+        assert_eq!(&input[spans[1].range.clone()], "धर्म");
+        assert_ne!(schemes[0], Devanagari);
+        assert_ne!(schemes[2], Devanagari);
+    }
+
+    #[test]
+    fn segments_with_several_scripts() {
+        let d = Detector::new();
+        let input = "रामः वनं गच्छति ৰামঃ বনং গচ্ছতি ರಾಮಃ ವನಂ ಗಚ್ಛತಿ";
+        let spans = d.segments(input);
+        let actual: Vec<_> = spans
+            .iter()
+            .map(|s| (&input[s.range.clone()], s.scheme))
+            .collect();
+        assert_eq!(
+            actual,
+            vec![
+                ("रामः वनं गच्छति", Devanagari),
+                ("ৰামঃ বনং গচ্ছতি", Assamese),
+                ("ರಾಮಃ ವನಂ ಗಚ್ಛತಿ", Kannada),
+            ]
+        );
+    }
+
+    #[test]
+    fn segments_with_empty_input() {
+        let d = Detector::new();
+        assert!(d.segments("").is_empty());
+    }
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words(""), vec![]);
+        assert_eq!(split_words("  a bc  d "), vec![2..3, 4..6, 8..9]);
+    }
+}
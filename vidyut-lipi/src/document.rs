@@ -0,0 +1,202 @@
+//! Transliterates documents that mix Sanskrit with other text.
+
+use crate::detect::detect;
+use crate::mapping::Mapping;
+use crate::transliterate::transliterate;
+use crate::unicode_norm;
+use rustc_hash::FxHashSet;
+
+/// The marker that starts and ends a run of text in `DocumentMode::Markup`.
+const MARKER: &str = "##";
+
+/// Decides which parts of a document `transliterate_document` should transliterate.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DocumentMode {
+    /// Transliterate only the text between pairs of `##` markers, and remove the markers from
+    /// the output. If the last `##` has no partner, we transliterate until the end of the input.
+    ///
+    /// This is the most reliable mode and the only one that works well for plain ASCII schemes
+    /// like `HarvardKyoto`, which are otherwise hard to distinguish from English.
+    Markup,
+
+    /// Transliterate only the words that use the characters of the source scheme.
+    ///
+    /// A word matches if all of its characters are used by the source scheme and at least one of
+    /// them is outside of ASCII. So this mode works well for scripts like `Devanagari` and for
+    /// schemes with diacritics like `Iast`, but it skips words in plain ASCII, such as `dharma` in
+    /// `Iast`.
+    Script,
+
+    /// Transliterate only the words for which `detect` returns the source scheme.
+    ///
+    /// `detect` uses heuristics that are tuned for Sanskrit, so it will sometimes misclassify
+    /// short words or words in other languages.
+    Detect,
+}
+
+/// Transliterates the runs of `input` that use `mapping`'s source scheme and passes through all
+/// other text as-is.
+///
+/// `mode` controls how we find these runs. In `DocumentMode::Script` and `DocumentMode::Detect`,
+/// we classify `input` word by word, and punctuation at the edges of a word is passed through
+/// unless the source scheme uses it.
+///
+/// ### Usage
+///
+/// ```
+/// use vidyut_lipi::{transliterate_document, DocumentMode, Mapping, Scheme};
+///
+/// let mapping = Mapping::new(Scheme::HarvardKyoto, Scheme::Devanagari);
+/// let input = "The word ##dharma## means duty.";
+/// let output = transliterate_document(input, &mapping, DocumentMode::Markup);
+/// assert_eq!(output, "The word धर्म means duty.");
+///
+/// let mapping = Mapping::new(Scheme::Devanagari, Scheme::Iast);
+/// let input = "The word धर्म means duty.";
+/// let output = transliterate_document(input, &mapping, DocumentMode::Script);
+/// assert_eq!(output, "The word dharma means duty.");
+/// ```
+pub fn transliterate_document(input: &str, mapping: &Mapping, mode: DocumentMode) -> String {
+    match mode {
+        DocumentMode::Markup => transliterate_markup(input, mapping),
+        DocumentMode::Script => {
+            let source_chars = source_chars(mapping);
+            transliterate_words(input, mapping, &source_chars, |word| {
+                word.chars().all(|c| source_chars.contains(&c)) && !word.is_ascii()
+            })
+        }
+        DocumentMode::Detect => {
+            let source_chars = source_chars(mapping);
+            transliterate_words(input, mapping, &source_chars, |word| {
+                detect(word) == Some(mapping.from())
+            })
+        }
+    }
+}
+
+/// Transliterates the text between pairs of `MARKER`s.
+fn transliterate_markup(input: &str, mapping: &Mapping) -> String {
+    let mut ret = String::with_capacity(input.len());
+    for (i, run) in input.split(MARKER).enumerate() {
+        // Runs alternate between plain text and marked text, starting with plain text.
+        if i % 2 == 0 {
+            ret.push_str(run);
+        } else {
+            ret.push_str(&transliterate(run, mapping));
+        }
+    }
+    ret
+}
+
+/// Transliterates each word in `input` for which `is_source` returns true.
+///
+/// Before we call `is_source`, we trim punctuation that is not in `source_chars` from the edges of
+/// the word and normalize the word to NFC.
+fn transliterate_words(
+    input: &str,
+    mapping: &Mapping,
+    source_chars: &FxHashSet<char>,
+    is_source: impl Fn(&str) -> bool,
+) -> String {
+    let is_trimmable = |c: char| !c.is_alphanumeric() && !source_chars.contains(&c);
+
+    let mut ret = String::with_capacity(input.len());
+    let mut rest = input;
+    while !rest.is_empty() {
+        // Copy leading whitespace as-is.
+        let space_len = rest.len() - rest.trim_start().len();
+        ret.push_str(&rest[..space_len]);
+        rest = &rest[space_len..];
+
+        let word_len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let word = &rest[..word_len];
+        rest = &rest[word_len..];
+
+        let core = word.trim_matches(is_trimmable);
+        if core.is_empty() || !is_source(&unicode_norm::to_nfc(core)) {
+            ret.push_str(word);
+            continue;
+        }
+
+        let start = word.len() - word.trim_start_matches(is_trimmable).len();
+        let end = start + core.len();
+        ret.push_str(&word[..start]);
+        ret.push_str(&transliterate(core, mapping));
+        ret.push_str(&word[end..]);
+    }
+    ret
+}
+
+/// Returns all characters used in the source scheme's tokens.
+fn source_chars(mapping: &Mapping) -> FxHashSet<char> {
+    mapping.all.keys().flat_map(|k| k.chars()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::Scheme::*;
+    use DocumentMode::*;
+
+    fn t(input: &str, mapping: &Mapping, mode: DocumentMode) -> String {
+        transliterate_document(input, mapping, mode)
+    }
+
+    #[test]
+    fn markup() {
+        let m = Mapping::new(HarvardKyoto, Devanagari);
+        assert_eq!(t("", &m, Markup), "");
+        assert_eq!(t("no markup", &m, Markup), "no markup");
+        assert_eq!(t("##rAma##", &m, Markup), "राम");
+        assert_eq!(
+            t("Both ##rAma## and ##sItA## appear.", &m, Markup),
+            "Both राम and सीता appear."
+        );
+        // Marked text can span several words.
+        assert_eq!(t("##rAmaH vanaM gacchati##", &m, Markup), "रामः वनं गच्छति");
+        // An unpaired marker runs until the end of the input.
+        assert_eq!(t("See ##rAma", &m, Markup), "See राम");
+        // An empty pair is removed.
+        assert_eq!(t("a####b", &m, Markup), "ab");
+    }
+
+    #[test]
+    fn script_with_devanagari() {
+        let m = Mapping::new(Devanagari, Iast);
+        assert_eq!(
+            t("The word धर्म means duty.", &m, Script),
+            "The word dharma means duty."
+        );
+        assert_eq!(
+            t("Quote: \"रामः वनं गच्छति।\"", &m, Script),
+            "Quote: \"rāmaḥ vanaṃ gacchati.\""
+        );
+        // Whitespace is preserved exactly.
+        assert_eq!(t("  राम\n\tसीता  ", &m, Script), "  rāma\n\tsītā  ");
+    }
+
+    #[test]
+    fn script_with_iast() {
+        let m = Mapping::new(Iast, Devanagari);
+        assert_eq!(
+            t("Arjuna asks Kṛṣṇa (kṛṣṇa) for advice.", &m, Script),
+            "Arjuna asks Kṛṣṇa (कृष्ण) for advice."
+        );
+        // Words that use other diacritics are not IAST.
+        assert_eq!(t("a café in Göttingen", &m, Script), "a café in Göttingen");
+        // Plain ASCII words are ambiguous, so we pass them through.
+        assert_eq!(t("dharma", &m, Script), "dharma");
+    }
+
+    #[test]
+    fn detect_mode() {
+        let m = Mapping::new(Iast, Devanagari);
+        assert_eq!(
+            t("The goddess śrī (śrī) appears.", &m, Detect),
+            "The goddess श्री (श्री) appears."
+        );
+
+        let m = Mapping::new(Devanagari, Iast);
+        assert_eq!(t("धर्म, or dharma", &m, Detect), "dharma, or dharma");
+    }
+}
@@ -5,11 +5,23 @@ use std::fmt;
 pub type Result<T> = std::result::Result<T, LipiError>;
 
 /// Models the error states of `vidyut-lipi`.
+///
+/// `LipiError` is `Copy`, so its variants refer to the offending text by position instead of
+/// owning a copy of it. Errors in custom scheme definitions need to report the offending keys, so
+/// we report them with `SchemeError` instead.
 #[allow(unused)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum LipiError {
     /// Could not parse an input value.
     ParseError,
+
+    /// A lossless transliteration found text that would not survive a round trip.
+    LossyToken {
+        /// The byte offset in the input where the text starts.
+        start: usize,
+        /// The byte offset in the input where the text ends.
+        end: usize,
+    },
 }
 
 impl std::error::Error for LipiError {}
@@ -20,6 +32,123 @@ impl fmt::Display for LipiError {
 
         match self {
             ParseError => write!(f, "parse error"),
+            LossyToken { start, end } => {
+                write!(
+                    f,
+                    "text at {start}..{end} cannot be transliterated losslessly"
+                )
+            }
+        }
+    }
+}
+
+/// Models the ways in which a `CustomScheme` definition can be invalid.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SchemeError {
+    /// The definition is not valid JSON or TOML.
+    Syntax {
+        /// The parser's description of the error.
+        message: String,
+        /// The line where the error occurs, starting from 1.
+        line: usize,
+        /// The column where the error occurs, starting from 1.
+        column: usize,
+    },
+
+    /// The definition is valid JSON or TOML but does not have the fields that we expect.
+    InvalidDefinition(&'static str),
+
+    /// A custom scheme contains a token with an empty key.
+    EmptyKey {
+        /// The index of the token in the scheme's token list.
+        index: usize,
+    },
+
+    /// A custom scheme maps `key` to the empty string.
+    ///
+    /// Only the virama may map to the empty string.
+    EmptyValue {
+        /// The index of the token in the scheme's token list.
+        index: usize,
+        /// The key that maps to the empty string.
+        key: String,
+    },
+
+    /// A custom scheme contains a key that is not in Devanagari.
+    NonDevanagariKey {
+        /// The index of the token in the scheme's token list.
+        index: usize,
+        /// The offending key.
+        key: String,
+    },
+
+    /// A custom scheme contains the same token more than once.
+    DuplicateToken {
+        /// The index of the second copy of the token in the scheme's token list.
+        index: usize,
+        /// The token's key.
+        key: String,
+        /// The token's value.
+        value: String,
+    },
+
+    /// A custom scheme maps two different keys to the same value, which means that we cannot
+    /// transliterate from this scheme unambiguously.
+    AmbiguousValue {
+        /// The index of the second token that uses `value` in the scheme's token list.
+        index: usize,
+        /// The shared value.
+        value: String,
+        /// The first key that uses `value`.
+        first: String,
+        /// The second key that uses `value`.
+        second: String,
+    },
+
+    /// A custom scheme does not define a token that it needs.
+    MissingToken {
+        /// The key of the missing token.
+        key: &'static str,
+    },
+}
+
+impl std::error::Error for SchemeError {}
+
+impl fmt::Display for SchemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use SchemeError::*;
+
+        match self {
+            Syntax {
+                message,
+                line,
+                column,
+            } => write!(
+                f,
+                "invalid scheme definition at line {line}, column {column}: {message}"
+            ),
+            InvalidDefinition(msg) => write!(f, "invalid scheme definition: {msg}"),
+            EmptyKey { index } => write!(f, "token {index} has an empty key"),
+            EmptyValue { index, key } => {
+                write!(f, "token {index}: key `{key}` maps to an empty value")
+            }
+            NonDevanagariKey { index, key } => {
+                write!(f, "token {index}: key `{key}` is not in Devanagari")
+            }
+            DuplicateToken { index, key, value } => write!(
+                f,
+                "token {index}: `{key}` -> `{value}` is defined more than once"
+            ),
+            AmbiguousValue {
+                index,
+                value,
+                first,
+                second,
+            } => write!(
+                f,
+                "token {index}: value `{value}` is used by both `{first}` and `{second}`"
+            ),
+            MissingToken { key } => write!(f, "scheme must define key `{key}`"),
         }
     }
 }
@@ -2,20 +2,37 @@
 #![deny(missing_docs)]
 #![deny(clippy::unwrap_used)]
 
+mod align;
 mod autogen_schemes;
+mod collation;
+mod custom_scheme;
 mod detect;
+mod detector;
+mod document;
 mod errors;
 mod lipika;
+mod lossless;
 mod mapping;
 mod numerals;
+mod phonetic;
 mod reshape;
 mod scheme;
+mod stream;
 mod transliterate;
 mod unicode_norm;
 pub mod wasm;
 
+pub use align::Alignment;
+pub use collation::{AnusvaraPlacement, Collator, SortKey, VisargaPlacement};
+pub use custom_scheme::CustomScheme;
 pub use detect::detect;
+pub use detector::{Detector, SchemeSpan};
+pub use document::{transliterate_document, DocumentMode};
+pub use errors::{LipiError, SchemeError};
 pub use lipika::Lipika;
+pub use lossless::{transliterate_lossless, LosslessMode, ESCAPE_END, ESCAPE_START};
 pub use mapping::Mapping;
-pub use scheme::Scheme;
-pub use transliterate::transliterate;
+pub use phonetic::PhoneticNormalizer;
+pub use scheme::{Scheme, VedicAccent};
+pub use stream::{transliterate_stream, StreamTransliterator};
+pub use transliterate::{transliterate, transliterate_with_alignment};
@@ -1,5 +1,6 @@
 //! Provides a convenient transliteration API for end users.
 
+use crate::document::{transliterate_document, DocumentMode};
 use crate::mapping::Mapping;
 use crate::scheme::Scheme;
 use crate::transliterate::transliterate;
@@ -82,6 +83,38 @@ impl Lipika {
         transliterate(input.as_ref(), mapping)
     }
 
+    /// Transliterates the given document, which might mix text in `from` with other text.
+    ///
+    /// Only the runs of text that use `from` are transliterated, and all other text is passed
+    /// through as-is. `mode` controls how we find these runs. For details, see the comments on
+    /// the `transliterate_document` function.
+    ///
+    /// ### Usage
+    ///
+    /// ```
+    /// use vidyut_lipi::{DocumentMode, Lipika, Scheme};
+    ///
+    /// let mut lipika = Lipika::new();
+    /// let input = "Arjuna asks kṛṣṇa for advice.";
+    /// let output = lipika.transliterate_document(
+    ///     input,
+    ///     Scheme::Iast,
+    ///     Scheme::Devanagari,
+    ///     DocumentMode::Script,
+    /// );
+    /// assert_eq!(output, "Arjuna asks कृष्ण for advice.");
+    /// ```
+    pub fn transliterate_document(
+        &mut self,
+        input: impl AsRef<str>,
+        from: Scheme,
+        to: Scheme,
+        mode: DocumentMode,
+    ) -> String {
+        let mapping = self.find_or_create_mapping(from, to);
+        transliterate_document(input.as_ref(), mapping, mode)
+    }
+
     /// Finds an existing mapping to reuse, or creates one if absent.
     ///
     /// This code assumes that a `Mapping` is a pure function of `from` and `to`.
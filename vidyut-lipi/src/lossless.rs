@@ -0,0 +1,269 @@
+//! Transliteration that guarantees an exact round trip.
+
+use crate::align::Alignment;
+use crate::errors::{LipiError, Result};
+use crate::mapping::Mapping;
+use crate::transliterate::{transliterate, transliterate_with_alignment};
+use crate::unicode_norm::to_nfc;
+
+/// Starts an escape sequence in `LosslessMode::Escape`.
+pub const ESCAPE_START: char = '⟦';
+
+/// Ends an escape sequence in `LosslessMode::Escape`.
+pub const ESCAPE_END: char = '⟧';
+
+/// Decides what `transliterate_lossless` does with text that it cannot transliterate faithfully.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LosslessMode {
+    /// Return `LipiError::LossyToken` for the first span of text that would not survive a round
+    /// trip.
+    Strict,
+
+    /// Copy each span of text that would not survive a round trip to the output as-is, wrapped
+    /// in `ESCAPE_START` and `ESCAPE_END`.
+    ///
+    /// When we transliterate the output back with `LosslessMode::Escape`, we unwrap these escape
+    /// sequences and reproduce the original text exactly.
+    Escape,
+}
+
+/// Transliterates `input` so that transliterating the output back to the source scheme reproduces
+/// `input` exactly, up to Unicode normalization.
+///
+/// Some mappings lose information. For example, Bengali uses the same letter for `व` and `ब`, so
+/// we cannot know whether `ব` should become `व` or `ब`. `mode` decides how we handle text like
+/// `व`: `LosslessMode::Strict` refuses to transliterate it, and `LosslessMode::Escape` passes it
+/// through in an escape sequence.
+///
+/// In both modes, we unwrap any escape sequences in `input` and copy their contents to the output
+/// as-is. So `input` should not otherwise contain `ESCAPE_START` or `ESCAPE_END`.
+///
+/// To list which tokens in a mapping are lossy, use `Mapping::lossy_tokens`.
+///
+/// ### Usage
+///
+/// ```
+/// use vidyut_lipi::{transliterate_lossless, LipiError, LosslessMode, Mapping, Scheme};
+///
+/// let to_bengali = Mapping::new(Scheme::Devanagari, Scheme::Bengali);
+/// let from_bengali = Mapping::new(Scheme::Bengali, Scheme::Devanagari);
+///
+/// let bengali = transliterate_lossless("बल", &to_bengali, LosslessMode::Strict).unwrap();
+/// assert_eq!(bengali, "বল");
+///
+/// let err = transliterate_lossless("वन", &to_bengali, LosslessMode::Strict);
+/// assert_eq!(err, Err(LipiError::LossyToken { start: 0, end: 3 }));
+///
+/// let bengali = transliterate_lossless("वन", &to_bengali, LosslessMode::Escape).unwrap();
+/// assert_eq!(bengali, "⟦व⟧ন");
+///
+/// let deva = transliterate_lossless(&bengali, &from_bengali, LosslessMode::Escape).unwrap();
+/// assert_eq!(deva, "वन");
+/// ```
+pub fn transliterate_lossless(
+    input: impl AsRef<str>,
+    mapping: &Mapping,
+    mode: LosslessMode,
+) -> Result<String> {
+    let input = input.as_ref();
+    let reverse = mapping.reverse();
+
+    let mut ret = String::with_capacity(input.len());
+    let mut rest = input;
+    while !rest.is_empty() {
+        let offset = input.len() - rest.len();
+        let (text, escaped, next) = split_escape(rest);
+        transliterate_checked(text, offset, mapping, &reverse, mode, &mut ret)?;
+        ret.push_str(escaped);
+        rest = next;
+    }
+    Ok(ret)
+}
+
+/// Returns whether `source` and its transliteration `target` survive a round trip in both
+/// directions.
+///
+/// We check both directions so that `target` also survives when we transliterate it back with
+/// `transliterate_lossless`.
+pub(crate) fn round_trips(
+    source: &str,
+    target: &str,
+    mapping: &Mapping,
+    reverse: &Mapping,
+) -> bool {
+    let same = |x: &str, y: &str| x == y || to_nfc(x) == to_nfc(y);
+    same(&transliterate(target, reverse), source) && same(&transliterate(source, mapping), target)
+}
+
+/// Splits `input` into three parts: the text before the first escape sequence, the contents of
+/// that escape sequence, and the text after it.
+///
+/// An escape sequence without an end runs until the end of `input`.
+fn split_escape(input: &str) -> (&str, &str, &str) {
+    match input.split_once(ESCAPE_START) {
+        Some((text, rest)) => match rest.split_once(ESCAPE_END) {
+            Some((escaped, rest)) => (text, escaped, rest),
+            None => (text, rest, ""),
+        },
+        None => (input, "", ""),
+    }
+}
+
+/// Transliterates `input`, which contains no escape sequences, and appends the result to `ret`.
+///
+/// `offset` is the byte offset of `input` in the text that the caller passed to
+/// `transliterate_lossless`, which we use to report the position of lossy text.
+fn transliterate_checked(
+    input: &str,
+    offset: usize,
+    mapping: &Mapping,
+    reverse: &Mapping,
+    mode: LosslessMode,
+    ret: &mut String,
+) -> Result<()> {
+    if input.is_empty() {
+        return Ok(());
+    }
+
+    let (output, alignments) = transliterate_with_alignment(input, mapping);
+    let span = |xs: &[Alignment]| match (xs.first(), xs.last()) {
+        (Some(x), Some(y)) => x.source.start..y.source.end,
+        _ => 0..0,
+    };
+    let source = |xs: &[Alignment]| &input[span(xs)];
+    let target = |xs: &[Alignment]| match (xs.first(), xs.last()) {
+        (Some(x), Some(y)) => &output[x.target.start..y.target.end],
+        _ => "",
+    };
+    let escape = |xs: &[Alignment], ret: &mut String| match mode {
+        LosslessMode::Strict => Err(LipiError::LossyToken {
+            start: offset + span(xs).start,
+            end: offset + span(xs).end,
+        }),
+        LosslessMode::Escape => {
+            ret.push(ESCAPE_START);
+            ret.push_str(source(xs));
+            ret.push(ESCAPE_END);
+            Ok(())
+        }
+    };
+
+    // A span might survive a round trip in isolation and still fail in context. So, check each
+    // word as a whole, and check its individual spans only if the word fails.
+    for word in
+        alignments.split_inclusive(|a| input[a.source.clone()].ends_with(char::is_whitespace))
+    {
+        if round_trips(source(word), target(word), mapping, reverse) {
+            ret.push_str(target(word));
+            continue;
+        }
+
+        // Escape the spans that fail. Spans that pass are joined into pieces, and we check each
+        // piece as a whole, since that is how we will read it back.
+        let ok = |a: &Alignment| {
+            let (x, y) = (&input[a.source.clone()], &output[a.target.clone()]);
+            x.is_empty() || round_trips(x, y, mapping, reverse)
+        };
+        let mut rest = word;
+        while let Some(first) = rest.first() {
+            if ok(first) {
+                let n = rest.iter().position(|a| !ok(a)).unwrap_or(rest.len());
+                let piece = &rest[..n];
+                if round_trips(source(piece), target(piece), mapping, reverse) {
+                    ret.push_str(target(piece));
+                } else {
+                    escape(piece, ret)?;
+                }
+                rest = &rest[n..];
+            } else {
+                escape(&rest[..1], ret)?;
+                rest = &rest[1..];
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::Scheme;
+    use crate::scheme::Scheme::*;
+
+    fn escape(input: &str, from: Scheme, to: Scheme) -> String {
+        let mapping = Mapping::new(from, to);
+        transliterate_lossless(input, &mapping, LosslessMode::Escape).expect("ok")
+    }
+
+    fn strict(input: &str, from: Scheme, to: Scheme) -> Result<String> {
+        let mapping = Mapping::new(from, to);
+        transliterate_lossless(input, &mapping, LosslessMode::Strict)
+    }
+
+    #[test]
+    fn strict_with_lossless_input() {
+        assert_eq!(
+            strict("rAmaH", HarvardKyoto, Devanagari),
+            Ok("रामः".to_string())
+        );
+        assert_eq!(strict("बल", Devanagari, Bengali), Ok("বল".to_string()));
+    }
+
+    #[test]
+    fn strict_with_lossy_input() {
+        assert_eq!(
+            strict("वन", Devanagari, Bengali),
+            Err(LipiError::LossyToken { start: 0, end: 3 })
+        );
+        // ITRANS has two spellings for `A`, so we cannot reproduce `aa` exactly. Since `r` and `aa`
+        // combine into a single akshara, we report both.
+        assert_eq!(
+            strict("raama", Itrans, Devanagari),
+            Err(LipiError::LossyToken { start: 0, end: 3 })
+        );
+        // Offsets are relative to the full input, including any escape sequences before the
+        // lossy text.
+        assert_eq!(
+            strict("⟦ব⟧वन", Devanagari, Bengali),
+            Err(LipiError::LossyToken { start: 9, end: 12 })
+        );
+    }
+
+    #[test]
+    fn escape_with_lossy_input() {
+        assert_eq!(escape("वन", Devanagari, Bengali), "⟦व⟧ন");
+        assert_eq!(escape("बलवान्", Devanagari, Bengali), "বল⟦व⟧\u{9be}ন\u{9cd}");
+        assert_eq!(escape("", Devanagari, Bengali), "");
+        // Escape sequences in the input are copied to the output as-is.
+        assert_eq!(escape("⟦व⟧ন", Bengali, Devanagari), "वन");
+        // So are unterminated escape sequences.
+        assert_eq!(escape("ন⟦व", Bengali, Devanagari), "नव");
+    }
+
+    #[test]
+    fn escape_round_trips_for_all_schemes() {
+        let samples = [
+            "धर्मक्षेत्रे कुरुक्षेत्रे समवेता युयुत्सवः ।",
+            "क्लृप्तं पितॄणां कृत्यम् औषधीनां च ऐक्यं शङ्करः",
+        ];
+        for from in Scheme::iter() {
+            for to in Scheme::iter() {
+                let forward = Mapping::new(*from, *to);
+                let backward = Mapping::new(*to, *from);
+                for sample in samples {
+                    let input = transliterate(sample, &Mapping::new(Devanagari, *from));
+                    let output =
+                        transliterate_lossless(&input, &forward, LosslessMode::Escape).expect("ok");
+                    let round_trip =
+                        transliterate_lossless(&output, &backward, LosslessMode::Escape)
+                            .expect("ok");
+                    assert_eq!(
+                        to_nfc(&input),
+                        to_nfc(&round_trip),
+                        "{from:?} -> {to:?}: {output}"
+                    );
+                }
+            }
+        }
+    }
+}
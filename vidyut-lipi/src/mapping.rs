@@ -1,6 +1,9 @@
 //! Creates maps between different schemes.   
 
+use crate::custom_scheme::CustomScheme;
+use crate::lossless::round_trips;
 use crate::scheme::Scheme;
+use crate::transliterate::transliterate;
 use rustc_hash::{FxHashMap, FxHashSet};
 
 /// A mapping between a span of input text and a span of output text.
@@ -73,6 +76,10 @@ impl SpanKind {
 
         const SVARITA: char = '\u{0951}';
         const ANUDATTA: char = '\u{0952}';
+        const PRENKHA: char = '\u{1cd2}';
+        const DIRGHA_SVARITA: char = '\u{1cda}';
+        const DOT_BELOW: char = '\u{1cdd}';
+        const ATHARVAVEDA_INDEPENDENT_SVARITA: char = '\u{1ce1}';
 
         if let Some(c) = s.chars().last() {
             match c {
@@ -81,7 +88,12 @@ impl SpanKind {
                 }
                 (MARK_AA..=MARK_AU) | MARK_PRISHTAMATRA_E | MARK_AW | MARK_L | MARK_LL => VowelMark,
                 CANDRABINDU..=VISARGA => Ayogavaha,
-                SVARITA | ANUDATTA => Accent,
+                SVARITA
+                | ANUDATTA
+                | PRENKHA
+                | DIRGHA_SVARITA
+                | DOT_BELOW
+                | ATHARVAVEDA_INDEPENDENT_SVARITA => Accent,
                 _ => Other,
             }
         } else {
@@ -93,8 +105,10 @@ impl SpanKind {
 /// A one-way mapping from our IR to some `Scheme`.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub(crate) struct OneWayMapping {
-    /// The scheme being mapped to.
+    /// The scheme being mapped to. For a `CustomScheme`, this is the custom scheme's base.
     to_scheme: Scheme,
+    /// The (IR, value) pairs that define this mapping, in their original order.
+    pairs: Vec<(String, String)>,
     /// Maps from IR to all options available in the given scheme.
     data: FxHashMap<String, Vec<String>>,
     /// Maps from this scheme's digit chars to their numeric values.
@@ -107,6 +121,17 @@ pub(crate) struct OneWayMapping {
 
 impl OneWayMapping {
     fn new(scheme: Scheme) -> Self {
+        let pairs = scheme
+            .token_pairs()
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Self::from_pairs(scheme, pairs)
+    }
+
+    /// Creates a mapping from the given `pairs`, using `scheme` for all other scheme-specific
+    /// behavior.
+    fn from_pairs(scheme: Scheme, pairs: Vec<(String, String)>) -> Self {
         const DEVA_VIRAMA: &str = "\u{094d}";
         let mut data = FxHashMap::default();
         let mut virama = String::new();
@@ -117,7 +142,7 @@ impl OneWayMapping {
             unicode_alts.insert(nfd, vec![nfc, nfd]);
         }
 
-        for (deva_key, value) in scheme.token_pairs() {
+        for (deva_key, value) in &pairs {
             let key = deva_key.to_string();
             if key == DEVA_VIRAMA {
                 virama += value;
@@ -169,7 +194,7 @@ impl OneWayMapping {
             ("\u{096e}", 8),
             ("\u{096f}", 9),
         ];
-        for (deva_key, value) in scheme.token_pairs() {
+        for (deva_key, value) in &pairs {
             for (digit, num) in DIGITS {
                 if deva_key == digit {
                     numeral_to_int.insert(value.to_string(), *num);
@@ -187,6 +212,7 @@ impl OneWayMapping {
 
         Self {
             to_scheme: scheme,
+            pairs,
             data,
             numeral_to_int,
             virama,
@@ -301,19 +327,73 @@ impl Mapping {
     ///    where `|` is again an SLP1 character and `ळ` is not defined in A. In this case, we
     ///    transliterate `x` to scheme `A` then programmatically create a new `a --> b` mapping.
     pub fn new(from: Scheme, to: Scheme) -> Mapping {
-        // Since `new` is a public API, our parameter names use `from` and `to`. Internally, use
-        // `a` and `b`, per our doc comments above.
+        Self::from_one_way_mappings(OneWayMapping::new(from), OneWayMapping::new(to))
+    }
+
+    /// Creates a mapping between the given `CustomScheme`s.
+    ///
+    /// Every built-in `Scheme` can be converted to a `CustomScheme`, so this function also
+    /// supports mappings between a custom scheme and a built-in one.
+    ///
+    /// ### Usage
+    ///
+    /// ```
+    /// use vidyut_lipi::{transliterate, CustomScheme, Mapping, Scheme};
+    ///
+    /// let json = r#"{
+    ///     "name": "HouseStyle",
+    ///     "base": "Iast",
+    ///     "tokens": [
+    ///         ["अ", "a"], ["आ", "aa"], ["ा", "aa"], ["्", ""],
+    ///         ["क", "k"], ["त", "t"], ["म", "m"], ["स", "s"], ["ं", "ṁ"], ["ृ", "ṛ"]
+    ///     ]
+    /// }"#;
+    /// let house = CustomScheme::from_json(json).unwrap();
+    ///
+    /// let m = Mapping::new_custom(&Scheme::HarvardKyoto.into(), &house);
+    /// assert_eq!(transliterate("saMskRtam", &m), "saṁskṛtam");
+    ///
+    /// let m = Mapping::new_custom(&house, &Scheme::Devanagari.into());
+    /// assert_eq!(transliterate("saṁskṛtam", &m), "संस्कृतम्");
+    /// ```
+    ///
+    /// A mapping that uses a custom scheme otherwise behaves like a mapping that uses the custom
+    /// scheme's base. In particular, `from()` and `to()` return the base scheme.
+    pub fn new_custom(from: &CustomScheme, to: &CustomScheme) -> Mapping {
+        Self::from_one_way_mappings(
+            OneWayMapping::from_pairs(from.base(), from.tokens().to_vec()),
+            OneWayMapping::from_pairs(to.base(), to.tokens().to_vec()),
+        )
+    }
 
-        let a_map = OneWayMapping::new(from);
-        let b_map = OneWayMapping::new(to);
+    /// Creates a mapping from `from` to `to` that uses `to_pairs` as the token table for `to`.
+    ///
+    /// The order of `to_pairs` decides which value we prefer when a key has more than one value.
+    pub(crate) fn with_target_pairs(
+        from: Scheme,
+        to: Scheme,
+        to_pairs: Vec<(String, String)>,
+    ) -> Mapping {
+        Self::from_one_way_mappings(
+            OneWayMapping::new(from),
+            OneWayMapping::from_pairs(to, to_pairs),
+        )
+    }
+
+    /// Creates a mapping that joins `a_map` and `b_map`.
+    fn from_one_way_mappings(a_map: OneWayMapping, b_map: OneWayMapping) -> Mapping {
+        // Since `new` is a public API, its parameter names use `from` and `to`. Internally, use
+        // `a` and `b`, per our doc comments above.
+        let from = a_map.to_scheme;
+        let to = b_map.to_scheme;
 
         let mut all = FxHashMap::default();
         let mut marks = FxHashMap::default();
         let mut seen_b: FxHashSet<&str> = FxHashSet::default();
 
-        // Iterate over `from.token_pairs()` so that we maintain a predictable input order.
-        for (deva_key, _) in from.token_pairs() {
-            // But, use the values in `a_map` instead of the values from `token_pairs` so that we
+        // Iterate over `a_map.pairs` so that we maintain a predictable input order.
+        for (deva_key, _) in &a_map.pairs {
+            // But, use the values in `a_map.data` instead of the values from `pairs` so that we
             // pick up Unicode equivalents.
             for a in a_map.get(deva_key).expect("present") {
                 let bs = match b_map.get(deva_key) {
@@ -346,9 +426,9 @@ impl Mapping {
             }
         }
 
-        for (deva_key, a) in from.token_pairs() {
+        for (deva_key, a) in &a_map.pairs {
             let token_kind = SpanKind::from_devanagari_key(deva_key);
-            if !all.contains_key(*a) && b_map.get(deva_key).is_none() {
+            if !all.contains_key(a) && b_map.get(deva_key).is_none() {
                 // Mapping `a --> x` doesn't have a corresponding `x --> b`.
                 // So, create one.
                 let new_b = match b_map.transliterate_key(deva_key) {
@@ -363,8 +443,8 @@ impl Mapping {
             }
         }
 
-        for (deva_key, b) in to.token_pairs() {
-            if seen_b.contains(b) {
+        for (deva_key, b) in &b_map.pairs {
+            if seen_b.contains(b.as_str()) {
                 continue;
             }
 
@@ -419,15 +499,71 @@ impl Mapping {
     }
 
     /// The source scheme.
+    ///
+    /// If the mapping was created with `new_custom`, this is the base of the source scheme.
     pub fn from(&self) -> Scheme {
         self.from
     }
 
     /// The destination scheme.
+    ///
+    /// If the mapping was created with `new_custom`, this is the base of the destination scheme.
     pub fn to(&self) -> Scheme {
         self.to
     }
 
+    /// Returns the source tokens that do not survive a round trip through this mapping, sorted in
+    /// lexicographic order.
+    ///
+    /// A token survives a round trip if transliterating it to the destination scheme and back
+    /// again reproduces the original token, up to Unicode normalization. Tokens fail if the
+    /// destination scheme lacks the corresponding sound or if the source scheme has more than one
+    /// spelling for that sound.
+    ///
+    /// To transliterate without losing these tokens, use `transliterate_lossless`.
+    ///
+    /// ### Usage
+    ///
+    /// ```
+    /// use vidyut_lipi::{Mapping, Scheme};
+    ///
+    /// let m = Mapping::new(Scheme::Devanagari, Scheme::Bengali);
+    /// let lossy = m.lossy_tokens();
+    /// assert!(lossy.contains(&"व"));
+    /// assert!(!lossy.contains(&"ब"));
+    /// ```
+    pub fn lossy_tokens(&self) -> Vec<&str> {
+        let reverse = self.reverse();
+        // A vowel mark must follow a consonant, so test it after one.
+        let consonant = self
+            .from_map
+            .get("क")
+            .and_then(|xs| xs.first())
+            .map_or("", |x| x.as_str());
+
+        let mut ret: Vec<&str> = self
+            .all
+            .values()
+            .filter(|span| {
+                let text = if span.is_mark() {
+                    format!("{consonant}{}", span.key)
+                } else {
+                    span.key.clone()
+                };
+                let output = transliterate(&text, self);
+                !span.key.is_empty() && !round_trips(&text, &output, self, &reverse)
+            })
+            .map(|span| span.key.as_str())
+            .collect();
+        ret.sort();
+        ret
+    }
+
+    /// Returns a mapping in the opposite direction.
+    pub(crate) fn reverse(&self) -> Mapping {
+        Self::from_one_way_mappings(self.to_map.clone(), self.from_map.clone())
+    }
+
     pub(crate) fn get(&self, key: &str) -> Option<&Span> {
         self.all.get(key)
     }
@@ -509,6 +645,25 @@ mod tests {
         assert_eq!(deva.transliterate_key("ळ्ह"), Some("ळ्ह".to_string()));
     }
 
+    #[test]
+    fn test_lossy_tokens() {
+        // HK has exactly one spelling per sound, and Devanagari has every sound.
+        assert!(Mapping::new(HarvardKyoto, Devanagari)
+            .lossy_tokens()
+            .is_empty());
+
+        let to_bengali = Mapping::new(Devanagari, Bengali);
+        let lossy = to_bengali.lossy_tokens();
+        assert!(lossy.contains(&"व"));
+        assert!(!lossy.contains(&"ब"));
+        assert!(!lossy.contains(&"\u{093e}"));
+
+        let from_itrans = Mapping::new(Itrans, Devanagari);
+        let lossy = from_itrans.lossy_tokens();
+        assert!(lossy.contains(&"aa"));
+        assert!(!lossy.contains(&"A"));
+    }
+
     #[test]
     fn test_mapping() {
         let other = |x: &str, y: &str| Span::new(x.to_string(), y.to_string(), SpanKind::Other);
@@ -0,0 +1,339 @@
+//! Creates search keys that are the same for different spellings of a word.
+
+use crate::detect::detect;
+use crate::lipika::Lipika;
+use crate::scheme::Scheme;
+
+/// Creates phonetic search keys for Sanskrit text.
+///
+/// Users often type Sanskrit in an informal romanization: `krishna`, `krsna`, and `kRSNa` all
+/// refer to `कृष्ण`. `PhoneticNormalizer` maps each of these spellings to the same key, so that
+/// all of them match the same entry in a search index.
+///
+/// To create a key, we transliterate the input to SLP1 then fold similar sounds together. Each
+/// `with_fold_*` method controls one group of sounds, and all groups are folded by default. Keys
+/// are meant for lookup only, and a key might not be valid SLP1.
+///
+/// ### Usage
+///
+/// ```
+/// use vidyut_lipi::PhoneticNormalizer;
+///
+/// let mut normalizer = PhoneticNormalizer::new();
+/// let key = normalizer.key("kṛṣṇa");
+/// assert_eq!(key, "krsna");
+/// assert_eq!(normalizer.key("Krishna"), key);
+/// assert_eq!(normalizer.key("krsna"), key);
+/// assert_eq!(normalizer.key("kRSNa"), key);
+/// assert_eq!(normalizer.key("कृष्ण"), key);
+/// ```
+///
+/// ### Input schemes
+///
+/// If the input scheme is not set with `with_scheme`, we guess it for each input:
+///
+/// - We read lowercase ASCII (ignoring the first letter of each word) as `Itrans`, which is
+///   closest to how most people type Sanskrit informally (`sh`, `ch`, `aa`, `ee`, ...).
+/// - We read all other text with the scheme that `detect` returns.
+#[derive(Clone, Default)]
+pub struct PhoneticNormalizer {
+    lipika: Lipika,
+    scheme: Option<Scheme>,
+    keep_aspirates: bool,
+    keep_sibilants: bool,
+    keep_nasals: bool,
+    keep_retroflexes: bool,
+    keep_vowel_length: bool,
+    keep_vocalic_r: bool,
+}
+
+impl PhoneticNormalizer {
+    /// Creates a normalizer that guesses the scheme of each input and folds all sound groups.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads all input with `scheme` instead of guessing it.
+    pub fn with_scheme(mut self, scheme: Scheme) -> Self {
+        self.scheme = Some(scheme);
+        self
+    }
+
+    /// Sets whether to fold aspirated stops into unaspirated ones (`kh` to `k`, `dh` to `d`).
+    pub fn with_fold_aspirates(mut self, fold: bool) -> Self {
+        self.keep_aspirates = !fold;
+        self
+    }
+
+    /// Sets whether to fold all sibilants into `s` (`ś` and `ṣ` to `s`).
+    pub fn with_fold_sibilants(mut self, fold: bool) -> Self {
+        self.keep_sibilants = !fold;
+        self
+    }
+
+    /// Sets whether to fold nasals together.
+    ///
+    /// We fold `ṅ`, `ñ`, and `ṇ` into `n` and the anusvara `ṃ` into `m`. Before a consonant,
+    /// informal spellings use `m` and `n` interchangeably (`samskrta`, `sanskrit`), so there we fold
+    /// all nasals into `n`.
+    pub fn with_fold_nasals(mut self, fold: bool) -> Self {
+        self.keep_nasals = !fold;
+        self
+    }
+
+    /// Sets whether to fold retroflex stops into dental ones (`ṭ` to `t`, `ḍh` to `dh`).
+    pub fn with_fold_retroflexes(mut self, fold: bool) -> Self {
+        self.keep_retroflexes = !fold;
+        self
+    }
+
+    /// Sets whether to fold long vowels into short ones (`ā` to `a`, `ī` to `i`).
+    pub fn with_fold_vowel_length(mut self, fold: bool) -> Self {
+        self.keep_vowel_length = !fold;
+        self
+    }
+
+    /// Sets whether to fold vocalic `ṛ` and its informal spelling `ri` into `r`.
+    ///
+    /// We fold `ri` only where `ṛ` could occur: after a consonant and before a consonant or the
+    /// end of a word, as in `krishna` and `pitri`. Since `ṛ` rarely comes before `y` or `v`, we
+    /// keep `ri` there, as in `priya`.
+    pub fn with_fold_vocalic_r(mut self, fold: bool) -> Self {
+        self.keep_vocalic_r = !fold;
+        self
+    }
+
+    /// Returns the search key for `text`.
+    ///
+    /// Words in the key are separated by a single space, and punctuation is removed.
+    pub fn key(&mut self, text: impl AsRef<str>) -> String {
+        let text = text.as_ref();
+        let (scheme, text) = match self.scheme {
+            Some(s) => (s, text.to_string()),
+            None => guess_scheme(text),
+        };
+        let slp1 = self.lipika.transliterate(text, scheme, Scheme::Slp1);
+
+        let mut sounds: Vec<char> = Vec::with_capacity(slp1.len());
+        for c in slp1.chars() {
+            if c.is_whitespace() {
+                if sounds.last().is_some_and(|x| *x != ' ') {
+                    sounds.push(' ');
+                }
+            } else if c.is_ascii_alphanumeric() {
+                sounds.push(self.fold(c));
+            }
+        }
+        if sounds.last() == Some(&' ') {
+            sounds.pop();
+        }
+
+        let mut ret = String::with_capacity(sounds.len());
+        for (i, c) in sounds.iter().enumerate() {
+            let next = sounds.get(i + 1).copied();
+            let before_consonant = next.is_some_and(is_consonant);
+            // Informal `ri` for vocalic `ṛ`, as in `krishna`.
+            if !self.keep_vocalic_r
+                && *c == 'i'
+                && i >= 2
+                && sounds[i - 1] == 'r'
+                && is_consonant(sounds[i - 2])
+                && (matches!(next, None | Some(' ')) || before_consonant)
+                && !matches!(next, Some('y' | 'v'))
+            {
+                continue;
+            }
+            if !self.keep_nasals && matches!(c, 'n' | 'm') && before_consonant {
+                ret.push('n');
+            } else {
+                ret.push(*c);
+            }
+        }
+        ret
+    }
+
+    /// Folds a single SLP1 sound according to our settings.
+    ///
+    /// We always write the visarga as `h`, as informal spellings do.
+    fn fold(&self, c: char) -> char {
+        if c == 'H' {
+            return 'h';
+        }
+        let c = if self.keep_aspirates {
+            c
+        } else {
+            match c {
+                'K' => 'k',
+                'G' => 'g',
+                'C' => 'c',
+                'J' => 'j',
+                'W' => 'w',
+                'Q' => 'q',
+                'T' => 't',
+                'D' => 'd',
+                'P' => 'p',
+                'B' => 'b',
+                _ => c,
+            }
+        };
+        let c = if self.keep_retroflexes {
+            c
+        } else {
+            match c {
+                'w' => 't',
+                'W' => 'T',
+                'q' => 'd',
+                'Q' => 'D',
+                _ => c,
+            }
+        };
+        let c = if self.keep_sibilants {
+            c
+        } else {
+            match c {
+                'S' | 'z' => 's',
+                _ => c,
+            }
+        };
+        let c = if self.keep_nasals {
+            c
+        } else {
+            match c {
+                'N' | 'Y' | 'R' => 'n',
+                'M' => 'm',
+                _ => c,
+            }
+        };
+        let c = if self.keep_vocalic_r {
+            c
+        } else {
+            match c {
+                'f' | 'F' => 'r',
+                _ => c,
+            }
+        };
+        if self.keep_vowel_length {
+            c
+        } else {
+            match c {
+                'A' => 'a',
+                'I' => 'i',
+                'U' => 'u',
+                'F' => 'f',
+                'X' => 'x',
+                _ => c,
+            }
+        }
+    }
+}
+
+/// Returns whether the SLP1 sound `c` is a consonant.
+fn is_consonant(c: char) -> bool {
+    "kKgGNcCjJYwWqQRtTdDnpPbBmyrlvSzshL".contains(c)
+}
+
+/// Guesses the scheme of `text` and returns it with `text` prepared for that scheme.
+fn guess_scheme(text: &str) -> (Scheme, String) {
+    // Lowercase ASCII, allowing a capital at the start of each word.
+    let is_informal = text
+        .split_whitespace()
+        .all(|word| word.is_ascii() && word.chars().skip(1).all(|c| !c.is_ascii_uppercase()));
+    if is_informal {
+        return (Scheme::Itrans, text.to_ascii_lowercase());
+    }
+
+    match detect(text) {
+        // IAST and ISO 15919 are case-insensitive, so we can safely lowercase them.
+        Some(s @ (Scheme::Iast | Scheme::Iso15919)) => (s, text.to_lowercase()),
+        Some(s) => (s, text.to_string()),
+        None => (Scheme::HarvardKyoto, text.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_same_key(words: &[&str]) {
+        let mut n = PhoneticNormalizer::new();
+        let key = n.key(words[0]);
+        for w in words {
+            assert_eq!(n.key(w), key, "{w}");
+        }
+    }
+
+    #[test]
+    fn informal_spellings_collide() {
+        assert_same_key(&[
+            "kṛṣṇa",
+            "Kṛṣṇa",
+            "krishna",
+            "Krishna",
+            "krsna",
+            "kRSNa",
+            "कृष्ण",
+        ]);
+        assert_same_key(&["śiva", "shiva", "Shiva", "శివ"]);
+        assert_same_key(&["dharma", "Dharma", "darma", "धर्म"]);
+        assert_same_key(&["rāma", "raama", "rama", "rAma", "राम"]);
+        assert_same_key(&["rāmaḥ", "ramah", "rAmaH"]);
+        assert_same_key(&["candra", "chandra", "चन्द्र", "caMdra"]);
+        assert_same_key(&["gaṇeśa", "ganesha", "gaNeza", "गणेश"]);
+        assert_same_key(&["amṛta", "amrita", "amRta", "अमृत"]);
+        assert_same_key(&["saṃskṛta", "samskrta", "samskrita", "sanskrta", "संस्कृत"]);
+        assert_same_key(&["vanam", "vanaṃ", "vanaM", "वनं"]);
+        assert_same_key(&["pitṛ", "pitri", "पितृ"]);
+    }
+
+    #[test]
+    fn ri_before_semivowels_is_kept() {
+        let mut n = PhoneticNormalizer::new();
+        assert_eq!(n.key("priya"), "priya");
+        assert_eq!(n.key("प्रिय"), "priya");
+        assert_eq!(n.key("kriyā"), "kriya");
+    }
+
+    #[test]
+    fn distinct_words_stay_distinct() {
+        let mut n = PhoneticNormalizer::new();
+        assert_ne!(n.key("rāma"), n.key("kāma"));
+        assert_ne!(n.key("deva"), n.key("devī"));
+        assert_ne!(n.key("dharma"), n.key("karma"));
+    }
+
+    #[test]
+    fn spaces_and_punctuation() {
+        let mut n = PhoneticNormalizer::new();
+        assert_eq!(n.key("  rāmaḥ  vanaṃ,  gacchati.\n"), "ramah vanam gaccati");
+        assert_eq!(n.key(""), "");
+    }
+
+    #[test]
+    fn rules_can_be_disabled() {
+        let mut n = PhoneticNormalizer::new().with_fold_aspirates(false);
+        assert_eq!(n.key("dharma"), "Darma");
+
+        let mut n = PhoneticNormalizer::new().with_fold_sibilants(false);
+        assert_eq!(n.key("śiva"), "Siva");
+
+        let mut n = PhoneticNormalizer::new().with_fold_nasals(false);
+        assert_eq!(n.key("kṛṣṇa"), "krsRa");
+
+        let mut n = PhoneticNormalizer::new().with_fold_retroflexes(false);
+        assert_eq!(n.key("kaṭu"), "kawu");
+
+        let mut n = PhoneticNormalizer::new().with_fold_vowel_length(false);
+        assert_eq!(n.key("rāma"), "rAma");
+
+        let mut n = PhoneticNormalizer::new().with_fold_vocalic_r(false);
+        assert_eq!(n.key("kṛṣṇa"), "kfsna");
+        assert_eq!(n.key("krishna"), "krisna");
+    }
+
+    #[test]
+    fn explicit_scheme() {
+        // As SLP1, `kfzRa` is kṛṣṇa.
+        let mut n = PhoneticNormalizer::new().with_scheme(Scheme::Slp1);
+        assert_eq!(n.key("kfzRa"), "krsna");
+    }
+}
@@ -22,6 +22,7 @@
 //! crates greatly increase the binary size of `vidyu-lipi`, which has serving implications for
 //! WASM. So instead of using regexes, we've rolled our own logic for these transformations.
 
+use crate::align::{compose_opt, Alignment, Tracker};
 use crate::scheme::Scheme;
 use crate::unicode_norm;
 use core::str::Chars;
@@ -446,30 +447,44 @@ struct Matcher {
     prev: usize,
     /// The output buffer.
     buf: String,
+    /// Records how spans of `text` map to spans of `buf`.
+    tracker: Tracker,
 }
 
+/// Text that has been reshaped, along with its alignment to the original text (if requested).
+pub(crate) type Reshaped = (String, Option<Vec<Alignment>>);
+
 impl Matcher {
-    /// Creates a new `Matcher`.
-    fn new(text: String) -> Self {
+    /// Creates a new `Matcher`. If `track` is true, also record alignments between the input
+    /// text and the output buffer.
+    fn new(text: String, track: bool) -> Self {
         Self {
             text,
             i: 0,
             prev: 0,
             buf: String::new(),
+            tracker: Tracker::new(track),
         }
     }
 
-    /// Returns the final output buffer.
-    fn finish(mut self) -> String {
+    /// Returns the final output buffer and its alignments.
+    fn finish(mut self) -> Reshaped {
         if !self.buf.is_empty() {
             self.flush();
-            self.buf
+            (self.buf, self.tracker.finish())
         } else {
             // Nothing matched, so return the original text.
-            self.text
+            let mut tracker = Tracker::new(self.tracker.is_enabled());
+            tracker.push_copy(&self.text);
+            (self.text, tracker.finish())
         }
     }
 
+    /// Records that we consumed input up to `self.i` and pushed output starting from `low`.
+    fn track(&mut self, low: usize) {
+        self.tracker.push(self.i, low, self.buf.len());
+    }
+
     /// Returns whether there is still content remaining in the input string.
     fn not_empty(&self) -> bool {
         self.i < self.text.len()
@@ -516,9 +531,11 @@ impl Matcher {
         self.flush();
         let mut chars = self.slice().chars();
         if let Some(x) = chars.next() {
+            let low = self.buf.len();
             func(&mut self.buf, x);
             self.i += x.len_utf8();
             self.prev = self.i;
+            self.track(low);
         }
     }
 
@@ -527,9 +544,11 @@ impl Matcher {
         self.flush();
         let mut chars = self.slice().chars();
         if let (Some(x), Some(y)) = (chars.next(), chars.next()) {
+            let low = self.buf.len();
             func(&mut self.buf, x, y);
             self.i += x.len_utf8() + y.len_utf8();
             self.prev = self.i;
+            self.track(low);
         }
     }
 
@@ -538,15 +557,18 @@ impl Matcher {
         self.flush();
         let mut chars = self.slice().chars();
         if let (Some(x), Some(y), Some(z)) = (chars.next(), chars.next(), chars.next()) {
+            let low = self.buf.len();
             func(&mut self.buf, x, y, z);
             self.i += x.len_utf8() + y.len_utf8() + z.len_utf8();
             self.prev = self.i;
+            self.track(low);
         }
     }
 
     fn flush(&mut self) {
         if self.prev < self.i {
             self.buf += &self.text[self.prev..self.i];
+            self.tracker.push_copy(&self.text[self.prev..self.i]);
             self.prev = self.i;
         }
     }
@@ -560,9 +582,11 @@ impl Matcher {
 
     fn swap(&mut self, old: &[char], new: &[char]) {
         self.flush();
+        let low = self.buf.len();
         self.buf.extend(new);
         self.i += old.iter().map(|c| c.len_utf8()).sum::<usize>();
         self.prev = self.i;
+        self.track(low);
     }
 
     fn add(&mut self, c: char) {
@@ -573,13 +597,20 @@ impl Matcher {
 /// Reshapes `input` before we run the main transliteration function.
 ///
 /// Once this function matures, we will consider switching to an iterator-based implementation.
-pub fn reshape_before(input: &str, from: Scheme) -> String {
+///
+/// If `track` is true, also returns how spans of `input` map to spans of the output.
+pub fn reshape_before(input: &str, from: Scheme, track: bool) -> Reshaped {
     // Convert to NFC first to avoid certain transliteration errors.
     // (See `iso_15919_bug_no_greedy_match_on_nfd` for an example of what we want to prevent.)
-    let input = unicode_norm::to_nfc(input);
-    let mut m = Matcher::new(input);
+    let (input, nfc_alignments) = if track {
+        let (text, alignments) = unicode_norm::to_nfc_with_alignment(input);
+        (text, Some(alignments))
+    } else {
+        (unicode_norm::to_nfc(input), None)
+    };
+    let mut m = Matcher::new(input, track);
 
-    match from {
+    let (output, alignments) = match from {
         Scheme::Assamese | Scheme::Bengali => {
             while m.not_empty() {
                 if m.match_2(|x, y| is_bengali_ayogavaha(x) && is_svara(y)) {
@@ -836,12 +867,15 @@ pub fn reshape_before(input: &str, from: Scheme) -> String {
             m.finish()
         }
         _ => m.finish(),
-    }
+    };
+    (output, compose_opt(nfc_alignments, alignments))
 }
 
 /// Reshapes `output` after we run the main transliteration function.
-pub fn reshape_after(output: String, to: Scheme) -> String {
-    let mut m = Matcher::new(output);
+///
+/// If `track` is true, also returns how spans of `output` map to spans of the reshaped text.
+pub fn reshape_after(output: String, to: Scheme, track: bool) -> Reshaped {
+    let mut m = Matcher::new(output, track);
 
     match to {
         Scheme::Assamese | Scheme::Bengali => {
@@ -911,8 +945,8 @@ pub fn reshape_after(output: String, to: Scheme) -> String {
             }
 
             // Substitution above blocks substitution here, so split into two Matchers.
-            let first = m.finish();
-            let mut m = Matcher::new(first);
+            let (first, first_alignments) = m.finish();
+            let mut m = Matcher::new(first, track);
             while m.not_empty() {
                 if m.match_2(|x, y| has_cham_final_consonant(x) && y == FAKE_VIRAMA) {
                     m.take_2(|buf, x, _| {
@@ -923,7 +957,8 @@ pub fn reshape_after(output: String, to: Scheme) -> String {
                     m.push_next();
                 }
             }
-            m.finish()
+            let (output, alignments) = m.finish();
+            (output, compose_opt(first_alignments, alignments))
         }
         Scheme::Devanagari
         | Scheme::Gujarati
@@ -668,6 +668,38 @@ impl Scheme {
         matches!(self, Scheme::Grantha)
     }
 
+    /// Returns how this scheme writes the given Vedic accent, or `None` if the scheme does not
+    /// support it.
+    ///
+    /// All Brahmic schemes support all `VedicAccent`s. Most use the Devanagari accent marks as-is,
+    /// since Unicode intends for these marks to be shared across scripts. Roman schemes support
+    /// those accents that their conventions define. For example, `Slp1` uses `^` and `\\`, and
+    /// `Iso15919` uses the ISO 15919 accent marks. `Iast` writes the svarita as a combining
+    /// circumflex below (U+032D) and keeps the Devanagari anudatta as-is, and it also accepts the
+    /// ISO 15919 marks on input. Neither supports the Atharvaveda independent svarita: its usual
+    /// grave accent composes with a preceding vowel under NFC, so it can't survive a round trip.
+    ///
+    /// When transliterating to a scheme that does not support an accent, we pass the Devanagari
+    /// accent mark through as-is.
+    ///
+    /// ### Usage
+    ///
+    /// ```
+    /// use vidyut_lipi::{Scheme, VedicAccent};
+    ///
+    /// assert_eq!(Scheme::Slp1.vedic_accent(VedicAccent::Anudatta), Some("\\"));
+    /// assert_eq!(Scheme::Iast.vedic_accent(VedicAccent::Svarita), Some("\u{032d}"));
+    /// assert_eq!(Scheme::Iso15919.vedic_accent(VedicAccent::Svarita), Some("\u{030d}"));
+    /// assert_eq!(Scheme::HarvardKyoto.vedic_accent(VedicAccent::Anudatta), None);
+    /// ```
+    pub fn vedic_accent(&self, accent: VedicAccent) -> Option<&'static str> {
+        let key = accent.as_devanagari();
+        self.token_pairs()
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| *v)
+    }
+
     /// Returns how well this scheme support Sanskrit.
     #[allow(unused)]
     pub(crate) fn coverage(&self) -> Coverage {
@@ -700,6 +732,59 @@ impl Scheme {
     }
 }
 
+/// A Vedic accent mark.
+///
+/// We name each accent after its Devanagari mark. Texts differ in how they use these marks: in
+/// the Rigveda, for example, `Svarita` marks the svarita and `Anudatta` marks the anudātta, and
+/// the udātta is left unmarked. Kampa is written with the digits `१` and `३`, which every scheme
+/// supports.
+///
+/// For how each scheme writes these accents, see `Scheme::vedic_accent`.
+#[derive(Clone, Copy, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub enum VedicAccent {
+    /// A vertical line above (U+0951).
+    Svarita,
+    /// A horizontal line below (U+0952).
+    Anudatta,
+    /// A double vertical line above (U+1CDA).
+    DirghaSvarita,
+    /// The independent svarita of the Atharvaveda (U+1CE1).
+    AtharvavedaIndependentSvarita,
+    /// A horizontal line above, used in the Samaveda (U+1CD2).
+    Prenkha,
+    /// A dot below (U+1CDD).
+    DotBelow,
+}
+
+impl VedicAccent {
+    /// Returns an iterator over all `VedicAccent`s.
+    pub fn iter() -> impl Iterator<Item = &'static VedicAccent> {
+        use VedicAccent::*;
+        const ACCENTS: &[VedicAccent] = &[
+            Svarita,
+            Anudatta,
+            DirghaSvarita,
+            AtharvavedaIndependentSvarita,
+            Prenkha,
+            DotBelow,
+        ];
+        ACCENTS.iter()
+    }
+
+    /// Returns the Devanagari mark for this accent.
+    pub fn as_devanagari(&self) -> &'static str {
+        use VedicAccent::*;
+        match self {
+            Svarita => "\u{0951}",
+            Anudatta => "\u{0952}",
+            DirghaSvarita => "\u{1cda}",
+            AtharvavedaIndependentSvarita => "\u{1ce1}",
+            Prenkha => "\u{1cd2}",
+            DotBelow => "\u{1cdd}",
+        }
+    }
+}
+
 /// Defines various Scheme utils without the boilerplate.
 macro_rules! scheme_utils {
     [$( $variant:ident ),*] => {
@@ -0,0 +1,299 @@
+//! Transliterates text that arrives in chunks, such as from a large file or a network stream.
+
+use crate::mapping::Mapping;
+use crate::transliterate::transliterate;
+use std::io::{self, Read, Write};
+
+/// The size of the buffer we use when reading from a `Read`.
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Transliterates text that arrives in chunks.
+///
+/// `StreamTransliterator` accepts input text in arbitrary chunks and produces the same output as
+/// calling `transliterate` on the full input string.
+///
+/// ### Usage
+///
+/// ```
+/// use vidyut_lipi::{Mapping, Scheme, StreamTransliterator};
+///
+/// let mapping = Mapping::new(Scheme::HarvardKyoto, Scheme::Devanagari);
+/// let mut stream = StreamTransliterator::new(&mapping);
+///
+/// let mut output = String::new();
+/// // A chunk can end in the middle of a word, such as between "k" and "R".
+/// output += &stream.push("saMsk");
+/// output += &stream.push("Rtam ");
+/// output += &stream.push("vAk");
+/// output += &stream.finish();
+/// assert_eq!(output, "संस्कृतम् वाक्");
+/// ```
+///
+/// ### Approach
+///
+/// Our transliteration logic uses context in various ways. For example, a consonant followed by a
+/// vowel sign is transliterated differently from a consonant followed by a space, and some
+/// schemes reorder characters before or after transliteration. So if we transliterate each chunk
+/// independently, a chunk boundary that falls within a word might produce incorrect output.
+///
+/// None of this context extends across whitespace. So, we buffer our input and transliterate it
+/// only up to the last whitespace character we have seen so far. The remainder of the buffer is
+/// kept until the next call to `push` or `finish`.
+///
+/// As a result, input with no whitespace at all will be buffered until `finish` is called.
+pub struct StreamTransliterator<'a> {
+    /// The mapping to use.
+    mapping: &'a Mapping,
+    /// Input text that we have not yet transliterated.
+    buffer: String,
+}
+
+impl<'a> StreamTransliterator<'a> {
+    /// Creates a new stream transliterator that uses the given `mapping`.
+    pub fn new(mapping: &'a Mapping) -> Self {
+        Self {
+            mapping,
+            buffer: String::new(),
+        }
+    }
+
+    /// Adds `chunk` to the stream and returns all output that is ready so far.
+    ///
+    /// The returned string might be empty if `chunk` does not complete any words.
+    pub fn push(&mut self, chunk: &str) -> String {
+        // We drain the buffer through its last whitespace character on every push, so any
+        // whitespace left in the buffer must come from `chunk`. Scanning only `chunk` keeps long
+        // inputs without whitespace linear.
+        let start = self.buffer.len();
+        self.buffer.push_str(chunk);
+
+        match chunk.rfind(char::is_whitespace) {
+            Some(i) => {
+                let i = start + i;
+                let c = self.buffer[i..].chars().next().expect("whitespace");
+                let end = i + c.len_utf8();
+                let output = transliterate(&self.buffer[..end], self.mapping);
+                self.buffer.drain(..end);
+                output
+            }
+            None => String::new(),
+        }
+    }
+
+    /// Transliterates all remaining input and returns the result.
+    pub fn finish(self) -> String {
+        if self.buffer.is_empty() {
+            String::new()
+        } else {
+            transliterate(&self.buffer, self.mapping)
+        }
+    }
+}
+
+/// Transliterates all text from `reader` and writes the result to `writer`.
+///
+/// The output is identical to calling `transliterate` on the reader's entire contents. Unlike
+/// `transliterate`, this function reads its input in small pieces, which makes it suitable for
+/// large files.
+///
+/// This function returns an `io::Error` with kind `InvalidData` if `reader` contains invalid
+/// UTF-8.
+///
+/// ### Usage
+///
+/// ```
+/// use vidyut_lipi::{transliterate_stream, Mapping, Scheme};
+///
+/// let mapping = Mapping::new(Scheme::HarvardKyoto, Scheme::Devanagari);
+/// let input = "saMskRtam\nvAk\n";
+/// let mut output = Vec::new();
+/// transliterate_stream(input.as_bytes(), &mut output, &mapping).unwrap();
+///
+/// assert_eq!(String::from_utf8(output).unwrap(), "संस्कृतम्\nवाक्\n");
+/// ```
+pub fn transliterate_stream(
+    mut reader: impl Read,
+    mut writer: impl Write,
+    mapping: &Mapping,
+) -> io::Result<()> {
+    let mut stream = StreamTransliterator::new(mapping);
+    let mut buf = vec![0_u8; READ_BUFFER_SIZE];
+    // The number of bytes at the start of `buf` that are left over from the previous read. These
+    // are the first bytes of a UTF-8 sequence that was split across two reads.
+    let mut num_pending = 0;
+
+    loop {
+        let num_read = reader.read(&mut buf[num_pending..])?;
+        if num_read == 0 {
+            break;
+        }
+
+        let num_filled = num_pending + num_read;
+        let (text, num_valid) = match std::str::from_utf8(&buf[..num_filled]) {
+            Ok(text) => (text, num_filled),
+            Err(e) => {
+                if e.error_len().is_some() {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+                }
+                // Otherwise, the input ends with an incomplete UTF-8 sequence, which should be
+                // completed by our next read.
+                let num_valid = e.valid_up_to();
+                let text = std::str::from_utf8(&buf[..num_valid]).expect("valid");
+                (text, num_valid)
+            }
+        };
+
+        let output = stream.push(text);
+        writer.write_all(output.as_bytes())?;
+
+        buf.copy_within(num_valid..num_filled, 0);
+        num_pending = num_filled - num_valid;
+    }
+
+    if num_pending > 0 {
+        // The input ended in the middle of a UTF-8 sequence.
+        let e = std::str::from_utf8(&buf[..num_pending]).expect_err("incomplete");
+        return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+    }
+
+    let output = stream.finish();
+    writer.write_all(output.as_bytes())?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheme::Scheme;
+    use Scheme::*;
+
+    /// Yields at most `n` bytes per read.
+    struct SmallReader<'a> {
+        data: &'a [u8],
+        n: usize,
+    }
+
+    impl Read for SmallReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.n.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    const EXAMPLES: &[(Scheme, &str)] = &[
+        (HarvardKyoto, "nArAyaNaM namaskRtya naraM caiva narottamam |\ndevIM sarasvatIM caiva tato jayamudIrayet || 1 ||"),
+        (Devanagari, "नारायणं नमस्कृत्य नरं चैव नरोत्तमम् ।\nदेवीं सरस्वतीं चैव ततो जयमुदीरयेत् ॥ १ ॥"),
+        (Iso15919, "ka:i ka:u  saṁskr̥tam"),
+        (Itrans, "a\\ b kRRiShNa"),
+        (Tibetan, "ནཱ་རཱ་ཡ་ཎཾ་ན་མ་སྐྲྀ་ཏྱ"),
+        (Grantha, "𑌨𑌮𑌃 ௧௰௨ ௲"),
+    ];
+
+    const TARGETS: &[Scheme] = &[
+        Devanagari, Slp1, Iast, Iso15919, Bengali, Cham, Grantha, Javanese, Khmer, Tamil, Tibetan,
+    ];
+
+    fn assert_stream_matches(input: &str, mapping: &Mapping, splits: &[usize]) {
+        let expected = transliterate(input, mapping);
+
+        let mut stream = StreamTransliterator::new(mapping);
+        let mut actual = String::new();
+        let mut prev = 0;
+        for i in splits {
+            actual += &stream.push(&input[prev..*i]);
+            prev = *i;
+        }
+        actual += &stream.push(&input[prev..]);
+        actual += &stream.finish();
+
+        assert_eq!(
+            expected, actual,
+            "{:?} --> {:?}, splits = {splits:?}",
+            mapping.from, mapping.to
+        );
+    }
+
+    #[test]
+    fn push_with_every_split_point() {
+        for (from, input) in EXAMPLES {
+            for to in TARGETS {
+                let mapping = Mapping::new(*from, *to);
+                for (i, _) in input.char_indices() {
+                    assert_stream_matches(input, &mapping, &[i]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn push_with_many_small_chunks() {
+        for (from, input) in EXAMPLES {
+            for to in TARGETS {
+                let mapping = Mapping::new(*from, *to);
+                let splits: Vec<_> = input.char_indices().map(|(i, _)| i).collect();
+                assert_stream_matches(input, &mapping, &splits);
+            }
+        }
+    }
+
+    #[test]
+    fn push_returns_only_complete_words() {
+        let mapping = Mapping::new(HarvardKyoto, Devanagari);
+        let mut stream = StreamTransliterator::new(&mapping);
+
+        // Consonant at the end of one chunk, vowel at the start of the next.
+        assert_eq!(stream.push("rAm"), "");
+        assert_eq!(stream.push("a rAm"), "राम ");
+        assert_eq!(stream.push("o"), "");
+        assert_eq!(stream.finish(), "रामो");
+    }
+
+    #[test]
+    fn push_without_whitespace() {
+        let mapping = Mapping::new(HarvardKyoto, Devanagari);
+        let mut stream = StreamTransliterator::new(&mapping);
+
+        // Many small pushes with no whitespace should stay buffered until `finish`.
+        let input = "rAma".repeat(20_000);
+        for _ in 0..20_000 {
+            assert_eq!(stream.push("rAma"), "");
+        }
+        assert_eq!(stream.finish(), transliterate(&input, &mapping));
+    }
+
+    #[test]
+    fn transliterate_stream_with_split_utf8() {
+        let mapping = Mapping::new(Devanagari, HarvardKyoto);
+        let (_, input) = EXAMPLES[1];
+        let expected = transliterate(input, &mapping);
+
+        for n in 1..8 {
+            let reader = SmallReader {
+                data: input.as_bytes(),
+                n,
+            };
+            let mut output = Vec::new();
+            transliterate_stream(reader, &mut output, &mapping).expect("valid input");
+            assert_eq!(String::from_utf8(output).expect("valid UTF-8"), expected);
+        }
+    }
+
+    #[test]
+    fn transliterate_stream_with_invalid_utf8() {
+        let mapping = Mapping::new(Devanagari, HarvardKyoto);
+
+        // Invalid byte
+        let mut output = Vec::new();
+        let err =
+            transliterate_stream(&b"a\xffb"[..], &mut output, &mapping).expect_err("invalid input");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // Incomplete sequence at end of input
+        let mut output = Vec::new();
+        let err = transliterate_stream(&b"a\xe0\xa4"[..], &mut output, &mapping)
+            .expect_err("invalid input");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
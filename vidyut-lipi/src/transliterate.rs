@@ -1,6 +1,7 @@
+use crate::align::{compose_opt, Alignment, Tracker};
 use crate::mapping::Mapping;
 use crate::numerals;
-use crate::reshape::{reshape_after, reshape_before};
+use crate::reshape::{reshape_after, reshape_before, Reshaped};
 use crate::scheme::Scheme;
 
 /// Transliterates the input string with the provided `Mapping`.
@@ -17,7 +18,39 @@ use crate::scheme::Scheme;
 /// assert_eq!(result, "संस्कृतम्");
 /// ```
 pub fn transliterate(input: impl AsRef<str>, mapping: &Mapping) -> String {
-    transliterate_inner(input.as_ref(), mapping)
+    let (output, _) = transliterate_inner(input.as_ref(), mapping, false);
+    output
+}
+
+/// Transliterates the input string with the provided `Mapping` and returns the output along with
+/// its alignment to the input.
+///
+/// Each `Alignment` maps a byte span of `input` to the byte span of the output that it produced.
+/// The alignments are sorted, contiguous, and cover both strings in full. This is useful for
+/// tasks like search highlighting, where we want to map a span of the output back to the input.
+///
+/// ### Usage
+///
+/// ```
+/// use vidyut_lipi::{transliterate_with_alignment, Mapping, Scheme};
+///
+/// let mapping = Mapping::new(Scheme::HarvardKyoto, Scheme::Devanagari);
+/// let input = "rAma";
+/// let (output, alignments) = transliterate_with_alignment(input, &mapping);
+/// assert_eq!(output, "राम");
+///
+/// let spans: Vec<_> = alignments
+///     .iter()
+///     .map(|a| (&input[a.source.clone()], &output[a.target.clone()]))
+///     .collect();
+/// assert_eq!(spans, vec![("rA", "रा"), ("ma", "म")]);
+/// ```
+pub fn transliterate_with_alignment(
+    input: impl AsRef<str>,
+    mapping: &Mapping,
+) -> (String, Vec<Alignment>) {
+    let (output, alignments) = transliterate_inner(input.as_ref(), mapping, true);
+    (output, alignments.unwrap_or_default())
 }
 
 /// Transliterates the input string with the provided `Mapping`.
@@ -43,8 +76,11 @@ pub fn transliterate(input: impl AsRef<str>, mapping: &Mapping) -> String {
 /// able to avoid doing work in stages (1) and (3) and thus process the input text in just one
 /// pass. For more complex scheme pairs (such as `Tibetan` to `Khmer`), this code will make three
 /// passes total.
-fn transliterate_inner(input: &str, mapping: &Mapping) -> String {
-    let input = reshape_before(input, mapping.from());
+///
+/// If `track` is true, each stage also records how its input aligns with its output, and we
+/// compose these alignments into a single alignment from `input` to the final output.
+fn transliterate_inner(input: &str, mapping: &Mapping, track: bool) -> Reshaped {
+    let (input, before_alignments) = reshape_before(input, mapping.from(), track);
 
     let is_to_alphabet = mapping.to.is_alphabet();
     let is_to_abugida = mapping.to.is_abugida();
@@ -56,9 +92,14 @@ fn transliterate_inner(input: &str, mapping: &Mapping) -> String {
         mapping.from.has_non_decimal_numerals() || mapping.to.has_non_decimal_numerals();
 
     let mut output = String::new();
+    let mut tracker = Tracker::new(track);
     let mut i = 0;
     let mut had_virama = false;
     while i < input.len() {
+        // The smallest length of `output` during this iteration. We use this to track alignments
+        // when we pop from `output`.
+        let mut low = output.len();
+
         // Special case: Numerals that don't use decimal place notation are transliterated
         // separately.
         if uses_non_decimal {
@@ -67,6 +108,7 @@ fn transliterate_inner(input: &str, mapping: &Mapping) -> String {
                 debug_assert!(next_i > i, "next_i = {next_i}, i = {i}");
                 numerals::transliterate_numeral(&mut output, &input[i..next_i], mapping);
                 i = next_i;
+                tracker.push(i, low, output.len());
                 continue;
             }
         }
@@ -82,6 +124,7 @@ fn transliterate_inner(input: &str, mapping: &Mapping) -> String {
             if is_to_abugida && had_virama {
                 // 'a' means we should pop virama.
                 output.pop();
+                low = output.len();
                 had_virama = false;
             } else {
                 // Otherwise, add independent 'a' vowel.
@@ -91,6 +134,7 @@ fn transliterate_inner(input: &str, mapping: &Mapping) -> String {
             }
             // Increment past "a:"
             i += 2;
+            tracker.push(i, low, output.len());
             continue;
         }
 
@@ -128,6 +172,7 @@ fn transliterate_inner(input: &str, mapping: &Mapping) -> String {
                     //
                     // So, pop the "a" that we added in the previous iteration.
                     output.pop();
+                    low = output.len();
                 }
 
                 output += &span.value;
@@ -144,12 +189,14 @@ fn transliterate_inner(input: &str, mapping: &Mapping) -> String {
                     // `key` is the default "a" vowel, so pop the virama that we added in the
                     // previous iteration.
                     output.pop();
+                    low = output.len();
                     had_virama = false;
                 } else {
                     let mut text = &span.value;
                     if had_virama {
                         if let Some(mark) = mapping.marks.get(key) {
                             output.pop();
+                            low = output.len();
                             text = mark;
                         }
                     }
@@ -178,6 +225,7 @@ fn transliterate_inner(input: &str, mapping: &Mapping) -> String {
                 && matches!(span.value.as_str(), "i" | "u")
             {
                 output.pop();
+                low = std::cmp::min(low, output.len());
                 output.push(':');
                 output.push_str(&span.value);
             }
@@ -192,6 +240,7 @@ fn transliterate_inner(input: &str, mapping: &Mapping) -> String {
                 output.push(c);
                 i += c.len_utf8();
             }
+            tracker.push(i, low, output.len());
             continue;
         } else {
             // Use the original character as-is.
@@ -201,9 +250,16 @@ fn transliterate_inner(input: &str, mapping: &Mapping) -> String {
             // Prepare for next loop.
             i += c.len_utf8();
         }
+
+        tracker.push(i, low, output.len());
     }
 
-    reshape_after(output, mapping.to())
+    let (output, after_alignments) = reshape_after(output, mapping.to(), track);
+    let alignments = compose_opt(
+        compose_opt(before_alignments, tracker.finish()),
+        after_alignments,
+    );
+    (output, alignments)
 }
 
 /// Finds the end byte of a sequence of numerals starting at byte offset `i`.
@@ -266,4 +322,145 @@ mod tests {
         let t = |s| transliterate(s, &mapping);
         assert_eq!(t("namaskRtya"), "नमस्कृत्य");
     }
+
+    /// Returns the (source, target) strings for each alignment.
+    fn aligned_spans(input: &str, from: Scheme, to: Scheme) -> (String, Vec<(&str, String)>) {
+        let mapping = Mapping::new(from, to);
+        let (output, alignments) = transliterate_with_alignment(input, &mapping);
+        let spans = alignments
+            .iter()
+            .map(|a| {
+                (
+                    &input[a.source.clone()],
+                    output[a.target.clone()].to_string(),
+                )
+            })
+            .collect();
+        (output, spans)
+    }
+
+    #[test]
+    fn test_transliterate_with_alignment_for_all_schemes() {
+        let slp1 = "saMskftam 123 kfzRaH rAmo vEdikaH Sakyate";
+        for from in Scheme::iter() {
+            let input = transliterate(slp1, &Mapping::new(Slp1, *from));
+            for to in Scheme::iter() {
+                let mapping = Mapping::new(*from, *to);
+                let (output, alignments) = transliterate_with_alignment(&input, &mapping);
+                assert_eq!(
+                    output,
+                    transliterate(&input, &mapping),
+                    "{from:?} --> {to:?}"
+                );
+
+                // Alignments are contiguous and cover both strings.
+                let mut source_end = 0;
+                let mut target_end = 0;
+                for a in &alignments {
+                    assert_eq!(a.source.start, source_end, "{from:?} --> {to:?}");
+                    assert_eq!(a.target.start, target_end, "{from:?} --> {to:?}");
+                    assert!(input.is_char_boundary(a.source.end));
+                    assert!(output.is_char_boundary(a.target.end));
+                    source_end = a.source.end;
+                    target_end = a.target.end;
+                }
+                assert_eq!(source_end, input.len(), "{from:?} --> {to:?}");
+                assert_eq!(target_end, output.len(), "{from:?} --> {to:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_transliterate_with_alignment() {
+        let (_, spans) = aligned_spans("saMskRtam", HarvardKyoto, Devanagari);
+        assert_eq!(
+            spans,
+            vec![
+                ("sa", "स".to_string()),
+                ("M", "ं".to_string()),
+                ("s", "स्".to_string()),
+                ("kR", "कृ".to_string()),
+                ("ta", "त".to_string()),
+                ("m", "म्".to_string()),
+            ]
+        );
+
+        let (_, spans) = aligned_spans("संस्कृतम्", Devanagari, HarvardKyoto);
+        assert_eq!(
+            spans,
+            vec![
+                ("स", "sa".to_string()),
+                ("ं", "M".to_string()),
+                ("स्", "s".to_string()),
+                ("कृ", "kR".to_string()),
+                ("त", "ta".to_string()),
+                ("म्", "m".to_string()),
+            ]
+        );
+
+        // Characters not in the mapping
+        let (_, spans) = aligned_spans("a, b", HarvardKyoto, Iast);
+        assert_eq!(
+            spans,
+            vec![
+                ("a", "a".to_string()),
+                (",", ",".to_string()),
+                (" ", " ".to_string()),
+                ("b", "b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transliterate_with_alignment_and_reshaping() {
+        // Cham final consonants
+        let (output, spans) = aligned_spans("kak", Slp1, Cham);
+        assert_eq!(output, "ꨆꩀ");
+        assert_eq!(spans, vec![("ka", "ꨆ".to_string()), ("k", "ꩀ".to_string())]);
+
+        // Javanese medials
+        let (output, spans) = aligned_spans("kra kya", Slp1, Javanese);
+        assert_eq!(output, "ꦏꦿ ꦏꦾ");
+        assert_eq!(
+            spans,
+            vec![
+                ("kra", "ꦏꦿ".to_string()),
+                (" ", " ".to_string()),
+                ("kya", "ꦏꦾ".to_string())
+            ]
+        );
+
+        let (_, spans) = aligned_spans("ꦏꦿ ꦏꦾ", Javanese, Slp1);
+        assert_eq!(
+            spans,
+            vec![
+                ("ꦏꦿ", "kra".to_string()),
+                (" ", " ".to_string()),
+                ("ꦏꦾ", "kya".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transliterate_with_alignment_and_numerals() {
+        let (_, spans) = aligned_spans("12 1000", Slp1, Grantha);
+        assert_eq!(
+            spans,
+            vec![
+                ("12", "௰௨".to_string()),
+                (" ", " ".to_string()),
+                ("1000", "௲".to_string()),
+            ]
+        );
+
+        let (_, spans) = aligned_spans("௧௰௨ ௲", Grantha, Slp1);
+        assert_eq!(
+            spans,
+            vec![
+                ("௧௰௨", "12".to_string()),
+                (" ", " ".to_string()),
+                ("௲", "1000".to_string()),
+            ]
+        );
+    }
 }
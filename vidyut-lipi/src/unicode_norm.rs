@@ -25,6 +25,7 @@
 //!
 //! [1]: https://docs.rs/unicode-normalization/latest/unicode_normalization/
 
+use crate::align::{Alignment, Tracker};
 use crate::scheme::Scheme;
 use rustc_hash::FxHashMap;
 use unicode_normalization::UnicodeNormalization;
@@ -321,6 +322,40 @@ pub(crate) fn to_nfc(s: &str) -> String {
     ret
 }
 
+/// Converts `s` to its NFC representation and returns how spans of `s` map to spans of the
+/// result.
+///
+/// We split `s` into chunks that NFC cannot combine with each other, then normalize each chunk
+/// separately. Each chunk is thus a single alignment.
+pub(crate) fn to_nfc_with_alignment(s: &str) -> (String, Vec<Alignment>) {
+    use unicode_normalization::char::canonical_combining_class;
+    use unicode_normalization::{is_nfc_quick, IsNormalized};
+
+    // Returns whether `c` can't interact with the text before it during normalization.
+    let starts_chunk = |c: char| {
+        canonical_combining_class(c) == 0 && is_nfc_quick(std::iter::once(c)) != IsNormalized::Maybe
+    };
+
+    let mut ret = String::with_capacity(s.len());
+    let mut tracker = Tracker::new(true);
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        if i > start && starts_chunk(c) {
+            let low = ret.len();
+            ret += &to_nfc(&s[start..i]);
+            tracker.push(i, low, ret.len());
+            start = i;
+        }
+    }
+    if start < s.len() {
+        let low = ret.len();
+        ret += &to_nfc(&s[start..]);
+        tracker.push(s.len(), low, ret.len());
+    }
+
+    (ret, tracker.finish().unwrap_or_default())
+}
+
 /// Converts the given string to its NFD representation.
 ///
 /// Our version of `to_nfd` supports only those characters that are part of a `Scheme`. All other
@@ -389,4 +424,27 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_to_nfc_with_alignment() {
+        // Bengali "ko", with the vowel sign "o" in NFD.
+        let input = "a\u{0995}\u{09c7}\u{09be} b";
+        let (output, alignments) = to_nfc_with_alignment(input);
+        assert_eq!(output, "a\u{0995}\u{09cb} b");
+
+        let spans: Vec<_> = alignments
+            .iter()
+            .map(|a| (&input[a.source.clone()], &output[a.target.clone()]))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                ("a", "a"),
+                ("\u{0995}", "\u{0995}"),
+                ("\u{09c7}\u{09be}", "\u{09cb}"),
+                (" ", " "),
+                ("b", "b"),
+            ]
+        );
+    }
 }
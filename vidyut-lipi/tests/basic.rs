@@ -1,5 +1,5 @@
 use vidyut_lipi::Scheme::*;
-use vidyut_lipi::{Lipika, Scheme};
+use vidyut_lipi::{Lipika, Scheme, VedicAccent};
 
 /// A quick alias for transliterating.
 fn t(input: &str, from: Scheme, to: Scheme) -> String {
@@ -762,6 +762,85 @@ fn vedic_svaras_with_ayogavahas() {
     ]);
 }
 
+#[test]
+fn vedic_svaras_in_iast_and_iso_15919() {
+    assert_two_way_pairwise(&[
+        (Iast, "a\u{0952} a\u{032d} kā\u{0952} kā\u{032d} a\u{1cda}"),
+        (
+            Iso15919,
+            "a\u{0331} a\u{030d} kā\u{0331} kā\u{030d} a\u{030e}",
+        ),
+        (Devanagari, "अ॒ अ॑ का॒ का॑ अ᳚"),
+        (Kannada, "ಅ॒ ಅ॑ ಕಾ॒ ಕಾ॑ ಅ᳚"),
+    ]);
+
+    // IAST also accepts the ISO 15919 accent marks on input.
+    assert_transliterate(
+        "a\u{0331} a\u{030d} a\u{030e} kā\u{0331} kā\u{030d} kā\u{030e}",
+        Iast,
+        Devanagari,
+        "अ॒ अ॑ अ᳚ का॒ का॑ का᳚",
+    );
+    // ISO 15919 also accepts the marks that IAST uses.
+    assert_transliterate(
+        "a\u{0952} a\u{032d} a\u{1cda} kā\u{0952} kā\u{032d} kā\u{1cda}",
+        Iso15919,
+        Devanagari,
+        "अ॒ अ॑ अ᳚ का॒ का॑ का᳚",
+    );
+}
+
+#[test]
+fn vedic_accents_round_trip_for_all_schemes() {
+    for scheme in Scheme::iter() {
+        for accent in VedicAccent::iter() {
+            let mark = accent.as_devanagari();
+            // Kharoshthi's visarga has a combining class, so Unicode normalization moves some
+            // accents in front of it.
+            let deva = if *scheme == Kharoshthi {
+                format!("अ{mark} क{mark} कि{mark} कं{mark} क्ष{mark}")
+            } else {
+                format!("अ{mark} क{mark} कि{mark} कः{mark} कं{mark} क्ष{mark}")
+            };
+
+            let text = t(&deva, Devanagari, *scheme);
+            // Unsupported accents are passed through as-is.
+            let expected_mark = scheme.vedic_accent(*accent).unwrap_or(mark);
+            assert!(
+                text.contains(expected_mark),
+                "{scheme:?} {accent:?}: {text} does not contain {expected_mark}"
+            );
+            assert_eq!(
+                t(&text, *scheme, Devanagari),
+                deva,
+                "{scheme:?} {accent:?}: {text}"
+            );
+        }
+    }
+}
+
+#[test]
+fn vedic_accents_are_supported_by_all_brahmic_schemes() {
+    let roman = [
+        BarahaSouth,
+        HarvardKyoto,
+        Iast,
+        Iso15919,
+        Itrans,
+        Slp1,
+        Velthuis,
+        Wx,
+    ];
+    for scheme in Scheme::iter().filter(|s| !roman.contains(s)) {
+        for accent in VedicAccent::iter() {
+            assert!(
+                scheme.vedic_accent(*accent).is_some(),
+                "{scheme:?} {accent:?}"
+            );
+        }
+    }
+}
+
 #[test]
 fn vedic_candrabindu_virama() {
     assert_two_way_pairwise(&[
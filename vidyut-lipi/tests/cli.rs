@@ -0,0 +1,197 @@
+//! Tests for the `lipi` command-line tool.
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use tempfile::tempdir;
+
+/// Runs `lipi` with the given `args` and `stdin`.
+fn lipi(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_lipi"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("can spawn lipi");
+    child
+        .stdin
+        .take()
+        .expect("has stdin")
+        .write_all(stdin.as_bytes())
+        .expect("can write stdin");
+    child.wait_with_output().expect("lipi finishes")
+}
+
+fn stdout(output: &Output) -> &str {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    std::str::from_utf8(&output.stdout).expect("utf-8")
+}
+
+fn stderr(output: &Output) -> &str {
+    assert!(!output.status.success());
+    std::str::from_utf8(&output.stderr).expect("utf-8")
+}
+
+fn path(p: &Path) -> &str {
+    p.to_str().expect("utf-8 path")
+}
+
+#[test]
+fn text_argument() {
+    let out = lipi(&["--from", "slp1", "--to", "devanagari", "rAmaH"], "");
+    assert_eq!(stdout(&out), "रामः\n");
+
+    // Scheme names ignore case, `-`, and `_`.
+    let out = lipi(&["-f", "harvard-kyoto", "-t", "IAST", "rAmaH"], "");
+    assert_eq!(stdout(&out), "rāmaḥ\n");
+}
+
+#[test]
+fn stdin_to_stdout() {
+    let out = lipi(
+        &["--from", "slp1", "--to", "devanagari"],
+        "rAmaH vanam\ngacCati\n",
+    );
+    assert_eq!(stdout(&out), "रामः वनम्\nगच्छति\n");
+
+    let out = lipi(&["--from", "auto", "--to", "slp1", "-i", "-"], "रामः");
+    assert_eq!(stdout(&out), "rAmaH");
+}
+
+#[test]
+fn file_to_file() {
+    let dir = tempdir().expect("ok");
+    let input = dir.path().join("in.txt");
+    let output = dir.path().join("out.txt");
+    fs::write(&input, "rAmaH\n").expect("ok");
+
+    let out = lipi(
+        &[
+            "-f",
+            "slp1",
+            "-t",
+            "devanagari",
+            "-i",
+            path(&input),
+            "-o",
+            path(&output),
+        ],
+        "",
+    );
+    assert_eq!(stdout(&out), "");
+    assert_eq!(fs::read_to_string(&output).expect("ok"), "रामः\n");
+
+    // Without `--output`, we write to stdout.
+    let out = lipi(&["-f", "slp1", "-t", "devanagari", "-i", path(&input)], "");
+    assert_eq!(stdout(&out), "रामः\n");
+}
+
+#[test]
+fn dir_to_dir() {
+    let dir = tempdir().expect("ok");
+    let input = dir.path().join("in");
+    let output = dir.path().join("out");
+    fs::create_dir_all(input.join("a/b")).expect("ok");
+    fs::write(input.join("x.txt"), "rAmaH").expect("ok");
+    fs::write(input.join("a/b/y.txt"), "vanam").expect("ok");
+
+    let out = lipi(
+        &[
+            "-f",
+            "slp1",
+            "-t",
+            "devanagari",
+            "-i",
+            path(&input),
+            "-o",
+            path(&output),
+            "-j",
+            "2",
+        ],
+        "",
+    );
+    stdout(&out);
+    assert_eq!(
+        fs::read_to_string(output.join("x.txt")).expect("ok"),
+        "रामः"
+    );
+    assert_eq!(
+        fs::read_to_string(output.join("a/b/y.txt")).expect("ok"),
+        "वनम्"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn dir_with_symlink_cycle() {
+    let dir = tempdir().expect("ok");
+    let input = dir.path().join("in");
+    let output = dir.path().join("out");
+    fs::create_dir_all(input.join("a")).expect("ok");
+    fs::write(input.join("a/x.txt"), "rAmaH").expect("ok");
+    // `a/loop` points back to `in`, which would recurse forever if we followed it.
+    std::os::unix::fs::symlink(&input, input.join("a/loop")).expect("ok");
+
+    let out = lipi(
+        &[
+            "-f",
+            "slp1",
+            "-t",
+            "devanagari",
+            "-i",
+            path(&input),
+            "-o",
+            path(&output),
+        ],
+        "",
+    );
+    stdout(&out);
+    assert_eq!(
+        fs::read_to_string(output.join("a/x.txt")).expect("ok"),
+        "रामः"
+    );
+    assert!(!output.join("a/loop").exists());
+}
+
+#[test]
+fn unknown_scheme() {
+    let out = lipi(&["--from", "foo", "--to", "slp1", "rAmaH"], "");
+    assert!(stderr(&out).contains("unknown scheme `foo`"));
+}
+
+#[test]
+fn text_and_input_conflict() {
+    let out = lipi(&["-f", "slp1", "-t", "iast", "-i", "-", "rAmaH"], "");
+    assert!(!stderr(&out).is_empty());
+}
+
+#[test]
+fn missing_input_file() {
+    let dir = tempdir().expect("ok");
+    let input = dir.path().join("missing.txt");
+    let out = lipi(&["-f", "slp1", "-t", "iast", "-i", path(&input)], "");
+    assert!(!stderr(&out).is_empty());
+}
+
+#[test]
+fn dir_to_stdout() {
+    let dir = tempdir().expect("ok");
+    let out = lipi(&["-f", "slp1", "-t", "iast", "-i", path(dir.path())], "");
+    assert!(stderr(&out).contains("`--output` must be a directory"));
+}
+
+#[test]
+fn unwritable_output() {
+    let dir = tempdir().expect("ok");
+    let output = dir.path().join("missing/out.txt");
+    let out = lipi(
+        &["-f", "slp1", "-t", "iast", "-o", path(&output), "rAmaH"],
+        "",
+    );
+    assert!(!stderr(&out).is_empty());
+}
@@ -0,0 +1,218 @@
+//! Pins the accuracy of `Detector` on a small held-out corpus.
+//!
+//! `Detector` has a few hand-picked weights: its per-scheme priors and its cost for switching
+//! schemes between words. The text below does not overlap with the detector's seed corpus, so
+//! these tests show how those weights behave on text the model has not seen. If you change the
+//! weights or the seed corpus, run these tests with `--nocapture` to see which samples changed,
+//! and raise the thresholds below if accuracy improves.
+use std::ops::Range;
+use vidyut_lipi::{transliterate, Detector, Mapping, Scheme};
+
+/// Held-out Sanskrit sentences in SLP1.
+///
+/// These come from well-known texts that are not in the detector's seed corpus.
+const SENTENCES_SLP1: &[&str] = &[
+    "asty uttarasyAM diSi devatAtmA himAlayo nAma nagADirAjaH",
+    "pUrvAparO toyaniDI vigAhya sTitaH pfTivyA iva mAnadaRqaH",
+    "kaScit kAntAvirahaguruRA svADikArapramattaH",
+    "SApenAstaMgamitamahimA varzaBogyeRa BartuH",
+    "satyaM brUyAt priyaM brUyAn na brUyAt satyam apriyam",
+    "vidyA dadAti vinayaM vinayAd yAti pAtratAm",
+    "udyamena hi siDyanti kAryARi na manoraTEH",
+    "na hi suptasya siMhasya praviSanti muKe mfgAH",
+    "ayaM nijaH paro veti gaRanA laGucetasAm",
+    "udAracaritAnAM tu vasuDEva kuwumbakam",
+    "sarve Bavantu suKinaH sarve santu nirAmayAH",
+    "asato mA sad gamaya tamaso mA jyotir gamaya",
+    "vAsAMsi jIrRAni yaTA vihAya navAni gfhRAti naro 'parARi",
+    "nEnaM Cindanti SastrARi nEnaM dahati pAvakaH",
+    "SrIgurave namaH iti SizyaH praRamya papracCa",
+    "GawapawAdayaH padArTAH kevalaM vyavahAramAtraM",
+];
+
+/// Held-out single words in SLP1.
+///
+/// Short inputs are the hardest case for detection, since many of them are valid in several
+/// schemes at once.
+const WORDS_SLP1: &[&str] = &[
+    "kfzRaH",
+    "SivaH",
+    "gaReSaH",
+    "lakzmIH",
+    "sItA",
+    "hanumAn",
+    "arjunaH",
+    "BIzmaH",
+    "draupadI",
+    "yuDizWiraH",
+    "jYAnam",
+    "DarmaH",
+    "vEdikam",
+    "OzaDam",
+    "fzayaH",
+    "pitFRAm",
+    "saMskftam",
+    "SAstram",
+    "cakzuH",
+    "vAyuH",
+    "agniH",
+    "sUryaH",
+    "candramAH",
+    "pfTivI",
+    "AkASaH",
+    "manaH",
+    "budDiH",
+    "ahaMkAraH",
+    "kzetrajYaH",
+    "vizRuH",
+];
+
+/// Schemes that users commonly type by hand, and so are the most likely to reach the detector
+/// without a label.
+const ROMAN_SCHEMES: &[Scheme] = &[
+    Scheme::HarvardKyoto,
+    Scheme::Iast,
+    Scheme::Iso15919,
+    Scheme::Itrans,
+    Scheme::Slp1,
+    Scheme::Velthuis,
+    Scheme::Wx,
+];
+
+/// Reads `text` as `scheme` and returns the result in Devanagari.
+fn read(text: &str, scheme: Scheme) -> String {
+    transliterate(text, &Mapping::new(scheme, Scheme::Devanagari))
+}
+
+/// Returns the fraction of `samples` that `detector` reads correctly when they are written in
+/// `scheme`.
+///
+/// A guess is correct if reading the sample with the guessed scheme gives the same Devanagari as
+/// reading it with `scheme`. So if a sample is valid and identical in two schemes, either guess
+/// is fine.
+fn rank_accuracy(detector: &Detector, scheme: Scheme, samples: &[&str]) -> f64 {
+    let to_scheme = Mapping::new(Scheme::Slp1, scheme);
+    let mut num_correct = 0;
+    for sample in samples {
+        let text = transliterate(sample, &to_scheme);
+        let guess = detector.rank(&text)[0].0;
+        if read(&text, guess) == read(&text, scheme) {
+            num_correct += 1;
+        } else {
+            println!("{scheme:?}: read `{text}` as {guess:?}");
+        }
+    }
+    num_correct as f64 / samples.len() as f64
+}
+
+/// Returns the number of words in `text` that `detector.segments` reads correctly, and the total
+/// number of words.
+///
+/// `truth` holds the true scheme of each byte range in `text`. As in `rank_accuracy`, a word is
+/// correct if reading it with the guessed scheme gives the same Devanagari as reading it with the
+/// true scheme.
+fn segment_counts(
+    detector: &Detector,
+    text: &str,
+    truth: &[(Range<usize>, Scheme)],
+) -> (usize, usize) {
+    let mut num_correct = 0;
+    let mut total = 0;
+    for span in detector.segments(text) {
+        let mut i = span.range.start;
+        for word in text[span.range.clone()].split_whitespace() {
+            let start = i + text[i..].find(word).expect("word is in text");
+            i = start + word.len();
+            let (_, expected) = truth
+                .iter()
+                .find(|(r, _)| r.contains(&start))
+                .expect("truth covers text");
+            total += 1;
+            if read(word, span.scheme) == read(word, *expected) {
+                num_correct += 1;
+            } else {
+                println!(
+                    "{expected:?}: read `{word}` in `{text}` as {:?}",
+                    span.scheme
+                );
+            }
+        }
+    }
+    (num_correct, total)
+}
+
+#[test]
+fn rank_sentences() {
+    let detector = Detector::new();
+    let mut total = 0.0;
+    for scheme in Scheme::iter() {
+        let acc = rank_accuracy(&detector, *scheme, SENTENCES_SLP1);
+        assert!(acc >= 0.85, "{scheme:?}: {acc}");
+        total += acc;
+    }
+    let mean = total / Scheme::iter().count() as f64;
+    assert!(mean >= 0.99, "{mean}");
+}
+
+#[test]
+fn rank_words() {
+    let detector = Detector::new();
+    // Most failures here are words that are valid in several ASCII schemes, such as `SivaH`,
+    // which is valid in both SLP1 and Harvard-Kyoto.
+    let expected = [
+        (Scheme::HarvardKyoto, 0.93),
+        (Scheme::Iast, 1.0),
+        (Scheme::Iso15919, 0.96),
+        (Scheme::Itrans, 0.96),
+        (Scheme::Slp1, 0.76),
+        (Scheme::Velthuis, 1.0),
+        (Scheme::Wx, 0.7),
+    ];
+    for (scheme, min_acc) in expected {
+        let acc = rank_accuracy(&detector, scheme, WORDS_SLP1);
+        assert!(acc >= min_acc, "{scheme:?}: {acc} < {min_acc}");
+    }
+}
+
+#[test]
+fn segments_with_one_scheme() {
+    let detector = Detector::new();
+    // Text in a single scheme should stay in a single span. This test pins the lower end of
+    // `SWITCH_COST`: at lower costs, ambiguous words start to split off into spans of their own.
+    for scheme in Scheme::iter() {
+        for sample in SENTENCES_SLP1 {
+            let text = transliterate(sample, &Mapping::new(Scheme::Slp1, *scheme));
+            let spans = detector.segments(&text);
+            assert_eq!(spans.len(), 1, "{scheme:?}: {text} {spans:?}");
+        }
+    }
+}
+
+#[test]
+fn segments_with_two_schemes() {
+    let detector = Detector::new();
+    // Adjacent sentences in two different schemes. This test pins the upper end of
+    // `SWITCH_COST`: at higher costs, we start to miss real switches.
+    let schemes: Vec<Scheme> = ROMAN_SCHEMES
+        .iter()
+        .copied()
+        .chain([Scheme::Devanagari])
+        .collect();
+    let mut num_correct = 0;
+    let mut total = 0;
+    for a in &schemes {
+        for b in schemes.iter().filter(|b| *b != a) {
+            for pair in SENTENCES_SLP1.windows(2) {
+                let x = transliterate(pair[0], &Mapping::new(Scheme::Slp1, *a));
+                let y = transliterate(pair[1], &Mapping::new(Scheme::Slp1, *b));
+                let text = format!("{x} {y}");
+                let truth = [(0..x.len(), *a), (x.len()..text.len(), *b)];
+                let (n, d) = segment_counts(&detector, &text, &truth);
+                num_correct += n;
+                total += d;
+            }
+        }
+    }
+    let acc = num_correct as f64 / total as f64;
+    assert!(acc >= 0.93, "{acc}");
+}
@@ -7,6 +7,12 @@ pub use crate::core::{Decision, Error, Prakriya, Rule, RuleChoice, Step};
 pub use crate::dhatupatha::Dhatupatha;
 pub use crate::vyakarana::{Vyakarana, VyakaranaBuilder};
 
+/// The version of this crate.
+///
+/// Programs that store derived data, such as `vidyut-kosha`, can record this version to detect
+/// data created with an incompatible version of this crate.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 // Public modules.
 // - `args` defines the API contract.
 // - `dhatupatha` defines convenience functions for reading our version of the Dhatupatha.